@@ -18,6 +18,9 @@ impl Architecture for AArch64Architecture {
             "sp" => return AArch64Register::Sp,
             "pc" => return AArch64Register::Pc,
             "pstate" => return AArch64Register::Pstate,
+            "fpcr" => return AArch64Register::Fpcr,
+            "fpsr" => return AArch64Register::Fpsr,
+            "tpidr_el0" => return AArch64Register::Tpidr,
             _ => {}
         }
 
@@ -64,6 +67,14 @@ impl Architecture for AArch64Architecture {
         AArch64Register::Pc
     }
 
+    fn get_fp_control_register() -> Self::Register {
+        AArch64Register::Fpcr
+    }
+
+    fn get_fp_status_register() -> Self::Register {
+        AArch64Register::Fpsr
+    }
+
     fn get_register_file_desc() -> RegisterFileDesc {
         let mut register = HashMap::new();
         register.insert(
@@ -113,6 +124,84 @@ impl Architecture for AArch64Architecture {
             },
         );
 
+        // Only the condition flags of PSTATE are kept, laid out like the NZCV register
+        register.insert(
+            AArch64Register::Pstate.raw(),
+            RegisterDesc {
+                is_read_only: false,
+                size: 8,
+                offset: current_offset + 16,
+            },
+        );
+
+        // The SIMD&FP registers, the narrower views alias the low bits like W does for X
+        let current_offset = current_offset + 24;
+        for i in 0..32 {
+            let offset = current_offset + 16 * i;
+            for (reg, size) in [
+                (AArch64Register::V(i as u8), 16),
+                (AArch64Register::Q(i as u8), 16),
+                (AArch64Register::D(i as u8), 8),
+                (AArch64Register::S(i as u8), 4),
+                (AArch64Register::H(i as u8), 2),
+                (AArch64Register::B(i as u8), 1),
+            ] {
+                register.insert(
+                    reg.raw(),
+                    RegisterDesc {
+                        is_read_only: false,
+                        size,
+                        offset,
+                    },
+                );
+            }
+        }
+
+        let current_offset = current_offset + 16 * 32;
+        for (i, reg) in [
+            AArch64Register::Fpcr,
+            AArch64Register::Fpsr,
+            AArch64Register::Tpidr,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            register.insert(
+                reg.raw(),
+                RegisterDesc {
+                    is_read_only: false,
+                    size: 8,
+                    offset: current_offset + 8 * i,
+                },
+            );
+        }
+
         RegisterFileDesc { register }
     }
 }
+
+/// Optional features of the architecture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AArch64Feature {
+    /// Floating-point.
+    Fp,
+    /// Advanced SIMD.
+    AdvSimd,
+    /// Large System Extensions, the atomic memory operations.
+    Atomics,
+    /// Half-precision floating-point arithmetic.
+    FpHp,
+    /// Half-precision Advanced SIMD arithmetic.
+    AdvSimdHp,
+}
+
+impl AArch64Architecture {
+    /// Optional features the translator implements.
+    pub const FEATURES: &'static [AArch64Feature] = &[
+        AArch64Feature::Fp,
+        AArch64Feature::AdvSimd,
+        AArch64Feature::Atomics,
+        AArch64Feature::FpHp,
+        AArch64Feature::AdvSimdHp,
+    ];
+}
@@ -1,25 +1,36 @@
 use core::{
     ir::{
-        BasicBlock, BasicBlockTerminator, IrConstant, IrInst, IrType, IrValue, Reordering, VecTy,
+        AtomicOp, BasicBlock, BasicBlockTerminator, Comparison, FpComparison, FpRounding,
+        IrConstant, IrInst, IrIntrinsic, IrType, IrValue, Reordering, TypeOf, VecTy,
     },
-    Architecture, Register,
+    Architecture, Error, Interrupt, Register,
 };
+use std::ops::RangeInclusive;
 
-use crate::aarch64::AArch64Register;
+use crate::aarch64::{AArch64MnemonicHint, AArch64Register};
 
 use super::{
     compiler_prelude::{self, *},
-    AArch64Architecture, AArch64Inst, AddSubtractExtReg, AdvSimdModifiedImm, AdvancedSimdCopy,
-    B5B40Imm14Rt, Bitfield, CondCmpImm, CondCmpReg, DataProc2Src, DataProc3Src, ExceptionGen,
-    HwImm16Rd, Imm19Cond, Imm19Rt, Imm26, LdStRegUnscaledImm, LoadStoreRegPair,
-    LoadStoreRegRegOffset, LogicalImm, OpcSizeImm12RnRt, PcRelAddressing, PstateOp, RmCondRnRd,
-    RnRd, RsRt2RnRt, ShImm12RnRd, ShiftRmImm6RnRd, SysRegMov, UncondBranchReg,
+    AArch64Architecture, AArch64Inst, AddSubtractExtReg, AdvSimdLdStMultiStructures,
+    AdvSimdLdStMultiStructuresPostIndexed, AdvSimdLdStSingleStructure,
+    AdvSimdLdStSingleStructurePostIndexed, AdvSimdModifiedImm, AdvSimdScalarPairwise,
+    AdvSimdScalarXIndexedElem, AdvSimdShiftByImm, AdvSimdTableLookup, AdvSimdXIndexedElem,
+    AdvancedSimdCopy, AdvancedSimdExtract, B5B40Imm14Rt, Bitfield, CompareAndSwapPair, CondCmpImm,
+    CondCmpReg, ConvBetweenFloatAndFixedPoint, DataProc2Src, DataProc3Src, ExceptionGen,
+    ExtractImm, FloatingPointCompare, FloatingPointImmediate, HwImm16Rd, Imm16, Imm19Cond, Imm19Rt,
+    Imm26, Imm9RnRt, LdStNoAllocPairOffset, LdStRegUnscaledImm, LoadStoreExclusivePair,
+    LoadStoreRegPair, LoadStoreRegRegOffset, LogicalImm, OpcSizeImm12RnRt, PcRelAddressing,
+    QSizeRmRnRd, QSizeRnRd, RmCondRnRd, RmRaRnRd, RmRnRd, RnRd, RsRnRt, RsRt2RnRt, ShImm12RnRd,
+    ShiftRmImm6RnRd, SysRegMov, SystemInstructions, UncondBranchReg,
 };
 
-pub(crate) fn compile_aarch64_to_ir(inst: &AArch64Inst, basic_block: &mut BasicBlock) {
+pub(crate) fn compile_aarch64_to_ir(
+    inst: &AArch64Inst,
+    basic_block: &mut BasicBlock,
+) -> Result<(), Error> {
     assert!(basic_block.terminator() == BasicBlockTerminator::None);
 
-    match inst {
+    let result = match inst {
         AArch64Inst::MovzVar32(operand) | AArch64Inst::MovzVar64(operand) => {
             compile_movz(basic_block, operand)
         }
@@ -27,723 +38,8047 @@ pub(crate) fn compile_aarch64_to_ir(inst: &AArch64Inst, basic_block: &mut BasicB
         AArch64Inst::MovnVar64(operand) => compile_movn(basic_block, operand, IrType::B64),
         AArch64Inst::MovkVar32(operand) => compile_movk(basic_block, operand, IrType::B32),
         AArch64Inst::MovkVar64(operand) => compile_movk(basic_block, operand, IrType::B64),
-        AArch64Inst::MoviVectorVar64(operand) => compile_movi(basic_block, operand),
         AArch64Inst::Adr(operand) => compile_adr(basic_block, operand),
         AArch64Inst::Adrp(operand) => compile_adrp(basic_block, operand),
 
-        AArch64Inst::RevVar32(operand) => compile_rev_var(basic_block, operand, IrType::B32),
-        AArch64Inst::RevVar64(operand) => compile_rev_var(basic_block, operand, IrType::B64),
-
         // Load and Stores
-        AArch64Inst::LdrImm32(operand) => compile_ldr_imm(basic_block, operand, IrType::B32),
-        AArch64Inst::LdrImm64(operand) => compile_ldr_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::LdrImmSimdFP64(operand) => {
-            compile_ldr_imm_simd_fp(basic_block, operand, IrType::B64)
+        AArch64Inst::LdrbImm(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B8, LoadExtend::Zero)
         }
-        AArch64Inst::LdrImmSimdFP128(operand) => {
-            compile_ldr_imm_simd_fp(basic_block, operand, IrType::Vector(VecTy::U64, 2))
-        }
-        AArch64Inst::LdrLitVar64(operand) => compile_ldr_lit_var64(basic_block, operand),
-        AArch64Inst::LdrhImm(operand) => compile_ldrh_imm(basic_block, operand),
-        AArch64Inst::LdrbImm(operand) => compile_ldrb_imm(basic_block, operand),
-        AArch64Inst::LdrReg32(operand) => compile_ldr_reg(basic_block, operand, IrType::B32),
-        AArch64Inst::LdrReg64(operand) => compile_ldr_reg(basic_block, operand, IrType::B64),
-        AArch64Inst::LdrbRegShiftedReg(operand) => {
-            compile_ldrb_reg_shifted_reg(basic_block, operand)
-        }
-        AArch64Inst::LdpVar64(operand) => compile_ldp(basic_block, operand, IrType::B64),
-        AArch64Inst::LdpVar32(operand) => compile_ldp(basic_block, operand, IrType::B32),
-        AArch64Inst::LdrshReg64(operand) => compile_ldrsh_reg(basic_block, operand, IrType::B64),
-        AArch64Inst::LdrshReg32(operand) => compile_ldrsh_reg(basic_block, operand, IrType::B32),
-        AArch64Inst::LdaxrVar32(operand) => compile_ldaxr(basic_block, operand, IrType::B32),
-        AArch64Inst::LdarVar64(operand) => compile_ldar(basic_block, operand, IrType::B64),
-        AArch64Inst::Ldur64(operand) => compile_ldur(basic_block, operand, IrType::B64),
-        AArch64Inst::LdpSimdFpVar128(operand) => {
-            compile_ldp_simd_fp(basic_block, operand, IrType::Vector(VecTy::U64, 2))
+        AArch64Inst::LdrsbImm32(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B8, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdrsbImm64(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B8, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdrhImm(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B16, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrshImm32(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B16, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdrshImm64(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B16, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdrImm32(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrswImm(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B32, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdrImm64(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B64, LoadExtend::Zero)
         }
-        AArch64Inst::LdrRegSimdFP(operand) => compile_ldr_reg_simd_fp(basic_block, operand),
-        AArch64Inst::LdxrVar64(operand) => compile_ldxr(basic_block, operand, IrType::B64),
 
+        AArch64Inst::StrbImm(operand) => compile_str_imm(basic_block, operand, IrType::B8),
+        AArch64Inst::StrhImm(operand) => compile_str_imm(basic_block, operand, IrType::B16),
         AArch64Inst::StrImm32(operand) => compile_str_imm(basic_block, operand, IrType::B32),
         AArch64Inst::StrImm64(operand) => compile_str_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::StpVar64(operand) => compile_stp_var(basic_block, operand, IrType::B64),
-        AArch64Inst::StpVar32(operand) => compile_stp_var(basic_block, operand, IrType::B32),
-        AArch64Inst::StrbImm(operand) => compile_strb_imm(basic_block, operand),
-        AArch64Inst::Sturb(operand) => compile_sturb_imm(basic_block, operand),
+
+        AArch64Inst::LdrbRegExtReg(operand) | AArch64Inst::LdrbRegShiftedReg(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B8, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrsbRegExtReg32(operand) | AArch64Inst::LdrsbRegShiftedReg32(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B8, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdrsbRegExtReg64(operand) | AArch64Inst::LdrsbRegShiftedReg64(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B8, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdrhReg(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B16, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrshReg32(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B16, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdrshReg64(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B16, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdrReg32(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrswReg(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B32, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdrReg64(operand) => {
+            compile_ldr_reg(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+
+        AArch64Inst::StrbRegExtReg(operand) | AArch64Inst::StrbRegShiftedReg(operand) => {
+            compile_str_reg(basic_block, operand, IrType::B8)
+        }
+        AArch64Inst::StrhReg(operand) => compile_str_reg(basic_block, operand, IrType::B16),
         AArch64Inst::StrReg32(operand) => compile_str_reg(basic_block, operand, IrType::B32),
         AArch64Inst::StrReg64(operand) => compile_str_reg(basic_block, operand, IrType::B64),
+
+        AArch64Inst::Ldurb(operand) => {
+            compile_ldur(basic_block, operand, IrType::B8, LoadExtend::Zero)
+        }
+        AArch64Inst::Ldursb32(operand) => {
+            compile_ldur(basic_block, operand, IrType::B8, LoadExtend::Sign32)
+        }
+        AArch64Inst::Ldursb64(operand) => {
+            compile_ldur(basic_block, operand, IrType::B8, LoadExtend::Sign64)
+        }
+        AArch64Inst::Ldurh(operand) => {
+            compile_ldur(basic_block, operand, IrType::B16, LoadExtend::Zero)
+        }
+        AArch64Inst::Ldursh32(operand) => {
+            compile_ldur(basic_block, operand, IrType::B16, LoadExtend::Sign32)
+        }
+        AArch64Inst::Ldursh64(operand) => {
+            compile_ldur(basic_block, operand, IrType::B16, LoadExtend::Sign64)
+        }
+        AArch64Inst::Ldur32(operand) => {
+            compile_ldur(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::Ldursw(operand) => {
+            compile_ldur(basic_block, operand, IrType::B32, LoadExtend::Sign64)
+        }
+        AArch64Inst::Ldur64(operand) => {
+            compile_ldur(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+
+        AArch64Inst::Sturb(operand) => compile_stur(basic_block, operand, IrType::B8),
+        AArch64Inst::Sturh(operand) => compile_stur(basic_block, operand, IrType::B16),
         AArch64Inst::Stur32(operand) => compile_stur(basic_block, operand, IrType::B32),
         AArch64Inst::Stur64(operand) => compile_stur(basic_block, operand, IrType::B64),
-        AArch64Inst::SturSimdFP64(operand) => {
-            compile_stur_simd_fp(basic_block, operand, IrType::B64)
+
+        // Unprivileged accesses behave like the normal ones, guests always run at EL0
+        AArch64Inst::Ldtrb(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B8, LoadExtend::Zero)
+        }
+        AArch64Inst::LdtrsbVar32(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B8, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdtrsbVar64(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B8, LoadExtend::Sign64)
+        }
+        AArch64Inst::Ldtrh(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B16, LoadExtend::Zero)
+        }
+        AArch64Inst::LdtrshVar32(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B16, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdtrshVar64(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B16, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdtrVar32(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::Ldtrsw(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B32, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdtrVar64(operand) => {
+            compile_ldtr(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+
+        AArch64Inst::Sttrb(operand) => compile_sttr(basic_block, operand, IrType::B8),
+        AArch64Inst::Sttrh(operand) => compile_sttr(basic_block, operand, IrType::B16),
+        AArch64Inst::SttrVar32(operand) => compile_sttr(basic_block, operand, IrType::B32),
+        AArch64Inst::SttrVar64(operand) => compile_sttr(basic_block, operand, IrType::B64),
+
+        AArch64Inst::LdrLitVar32(operand) => {
+            compile_ldr_lit(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrswLit(operand) => {
+            compile_ldr_lit(basic_block, operand, IrType::B32, LoadExtend::Sign64)
         }
-        AArch64Inst::SturSimdFP128(operand) => {
-            compile_stur_simd_fp(basic_block, operand, IrType::Vector(VecTy::U64, 2))
+        AArch64Inst::LdrLitVar64(operand) => {
+            compile_ldr_lit(basic_block, operand, IrType::B64, LoadExtend::Zero)
         }
-        AArch64Inst::StpSimdFpVar128(operand) => {
-            compile_stp_simd_fp(basic_block, operand, IrType::Vector(VecTy::U64, 2))
+
+        AArch64Inst::LdpVar32(operand) => {
+            compile_ldp(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::Ldpsw(operand) => {
+            compile_ldp(basic_block, operand, IrType::B32, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdpVar64(operand) => {
+            compile_ldp(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+        AArch64Inst::StpVar32(operand) => compile_stp(basic_block, operand, IrType::B32),
+        AArch64Inst::StpVar64(operand) => compile_stp(basic_block, operand, IrType::B64),
+
+        AArch64Inst::LdnpVar32(operand) => compile_ldnp(basic_block, operand, IrType::B32),
+        AArch64Inst::LdnpVar64(operand) => compile_ldnp(basic_block, operand, IrType::B64),
+        AArch64Inst::StnpVar32(operand) => compile_stnp(basic_block, operand, IrType::B32),
+        AArch64Inst::StnpVar64(operand) => compile_stnp(basic_block, operand, IrType::B64),
+
+        // SIMD&FP loads and stores only move bits, loads clear the rest of the register
+        AArch64Inst::LdrImmSimdFP8(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B8, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrImmSimdFP16(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B16, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrImmSimdFP32(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B32, LoadExtend::Zero)
         }
-        AArch64Inst::StrImmSimdFP64(operand) => {
-            compile_str_imm_simd_fp(basic_block, operand, IrType::B64)
+        AArch64Inst::LdrImmSimdFP64(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrImmSimdFP128(operand) => {
+            compile_ldr_imm(basic_block, operand, IrType::B128, LoadExtend::Zero)
         }
+        AArch64Inst::StrImmSimdFP8(operand) => compile_str_imm(basic_block, operand, IrType::B8),
+        AArch64Inst::StrImmSimdFP16(operand) => compile_str_imm(basic_block, operand, IrType::B16),
+        AArch64Inst::StrImmSimdFP32(operand) => compile_str_imm(basic_block, operand, IrType::B32),
+        AArch64Inst::StrImmSimdFP64(operand) => compile_str_imm(basic_block, operand, IrType::B64),
         AArch64Inst::StrImmSimdFP128(operand) => {
-            compile_str_imm_simd_fp(basic_block, operand, IrType::Vector(VecTy::U64, 2))
+            compile_str_imm(basic_block, operand, IrType::B128)
+        }
+        AArch64Inst::LdrRegSimdFP(operand) => compile_ldr_reg(
+            basic_block,
+            operand,
+            simd_fp_reg_size(operand),
+            LoadExtend::Zero,
+        ),
+        AArch64Inst::StrRegSimdFP(operand) => {
+            compile_str_reg(basic_block, operand, simd_fp_reg_size(operand))
+        }
+        AArch64Inst::LdurSimdFP8(operand) => {
+            compile_ldur(basic_block, operand, IrType::B8, LoadExtend::Zero)
+        }
+        AArch64Inst::LdurSimdFP16(operand) => {
+            compile_ldur(basic_block, operand, IrType::B16, LoadExtend::Zero)
+        }
+        AArch64Inst::LdurSimdFP32(operand) => {
+            compile_ldur(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::LdurSimdFP64(operand) => {
+            compile_ldur(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+        AArch64Inst::LdurSimdFP128(operand) => {
+            compile_ldur(basic_block, operand, IrType::B128, LoadExtend::Zero)
+        }
+        AArch64Inst::SturSimdFP8(operand) => compile_stur(basic_block, operand, IrType::B8),
+        AArch64Inst::SturSimdFP16(operand) => compile_stur(basic_block, operand, IrType::B16),
+        AArch64Inst::SturSimdFP32(operand) => compile_stur(basic_block, operand, IrType::B32),
+        AArch64Inst::SturSimdFP64(operand) => compile_stur(basic_block, operand, IrType::B64),
+        AArch64Inst::SturSimdFP128(operand) => compile_stur(basic_block, operand, IrType::B128),
+        AArch64Inst::LdrLitSimdFPVar32(operand) => {
+            compile_ldr_lit(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrLitSimdFPVar64(operand) => {
+            compile_ldr_lit(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+        AArch64Inst::LdrLitSimdFPVar128(operand) => {
+            compile_ldr_lit(basic_block, operand, IrType::B128, LoadExtend::Zero)
+        }
+        AArch64Inst::LdpSimdFPVar32(operand) => {
+            compile_ldp(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::LdpSimdFPVar64(operand) => {
+            compile_ldp(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+        AArch64Inst::LdpSimdFpVar128(operand) => {
+            compile_ldp(basic_block, operand, IrType::B128, LoadExtend::Zero)
+        }
+        AArch64Inst::StpSimdFPVar32(operand) => compile_stp(basic_block, operand, IrType::B32),
+        AArch64Inst::StpSimdFPVar64(operand) => compile_stp(basic_block, operand, IrType::B64),
+        AArch64Inst::StpSimdFpVar128(operand) => compile_stp(basic_block, operand, IrType::B128),
+        AArch64Inst::LdnpSimdFPVar32(operand) => compile_ldnp(basic_block, operand, IrType::B32),
+        AArch64Inst::LdnpSimdFPVar64(operand) => compile_ldnp(basic_block, operand, IrType::B64),
+        AArch64Inst::LdnpSimdFPVar128(operand) => compile_ldnp(basic_block, operand, IrType::B128),
+        AArch64Inst::StnpSimdFPVar32(operand) => compile_stnp(basic_block, operand, IrType::B32),
+        AArch64Inst::StnpSimdFPVar64(operand) => compile_stnp(basic_block, operand, IrType::B64),
+        AArch64Inst::StnpSimdFPVar128(operand) => compile_stnp(basic_block, operand, IrType::B128),
+
+        AArch64Inst::Ldxrb(operand) => compile_ldxr(basic_block, operand, IrType::B8, false),
+        AArch64Inst::Ldxrh(operand) => compile_ldxr(basic_block, operand, IrType::B16, false),
+        AArch64Inst::LdxrVar32(operand) => compile_ldxr(basic_block, operand, IrType::B32, false),
+        AArch64Inst::LdxrVar64(operand) => compile_ldxr(basic_block, operand, IrType::B64, false),
+        AArch64Inst::Ldaxrb(operand) => compile_ldxr(basic_block, operand, IrType::B8, true),
+        AArch64Inst::Ldaxrh(operand) => compile_ldxr(basic_block, operand, IrType::B16, true),
+        AArch64Inst::LdaxrVar32(operand) => compile_ldxr(basic_block, operand, IrType::B32, true),
+        AArch64Inst::LdaxrVar64(operand) => compile_ldxr(basic_block, operand, IrType::B64, true),
+        AArch64Inst::Stxrb(operand) => compile_stxr(basic_block, operand, IrType::B8, false),
+        AArch64Inst::Stxrh(operand) => compile_stxr(basic_block, operand, IrType::B16, false),
+        AArch64Inst::StxrVar32(operand) => compile_stxr(basic_block, operand, IrType::B32, false),
+        AArch64Inst::StxrVar64(operand) => compile_stxr(basic_block, operand, IrType::B64, false),
+        AArch64Inst::Stlxrb(operand) => compile_stxr(basic_block, operand, IrType::B8, true),
+        AArch64Inst::Stlxrh(operand) => compile_stxr(basic_block, operand, IrType::B16, true),
+        AArch64Inst::StlxrVar32(operand) => compile_stxr(basic_block, operand, IrType::B32, true),
+        AArch64Inst::StlxrVar64(operand) => compile_stxr(basic_block, operand, IrType::B64, true),
+
+        AArch64Inst::LdxpVar32(operand) => compile_ldxp(basic_block, operand, IrType::B32, false),
+        AArch64Inst::LdxpVar64(operand) => compile_ldxp(basic_block, operand, IrType::B64, false),
+        AArch64Inst::LdaxpVar32(operand) => compile_ldxp(basic_block, operand, IrType::B32, true),
+        AArch64Inst::LdaxpVar64(operand) => compile_ldxp(basic_block, operand, IrType::B64, true),
+        AArch64Inst::StxpVar32(operand) => compile_stxp(basic_block, operand, IrType::B32, false),
+        AArch64Inst::StxpVar64(operand) => compile_stxp(basic_block, operand, IrType::B64, false),
+        AArch64Inst::StlxpVar32(operand) => compile_stxp(basic_block, operand, IrType::B32, true),
+        AArch64Inst::StlxpVar64(operand) => compile_stxp(basic_block, operand, IrType::B64, true),
+
+        AArch64Inst::Ldarb(operand) => compile_ldar(basic_block, operand, IrType::B8),
+        AArch64Inst::Ldarh(operand) => compile_ldar(basic_block, operand, IrType::B16),
+        AArch64Inst::LdarVar32(operand) => compile_ldar(basic_block, operand, IrType::B32),
+        AArch64Inst::LdarVar64(operand) => compile_ldar(basic_block, operand, IrType::B64),
+        AArch64Inst::Stlrb(operand) => compile_stlr(basic_block, operand, IrType::B8),
+        AArch64Inst::Stlrh(operand) => compile_stlr(basic_block, operand, IrType::B16),
+        AArch64Inst::StlrVar32(operand) => compile_stlr(basic_block, operand, IrType::B32),
+        AArch64Inst::StlrVar64(operand) => compile_stlr(basic_block, operand, IrType::B64),
+
+        AArch64Inst::Casb(operand) => compile_cas(basic_block, operand, IrType::B8, false, false),
+        AArch64Inst::Casab(operand) => compile_cas(basic_block, operand, IrType::B8, true, false),
+        AArch64Inst::Caslb(operand) => compile_cas(basic_block, operand, IrType::B8, false, true),
+        AArch64Inst::Casalb(operand) => compile_cas(basic_block, operand, IrType::B8, true, true),
+        AArch64Inst::Cash(operand) => compile_cas(basic_block, operand, IrType::B16, false, false),
+        AArch64Inst::Casah(operand) => compile_cas(basic_block, operand, IrType::B16, true, false),
+        AArch64Inst::Caslh(operand) => compile_cas(basic_block, operand, IrType::B16, false, true),
+        AArch64Inst::Casalh(operand) => compile_cas(basic_block, operand, IrType::B16, true, true),
+        AArch64Inst::CasVar32(operand) => {
+            compile_cas(basic_block, operand, IrType::B32, false, false)
+        }
+        AArch64Inst::CasaVar32(operand) => {
+            compile_cas(basic_block, operand, IrType::B32, true, false)
+        }
+        AArch64Inst::CaslVar32(operand) => {
+            compile_cas(basic_block, operand, IrType::B32, false, true)
+        }
+        AArch64Inst::CasalVar32(operand) => {
+            compile_cas(basic_block, operand, IrType::B32, true, true)
+        }
+        AArch64Inst::CasVar64(operand) => {
+            compile_cas(basic_block, operand, IrType::B64, false, false)
+        }
+        AArch64Inst::CasaVar64(operand) => {
+            compile_cas(basic_block, operand, IrType::B64, true, false)
+        }
+        AArch64Inst::CaslVar64(operand) => {
+            compile_cas(basic_block, operand, IrType::B64, false, true)
+        }
+        AArch64Inst::CasalVar64(operand) => {
+            compile_cas(basic_block, operand, IrType::B64, true, true)
+        }
+        AArch64Inst::CaspVar32(operand) => {
+            compile_casp(basic_block, operand, IrType::B32, false, false)
+        }
+        AArch64Inst::CaspaVar32(operand) => {
+            compile_casp(basic_block, operand, IrType::B32, true, false)
+        }
+        AArch64Inst::CasplVar32(operand) => {
+            compile_casp(basic_block, operand, IrType::B32, false, true)
+        }
+        AArch64Inst::CaspalVar32(operand) => {
+            compile_casp(basic_block, operand, IrType::B32, true, true)
+        }
+        AArch64Inst::CaspVar64(operand) => {
+            compile_casp(basic_block, operand, IrType::B64, false, false)
+        }
+        AArch64Inst::CaspaVar64(operand) => {
+            compile_casp(basic_block, operand, IrType::B64, true, false)
+        }
+        AArch64Inst::CasplVar64(operand) => {
+            compile_casp(basic_block, operand, IrType::B64, false, true)
+        }
+        AArch64Inst::CaspalVar64(operand) => {
+            compile_casp(basic_block, operand, IrType::B64, true, true)
         }
-        AArch64Inst::StrRegSimdFP(operand) => compile_str_reg_simd_fp(basic_block, operand),
-        AArch64Inst::StlxrVar32(operand) => compile_stlxr(basic_block, operand, IrType::B32),
-        AArch64Inst::StxrVar64(operand) => compile_stxr(basic_block, operand, IrType::B64),
-        AArch64Inst::StxrVar32(operand) => compile_stxr(basic_block, operand, IrType::B32),
-        AArch64Inst::StrbRegShiftedReg(operand) => compile_strb_reg(basic_block, operand),
 
-        // Advanced SIMD and FP
-        AArch64Inst::DupGeneral(operand) => compile_dup_general(basic_block, operand),
+        // RCpc loads are lowered like the stronger LDAR
+        AArch64Inst::Ldaprb(operand) => compile_ldapr(basic_block, operand, IrType::B8),
+        AArch64Inst::Ldaprh(operand) => compile_ldapr(basic_block, operand, IrType::B16),
+        AArch64Inst::LdaprVar32(operand) => compile_ldapr(basic_block, operand, IrType::B32),
+        AArch64Inst::LdaprVar64(operand) => compile_ldapr(basic_block, operand, IrType::B64),
+        AArch64Inst::Ldapurb(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B8, LoadExtend::Zero)
+        }
+        AArch64Inst::LdapursbVar32(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B8, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdapursbVar64(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B8, LoadExtend::Sign64)
+        }
+        AArch64Inst::Ldapurh(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B16, LoadExtend::Zero)
+        }
+        AArch64Inst::LdapurshVar32(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B16, LoadExtend::Sign32)
+        }
+        AArch64Inst::LdapurshVar64(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B16, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdapurVar32(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B32, LoadExtend::Zero)
+        }
+        AArch64Inst::Ldapursw(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B32, LoadExtend::Sign64)
+        }
+        AArch64Inst::LdapurVar64(operand) => {
+            compile_ldapur(basic_block, operand, IrType::B64, LoadExtend::Zero)
+        }
+        AArch64Inst::Stlurb(operand) => compile_stlur(basic_block, operand, IrType::B8),
+        AArch64Inst::Stlurh(operand) => compile_stlur(basic_block, operand, IrType::B16),
+        AArch64Inst::StlurVar32(operand) => compile_stlur(basic_block, operand, IrType::B32),
+        AArch64Inst::StlurVar64(operand) => compile_stlur(basic_block, operand, IrType::B64),
+
+        // Advanced SIMD structure loads and stores, the post-index forms advance `rn` by XZR
+        // for the size of the transfer
+        AArch64Inst::Ld1MulStructures1RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 1, true)
+        }
+        AArch64Inst::Ld1MulStructures1RegRegOffsetVar(operand)
+        | AArch64Inst::Ld1MulStructures1RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 1, true)
+        }
+        AArch64Inst::Ld1MulStructures2RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 2, 1, true)
+        }
+        AArch64Inst::Ld1MulStructures2RegRegOffsetVar(operand)
+        | AArch64Inst::Ld1MulStructures2RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 2, 1, true)
+        }
+        AArch64Inst::Ld1MulStructures3RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 3, 1, true)
+        }
+        AArch64Inst::Ld1MulStructures3RegRegOffsetVar(operand)
+        | AArch64Inst::Ld1MulStructures3RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 3, 1, true)
+        }
+        AArch64Inst::Ld1MulStructures4RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 4, 1, true)
+        }
+        AArch64Inst::Ld1MulStructures4RegRegOffsetVar(operand)
+        | AArch64Inst::Ld1MulStructures4RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 4, 1, true)
+        }
+        AArch64Inst::Ld2MulStructures(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 2, true)
+        }
+        AArch64Inst::Ld2MulStructuresRegOffsetVar(operand)
+        | AArch64Inst::Ld2MulStructuresImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 2, true)
+        }
+        AArch64Inst::Ld3MulStructures(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 3, true)
+        }
+        AArch64Inst::Ld3MulStructuresRegOffsetVar(operand)
+        | AArch64Inst::Ld3MulStructuresImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 3, true)
+        }
+        AArch64Inst::Ld4MulStructures(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 4, true)
+        }
+        AArch64Inst::Ld4MulStructuresRegOffsetVar(operand)
+        | AArch64Inst::Ld4MulStructuresImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 4, true)
+        }
+        AArch64Inst::St1MulStructures1RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 1, false)
+        }
+        AArch64Inst::St1MulStructures1RegRegOffsetVar(operand)
+        | AArch64Inst::St1MulStructures1RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 1, false)
+        }
+        AArch64Inst::St1MulStructures2RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 2, 1, false)
+        }
+        AArch64Inst::St1MulStructures2RegRegOffsetVar(operand)
+        | AArch64Inst::St1MulStructures2RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 2, 1, false)
+        }
+        AArch64Inst::St1MulStructures3RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 3, 1, false)
+        }
+        AArch64Inst::St1MulStructures3RegRegOffsetVar(operand)
+        | AArch64Inst::St1MulStructures3RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 3, 1, false)
+        }
+        AArch64Inst::St1MulStructures4RegsVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 4, 1, false)
+        }
+        AArch64Inst::St1MulStructures4RegRegOffsetVar(operand)
+        | AArch64Inst::St1MulStructures4RegImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 4, 1, false)
+        }
+        AArch64Inst::St2MulStructures(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 2, false)
+        }
+        AArch64Inst::St2MulStructuresRegOffsetVar(operand)
+        | AArch64Inst::St2MulStructuresImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 2, false)
+        }
+        AArch64Inst::St3MulStructures(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 3, false)
+        }
+        AArch64Inst::St3MulStructuresRegOffsetVar(operand)
+        | AArch64Inst::St3MulStructuresImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 3, false)
+        }
+        AArch64Inst::St4MulStructures(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 4, false)
+        }
+        AArch64Inst::St4MulStructuresRegOffsetVar(operand)
+        | AArch64Inst::St4MulStructuresImmOffsetVar(operand) => {
+            compile_ld_st_multi_structures(basic_block, operand.into(), 1, 4, false)
+        }
+        AArch64Inst::Ld1SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 0, true)
+        }
+        AArch64Inst::Ld1SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::Ld1SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 0, true)
+        }
+        AArch64Inst::Ld1SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 1, true)
+        }
+        AArch64Inst::Ld1SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::Ld1SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 1, true)
+        }
+        AArch64Inst::Ld1SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 2, true)
+        }
+        AArch64Inst::Ld1SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::Ld1SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 2, true)
+        }
+        AArch64Inst::Ld1SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 3, true)
+        }
+        AArch64Inst::Ld1SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::Ld1SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 3, true)
+        }
+        AArch64Inst::Ld2SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 0, true)
+        }
+        AArch64Inst::Ld2SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::Ld2SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 0, true)
+        }
+        AArch64Inst::Ld2SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 1, true)
+        }
+        AArch64Inst::Ld2SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::Ld2SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 1, true)
+        }
+        AArch64Inst::Ld2SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 2, true)
+        }
+        AArch64Inst::Ld2SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::Ld2SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 2, true)
+        }
+        AArch64Inst::Ld2SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 3, true)
+        }
+        AArch64Inst::Ld2SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::Ld2SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 3, true)
+        }
+        AArch64Inst::Ld3SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 0, true)
+        }
+        AArch64Inst::Ld3SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::Ld3SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 0, true)
+        }
+        AArch64Inst::Ld3SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 1, true)
+        }
+        AArch64Inst::Ld3SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::Ld3SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 1, true)
+        }
+        AArch64Inst::Ld3SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 2, true)
+        }
+        AArch64Inst::Ld3SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::Ld3SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 2, true)
+        }
+        AArch64Inst::Ld3SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 3, true)
+        }
+        AArch64Inst::Ld3SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::Ld3SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 3, true)
+        }
+        AArch64Inst::Ld4SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 0, true)
+        }
+        AArch64Inst::Ld4SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::Ld4SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 0, true)
+        }
+        AArch64Inst::Ld4SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 1, true)
+        }
+        AArch64Inst::Ld4SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::Ld4SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 1, true)
+        }
+        AArch64Inst::Ld4SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 2, true)
+        }
+        AArch64Inst::Ld4SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::Ld4SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 2, true)
+        }
+        AArch64Inst::Ld4SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 3, true)
+        }
+        AArch64Inst::Ld4SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::Ld4SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 3, true)
+        }
+        AArch64Inst::St1SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 0, false)
+        }
+        AArch64Inst::St1SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::St1SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 0, false)
+        }
+        AArch64Inst::St1SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 1, false)
+        }
+        AArch64Inst::St1SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::St1SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 1, false)
+        }
+        AArch64Inst::St1SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 2, false)
+        }
+        AArch64Inst::St1SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::St1SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 2, false)
+        }
+        AArch64Inst::St1SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 3, false)
+        }
+        AArch64Inst::St1SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::St1SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 1, 3, false)
+        }
+        AArch64Inst::St2SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 0, false)
+        }
+        AArch64Inst::St2SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::St2SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 0, false)
+        }
+        AArch64Inst::St2SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 1, false)
+        }
+        AArch64Inst::St2SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::St2SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 1, false)
+        }
+        AArch64Inst::St2SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 2, false)
+        }
+        AArch64Inst::St2SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::St2SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 2, false)
+        }
+        AArch64Inst::St2SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 3, false)
+        }
+        AArch64Inst::St2SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::St2SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 2, 3, false)
+        }
+        AArch64Inst::St3SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 0, false)
+        }
+        AArch64Inst::St3SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::St3SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 0, false)
+        }
+        AArch64Inst::St3SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 1, false)
+        }
+        AArch64Inst::St3SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::St3SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 1, false)
+        }
+        AArch64Inst::St3SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 2, false)
+        }
+        AArch64Inst::St3SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::St3SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 2, false)
+        }
+        AArch64Inst::St3SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 3, false)
+        }
+        AArch64Inst::St3SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::St3SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 3, 3, false)
+        }
+        AArch64Inst::St4SingleStructureVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 0, false)
+        }
+        AArch64Inst::St4SingleStructureRegOffsetVar8(operand)
+        | AArch64Inst::St4SingleStructureImmOffsetVar8(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 0, false)
+        }
+        AArch64Inst::St4SingleStructureVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 1, false)
+        }
+        AArch64Inst::St4SingleStructureRegOffsetVar16(operand)
+        | AArch64Inst::St4SingleStructureImmOffsetVar16(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 1, false)
+        }
+        AArch64Inst::St4SingleStructureVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 2, false)
+        }
+        AArch64Inst::St4SingleStructureRegOffsetVar32(operand)
+        | AArch64Inst::St4SingleStructureImmOffsetVar32(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 2, false)
+        }
+        AArch64Inst::St4SingleStructureVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 3, false)
+        }
+        AArch64Inst::St4SingleStructureRegOffsetVar64(operand)
+        | AArch64Inst::St4SingleStructureImmOffsetVar64(operand) => {
+            compile_ld_st_single_structure(basic_block, operand.into(), 4, 3, false)
+        }
+        AArch64Inst::Ld1r(operand) => compile_ld_replicate(basic_block, operand.into(), 1),
+        AArch64Inst::Ld1rRegOffset(operand) | AArch64Inst::Ld1rImmOffset(operand) => {
+            compile_ld_replicate(basic_block, operand.into(), 1)
+        }
+        AArch64Inst::Ld2r(operand) => compile_ld_replicate(basic_block, operand.into(), 2),
+        AArch64Inst::Ld2rRegOffset(operand) | AArch64Inst::Ld2rImmOffset(operand) => {
+            compile_ld_replicate(basic_block, operand.into(), 2)
+        }
+        AArch64Inst::Ld3r(operand) => compile_ld_replicate(basic_block, operand.into(), 3),
+        AArch64Inst::Ld3rRegOffset(operand) | AArch64Inst::Ld3rImmOffset(operand) => {
+            compile_ld_replicate(basic_block, operand.into(), 3)
+        }
+        AArch64Inst::Ld4r(operand) => compile_ld_replicate(basic_block, operand.into(), 4),
+        AArch64Inst::Ld4rRegOffset(operand) | AArch64Inst::Ld4rImmOffset(operand) => {
+            compile_ld_replicate(basic_block, operand.into(), 4)
+        }
+
+        // Prefetches are only hints
+        AArch64Inst::PrfmImm(_)
+        | AArch64Inst::PrfmReg(_)
+        | AArch64Inst::PrfmLit(_)
+        | AArch64Inst::Prefum(_) => Ok(()),
 
         // Arithmetic instructions
         AArch64Inst::AddImm64(operand) => compile_add_imm(basic_block, operand, IrType::B64),
         AArch64Inst::AddImm32(operand) => compile_add_imm(basic_block, operand, IrType::B32),
+        AArch64Inst::SubImm64(operand) => compile_sub_imm(basic_block, operand, IrType::B64),
+        AArch64Inst::SubImm32(operand) => compile_sub_imm(basic_block, operand, IrType::B32),
         AArch64Inst::AddsImm64(operand) => compile_adds_imm(basic_block, operand, IrType::B64),
         AArch64Inst::AddsImm32(operand) => compile_adds_imm(basic_block, operand, IrType::B32),
+        AArch64Inst::SubsImm64(operand) => compile_subs_imm(basic_block, operand, IrType::B64),
+        AArch64Inst::SubsImm32(operand) => compile_subs_imm(basic_block, operand, IrType::B32),
+        AArch64Inst::AddShiftedReg32(operand) => {
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B32, false, false)
+        }
+        AArch64Inst::AddsShiftedReg32(operand) => {
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B32, false, true)
+        }
+        AArch64Inst::SubShiftedReg32(operand) => {
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B32, true, false)
+        }
+        AArch64Inst::SubsShiftedReg32(operand) => {
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B32, true, true)
+        }
         AArch64Inst::AddShiftedReg64(operand) => {
-            compile_add_shifted_reg(basic_block, operand, IrType::B64)
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B64, false, false)
         }
         AArch64Inst::AddsShiftedReg64(operand) => {
-            compile_adds_shifted_reg(basic_block, operand, IrType::B64)
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B64, false, true)
         }
-        AArch64Inst::AddExtReg64(operand) => compile_add_ext_reg(basic_block, operand, IrType::B64),
-        AArch64Inst::SubImm64(operand) => compile_sub_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::SubImm32(operand) => compile_sub_imm(basic_block, operand, IrType::B32),
         AArch64Inst::SubShiftedReg64(operand) => {
-            compile_sub_shifted_reg(basic_block, operand, IrType::B64)
-        }
-        AArch64Inst::SubsShiftedReg32(operand) => {
-            compile_subs_shifted_reg(basic_block, operand, IrType::B32)
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B64, true, false)
         }
         AArch64Inst::SubsShiftedReg64(operand) => {
-            compile_subs_shifted_reg(basic_block, operand, IrType::B64)
+            compile_add_sub_shifted_reg(basic_block, operand, IrType::B64, true, true)
+        }
+        AArch64Inst::AddExtReg32(operand) => {
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B32, false, false)
+        }
+        AArch64Inst::AddsExtReg32(operand) => {
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B32, false, true)
+        }
+        AArch64Inst::SubExtReg32(operand) => {
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B32, true, false)
+        }
+        AArch64Inst::SubsExtReg32(operand) => {
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B32, true, true)
+        }
+        AArch64Inst::AddExtReg64(operand) => {
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B64, false, false)
+        }
+        AArch64Inst::AddsExtReg64(operand) => {
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B64, false, true)
+        }
+        AArch64Inst::SubExtReg64(operand) => {
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B64, true, false)
         }
         AArch64Inst::SubsExtReg64(operand) => {
-            compile_subs_ext_reg(basic_block, operand, IrType::B64)
+            compile_add_sub_ext_reg(basic_block, operand, IrType::B64, true, true)
         }
-        AArch64Inst::SubsImm64(operand) => compile_subs_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::SubsImm32(operand) => compile_subs_imm(basic_block, operand, IrType::B32),
-        AArch64Inst::Madd32(operand) => compile_madd(basic_block, operand, IrType::B32),
-        AArch64Inst::Madd64(operand) => compile_madd(basic_block, operand, IrType::B64),
-        AArch64Inst::Msub32(operand) => compile_msub(basic_block, operand, IrType::B32),
-        AArch64Inst::SdivVar32(operand) => compile_div(basic_block, operand, IrType::B32),
-        AArch64Inst::SdivVar64(operand) => compile_div(basic_block, operand, IrType::B64),
-        AArch64Inst::UdivVar32(operand) => compile_div(basic_block, operand, IrType::B32),
-        AArch64Inst::UdivVar64(operand) => compile_div(basic_block, operand, IrType::B64),
+        AArch64Inst::AdcVar32(operand) => {
+            compile_adc(basic_block, operand, IrType::B32, false, false)
+        }
+        AArch64Inst::AdcsVar32(operand) => {
+            compile_adc(basic_block, operand, IrType::B32, false, true)
+        }
+        AArch64Inst::SbcVar32(operand) => {
+            compile_adc(basic_block, operand, IrType::B32, true, false)
+        }
+        AArch64Inst::SbcsVar32(operand) => {
+            compile_adc(basic_block, operand, IrType::B32, true, true)
+        }
+        AArch64Inst::AdcVar64(operand) => {
+            compile_adc(basic_block, operand, IrType::B64, false, false)
+        }
+        AArch64Inst::AdcsVar64(operand) => {
+            compile_adc(basic_block, operand, IrType::B64, false, true)
+        }
+        AArch64Inst::SbcVar64(operand) => {
+            compile_adc(basic_block, operand, IrType::B64, true, false)
+        }
+        AArch64Inst::SbcsVar64(operand) => {
+            compile_adc(basic_block, operand, IrType::B64, true, true)
+        }
+        AArch64Inst::UdivVar32(operand) => compile_div(basic_block, operand, IrType::B32, false),
+        AArch64Inst::UdivVar64(operand) => compile_div(basic_block, operand, IrType::B64, false),
+        AArch64Inst::SdivVar32(operand) => compile_div(basic_block, operand, IrType::B32, true),
+        AArch64Inst::SdivVar64(operand) => compile_div(basic_block, operand, IrType::B64, true),
+        AArch64Inst::Madd32(operand) => compile_madd(basic_block, operand, IrType::B32, false),
+        AArch64Inst::Msub32(operand) => compile_madd(basic_block, operand, IrType::B32, true),
+        AArch64Inst::Madd64(operand) => compile_madd(basic_block, operand, IrType::B64, false),
+        AArch64Inst::Msub64(operand) => compile_madd(basic_block, operand, IrType::B64, true),
+        AArch64Inst::Smaddl(operand) => compile_maddl(basic_block, operand, true, false),
+        AArch64Inst::Smsubl(operand) => compile_maddl(basic_block, operand, true, true),
+        AArch64Inst::Umaddl(operand) => compile_maddl(basic_block, operand, false, false),
+        AArch64Inst::Umsubl(operand) => compile_maddl(basic_block, operand, false, true),
+        AArch64Inst::Smulh(operand) => compile_mulh(basic_block, operand, true),
+        AArch64Inst::Umulh(operand) => compile_mulh(basic_block, operand, false),
 
         // bitwise isntructions
-        AArch64Inst::Ubfm32(operand) => compile_ubfm(basic_block, operand, IrType::B32),
-        AArch64Inst::Ubfm64(operand) => compile_ubfm(basic_block, operand, IrType::B64),
-        AArch64Inst::Sbfm64(operand) => compile_sbfm(basic_block, operand, IrType::B64),
-        AArch64Inst::AndImm64(operand) => compile_and_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::AndImm32(operand) => compile_and_imm(basic_block, operand, IrType::B32),
-        AArch64Inst::AndsImm64(operand) => compile_ands_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::AndsImm32(operand) => compile_ands_imm(basic_block, operand, IrType::B32),
-        AArch64Inst::AndsShiftedReg32(operand) => {
-            compile_ands_shifted_reg(basic_block, operand, IrType::B32)
-        }
-        AArch64Inst::AndsShiftedReg64(operand) => {
-            compile_ands_shifted_reg(basic_block, operand, IrType::B64)
-        }
-        AArch64Inst::AndShiftedReg64(operand) => {
-            compile_and_shifted_reg(basic_block, operand, IrType::B64)
-        }
-        AArch64Inst::OrrImm64(operand) => compile_orr_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::OrrImm32(operand) => compile_orr_imm(basic_block, operand, IrType::B32),
+        AArch64Inst::AndImm32(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B32, LogicalOp::And, false)
+        }
+        AArch64Inst::OrrImm32(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B32, LogicalOp::Orr, false)
+        }
+        AArch64Inst::EorImm32(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B32, LogicalOp::Eor, false)
+        }
+        AArch64Inst::AndsImm32(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B32, LogicalOp::And, true)
+        }
+        AArch64Inst::AndImm64(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B64, LogicalOp::And, false)
+        }
+        AArch64Inst::OrrImm64(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B64, LogicalOp::Orr, false)
+        }
+        AArch64Inst::EorImm64(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B64, LogicalOp::Eor, false)
+        }
+        AArch64Inst::AndsImm64(operand) => {
+            compile_logical_imm(basic_block, operand, IrType::B64, LogicalOp::And, true)
+        }
         AArch64Inst::OrrShiftedReg64(operand) => {
             compile_orr_shifted_reg(basic_block, operand, IrType::B64)
         }
         AArch64Inst::OrrShiftedReg32(operand) => {
             compile_orr_shifted_reg(basic_block, operand, IrType::B32)
         }
-        AArch64Inst::OrnShiftedReg64(operand) => {
-            compile_orn_shifted_reg(basic_block, operand, IrType::B64)
+
+        // Bitfield and bit manipulation instructions
+        AArch64Inst::Sbfm32(operand) => {
+            compile_bfm(basic_block, operand, IrType::B32, BitfieldMove::Signed)
         }
-        AArch64Inst::OrnShiftedReg32(operand) => {
-            compile_orn_shifted_reg(basic_block, operand, IrType::B32)
+        AArch64Inst::Bfm32(operand) => {
+            compile_bfm(basic_block, operand, IrType::B32, BitfieldMove::Insert)
         }
-
-        AArch64Inst::LslvVar64(operand) => compile_lslv(basic_block, operand, IrType::B64),
-        AArch64Inst::LslvVar32(operand) => compile_lslv(basic_block, operand, IrType::B32),
+        AArch64Inst::Ubfm32(operand) => {
+            compile_bfm(basic_block, operand, IrType::B32, BitfieldMove::Unsigned)
+        }
+        AArch64Inst::Sbfm64(operand) => {
+            compile_bfm(basic_block, operand, IrType::B64, BitfieldMove::Signed)
+        }
+        AArch64Inst::Bfm64(operand) => {
+            compile_bfm(basic_block, operand, IrType::B64, BitfieldMove::Insert)
+        }
+        AArch64Inst::Ubfm64(operand) => {
+            compile_bfm(basic_block, operand, IrType::B64, BitfieldMove::Unsigned)
+        }
+        AArch64Inst::Extr32(operand) => compile_extr(basic_block, operand, IrType::B32),
+        AArch64Inst::Extr64(operand) => compile_extr(basic_block, operand, IrType::B64),
+        AArch64Inst::ClzVar32(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B32, |dst, src| IrInst::Clz {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::ClzVar64(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B64, |dst, src| IrInst::Clz {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::ClsVar32(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B32, |dst, src| IrInst::Cls {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::ClsVar64(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B64, |dst, src| IrInst::Cls {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::RbitVar32(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B32, |dst, src| {
+                IrInst::Bitrev { dst, src }
+            })
+        }
+        AArch64Inst::RbitVar64(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B64, |dst, src| {
+                IrInst::Bitrev { dst, src }
+            })
+        }
+        AArch64Inst::RevVar32(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B32, |dst, src| {
+                IrInst::Bswap { dst, src }
+            })
+        }
+        AArch64Inst::RevVar64(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B64, |dst, src| {
+                IrInst::Bswap { dst, src }
+            })
+        }
+        AArch64Inst::Rev16Var32(operand) => compile_rev16(basic_block, operand, IrType::B32),
+        AArch64Inst::Rev16Var64(operand) => compile_rev16(basic_block, operand, IrType::B64),
+        AArch64Inst::Rev32(operand) => compile_rev32(basic_block, operand),
 
         // Branch instructions
-        AArch64Inst::BlImm(operand) => compile_bl_imm(basic_block, operand),
         AArch64Inst::BImm(operand) => compile_b_imm(basic_block, operand),
+        AArch64Inst::BlImm(operand) => compile_bl_imm(basic_block, operand),
         AArch64Inst::Br(operand) => compile_br(basic_block, operand),
         AArch64Inst::Blr(operand) => compile_blr(basic_block, operand),
-        AArch64Inst::BCond(operand) => compile_b_cond(basic_block, operand),
-        AArch64Inst::Cbz64(operand) => compile_cbz(basic_block, operand, IrType::B32),
-        AArch64Inst::Cbz32(operand) => compile_cbz(basic_block, operand, IrType::B64),
-        AArch64Inst::Cbnz32(operand) => compile_cbnz(basic_block, operand, IrType::B32),
-        AArch64Inst::Cbnz64(operand) => compile_cbnz(basic_block, operand, IrType::B64),
-        AArch64Inst::Ret(operand) => compile_ret(basic_block, operand),
-        AArch64Inst::Tbz(operand) => compile_tbz(basic_block, operand),
-        AArch64Inst::Tbnz(operand) => compile_tbnz(basic_block, operand),
-
-        // Conditional Instructions
-        AArch64Inst::CcmpImmVar32(operand) => compile_ccmp_imm(basic_block, operand, IrType::B32),
-        AArch64Inst::CcmpImmVar64(operand) => compile_ccmp_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::CcmpRegVar64(operand) => compile_ccmp_reg(basic_block, operand, IrType::B64),
-        AArch64Inst::CcmnImmVar64(operand) => compile_ccmn_imm(basic_block, operand, IrType::B64),
-        AArch64Inst::Csel32(operand) => compile_csel(basic_block, operand, IrType::B32),
-        AArch64Inst::Csel64(operand) => compile_csel(basic_block, operand, IrType::B64),
-        AArch64Inst::Csinv64(operand) => compile_csinv(basic_block, operand, IrType::B64),
-
-        // Interrupt Instructions
-        AArch64Inst::Svc(operand) => compile_svc(basic_block, operand),
-        AArch64Inst::Brk(operand) => compile_brk(basic_block, operand),
-
-        // Speical instructions
-        AArch64Inst::Mrs(operand) => compile_mrs(basic_block, operand),
+        AArch64Inst::Ret(operand) => compile_br(basic_block, operand),
+        AArch64Inst::BCond(operand) | AArch64Inst::BcCond(operand) => {
+            compile_b_cond(basic_block, operand)
+        }
+        AArch64Inst::Cbz32(operand) => compile_cbz(basic_block, operand, IrType::B32, false),
+        AArch64Inst::Cbnz32(operand) => compile_cbz(basic_block, operand, IrType::B32, true),
+        AArch64Inst::Cbz64(operand) => compile_cbz(basic_block, operand, IrType::B64, false),
+        AArch64Inst::Cbnz64(operand) => compile_cbz(basic_block, operand, IrType::B64, true),
+        AArch64Inst::Tbz(operand) => compile_tbz(basic_block, operand, false),
+        AArch64Inst::Tbnz(operand) => compile_tbz(basic_block, operand, true),
+
+        // Conditional instructions
+        AArch64Inst::Csel32(operand) => {
+            compile_csel(basic_block, operand, IrType::B32, CondSelect::Csel)
+        }
+        AArch64Inst::Csinc32(operand) => {
+            compile_csel(basic_block, operand, IrType::B32, CondSelect::Csinc)
+        }
+        AArch64Inst::Csinv32(operand) => {
+            compile_csel(basic_block, operand, IrType::B32, CondSelect::Csinv)
+        }
+        AArch64Inst::Csneg32(operand) => {
+            compile_csel(basic_block, operand, IrType::B32, CondSelect::Csneg)
+        }
+        AArch64Inst::Csel64(operand) => {
+            compile_csel(basic_block, operand, IrType::B64, CondSelect::Csel)
+        }
+        AArch64Inst::Csinc64(operand) => {
+            compile_csel(basic_block, operand, IrType::B64, CondSelect::Csinc)
+        }
+        AArch64Inst::Csinv64(operand) => {
+            compile_csel(basic_block, operand, IrType::B64, CondSelect::Csinv)
+        }
+        AArch64Inst::Csneg64(operand) => {
+            compile_csel(basic_block, operand, IrType::B64, CondSelect::Csneg)
+        }
+        AArch64Inst::CcmpRegVar32(operand) => {
+            compile_ccmp_reg(basic_block, operand, IrType::B32, true)
+        }
+        AArch64Inst::CcmnRegVar32(operand) => {
+            compile_ccmp_reg(basic_block, operand, IrType::B32, false)
+        }
+        AArch64Inst::CcmpRegVar64(operand) => {
+            compile_ccmp_reg(basic_block, operand, IrType::B64, true)
+        }
+        AArch64Inst::CcmnRegVar64(operand) => {
+            compile_ccmp_reg(basic_block, operand, IrType::B64, false)
+        }
+        AArch64Inst::CcmpImmVar32(operand) => {
+            compile_ccmp_imm(basic_block, operand, IrType::B32, true)
+        }
+        AArch64Inst::CcmnImmVar32(operand) => {
+            compile_ccmp_imm(basic_block, operand, IrType::B32, false)
+        }
+        AArch64Inst::CcmpImmVar64(operand) => {
+            compile_ccmp_imm(basic_block, operand, IrType::B64, true)
+        }
+        AArch64Inst::CcmnImmVar64(operand) => {
+            compile_ccmp_imm(basic_block, operand, IrType::B64, false)
+        }
+
+        // Floating-point instructions
+        AArch64Inst::FmAddSinglePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F32, false, false)
+        }
+        AArch64Inst::FmSubSinglePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F32, true, false)
+        }
+        AArch64Inst::FnmAddSinglePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F32, true, true)
+        }
+        AArch64Inst::FnmSubSinglePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F32, false, true)
+        }
+        AArch64Inst::FmAddDoublePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F64, false, false)
+        }
+        AArch64Inst::FmSubDoublePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F64, true, false)
+        }
+        AArch64Inst::FnmAddDoublePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F64, true, true)
+        }
+        AArch64Inst::FnmSubDoublePrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F64, false, true)
+        }
+        AArch64Inst::FmAddHalfPrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F16, false, false)
+        }
+        AArch64Inst::FmSubHalfPrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F16, true, false)
+        }
+        AArch64Inst::FnmAddHalfPrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F16, true, true)
+        }
+        AArch64Inst::FnmSubHalfPrecision(operand) => {
+            compile_fmadd(basic_block, operand, IrType::F16, false, true)
+        }
+        AArch64Inst::FaddScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fadd { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FsubScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fsub { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmulScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fmul { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FdivScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fdiv { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmaxScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fmax { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FminScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fmin { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmaxnmScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fmaxnm { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FminnmScalarSinglePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F32, |dst, lhs, rhs| {
+                IrInst::Fminnm { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FnmulScalarSinglePrecisionVar(operand) => {
+            compile_fnmul(basic_block, operand, IrType::F32)
+        }
+        AArch64Inst::FaddScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fadd { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FsubScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fsub { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmulScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fmul { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FdivScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fdiv { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmaxScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fmax { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FminScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fmin { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmaxnmScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fmaxnm { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FminnmScalarDoublePrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F64, |dst, lhs, rhs| {
+                IrInst::Fminnm { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FnmulScalarDoublePrecisionVar(operand) => {
+            compile_fnmul(basic_block, operand, IrType::F64)
+        }
+        AArch64Inst::FaddScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fadd { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FsubScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fsub { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmulScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fmul { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FdivScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fdiv { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmaxScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fmax { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FminScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fmin { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FmaxnmScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fmaxnm { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FminnmScalarHalfPrecisionVar(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fminnm { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FnmulScalarHalfPrecisionVar(operand) => {
+            compile_fnmul(basic_block, operand, IrType::F16)
+        }
+        AArch64Inst::FmovRegSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B32, |dst, src| {
+                IrInst::Assign { dst, src }
+            })
+        }
+        AArch64Inst::FabsScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| IrInst::Fabs {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::FnegScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| IrInst::Fneg {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::FsqrtScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fsqrt { dst, src }
+            })
+        }
+        AArch64Inst::FrintnScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToEven,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintpScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardPositive,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintmScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardNegative,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintzScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardZero,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintaScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToAway,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FmovRegDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B64, |dst, src| {
+                IrInst::Assign { dst, src }
+            })
+        }
+        AArch64Inst::FabsScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| IrInst::Fabs {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::FnegScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| IrInst::Fneg {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::FsqrtScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fsqrt { dst, src }
+            })
+        }
+        AArch64Inst::FrintnScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToEven,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintpScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardPositive,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintmScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardNegative,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintzScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardZero,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintaScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToAway,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintxScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: true,
+                }
+            })
+        }
+        AArch64Inst::FrintiScalarSinglePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F32, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintxScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: true,
+                }
+            })
+        }
+        AArch64Inst::FrintiScalarDoublePrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F64, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FmovRegHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B16, |dst, src| {
+                IrInst::Assign { dst, src }
+            })
+        }
+        AArch64Inst::FabsScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| IrInst::Fabs {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::FnegScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| IrInst::Fneg {
+                dst,
+                src,
+            })
+        }
+        AArch64Inst::FsqrtScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fsqrt { dst, src }
+            })
+        }
+        AArch64Inst::FrintnScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToEven,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintpScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardPositive,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintmScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardNegative,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintzScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardZero,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintaScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToAway,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintxScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: true,
+                }
+            })
+        }
+        AArch64Inst::FrintiScalarHalfPrecisionVar(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FcvtSingleToDoublePrecisionVar(operand) => {
+            compile_fcvt_precision(basic_block, operand, IrType::F32, IrType::F64)
+        }
+        AArch64Inst::FcvtDoubleToSinglePrecisionVar(operand) => {
+            compile_fcvt_precision(basic_block, operand, IrType::F64, IrType::F32)
+        }
+        AArch64Inst::FcvtHalfToSinglePrecisionVar(operand) => {
+            compile_fcvt_precision(basic_block, operand, IrType::F16, IrType::F32)
+        }
+        AArch64Inst::FcvtHalfToDoublePrecisionVar(operand) => {
+            compile_fcvt_precision(basic_block, operand, IrType::F16, IrType::F64)
+        }
+        AArch64Inst::FcvtSingleToHalfPrecisionVar(operand) => {
+            compile_fcvt_precision(basic_block, operand, IrType::F32, IrType::F16)
+        }
+        AArch64Inst::FcvtDoubleToHalfPrecisionVar(operand) => {
+            compile_fcvt_precision(basic_block, operand, IrType::F64, IrType::F16)
+        }
+        AArch64Inst::FcvtnsScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            true,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtnuScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            false,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtasScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            true,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtauScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            false,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtpsScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            true,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtpuScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            false,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtmsScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            true,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtmuScalarSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            false,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtzsScalarIntSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            true,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtzuScalarIntSinglePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B32,
+            false,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtnsScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            true,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtnuScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            false,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtasScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            true,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtauScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            false,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtpsScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            true,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtpuScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            false,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtmsScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            true,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtmuScalarSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            false,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtzsScalarIntSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            true,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtzuScalarIntSinglePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F32,
+            IrType::B64,
+            false,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtnsScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            true,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtnuScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            false,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtasScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            true,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtauScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            false,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtpsScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            true,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtpuScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            false,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtmsScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            true,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtmuScalarDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            false,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtzsScalarIntDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            true,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtzuScalarIntDoublePrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B32,
+            false,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtnsScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            true,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtnuScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            false,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtasScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            true,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtauScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            false,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtpsScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            true,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtpuScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            false,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtmsScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            true,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtmuScalarDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            false,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtzsScalarIntDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            true,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtzuScalarIntDoublePrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F64,
+            IrType::B64,
+            false,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtnsScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            true,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtnuScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            false,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtasScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            true,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtauScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            false,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtpsScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            true,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtpuScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            false,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtmsScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            true,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtmuScalarHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            false,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtzsScalarIntHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            true,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtzuScalarIntHalfPrecisionTo32(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B32,
+            false,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtnsScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            true,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtnuScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            false,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtasScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            true,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtauScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            false,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtpsScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            true,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtpuScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            false,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtmsScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            true,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtmuScalarHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            false,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtzsScalarIntHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            true,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtzuScalarIntHalfPrecisionTo64(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B64,
+            false,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::ScvtfScalarInt32ToSinglePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B32, IrType::F32, true)
+        }
+        AArch64Inst::UcvtfScalarInt32ToSinglePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B32, IrType::F32, false)
+        }
+        AArch64Inst::ScvtfScalarInt32ToDoublePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B32, IrType::F64, true)
+        }
+        AArch64Inst::UcvtfScalarInt32ToDoublePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B32, IrType::F64, false)
+        }
+        AArch64Inst::ScvtfScalarInt64ToSinglePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B64, IrType::F32, true)
+        }
+        AArch64Inst::UcvtfScalarInt64ToSinglePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B64, IrType::F32, false)
+        }
+        AArch64Inst::ScvtfScalarInt64ToDoublePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B64, IrType::F64, true)
+        }
+        AArch64Inst::UcvtfScalarInt64ToDoublePrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B64, IrType::F64, false)
+        }
+        AArch64Inst::ScvtfScalarInt32ToHalfPrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B32, IrType::F16, true)
+        }
+        AArch64Inst::UcvtfScalarInt32ToHalfPrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B32, IrType::F16, false)
+        }
+        AArch64Inst::ScvtfScalarInt64ToHalfPrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B64, IrType::F16, true)
+        }
+        AArch64Inst::UcvtfScalarInt64ToHalfPrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B64, IrType::F16, false)
+        }
+        AArch64Inst::ScvtfScalarFixedPt32ToSinglePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B32, IrType::F32, true)
+        }
+        AArch64Inst::UcvtfScalarFixedPt32ToSinglePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B32, IrType::F32, false)
+        }
+        AArch64Inst::FcvtzsScalarFixedPtSinglePrecisionTo32(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F32, IrType::B32, true)
+        }
+        AArch64Inst::FcvtzuScalarFixedPtSinglePrecisionTo32(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F32, IrType::B32, false)
+        }
+        AArch64Inst::ScvtfScalarFixedPt32ToDoublePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B32, IrType::F64, true)
+        }
+        AArch64Inst::UcvtfScalarFixedPt32ToDoublePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B32, IrType::F64, false)
+        }
+        AArch64Inst::FcvtzsScalarFixedPtDoublePrecisionTo32(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F64, IrType::B32, true)
+        }
+        AArch64Inst::FcvtzuScalarFixedPtDoublePrecisionTo32(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F64, IrType::B32, false)
+        }
+        AArch64Inst::ScvtfScalarFixedPt64ToSinglePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B64, IrType::F32, true)
+        }
+        AArch64Inst::UcvtfScalarFixedPt64ToSinglePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B64, IrType::F32, false)
+        }
+        AArch64Inst::FcvtzsScalarFixedPtSinglePrecisionTo64(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F32, IrType::B64, true)
+        }
+        AArch64Inst::FcvtzuScalarFixedPtSinglePrecisionTo64(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F32, IrType::B64, false)
+        }
+        AArch64Inst::ScvtfScalarFixedPt64ToDoublePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B64, IrType::F64, true)
+        }
+        AArch64Inst::UcvtfScalarFixedPt64ToDoublePrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B64, IrType::F64, false)
+        }
+        AArch64Inst::FcvtzsScalarFixedPtDoublePrecisionTo64(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F64, IrType::B64, true)
+        }
+        AArch64Inst::FcvtzuScalarFixedPtDoublePrecisionTo64(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F64, IrType::B64, false)
+        }
+        AArch64Inst::ScvtfScalarFixedPt32ToHalfPrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B32, IrType::F16, true)
+        }
+        AArch64Inst::UcvtfScalarFixedPt32ToHalfPrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B32, IrType::F16, false)
+        }
+        AArch64Inst::FcvtzsScalarFixedPtHalfPrecisionTo32(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F16, IrType::B32, true)
+        }
+        AArch64Inst::FcvtzuScalarFixedPtHalfPrecisionTo32(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F16, IrType::B32, false)
+        }
+        AArch64Inst::ScvtfScalarFixedPt64ToHalfPrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B64, IrType::F16, true)
+        }
+        AArch64Inst::UcvtfScalarFixedPt64ToHalfPrecision(operand) => {
+            compile_fixed_to_fp(basic_block, operand, IrType::B64, IrType::F16, false)
+        }
+        AArch64Inst::FcvtzsScalarFixedPtHalfPrecisionTo64(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F16, IrType::B64, true)
+        }
+        AArch64Inst::FcvtzuScalarFixedPtHalfPrecisionTo64(operand) => {
+            compile_fp_to_fixed(basic_block, operand, IrType::F16, IrType::B64, false)
+        }
+        // The general-purpose FMOVs only move bits
+        AArch64Inst::FmovGeneralSinglePrecisionTo32(operand)
+        | AArch64Inst::FmovGeneral32ToSinglePrecision(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B32, |dst, src| {
+                IrInst::Assign { dst, src }
+            })
+        }
+        AArch64Inst::FmovGeneralDoublePrecisionTo64(operand)
+        | AArch64Inst::FmovGeneral64ToDoublePrecision(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B64, |dst, src| {
+                IrInst::Assign { dst, src }
+            })
+        }
+        AArch64Inst::FmovGeneralHalfPrecisionTo32(operand)
+        | AArch64Inst::FmovGeneral32ToHalfPrecision(operand)
+        | AArch64Inst::FmovGeneralHalfPrecisionTo64(operand)
+        | AArch64Inst::FmovGeneral64ToHalfPrecision(operand) => {
+            compile_bit_op(basic_block, operand, IrType::B16, |dst, src| {
+                IrInst::Assign { dst, src }
+            })
+        }
+        AArch64Inst::FmovGeneralTopHalfOf128To64(operand) => {
+            compile_fmov_from_top_half(basic_block, operand)
+        }
+        AArch64Inst::FmovGeneral64toTopHalfOf128(operand) => {
+            compile_fmov_to_top_half(basic_block, operand)
+        }
+        AArch64Inst::FmovScalarImmSinglePrecisionVar(operand) => {
+            compile_fmov_imm(basic_block, operand, IrType::F32)
+        }
+        AArch64Inst::FmovScalarImmDoublePrecisionVar(operand) => {
+            compile_fmov_imm(basic_block, operand, IrType::F64)
+        }
+        AArch64Inst::FmovScalarImmHalfPrecisionVar(operand) => {
+            compile_fmov_imm(basic_block, operand, IrType::F16)
+        }
+        AArch64Inst::Fcmp(operand) => compile_fcmp(basic_block, operand, false),
+        AArch64Inst::Fcmpe(operand) => compile_fcmp(basic_block, operand, true),
+        AArch64Inst::FccmpSinglePrecisionVar(operand) => {
+            compile_fccmp(basic_block, operand, IrType::F32, false)
+        }
+        AArch64Inst::FccmpeSinglePrecisionVar(operand) => {
+            compile_fccmp(basic_block, operand, IrType::F32, true)
+        }
+        AArch64Inst::FccmpDoublePrecisionVar(operand) => {
+            compile_fccmp(basic_block, operand, IrType::F64, false)
+        }
+        AArch64Inst::FccmpeDoublePrecisionVar(operand) => {
+            compile_fccmp(basic_block, operand, IrType::F64, true)
+        }
+        AArch64Inst::FccmpHalfPrecisionVar(operand) => {
+            compile_fccmp(basic_block, operand, IrType::F16, false)
+        }
+        AArch64Inst::FccmpeHalfPrecisionVar(operand) => {
+            compile_fccmp(basic_block, operand, IrType::F16, true)
+        }
+        // FCSEL only moves bits
+        AArch64Inst::FcselSinglePrecisionVar(operand) => {
+            compile_csel(basic_block, operand, IrType::B32, CondSelect::Csel)
+        }
+        AArch64Inst::FcselDoublePrecisionVar(operand) => {
+            compile_csel(basic_block, operand, IrType::B64, CondSelect::Csel)
+        }
+        AArch64Inst::FcselHalfPrecisionVar(operand) => {
+            compile_csel(basic_block, operand, IrType::B16, CondSelect::Csel)
+        }
+        // The half-precision scalar SIMD forms, their integers and masks are 16 bits wide
+        AArch64Inst::FabdScalarHalfPrecision(operand) => {
+            compile_fabd(basic_block, operand, IrType::F16)
+        }
+        AArch64Inst::FmulxScalarHalfPrecision(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::Fmulx { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FrecpsScalarHalfPrecision(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::FrecipStep { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FrsqrtsScalarHalfPrecision(operand) => {
+            compile_fp_binary(basic_block, operand, IrType::F16, |dst, lhs, rhs| {
+                IrInst::FrsqrtStep { dst, lhs, rhs }
+            })
+        }
+        AArch64Inst::FrecpeScalarHalfPrecision(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::FrecipEstimate { dst, src }
+            })
+        }
+        AArch64Inst::FrecpxScalarHalfPrecision(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::FrecipExponent { dst, src }
+            })
+        }
+        AArch64Inst::FrsqrteScalarHalfPrecision(operand) => {
+            compile_bit_op(basic_block, operand, IrType::F16, |dst, src| {
+                IrInst::FrsqrtEstimate { dst, src }
+            })
+        }
+        AArch64Inst::FcmeqRegScalarHalfPrecision(operand) => compile_fcm_reg(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Eq,
+            false,
+        ),
+        AArch64Inst::FcmgeRegScalarHalfPrecision(operand) => compile_fcm_reg(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Le,
+            false,
+        ),
+        AArch64Inst::FcmgtRegScalarHalfPrecision(operand) => compile_fcm_reg(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Lt,
+            false,
+        ),
+        AArch64Inst::FacgeScalarHalfPrecision(operand) => compile_fcm_reg(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Le,
+            true,
+        ),
+        AArch64Inst::FacgtScalarHalfPrecision(operand) => compile_fcm_reg(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Lt,
+            true,
+        ),
+        AArch64Inst::FcmeqZeroScalarHalfPrecision(operand) => compile_fcm_zero(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Eq,
+            false,
+        ),
+        AArch64Inst::FcmgeZeroScalarHalfPrecision(operand) => compile_fcm_zero(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Le,
+            true,
+        ),
+        AArch64Inst::FcmgtZeroScalarHalfPrecision(operand) => compile_fcm_zero(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Lt,
+            true,
+        ),
+        AArch64Inst::FcmleZeroScalarHalfPrecision(operand) => compile_fcm_zero(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Le,
+            false,
+        ),
+        AArch64Inst::FcmltZeroScalarHalfPrecision(operand) => compile_fcm_zero(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            FpComparison::Lt,
+            false,
+        ),
+        AArch64Inst::FcvtnsScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            true,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtnuScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            false,
+            FpRounding::TiesToEven,
+        ),
+        AArch64Inst::FcvtasScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            true,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtauScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            false,
+            FpRounding::TiesToAway,
+        ),
+        AArch64Inst::FcvtpsScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            true,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtpuScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            false,
+            FpRounding::TowardPositive,
+        ),
+        AArch64Inst::FcvtmsScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            true,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtmuScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            false,
+            FpRounding::TowardNegative,
+        ),
+        AArch64Inst::FcvtzsScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            true,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::FcvtzuScalarHalfPrecision(operand) => compile_fcvt_to_int(
+            basic_block,
+            operand,
+            IrType::F16,
+            IrType::B16,
+            false,
+            FpRounding::TowardZero,
+        ),
+        AArch64Inst::ScvtfScalarHalfPrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B16, IrType::F16, true)
+        }
+        AArch64Inst::UcvtfScalarHalfPrecision(operand) => {
+            compile_int_to_fp(basic_block, operand, IrType::B16, IrType::F16, false)
+        }
+
+        // Advanced SIMD integer instructions
+        AArch64Inst::Shadd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_halving_add(bb, n, m, true, false)
+            })
+        }
+        AArch64Inst::Uhadd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_halving_add(bb, n, m, false, false)
+            })
+        }
+        AArch64Inst::Srhadd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_halving_add(bb, n, m, true, true)
+            })
+        }
+        AArch64Inst::Urhadd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_halving_add(bb, n, m, false, true)
+            })
+        }
+        AArch64Inst::Shsub(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_halving_sub(bb, n, m, true)
+            })
+        }
+        AArch64Inst::Uhsub(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_halving_sub(bb, n, m, false)
+            })
+        }
+        AArch64Inst::Sqadd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_add_sub_sat(bb, n, m, true, false)
+            })
+        }
+        AArch64Inst::Uqadd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_add_sub_sat(bb, n, m, false, false)
+            })
+        }
+        AArch64Inst::Sqsub(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_add_sub_sat(bb, n, m, true, true)
+            })
+        }
+        AArch64Inst::Uqsub(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_add_sub_sat(bb, n, m, false, true)
+            })
+        }
+        AArch64Inst::CmgtReg(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_cmp(bb, n, m, Comparison::Sgt)
+            })
+        }
+        AArch64Inst::CmgeReg(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_cmp(bb, n, m, Comparison::Sge)
+            })
+        }
+        AArch64Inst::CmhiReg(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_cmp(bb, n, m, Comparison::Ugt)
+            })
+        }
+        AArch64Inst::CmhsReg(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_cmp(bb, n, m, Comparison::Uge)
+            })
+        }
+        AArch64Inst::CmeqReg(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_vec_cmp(bb, n, m, Comparison::Eq)
+            })
+        }
+        AArch64Inst::Cmtst(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                let both = gen_vec_and(bb, n, m);
+                let zero = gen_vec_const(bb, both.ty(), 0);
+                gen_vec_cmp(bb, both, zero, Comparison::Ne)
+            })
+        }
+        AArch64Inst::Sshl(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, true, false, false)
+            })
+        }
+        AArch64Inst::Ushl(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, false, false, false)
+            })
+        }
+        AArch64Inst::Srshl(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, true, true, false)
+            })
+        }
+        AArch64Inst::Urshl(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, false, true, false)
+            })
+        }
+        AArch64Inst::SqshlReg(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, true, false, true)
+            })
+        }
+        AArch64Inst::UqshlReg(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, false, false, true)
+            })
+        }
+        AArch64Inst::Sqrshl(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, true, true, true)
+            })
+        }
+        AArch64Inst::Uqrshl(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_shl_by_lanes(bb, n, m, false, true, true)
+            })
+        }
+        AArch64Inst::Smax(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_vec_max_min(bb, n, m, true, true)
+            })
+        }
+        AArch64Inst::Smin(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_vec_max_min(bb, n, m, true, false)
+            })
+        }
+        AArch64Inst::Umax(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_vec_max_min(bb, n, m, false, true)
+            })
+        }
+        AArch64Inst::Umin(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_vec_max_min(bb, n, m, false, false)
+            })
+        }
+        AArch64Inst::Sabd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_abs_diff(bb, n, m, true)
+            })
+        }
+        AArch64Inst::Uabd(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_abs_diff(bb, n, m, false)
+            })
+        }
+        AArch64Inst::Saba(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                let diff = gen_abs_diff(bb, n, m, true);
+                gen_vec_accumulate(bb, operand.rd, diff, false)
+            })
+        }
+        AArch64Inst::Uaba(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                let diff = gen_abs_diff(bb, n, m, false);
+                gen_vec_accumulate(bb, operand.rd, diff, false)
+            })
+        }
+        AArch64Inst::AddVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, gen_vec_add)
+        }
+        AArch64Inst::SubVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, gen_vec_sub)
+        }
+        AArch64Inst::MulVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, gen_vec_mul)
+        }
+        AArch64Inst::MlaVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                let product = gen_vec_mul(bb, n, m);
+                gen_vec_accumulate(bb, operand.rd, product, false)
+            })
+        }
+        AArch64Inst::MlsVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                let product = gen_vec_mul(bb, n, m);
+                gen_vec_accumulate(bb, operand.rd, product, true)
+            })
+        }
+        AArch64Inst::Pmul(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=0, |bb, n, m| {
+                gen_polynomial_mul(bb, n, m, 8)
+            })
+        }
+        AArch64Inst::SqdmulhVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 1..=2, |bb, n, m| {
+                gen_sqdmulh(bb, n, m, false)
+            })
+        }
+        AArch64Inst::SqrdmulhVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 1..=2, |bb, n, m| {
+                gen_sqdmulh(bb, n, m, true)
+            })
+        }
+        AArch64Inst::AddpVec(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=3, |bb, n, m| {
+                gen_pairwise(bb, n, m, gen_vec_add)
+            })
+        }
+        AArch64Inst::Smaxp(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_pairwise(bb, n, m, |bb, a, b| gen_vec_max_min(bb, a, b, true, true))
+            })
+        }
+        AArch64Inst::Sminp(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_pairwise(bb, n, m, |bb, a, b| gen_vec_max_min(bb, a, b, true, false))
+            })
+        }
+        AArch64Inst::Umaxp(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_pairwise(bb, n, m, |bb, a, b| gen_vec_max_min(bb, a, b, false, true))
+            })
+        }
+        AArch64Inst::Uminp(operand) => {
+            compile_vec_three_same(basic_block, operand, 0..=2, |bb, n, m| {
+                gen_pairwise(bb, n, m, |bb, a, b| gen_vec_max_min(bb, a, b, false, false))
+            })
+        }
+        AArch64Inst::AndVec(operand) => {
+            compile_vec_bitwise(basic_block, operand, |bb, _, n, m| gen_vec_and(bb, n, m))
+        }
+        AArch64Inst::BicVecReg(operand) => {
+            compile_vec_bitwise(basic_block, operand, |bb, _, n, m| {
+                let m = gen_vec_not(bb, m);
+                gen_vec_and(bb, n, m)
+            })
+        }
+        AArch64Inst::OrrVecReg(operand) => {
+            compile_vec_bitwise(basic_block, operand, |bb, _, n, m| gen_vec_or(bb, n, m))
+        }
+        AArch64Inst::OrnVec(operand) => compile_vec_bitwise(basic_block, operand, |bb, _, n, m| {
+            let m = gen_vec_not(bb, m);
+            gen_vec_or(bb, n, m)
+        }),
+        AArch64Inst::EorVec(operand) => {
+            compile_vec_bitwise(basic_block, operand, |bb, _, n, m| gen_vec_xor(bb, n, m))
+        }
+        // BSL selects with the bits of rd, BIT inserts the bits of rn where rm is set, and BIF
+        // where it is clear
+        AArch64Inst::Bsl(operand) => compile_vec_bitwise(basic_block, operand, |bb, d, n, m| {
+            let diff = gen_vec_xor(bb, n, m);
+            let diff = gen_vec_and(bb, diff, d);
+            gen_vec_xor(bb, diff, m)
+        }),
+        AArch64Inst::Bit(operand) => compile_vec_bitwise(basic_block, operand, |bb, d, n, m| {
+            let diff = gen_vec_xor(bb, n, d);
+            let diff = gen_vec_and(bb, diff, m);
+            gen_vec_xor(bb, diff, d)
+        }),
+        AArch64Inst::Bif(operand) => compile_vec_bitwise(basic_block, operand, |bb, d, n, m| {
+            let diff = gen_vec_xor(bb, n, d);
+            let m = gen_vec_not(bb, m);
+            let diff = gen_vec_and(bb, diff, m);
+            gen_vec_xor(bb, diff, d)
+        }),
+        AArch64Inst::Saddl(operand) | AArch64Inst::Saddw(operand) => compile_vec_long(
+            basic_block,
+            operand,
+            0..=2,
+            true,
+            matches!(inst, AArch64Inst::Saddw(_)),
+            gen_vec_add,
+        ),
+        AArch64Inst::Uaddl(operand) | AArch64Inst::Uaddw(operand) => compile_vec_long(
+            basic_block,
+            operand,
+            0..=2,
+            false,
+            matches!(inst, AArch64Inst::Uaddw(_)),
+            gen_vec_add,
+        ),
+        AArch64Inst::Ssubl(operand) | AArch64Inst::Ssubw(operand) => compile_vec_long(
+            basic_block,
+            operand,
+            0..=2,
+            true,
+            matches!(inst, AArch64Inst::Ssubw(_)),
+            gen_vec_sub,
+        ),
+        AArch64Inst::Usubl(operand) | AArch64Inst::Usubw(operand) => compile_vec_long(
+            basic_block,
+            operand,
+            0..=2,
+            false,
+            matches!(inst, AArch64Inst::Usubw(_)),
+            gen_vec_sub,
+        ),
+        AArch64Inst::Addhn(operand) => compile_vec_add_sub_high(basic_block, operand, false, false),
+        AArch64Inst::Raddhn(operand) => compile_vec_add_sub_high(basic_block, operand, false, true),
+        AArch64Inst::Subhn(operand) => compile_vec_add_sub_high(basic_block, operand, true, false),
+        AArch64Inst::Rsubhn(operand) => compile_vec_add_sub_high(basic_block, operand, true, true),
+        AArch64Inst::Sabdl(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, true, false, |bb, n, m| {
+                gen_abs_diff(bb, n, m, true)
+            })
+        }
+        AArch64Inst::Uabdl(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, false, false, |bb, n, m| {
+                gen_abs_diff(bb, n, m, false)
+            })
+        }
+        AArch64Inst::Sabal(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, true, false, |bb, n, m| {
+                let diff = gen_abs_diff(bb, n, m, true);
+                gen_vec_accumulate(bb, operand.rd, diff, false)
+            })
+        }
+        AArch64Inst::Uabal(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, false, false, |bb, n, m| {
+                let diff = gen_abs_diff(bb, n, m, false);
+                gen_vec_accumulate(bb, operand.rd, diff, false)
+            })
+        }
+        AArch64Inst::SmullVec(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, true, false, gen_vec_mul)
+        }
+        AArch64Inst::UmullVec(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, false, false, gen_vec_mul)
+        }
+        AArch64Inst::SmlalVec(operand) | AArch64Inst::SmlslVec(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, true, false, |bb, n, m| {
+                let product = gen_vec_mul(bb, n, m);
+                gen_vec_accumulate(
+                    bb,
+                    operand.rd,
+                    product,
+                    matches!(inst, AArch64Inst::SmlslVec(_)),
+                )
+            })
+        }
+        AArch64Inst::UmlalVec(operand) | AArch64Inst::UmlslVec(operand) => {
+            compile_vec_long(basic_block, operand, 0..=2, false, false, |bb, n, m| {
+                let product = gen_vec_mul(bb, n, m);
+                gen_vec_accumulate(
+                    bb,
+                    operand.rd,
+                    product,
+                    matches!(inst, AArch64Inst::UmlslVec(_)),
+                )
+            })
+        }
+        AArch64Inst::SqdmullVec(operand) => {
+            compile_vec_long(basic_block, operand, 1..=2, true, false, gen_sqdmull)
+        }
+        AArch64Inst::SqdmlalVec(operand) | AArch64Inst::SqdmlslVec(operand) => {
+            compile_vec_long(basic_block, operand, 1..=2, true, false, |bb, n, m| {
+                gen_sqdmlal(
+                    bb,
+                    operand.rd,
+                    n,
+                    m,
+                    matches!(inst, AArch64Inst::SqdmlslVec(_)),
+                )
+            })
+        }
+        AArch64Inst::MulByElem(operand) => {
+            compile_vec_by_elem(basic_block, operand.into(), gen_vec_mul)
+        }
+        AArch64Inst::MlaByElem(operand) | AArch64Inst::MlsByElem(operand) => {
+            compile_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                let product = gen_vec_mul(bb, n, m);
+                gen_vec_accumulate(
+                    bb,
+                    operand.rd,
+                    product,
+                    matches!(inst, AArch64Inst::MlsByElem(_)),
+                )
+            })
+        }
+        AArch64Inst::SqdmulhByElem(operand) | AArch64Inst::SqrdmulhByElem(operand) => {
+            compile_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_sqdmulh(bb, n, m, matches!(inst, AArch64Inst::SqrdmulhByElem(_)))
+            })
+        }
+        AArch64Inst::SqdmulhByElemScalar(operand) | AArch64Inst::SqrdmulhByElemScalar(operand) => {
+            compile_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_sqdmulh(
+                    bb,
+                    n,
+                    m,
+                    matches!(inst, AArch64Inst::SqrdmulhByElemScalar(_)),
+                )
+            })
+        }
+        AArch64Inst::SmullByElem(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), true, gen_vec_mul)
+        }
+        AArch64Inst::UmullByElem(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), false, gen_vec_mul)
+        }
+        AArch64Inst::SmlalByElem(operand) | AArch64Inst::SmlslByElem(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), true, |bb, n, m| {
+                let product = gen_vec_mul(bb, n, m);
+                gen_vec_accumulate(
+                    bb,
+                    operand.rd,
+                    product,
+                    matches!(inst, AArch64Inst::SmlslByElem(_)),
+                )
+            })
+        }
+        AArch64Inst::UmlalByElem(operand) | AArch64Inst::UmlslByElem(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), false, |bb, n, m| {
+                let product = gen_vec_mul(bb, n, m);
+                gen_vec_accumulate(
+                    bb,
+                    operand.rd,
+                    product,
+                    matches!(inst, AArch64Inst::UmlslByElem(_)),
+                )
+            })
+        }
+        AArch64Inst::SqdmullByElem(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), true, gen_sqdmull)
+        }
+        AArch64Inst::SqdmullByElemScalar(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), true, gen_sqdmull)
+        }
+        AArch64Inst::SqdmlalByElem(operand) | AArch64Inst::SqdmlslByElem(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), true, |bb, n, m| {
+                gen_sqdmlal(
+                    bb,
+                    operand.rd,
+                    n,
+                    m,
+                    matches!(inst, AArch64Inst::SqdmlslByElem(_)),
+                )
+            })
+        }
+        AArch64Inst::SqdmlalByElemScalar(operand) | AArch64Inst::SqdmlslByElemScalar(operand) => {
+            compile_vec_long_by_elem(basic_block, operand.into(), true, |bb, n, m| {
+                let sub = matches!(inst, AArch64Inst::SqdmlslByElemScalar(_));
+                gen_sqdmlal(bb, operand.rd, n, m, sub)
+            })
+        }
+        AArch64Inst::Pmull(operand) => compile_pmull(basic_block, operand),
+        AArch64Inst::Sshr(operand) => compile_vec_shr_imm(basic_block, operand, true, false, false),
+        AArch64Inst::Ushr(operand) => {
+            compile_vec_shr_imm(basic_block, operand, false, false, false)
+        }
+        AArch64Inst::Ssra(operand) => compile_vec_shr_imm(basic_block, operand, true, false, true),
+        AArch64Inst::Usra(operand) => compile_vec_shr_imm(basic_block, operand, false, false, true),
+        AArch64Inst::Srshr(operand) => compile_vec_shr_imm(basic_block, operand, true, true, false),
+        AArch64Inst::Urshr(operand) => {
+            compile_vec_shr_imm(basic_block, operand, false, true, false)
+        }
+        AArch64Inst::Srsra(operand) => compile_vec_shr_imm(basic_block, operand, true, true, true),
+        AArch64Inst::Ursra(operand) => compile_vec_shr_imm(basic_block, operand, false, true, true),
+        AArch64Inst::Shl(operand) => compile_vec_shl_imm(basic_block, operand),
+        AArch64Inst::SqshlImm(operand) => compile_vec_qshl_imm(basic_block, operand, true, true),
+        AArch64Inst::UqshlImm(operand) => compile_vec_qshl_imm(basic_block, operand, false, false),
+        AArch64Inst::Sqshlu(operand) => compile_vec_qshl_imm(basic_block, operand, true, false),
+        AArch64Inst::Sri(operand) => compile_vec_shift_insert(basic_block, operand, false),
+        AArch64Inst::Sli(operand) => compile_vec_shift_insert(basic_block, operand, true),
+        AArch64Inst::Shrn(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, false, false, None)
+        }
+        AArch64Inst::Rshrn(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, false, true, None)
+        }
+        AArch64Inst::Sqshrn(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, true, false, Some(true))
+        }
+        AArch64Inst::Sqrshrn(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, true, true, Some(true))
+        }
+        AArch64Inst::Sqshrun(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, true, false, Some(false))
+        }
+        AArch64Inst::Sqrshrun(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, true, true, Some(false))
+        }
+        AArch64Inst::Uqshrn(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, false, false, Some(false))
+        }
+        AArch64Inst::Uqrshrn(operand) => {
+            compile_vec_shr_narrow(basic_block, operand, false, true, Some(false))
+        }
+        AArch64Inst::Sshll(operand) => compile_vec_shll_imm(basic_block, operand, true),
+        AArch64Inst::Ushll(operand) => compile_vec_shll_imm(basic_block, operand, false),
+        AArch64Inst::Rev64(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=2, |bb, n| gen_vec_rev(bb, n, 64))
+        }
+        AArch64Inst::Rev32Vec(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=1, |bb, n| gen_vec_rev(bb, n, 32))
+        }
+        AArch64Inst::Rev16Vec(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=0, |bb, n| gen_vec_rev(bb, n, 16))
+        }
+        AArch64Inst::Saddlp(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=2, |bb, n| {
+                gen_add_pairs_long(bb, n, true)
+            })
+        }
+        AArch64Inst::Uaddlp(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=2, |bb, n| {
+                gen_add_pairs_long(bb, n, false)
+            })
+        }
+        AArch64Inst::Sadalp(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=2, |bb, n| {
+                let sums = gen_add_pairs_long(bb, n, true);
+                gen_vec_accumulate(bb, operand.rd, sums, false)
+            })
+        }
+        AArch64Inst::Uadalp(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=2, |bb, n| {
+                let sums = gen_add_pairs_long(bb, n, false);
+                gen_vec_accumulate(bb, operand.rd, sums, false)
+            })
+        }
+        AArch64Inst::ClsVec(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=2, |bb, src| {
+                gen_op(bb, src.ty(), |dst| IrInst::Cls { dst, src })
+            })
+        }
+        AArch64Inst::ClzVec(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=2, |bb, src| {
+                gen_op(bb, src.ty(), |dst| IrInst::Clz { dst, src })
+            })
+        }
+        AArch64Inst::Cnt(operand) => compile_vec_two_reg(basic_block, operand, 0..=0, |bb, src| {
+            gen_op(bb, src.ty(), |dst| IrInst::Popcnt { dst, src })
+        }),
+        AArch64Inst::Abs(operand) | AArch64Inst::Sqabs(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=3, |bb, n| {
+                let sign = gen_vec_shr(bb, n, const_shift(lane_bits(n.ty()) - 1), true);
+                let flipped = gen_vec_xor(bb, n, sign);
+                match inst {
+                    AArch64Inst::Sqabs(_) => gen_vec_add_sub_sat(bb, flipped, sign, true, true),
+                    _ => gen_vec_sub(bb, flipped, sign),
+                }
+            })
+        }
+        AArch64Inst::NegVec(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=3, |bb, n| {
+                let zero = gen_vec_const(bb, n.ty(), 0);
+                gen_vec_sub(bb, zero, n)
+            })
+        }
+        AArch64Inst::Sqneg(operand) => compile_vec_two_reg(basic_block, operand, 0..=3, |bb, n| {
+            let zero = gen_vec_const(bb, n.ty(), 0);
+            gen_vec_add_sub_sat(bb, zero, n, true, true)
+        }),
+        AArch64Inst::Suqadd(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=3, |bb, n| {
+                gen_mixed_sign_accumulate(bb, operand.rd, n, true)
+            })
+        }
+        AArch64Inst::Usqadd(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=3, |bb, n| {
+                gen_mixed_sign_accumulate(bb, operand.rd, n, false)
+            })
+        }
+        AArch64Inst::Urecpe(operand) => {
+            compile_vec_two_reg(basic_block, operand, 2..=2, |bb, src| {
+                gen_op(bb, src.ty(), |dst| IrInst::UrecipEstimate { dst, src })
+            })
+        }
+        AArch64Inst::Ursqrte(operand) => {
+            compile_vec_two_reg(basic_block, operand, 2..=2, |bb, src| {
+                gen_op(bb, src.ty(), |dst| IrInst::UrsqrtEstimate { dst, src })
+            })
+        }
+        AArch64Inst::CmgtZero(operand)
+        | AArch64Inst::CmgeZero(operand)
+        | AArch64Inst::CmeqZero(operand)
+        | AArch64Inst::CmleZero(operand)
+        | AArch64Inst::CmltZero(operand) => {
+            compile_vec_two_reg(basic_block, operand, 0..=3, |bb, n| {
+                let cond = match inst {
+                    AArch64Inst::CmgtZero(_) => Comparison::Sgt,
+                    AArch64Inst::CmgeZero(_) => Comparison::Sge,
+                    AArch64Inst::CmeqZero(_) => Comparison::Eq,
+                    AArch64Inst::CmleZero(_) => Comparison::Sle,
+                    _ => Comparison::Slt,
+                };
+                let zero = gen_vec_const(bb, n.ty(), 0);
+                gen_vec_cmp(bb, n, zero, cond)
+            })
+        }
+        AArch64Inst::XtnXtn2(operand) => compile_vec_narrow(basic_block, operand, None),
+        AArch64Inst::Sqxtn(operand) => compile_vec_narrow(basic_block, operand, Some((true, true))),
+        AArch64Inst::Sqxtun(operand) => {
+            compile_vec_narrow(basic_block, operand, Some((true, false)))
+        }
+        AArch64Inst::Uqxtn(operand) => {
+            compile_vec_narrow(basic_block, operand, Some((false, false)))
+        }
+        AArch64Inst::Shll(operand) => compile_shll(basic_block, operand),
+        AArch64Inst::Not(operand) => compile_vec_bitwise_unary(basic_block, operand, gen_vec_not),
+        AArch64Inst::RbitVec(operand) => {
+            compile_vec_bitwise_unary(basic_block, operand, |bb, src| {
+                gen_op(bb, src.ty(), |dst| IrInst::Bitrev { dst, src })
+            })
+        }
+        AArch64Inst::Addv(operand) => compile_vec_reduce(basic_block, operand, None, gen_vec_add),
+        AArch64Inst::Saddlv(operand) => {
+            compile_vec_reduce(basic_block, operand, Some(true), gen_vec_add)
+        }
+        AArch64Inst::Uaddlv(operand) => {
+            compile_vec_reduce(basic_block, operand, Some(false), gen_vec_add)
+        }
+        AArch64Inst::Smaxv(operand) => {
+            compile_vec_reduce(basic_block, operand, None, |bb, a, b| {
+                gen_vec_max_min(bb, a, b, true, true)
+            })
+        }
+        AArch64Inst::Sminv(operand) => {
+            compile_vec_reduce(basic_block, operand, None, |bb, a, b| {
+                gen_vec_max_min(bb, a, b, true, false)
+            })
+        }
+        AArch64Inst::Umaxv(operand) => {
+            compile_vec_reduce(basic_block, operand, None, |bb, a, b| {
+                gen_vec_max_min(bb, a, b, false, true)
+            })
+        }
+        AArch64Inst::Uminv(operand) => {
+            compile_vec_reduce(basic_block, operand, None, |bb, a, b| {
+                gen_vec_max_min(bb, a, b, false, false)
+            })
+        }
+        AArch64Inst::AddpScalar(operand) => compile_addp_scalar(basic_block, operand),
+        AArch64Inst::MoviShiftedImmVar32(operand)
+        | AArch64Inst::MoviShiftedImmVar16(operand)
+        | AArch64Inst::MoviShiftingOnesVar32(operand)
+        | AArch64Inst::MoviVar8(operand)
+        | AArch64Inst::MoviScalarVar64(operand)
+        | AArch64Inst::MoviVectorVar64(operand) => compile_movi(basic_block, operand, false),
+        AArch64Inst::MvniShiftedImmVar32(operand)
+        | AArch64Inst::MvniShiftedImmVar16(operand)
+        | AArch64Inst::MvniShiftingOnesVar32(operand) => compile_movi(basic_block, operand, true),
+        AArch64Inst::OrrVecImmVar32(operand) | AArch64Inst::OrrVecImmVar16(operand) => {
+            compile_vec_orr_bic_imm(basic_block, operand, false)
+        }
+        AArch64Inst::BicVecImmVar32(operand) | AArch64Inst::BicVecImmVar16(operand) => {
+            compile_vec_orr_bic_imm(basic_block, operand, true)
+        }
+        AArch64Inst::DupGeneral(operand) => compile_dup_general(basic_block, operand),
+        AArch64Inst::DupElement(operand) => compile_dup_element(basic_block, operand),
+        AArch64Inst::Smov(operand) => compile_mov_to_general(basic_block, operand, true),
+        AArch64Inst::Umov(operand) => compile_mov_to_general(basic_block, operand, false),
+        AArch64Inst::InsGeneral(operand) => compile_ins_general(basic_block, operand),
+        AArch64Inst::InsElement(operand) => compile_ins_element(basic_block, operand),
+        AArch64Inst::Zip1(operand) => {
+            compile_vec_permute(basic_block, operand, |lanes, i| (i % 2) * lanes + i / 2)
+        }
+        AArch64Inst::Zip2(operand) => compile_vec_permute(basic_block, operand, |lanes, i| {
+            (i % 2) * lanes + lanes / 2 + i / 2
+        }),
+        AArch64Inst::Uzp1(operand) => compile_vec_permute(basic_block, operand, |_, i| i * 2),
+        AArch64Inst::Uzp2(operand) => compile_vec_permute(basic_block, operand, |_, i| i * 2 + 1),
+        AArch64Inst::Trn1(operand) => {
+            compile_vec_permute(basic_block, operand, |lanes, i| (i % 2) * lanes + (i & !1))
+        }
+        AArch64Inst::Trn2(operand) => {
+            compile_vec_permute(basic_block, operand, |lanes, i| (i % 2) * lanes + (i | 1))
+        }
+        AArch64Inst::Ext(operand) => compile_ext(basic_block, operand),
+        AArch64Inst::Tbl(operand) => compile_table_lookup(basic_block, operand, false),
+        AArch64Inst::Tbx(operand) => compile_table_lookup(basic_block, operand, true),
+
+        // Advanced SIMD floating-point instructions
+        AArch64Inst::FaddVec(operand) | AArch64Inst::FaddVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fadd { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FsubVec(operand) | AArch64Inst::FsubVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fsub { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmulVec(operand) | AArch64Inst::FmulVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmul { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FdivVec(operand) | AArch64Inst::FdivVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fdiv { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmaxVec(operand) | AArch64Inst::FmaxVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmax { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FminVec(operand) | AArch64Inst::FminVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmin { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmaxnmVec(operand) | AArch64Inst::FmaxnmVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmaxnm { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FminnmVec(operand) | AArch64Inst::FminnmVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fminnm { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::Frecps(operand) | AArch64Inst::FrecpsVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::FrecipStep { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::Frsqrts(operand) | AArch64Inst::FrsqrtsVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::FrsqrtStep { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::Fmulx(operand) | AArch64Inst::FmulxVecHalfPrecision(operand) => {
+            compile_fp_vec_binary(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmulx { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmlaVec(operand) | AArch64Inst::FmlaVecHalfPrecision(operand) => {
+            compile_fp_vec_three_same(basic_block, operand, half_precision(inst), |bb, n, m| {
+                gen_fp_vec_fma(bb, operand.rd, n, m, false)
+            })
+        }
+        AArch64Inst::FmlsVec(operand) | AArch64Inst::FmlsVecHalfPrecision(operand) => {
+            compile_fp_vec_three_same(basic_block, operand, half_precision(inst), |bb, n, m| {
+                gen_fp_vec_fma(bb, operand.rd, n, m, true)
+            })
+        }
+        AArch64Inst::Fabd(operand) | AArch64Inst::FabdVecHalfPrecision(operand) => {
+            compile_fp_vec_three_same(basic_block, operand, half_precision(inst), |bb, n, m| {
+                let difference = gen_op(bb, n.ty(), |dst| IrInst::Fsub {
+                    dst,
+                    lhs: n,
+                    rhs: m,
+                });
+                gen_fabs(bb, difference)
+            })
+        }
+        AArch64Inst::FcmeqReg(operand) | AArch64Inst::FcmeqRegVecHalfPrecision(operand) => {
+            compile_fp_vec_compare(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Eq,
+                false,
+            )
+        }
+        AArch64Inst::FcmgeReg(operand) | AArch64Inst::FcmgeRegVecHalfPrecision(operand) => {
+            compile_fp_vec_compare(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Le,
+                false,
+            )
+        }
+        AArch64Inst::FcmgtReg(operand) | AArch64Inst::FcmgtRegVecHalfPrecision(operand) => {
+            compile_fp_vec_compare(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Lt,
+                false,
+            )
+        }
+        AArch64Inst::Facge(operand) | AArch64Inst::FacgeVecHalfPrecision(operand) => {
+            compile_fp_vec_compare(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Le,
+                true,
+            )
+        }
+        AArch64Inst::Facgt(operand) | AArch64Inst::FacgtVecHalfPrecision(operand) => {
+            compile_fp_vec_compare(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Lt,
+                true,
+            )
+        }
+        AArch64Inst::FaddpVec(operand) | AArch64Inst::FaddpVecHalfPrecision(operand) => {
+            compile_fp_vec_pairwise(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fadd { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmaxpVec(operand) | AArch64Inst::FmaxpVecHalfPrecision(operand) => {
+            compile_fp_vec_pairwise(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmax { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FminpVec(operand) | AArch64Inst::FminpVecHalfPrecision(operand) => {
+            compile_fp_vec_pairwise(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmin { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmaxnmpVec(operand) | AArch64Inst::FmaxnmpVecHalfPrecision(operand) => {
+            compile_fp_vec_pairwise(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmaxnm { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FminnmpVec(operand) | AArch64Inst::FminnmpVecHalfPrecision(operand) => {
+            compile_fp_vec_pairwise(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fminnm { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmlaByElemEncoding(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_fp_vec_fma(bb, operand.rd, n, m, false)
+            })
+        }
+        AArch64Inst::FmlaByElemScalar(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_fp_vec_fma(bb, operand.rd, n, m, false)
+            })
+        }
+        AArch64Inst::FmlsByElemEncoding(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_fp_vec_fma(bb, operand.rd, n, m, true)
+            })
+        }
+        AArch64Inst::FmlsByElemScalar(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_fp_vec_fma(bb, operand.rd, n, m, true)
+            })
+        }
+        AArch64Inst::FmulByElemEncoding(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_op(bb, n.ty(), |dst| IrInst::Fmul {
+                    dst,
+                    lhs: n,
+                    rhs: m,
+                })
+            })
+        }
+        AArch64Inst::FmulByElemScalar(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_op(bb, n.ty(), |dst| IrInst::Fmul {
+                    dst,
+                    lhs: n,
+                    rhs: m,
+                })
+            })
+        }
+        AArch64Inst::FmulxByElemEncoding(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_op(bb, n.ty(), |dst| IrInst::Fmulx {
+                    dst,
+                    lhs: n,
+                    rhs: m,
+                })
+            })
+        }
+        AArch64Inst::FmulxByElemScalar(operand) => {
+            compile_fp_vec_by_elem(basic_block, operand.into(), |bb, n, m| {
+                gen_op(bb, n.ty(), |dst| IrInst::Fmulx {
+                    dst,
+                    lhs: n,
+                    rhs: m,
+                })
+            })
+        }
+        AArch64Inst::FabsVec(operand) | AArch64Inst::FabsVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fabs { dst, src }
+            })
+        }
+        AArch64Inst::FnegVec(operand) | AArch64Inst::FnegVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fneg { dst, src }
+            })
+        }
+        AArch64Inst::FsqrtVec(operand) | AArch64Inst::FsqrtVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fsqrt { dst, src }
+            })
+        }
+        AArch64Inst::Frecpe(operand) | AArch64Inst::FrecpeVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::FrecipEstimate { dst, src }
+            })
+        }
+        AArch64Inst::Frsqrte(operand) | AArch64Inst::FrsqrteVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::FrsqrtEstimate { dst, src }
+            })
+        }
+        AArch64Inst::FrintnVec(operand) | AArch64Inst::FrintnVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToEven,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintpVec(operand) | AArch64Inst::FrintpVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardPositive,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintmVec(operand) | AArch64Inst::FrintmVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardNegative,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintzVec(operand) | AArch64Inst::FrintzVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TowardZero,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintaVec(operand) | AArch64Inst::FrintaVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::TiesToAway,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FrintxVec(operand) | AArch64Inst::FrintxVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: true,
+                }
+            })
+        }
+        AArch64Inst::FrintiVec(operand) | AArch64Inst::FrintiVecHalfPrecision(operand) => {
+            compile_fp_vec_unary(basic_block, operand, half_precision(inst), |dst, src| {
+                IrInst::Fround {
+                    dst,
+                    src,
+                    rounding: FpRounding::Current,
+                    signal_inexact: false,
+                }
+            })
+        }
+        AArch64Inst::FcvtnsVec(operand) | AArch64Inst::FcvtnsVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                true,
+                FpRounding::TiesToEven,
+            )
+        }
+        AArch64Inst::FcvtnuVec(operand) | AArch64Inst::FcvtnuVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                false,
+                FpRounding::TiesToEven,
+            )
+        }
+        AArch64Inst::FcvtasVec(operand) | AArch64Inst::FcvtasVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                true,
+                FpRounding::TiesToAway,
+            )
+        }
+        AArch64Inst::FcvtauVec(operand) | AArch64Inst::FcvtauVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                false,
+                FpRounding::TiesToAway,
+            )
+        }
+        AArch64Inst::FcvtpsVec(operand) | AArch64Inst::FcvtpsVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                true,
+                FpRounding::TowardPositive,
+            )
+        }
+        AArch64Inst::FcvtpuVec(operand) | AArch64Inst::FcvtpuVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                false,
+                FpRounding::TowardPositive,
+            )
+        }
+        AArch64Inst::FcvtmsVec(operand) | AArch64Inst::FcvtmsVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                true,
+                FpRounding::TowardNegative,
+            )
+        }
+        AArch64Inst::FcvtmuVec(operand) | AArch64Inst::FcvtmuVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                false,
+                FpRounding::TowardNegative,
+            )
+        }
+        AArch64Inst::FcvtzsVecInt(operand) | AArch64Inst::FcvtzsVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                true,
+                FpRounding::TowardZero,
+            )
+        }
+        AArch64Inst::FcvtzuVecInt(operand) | AArch64Inst::FcvtzuVecHalfPrecision(operand) => {
+            compile_fp_vec_to_int(
+                basic_block,
+                operand,
+                half_precision(inst),
+                false,
+                FpRounding::TowardZero,
+            )
+        }
+        AArch64Inst::ScvtfVecInt(operand) | AArch64Inst::ScvtfVecHalfPrecision(operand) => {
+            compile_int_to_fp_vec(basic_block, operand, half_precision(inst), true)
+        }
+        AArch64Inst::UcvtfVecInt(operand) | AArch64Inst::UcvtfVecHalfPrecision(operand) => {
+            compile_int_to_fp_vec(basic_block, operand, half_precision(inst), false)
+        }
+        AArch64Inst::FcvtzsVecFixedPt(operand) => {
+            compile_fp_to_fixed_vec(basic_block, operand, true)
+        }
+        AArch64Inst::FcvtzuVecFixedPt(operand) => {
+            compile_fp_to_fixed_vec(basic_block, operand, false)
+        }
+        AArch64Inst::ScvtfVecFixedPt(operand) => {
+            compile_fixed_to_fp_vec(basic_block, operand, true)
+        }
+        AArch64Inst::UcvtfVecFixedPt(operand) => {
+            compile_fixed_to_fp_vec(basic_block, operand, false)
+        }
+        AArch64Inst::Fcvtl(operand) => compile_fcvtl(basic_block, operand),
+        AArch64Inst::Fcvtn(operand) => compile_fcvtn(basic_block, operand, FpRounding::Current),
+        // Only converts double to single precision, rounding to odd so that a second
+        // conversion to half precision rounds correctly
+        AArch64Inst::Fcvtxn(operand) if operand.size & 1 == 0 => Err(reserved_encoding()),
+        AArch64Inst::Fcvtxn(operand) => compile_fcvtn(basic_block, operand, FpRounding::ToOdd),
+        AArch64Inst::FcmeqZero(operand) | AArch64Inst::FcmeqZeroVecHalfPrecision(operand) => {
+            compile_fp_vec_compare_zero(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Eq,
+                false,
+            )
+        }
+        AArch64Inst::FcmgeZero(operand) | AArch64Inst::FcmgeZeroVecHalfPrecision(operand) => {
+            compile_fp_vec_compare_zero(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Le,
+                true,
+            )
+        }
+        AArch64Inst::FcmgtZero(operand) | AArch64Inst::FcmgtZeroVecHalfPrecision(operand) => {
+            compile_fp_vec_compare_zero(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Lt,
+                true,
+            )
+        }
+        AArch64Inst::FcmleZero(operand) | AArch64Inst::FcmleZeroVecHalfPrecision(operand) => {
+            compile_fp_vec_compare_zero(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Le,
+                false,
+            )
+        }
+        AArch64Inst::FcmltZero(operand) | AArch64Inst::FcmltZeroVecHalfPrecision(operand) => {
+            compile_fp_vec_compare_zero(
+                basic_block,
+                operand,
+                half_precision(inst),
+                FpComparison::Lt,
+                false,
+            )
+        }
+        AArch64Inst::FmaxvEncoding(operand) | AArch64Inst::FmaxvHalfPrecision(operand) => {
+            compile_fp_vec_reduce(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmax { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FminvEncoding(operand) | AArch64Inst::FminvHalfPrecision(operand) => {
+            compile_fp_vec_reduce(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmin { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FmaxnmvEncoding(operand) | AArch64Inst::FmaxnmvHalfPrecision(operand) => {
+            compile_fp_vec_reduce(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fmaxnm { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FminnmvEncoding(operand) | AArch64Inst::FminnmvHalfPrecision(operand) => {
+            compile_fp_vec_reduce(
+                basic_block,
+                operand,
+                half_precision(inst),
+                |dst, lhs, rhs| IrInst::Fminnm { dst, lhs, rhs },
+            )
+        }
+        AArch64Inst::FaddpScalarEncoding(operand)
+        | AArch64Inst::FaddpScalarHalfPrecision(operand) => compile_fp_pairwise_scalar(
+            basic_block,
+            operand,
+            half_precision(inst),
+            |dst, lhs, rhs| IrInst::Fadd { dst, lhs, rhs },
+        ),
+        AArch64Inst::FmaxpScalarEncoding(operand)
+        | AArch64Inst::FmaxpScalarHalfPrecision(operand) => compile_fp_pairwise_scalar(
+            basic_block,
+            operand,
+            half_precision(inst),
+            |dst, lhs, rhs| IrInst::Fmax { dst, lhs, rhs },
+        ),
+        AArch64Inst::FminpScalarEncoding(operand)
+        | AArch64Inst::FminpScalarHalfPrecision(operand) => compile_fp_pairwise_scalar(
+            basic_block,
+            operand,
+            half_precision(inst),
+            |dst, lhs, rhs| IrInst::Fmin { dst, lhs, rhs },
+        ),
+        AArch64Inst::FmaxnmpScalarEncoding(operand)
+        | AArch64Inst::FmaxnmpScalarHalfPrecision(operand) => compile_fp_pairwise_scalar(
+            basic_block,
+            operand,
+            half_precision(inst),
+            |dst, lhs, rhs| IrInst::Fmaxnm { dst, lhs, rhs },
+        ),
+        AArch64Inst::FminnmpScalarEncoding(operand)
+        | AArch64Inst::FminnmpScalarHalfPrecision(operand) => compile_fp_pairwise_scalar(
+            basic_block,
+            operand,
+            half_precision(inst),
+            |dst, lhs, rhs| IrInst::Fminnm { dst, lhs, rhs },
+        ),
+        AArch64Inst::FmovVecImmSinglePrecisionVar(operand) => {
+            compile_fmov_vec_imm(basic_block, operand, VecTy::F32)
+        }
+        AArch64Inst::FmovVecImmDoublePrecisionVar(operand) => {
+            compile_fmov_vec_imm(basic_block, operand, VecTy::F64)
+        }
+
+        // Interrupt Instructions
+        AArch64Inst::Udf(operand) => compile_udf(basic_block, operand),
+        AArch64Inst::Svc(operand) => compile_svc(basic_block, operand),
+
+        // Speical instructions
+        AArch64Inst::Sys(operand) => compile_sys(basic_block, operand),
+        // No SYSL operation is available to userland
+        AArch64Inst::Sysl(_) => Err(reserved_encoding()),
+        AArch64Inst::Mrs(operand) => compile_mrs(basic_block, operand),
         AArch64Inst::MsrReg(operand) => compile_msr_reg(basic_block, operand),
-        AArch64Inst::MsrImm(operand) => compile_msr_imm(basic_block, operand),
-        AArch64Inst::Nop | AArch64Inst::Wfi | AArch64Inst::Dmb(_) | AArch64Inst::Isb(_) => {
-            todo!()
+
+        // Barriers
+        AArch64Inst::Clrex(_) => {
+            basic_block.push_inst(IrInst::ClearLink);
+            Ok(())
+        }
+        AArch64Inst::Dmb(_) | AArch64Inst::DsbEncoding(_) => {
+            basic_block.push_inst(IrInst::Fence(Reordering::SeqCst));
+            Ok(())
+        }
+        // Translated code is invalidated as soon as it is written, there is no pipeline to flush
+        AArch64Inst::Isb(_) => Ok(()),
+
+        // Hints. Pointer authentication and BTI are not implemented, so their hints execute as
+        // NOP like every unallocated one, and with a single thread per core there is nothing to
+        // wait for or wake up.
+        AArch64Inst::Hint
+        | AArch64Inst::Nop
+        | AArch64Inst::Yield
+        | AArch64Inst::Wfe
+        | AArch64Inst::Wfi
+        | AArch64Inst::Sev
+        | AArch64Inst::Sevl
+        | AArch64Inst::Xpaclri
+        | AArch64Inst::Pacia1716Var
+        | AArch64Inst::Pacib1716Var
+        | AArch64Inst::Autia1716Var
+        | AArch64Inst::Autib1716Var
+        | AArch64Inst::PaciazVar
+        | AArch64Inst::PaciaspVar
+        | AArch64Inst::PacibzVar
+        | AArch64Inst::PacibspVar
+        | AArch64Inst::AutiazVar
+        | AArch64Inst::AutiaspVar
+        | AArch64Inst::AutibzVar
+        | AArch64Inst::AutibspVar => Ok(()),
+        // The atomic memory operations are looked up in their own table
+        _ => match atomic_memory_op(inst) {
+            Some((operand, size, op, ordering)) => {
+                compile_atomic_memory_op(basic_block, operand, size, op, ordering)
+            }
+            None => Err(Error::UnimplementedInstruction(format!("{inst:?}"))),
+        },
+    };
+
+    // Everything that does not branch falls through to the next instruction
+    if result.is_ok() && basic_block.terminator() == BasicBlockTerminator::None {
+        compiler_prelude::gen_move_pc(basic_block);
+    }
+
+    result
+}
+
+fn compile_movz(bb: &mut BasicBlock, operand: &HwImm16Rd) -> Result<(), Error> {
+    let rd = operand.rd.raw();
+
+    let pos = operand.hw << 4;
+    bb.push_inst(IrInst::Assign {
+        dst: IrValue::Register(IrType::B64, rd),
+        src: IrValue::Constant(IrConstant::B64((operand.imm16 as u64) << pos)),
+    });
+
+    Ok(())
+}
+
+fn compile_movn(bb: &mut BasicBlock, operand: &HwImm16Rd, ty: IrType) -> Result<(), Error> {
+    let rd = operand.rd.raw();
+
+    bb.push_inst(IrInst::ZextCast {
+        dst: IrValue::Register(IrType::B64, rd),
+        src: IrValue::Constant(IrConstant::new(ty, {
+            let pos = operand.hw << 4;
+            !((operand.imm16 as u64) << pos) & ones::<u64>(ty.size_of() as u32 * 8)
+        })),
+    });
+
+    Ok(())
+}
+
+fn compile_movk(bb: &mut BasicBlock, operand: &HwImm16Rd, ty: IrType) -> Result<(), Error> {
+    let rd = operand.rd.raw();
+
+    let pos = operand.hw << 4;
+    let mask = !(0xffff << pos) & ones::<u64>(ty.size_of() as u32 * 8);
+
+    // t1 = rd & !(0xffff << pos)
+    // t2 = t1 | (imm16 << pos)
+    // rd = zext(t2)
+    let t1 = bb.new_variable(ty);
+    let t2 = bb.new_variable(ty);
+    bb.push_inst(IrInst::And {
+        dst: t1,
+        lhs: IrValue::Register(ty, rd),
+        rhs: IrValue::Constant(IrConstant::new(ty, mask)),
+    });
+    bb.push_inst(IrInst::Or {
+        dst: t2,
+        lhs: t1,
+        rhs: IrValue::Constant(IrConstant::new(ty, (operand.imm16 as u64) << pos)),
+    });
+    bb.push_inst(IrInst::ZextCast {
+        dst: IrValue::Register(IrType::B64, rd),
+        src: t2,
+    });
+
+    Ok(())
+}
+
+fn compile_adr(bb: &mut BasicBlock, operand: &PcRelAddressing) -> Result<(), Error> {
+    let rd = operand.rd.raw();
+    let imm = sign_extend((operand.immhi as i64) << 2 | (operand.immlo as i64), 21);
+    let off = gen_pc_rel(bb, IrValue::Constant(IrConstant::B64(imm as u64)));
+
+    bb.push_inst(IrInst::Assign {
+        dst: IrValue::Register(IrType::B64, rd),
+        src: off,
+    });
+
+    Ok(())
+}
+
+fn compile_adrp(bb: &mut BasicBlock, operand: &PcRelAddressing) -> Result<(), Error> {
+    let rd = operand.rd.raw();
+    let pc = AArch64Architecture::get_pc_register().raw();
+
+    let imm = sign_extend(
+        ((operand.immhi as i64) << 2 | (operand.immlo as i64)) << 12,
+        33,
+    );
+
+    let t1 = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::And {
+        dst: t1,
+        lhs: IrValue::Register(IrType::B64, pc),
+        rhs: IrValue::Constant(IrConstant::new(IrType::B64, 0xFFFF_FFFF_FFFF_F000u64)),
+    });
+    bb.push_inst(IrInst::Add {
+        dst: IrValue::Register(IrType::B64, rd),
+        lhs: t1,
+        rhs: IrValue::Constant(IrConstant::B64(imm as u64)),
+    });
+
+    Ok(())
+}
+
+/// How a load widens the value it read into the 64 bit destination register.
+#[derive(Clone, Copy)]
+enum LoadExtend {
+    Zero,
+    /// Sign extend to 32 bits, then zero extend like every write to a W register
+    Sign32,
+    Sign64,
+}
+
+/// Where a load or store accesses memory relative to its base register.
+#[derive(Clone, Copy)]
+enum Indexing {
+    /// `[rn, #offset]`
+    Offset(i64),
+    /// `[rn, #offset]!`, the address is written back to `rn`
+    PreIndex(i64),
+    /// `[rn], #offset`, `rn` is accessed and then advanced by the offset
+    PostIndex(i64),
+    /// `[rn], rm`, `rn` is accessed and then advanced by the register `rm`
+    PostIndexReg(AArch64Register),
+}
+
+impl Indexing {
+    fn from_imm(operand: &OpcSizeImm12RnRt) -> Self {
+        let is_simd_fp = matches!(operand.rt, AArch64Register::V(_));
+        match decode_operand_for_ld_st_reg_imm(operand, is_simd_fp) {
+            (false, _, offset) => Indexing::Offset(offset),
+            (true, false, offset) => Indexing::PreIndex(offset),
+            (true, true, offset) => Indexing::PostIndex(offset),
+        }
+    }
+
+    fn from_pair(operand: &LoadStoreRegPair, size: IrType) -> Self {
+        let offset = sign_extend(operand.imm7 as i64, 7) * size.size_of() as i64;
+        match operand.o {
+            0b001 => Indexing::PostIndex(offset),
+            0b011 => Indexing::PreIndex(offset),
+            _ => Indexing::Offset(offset),
+        }
+    }
+
+    fn unscaled(imm9: u16) -> Self {
+        Indexing::Offset(sign_extend(imm9 as i64, 9))
+    }
+}
+
+fn gen_address(bb: &mut BasicBlock, rn: AArch64Register, indexing: Indexing) -> IrValue {
+    let offset = match indexing {
+        Indexing::Offset(offset) | Indexing::PreIndex(offset) => offset,
+        Indexing::PostIndex(_) | Indexing::PostIndexReg(_) => 0,
+    };
+
+    // The address is copied, loads may overwrite the base register before the writeback
+    let address = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Add {
+        dst: address,
+        lhs: read_reg(rn, IrType::B64),
+        rhs: IrValue::Constant(IrConstant::B64(offset as u64)),
+    });
+
+    address
+}
+
+fn gen_writeback(bb: &mut BasicBlock, rn: AArch64Register, address: IrValue, indexing: Indexing) {
+    match indexing {
+        Indexing::Offset(_) => {}
+        Indexing::PreIndex(_) => gen_write_reg(bb, rn, address),
+        Indexing::PostIndex(offset) => bb.push_inst(IrInst::Add {
+            dst: IrValue::Register(IrType::B64, rn.raw()),
+            lhs: address,
+            rhs: IrValue::Constant(IrConstant::B64(offset as u64)),
+        }),
+        Indexing::PostIndexReg(rm) => bb.push_inst(IrInst::Add {
+            dst: IrValue::Register(IrType::B64, rn.raw()),
+            lhs: address,
+            rhs: read_reg(rm, IrType::B64),
+        }),
+    }
+}
+
+fn gen_load(
+    bb: &mut BasicBlock,
+    address: IrValue,
+    rt: AArch64Register,
+    size: IrType,
+    extend: LoadExtend,
+) {
+    let data = bb.new_variable(size);
+    bb.push_inst(IrInst::Load {
+        dst: data,
+        src: address,
+    });
+
+    let ty = match extend {
+        LoadExtend::Zero => size,
+        LoadExtend::Sign32 => IrType::B32,
+        LoadExtend::Sign64 => IrType::B64,
+    };
+
+    let value = if ty == size {
+        data
+    } else {
+        let extended = bb.new_variable(ty);
+        bb.push_inst(IrInst::SextCast {
+            dst: extended,
+            src: data,
+        });
+        extended
+    };
+
+    gen_write_reg(bb, rt, value);
+}
+
+fn gen_store(bb: &mut BasicBlock, address: IrValue, rt: AArch64Register, size: IrType) {
+    bb.push_inst(IrInst::Store {
+        dst: address,
+        src: read_reg(rt, size),
+    });
+}
+
+/// `address + size`, the address of the second register of a pair
+fn gen_pair_address(bb: &mut BasicBlock, address: IrValue, size: IrType) -> IrValue {
+    let address2 = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Add {
+        dst: address2,
+        lhs: address,
+        rhs: IrValue::Constant(IrConstant::B64(size.size_of() as u64)),
+    });
+
+    address2
+}
+
+fn compile_ldr_imm(
+    bb: &mut BasicBlock,
+    operand: &OpcSizeImm12RnRt,
+    size: IrType,
+    extend: LoadExtend,
+) -> Result<(), Error> {
+    let indexing = Indexing::from_imm(operand);
+
+    let address = gen_address(bb, operand.rn, indexing);
+    gen_load(bb, address, operand.rt, size, extend);
+    gen_writeback(bb, operand.rn, address, indexing);
+
+    Ok(())
+}
+
+fn compile_str_imm(
+    bb: &mut BasicBlock,
+    operand: &OpcSizeImm12RnRt,
+    size: IrType,
+) -> Result<(), Error> {
+    let indexing = Indexing::from_imm(operand);
+
+    let address = gen_address(bb, operand.rn, indexing);
+    gen_store(bb, address, operand.rt, size);
+    gen_writeback(bb, operand.rn, address, indexing);
+
+    Ok(())
+}
+
+/// `rn + extend(rm) << amount`, the address of the register offset forms. The shift amount
+/// is the log2 of the access `size`.
+fn gen_reg_offset_address(
+    bb: &mut BasicBlock,
+    operand: &LoadStoreRegRegOffset,
+    size: IrType,
+) -> IrValue {
+    let offset = bb.new_variable(IrType::B64);
+    bb.push_inst(match operand.option {
+        // UXTW
+        0b010 => IrInst::ZextCast {
+            dst: offset,
+            src: read_reg(operand.rm, IrType::B32),
+        },
+        // SXTW
+        0b110 => IrInst::SextCast {
+            dst: offset,
+            src: read_reg(operand.rm, IrType::B32),
+        },
+        // LSL and SXTX, the other extends are rejected by the decoder
+        _ => IrInst::Assign {
+            dst: offset,
+            src: read_reg(operand.rm, IrType::B64),
+        },
+    });
+
+    if operand.s == 0b1 {
+        bb.push_inst(IrInst::Shl {
+            dst: offset,
+            lhs: offset,
+            rhs: IrValue::Constant(IrConstant::B8(size.size_of().trailing_zeros() as u8)),
+        });
+    }
+
+    let address = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Add {
+        dst: address,
+        lhs: read_reg(operand.rn, IrType::B64),
+        rhs: offset,
+    });
+
+    address
+}
+
+/// Access size of the SIMD&FP register offset forms, 128 bits are encoded in `opc`.
+fn simd_fp_reg_size(operand: &LoadStoreRegRegOffset) -> IrType {
+    match (operand.size, operand.opc >> 1) {
+        (0b00, 0b1) => IrType::B128,
+        (0b00, _) => IrType::B8,
+        (0b01, _) => IrType::B16,
+        (0b10, _) => IrType::B32,
+        _ => IrType::B64,
+    }
+}
+
+fn compile_ldr_reg(
+    bb: &mut BasicBlock,
+    operand: &LoadStoreRegRegOffset,
+    size: IrType,
+    extend: LoadExtend,
+) -> Result<(), Error> {
+    let address = gen_reg_offset_address(bb, operand, size);
+    gen_load(bb, address, operand.rt, size, extend);
+
+    Ok(())
+}
+
+fn compile_str_reg(
+    bb: &mut BasicBlock,
+    operand: &LoadStoreRegRegOffset,
+    size: IrType,
+) -> Result<(), Error> {
+    let address = gen_reg_offset_address(bb, operand, size);
+    gen_store(bb, address, operand.rt, size);
+
+    Ok(())
+}
+
+fn compile_ldur(
+    bb: &mut BasicBlock,
+    operand: &LdStRegUnscaledImm,
+    size: IrType,
+    extend: LoadExtend,
+) -> Result<(), Error> {
+    let address = gen_address(bb, operand.rn, Indexing::unscaled(operand.imm9));
+    gen_load(bb, address, operand.rt, size, extend);
+
+    Ok(())
+}
+
+fn compile_stur(
+    bb: &mut BasicBlock,
+    operand: &LdStRegUnscaledImm,
+    size: IrType,
+) -> Result<(), Error> {
+    let address = gen_address(bb, operand.rn, Indexing::unscaled(operand.imm9));
+    gen_store(bb, address, operand.rt, size);
+
+    Ok(())
+}
+
+fn compile_ldtr(
+    bb: &mut BasicBlock,
+    operand: &Imm9RnRt,
+    size: IrType,
+    extend: LoadExtend,
+) -> Result<(), Error> {
+    let address = gen_address(bb, operand.rn, Indexing::unscaled(operand.imm9));
+    gen_load(bb, address, operand.rt, size, extend);
+
+    Ok(())
+}
+
+fn compile_sttr(bb: &mut BasicBlock, operand: &Imm9RnRt, size: IrType) -> Result<(), Error> {
+    let address = gen_address(bb, operand.rn, Indexing::unscaled(operand.imm9));
+    gen_store(bb, address, operand.rt, size);
+
+    Ok(())
+}
+
+fn compile_ldr_lit(
+    bb: &mut BasicBlock,
+    operand: &Imm19Rt,
+    size: IrType,
+    extend: LoadExtend,
+) -> Result<(), Error> {
+    let offset = sign_extend((operand.imm19 as i64) << 2, 21);
+    let address = gen_pc_rel(bb, IrValue::Constant(IrConstant::B64(offset as u64)));
+    gen_load(bb, address, operand.rt, size, extend);
+
+    Ok(())
+}
+
+/// The second register of a pair, it is of the same kind as the first one.
+fn pair_rt2(operand: &LoadStoreRegPair) -> AArch64Register {
+    let hint = match operand.rt {
+        AArch64Register::V(_) => AArch64MnemonicHint::V,
+        _ => AArch64MnemonicHint::X,
+    };
+
+    AArch64Architecture::get_register_by_mnemonic(hint, operand.rt2)
+}
+
+fn compile_ldp(
+    bb: &mut BasicBlock,
+    operand: &LoadStoreRegPair,
+    size: IrType,
+    extend: LoadExtend,
+) -> Result<(), Error> {
+    let indexing = Indexing::from_pair(operand, size);
+    let rt2 = pair_rt2(operand);
+
+    let address = gen_address(bb, operand.rn, indexing);
+    let address2 = gen_pair_address(bb, address, size);
+    gen_load(bb, address, operand.rt, size, extend);
+    gen_load(bb, address2, rt2, size, extend);
+    gen_writeback(bb, operand.rn, address, indexing);
+
+    Ok(())
+}
+
+fn compile_stp(bb: &mut BasicBlock, operand: &LoadStoreRegPair, size: IrType) -> Result<(), Error> {
+    let indexing = Indexing::from_pair(operand, size);
+    let rt2 = pair_rt2(operand);
+
+    let address = gen_address(bb, operand.rn, indexing);
+    let address2 = gen_pair_address(bb, address, size);
+    gen_store(bb, address, operand.rt, size);
+    gen_store(bb, address2, rt2, size);
+    gen_writeback(bb, operand.rn, address, indexing);
+
+    Ok(())
+}
+
+fn compile_ldnp(
+    bb: &mut BasicBlock,
+    operand: &LdStNoAllocPairOffset,
+    size: IrType,
+) -> Result<(), Error> {
+    let offset = sign_extend(operand.imm7 as i64, 7) * size.size_of() as i64;
+
+    let address = gen_address(bb, operand.rn, Indexing::Offset(offset));
+    let address2 = gen_pair_address(bb, address, size);
+    gen_load(bb, address, operand.rt, size, LoadExtend::Zero);
+    gen_load(bb, address2, operand.rt2, size, LoadExtend::Zero);
+
+    Ok(())
+}
+
+fn compile_stnp(
+    bb: &mut BasicBlock,
+    operand: &LdStNoAllocPairOffset,
+    size: IrType,
+) -> Result<(), Error> {
+    let offset = sign_extend(operand.imm7 as i64, 7) * size.size_of() as i64;
+
+    let address = gen_address(bb, operand.rn, Indexing::Offset(offset));
+    let address2 = gen_pair_address(bb, address, size);
+    gen_store(bb, address, operand.rt, size);
+    gen_store(bb, address2, operand.rt2, size);
+
+    Ok(())
+}
+
+/// Load like `gen_load` and order it before every later access.
+fn gen_load_acquire(
+    bb: &mut BasicBlock,
+    address: IrValue,
+    rt: AArch64Register,
+    size: IrType,
+    extend: LoadExtend,
+) {
+    gen_load(bb, address, rt, size, extend);
+    bb.push_inst(IrInst::Fence(Reordering::Acquire));
+}
+
+/// Store like `gen_store` after every earlier access. The store is also ordered before later
+/// acquire loads, as release stores and acquire loads are sequentially consistent.
+fn gen_store_release(bb: &mut BasicBlock, address: IrValue, rt: AArch64Register, size: IrType) {
+    bb.push_inst(IrInst::Fence(Reordering::Release));
+    gen_store(bb, address, rt, size);
+    bb.push_inst(IrInst::Fence(Reordering::SeqCst));
+}
+
+/// Write the status of a store exclusive to `rs`, 0 if it stored and 1 if it did not.
+fn gen_exclusive_status(bb: &mut BasicBlock, rs: AArch64Register, stored: IrValue) {
+    let status = gen_select(
+        bb,
+        stored,
+        IrValue::Constant(IrConstant::B32(0)),
+        IrValue::Constant(IrConstant::B32(1)),
+    );
+    gen_write_reg(bb, rs, status);
+}
+
+fn compile_ldxr(
+    bb: &mut BasicBlock,
+    operand: &RsRt2RnRt,
+    size: IrType,
+    acquire: bool,
+) -> Result<(), Error> {
+    let data = bb.new_variable(size);
+    bb.push_inst(IrInst::LoadLinked {
+        dst: data,
+        src: read_reg(operand.rn, IrType::B64),
+    });
+    if acquire {
+        bb.push_inst(IrInst::Fence(Reordering::Acquire));
+    }
+    gen_write_reg(bb, operand.rt, data);
+
+    Ok(())
+}
+
+fn compile_stxr(
+    bb: &mut BasicBlock,
+    operand: &RsRt2RnRt,
+    size: IrType,
+    release: bool,
+) -> Result<(), Error> {
+    if release {
+        bb.push_inst(IrInst::Fence(Reordering::Release));
+    }
+    let stored = bb.new_variable(IrType::Bool);
+    bb.push_inst(IrInst::StoreConditional {
+        dst: read_reg(operand.rn, IrType::B64),
+        src: read_reg(operand.rt, size),
+        stored,
+    });
+    if release {
+        bb.push_inst(IrInst::Fence(Reordering::SeqCst));
+    }
+    gen_exclusive_status(bb, operand.rs, stored);
+
+    Ok(())
+}
+
+/// Combine `low` and `high` into a single value twice their size, with `low` in the lower half.
+fn gen_pair(bb: &mut BasicBlock, low: IrValue, high: IrValue) -> IrValue {
+    let (pair_ty, bits) = match low.ty() {
+        IrType::B32 => (IrType::B64, 32),
+        _ => (IrType::B128, 64),
+    };
+
+    let pair = bb.new_variable(pair_ty);
+    bb.push_inst(IrInst::ZextCast {
+        dst: pair,
+        src: low,
+    });
+    let shifted = bb.new_variable(pair_ty);
+    bb.push_inst(IrInst::ZextCast {
+        dst: shifted,
+        src: high,
+    });
+    bb.push_inst(IrInst::Shl {
+        dst: shifted,
+        lhs: shifted,
+        rhs: IrValue::Constant(IrConstant::B8(bits)),
+    });
+    bb.push_inst(IrInst::Or {
+        dst: pair,
+        lhs: pair,
+        rhs: shifted,
+    });
+
+    pair
+}
+
+/// Split a value made by `gen_pair` into its lower and upper half.
+fn gen_split_pair(bb: &mut BasicBlock, pair: IrValue) -> (IrValue, IrValue) {
+    let (half_ty, bits) = match pair.ty() {
+        IrType::B64 => (IrType::B32, 32),
+        _ => (IrType::B64, 64),
+    };
+
+    let low = bb.new_variable(half_ty);
+    bb.push_inst(IrInst::ZextCast {
+        dst: low,
+        src: pair,
+    });
+    let shifted = bb.new_variable(pair.ty());
+    bb.push_inst(IrInst::Lshr {
+        dst: shifted,
+        lhs: pair,
+        rhs: IrValue::Constant(IrConstant::B8(bits)),
+    });
+    let high = bb.new_variable(half_ty);
+    bb.push_inst(IrInst::ZextCast {
+        dst: high,
+        src: shifted,
+    });
+
+    (low, high)
+}
+
+/// Exclusive pairs are accessed as a single value twice the size of a register, with `rt` in
+/// the lower half.
+fn compile_ldxp(
+    bb: &mut BasicBlock,
+    operand: &LoadStoreExclusivePair,
+    size: IrType,
+    acquire: bool,
+) -> Result<(), Error> {
+    let pair_ty = match size {
+        IrType::B32 => IrType::B64,
+        _ => IrType::B128,
+    };
+
+    let data = bb.new_variable(pair_ty);
+    bb.push_inst(IrInst::LoadLinked {
+        dst: data,
+        src: read_reg(operand.rn, IrType::B64),
+    });
+    if acquire {
+        bb.push_inst(IrInst::Fence(Reordering::Acquire));
+    }
+
+    let (low, high) = gen_split_pair(bb, data);
+    gen_write_reg(bb, operand.rt, low);
+    gen_write_reg(bb, operand.rt2, high);
+
+    Ok(())
+}
+
+fn compile_stxp(
+    bb: &mut BasicBlock,
+    operand: &LoadStoreExclusivePair,
+    size: IrType,
+    release: bool,
+) -> Result<(), Error> {
+    let data = gen_pair(bb, read_reg(operand.rt, size), read_reg(operand.rt2, size));
+
+    if release {
+        bb.push_inst(IrInst::Fence(Reordering::Release));
+    }
+    let stored = bb.new_variable(IrType::Bool);
+    bb.push_inst(IrInst::StoreConditional {
+        dst: read_reg(operand.rn, IrType::B64),
+        src: data,
+        stored,
+    });
+    if release {
+        bb.push_inst(IrInst::Fence(Reordering::SeqCst));
+    }
+    gen_exclusive_status(bb, operand.rs, stored);
+
+    Ok(())
+}
+
+fn compile_ldar(bb: &mut BasicBlock, operand: &RsRt2RnRt, size: IrType) -> Result<(), Error> {
+    let address = read_reg(operand.rn, IrType::B64);
+    gen_load_acquire(bb, address, operand.rt, size, LoadExtend::Zero);
+
+    Ok(())
+}
+
+fn compile_stlr(bb: &mut BasicBlock, operand: &RsRt2RnRt, size: IrType) -> Result<(), Error> {
+    let address = read_reg(operand.rn, IrType::B64);
+    gen_store_release(bb, address, operand.rt, size);
+
+    Ok(())
+}
+
+fn compile_ldapr(bb: &mut BasicBlock, operand: &RsRnRt, size: IrType) -> Result<(), Error> {
+    let address = read_reg(operand.rn, IrType::B64);
+    gen_load_acquire(bb, address, operand.rt, size, LoadExtend::Zero);
+
+    Ok(())
+}
+
+fn compile_ldapur(
+    bb: &mut BasicBlock,
+    operand: &Imm9RnRt,
+    size: IrType,
+    extend: LoadExtend,
+) -> Result<(), Error> {
+    let address = gen_address(bb, operand.rn, Indexing::unscaled(operand.imm9));
+    gen_load_acquire(bb, address, operand.rt, size, extend);
+
+    Ok(())
+}
+
+fn compile_stlur(bb: &mut BasicBlock, operand: &Imm9RnRt, size: IrType) -> Result<(), Error> {
+    let address = gen_address(bb, operand.rn, Indexing::unscaled(operand.imm9));
+    gen_store_release(bb, address, operand.rt, size);
+
+    Ok(())
+}
+
+/// Ordering of an atomic access with acquire and/or release semantics.
+fn atomic_ordering(acquire: bool, release: bool) -> Reordering {
+    match (acquire, release) {
+        (false, false) => Reordering::Relaxed,
+        (true, false) => Reordering::Acquire,
+        (false, true) => Reordering::Release,
+        (true, true) => Reordering::SeqCst,
+    }
+}
+
+/// Operand, size, operation and ordering of the atomic memory operations of FEAT_LSE.
+fn atomic_memory_op(inst: &AArch64Inst) -> Option<(&RsRnRt, IrType, AtomicOp, Reordering)> {
+    use AtomicOp::{Add, AndNot, Or, Smax, Smin, Swap, Umax, Umin, Xor};
+    use IrType::{B16, B32, B64, B8};
+    use Reordering::{Acquire, Relaxed, Release, SeqCst};
+
+    Some(match inst {
+        AArch64Inst::LdaddbVar(operand) => (operand, B8, Add, Relaxed),
+        AArch64Inst::LdclrbVar(operand) => (operand, B8, AndNot, Relaxed),
+        AArch64Inst::LdeorbVar(operand) => (operand, B8, Xor, Relaxed),
+        AArch64Inst::LdsetbVar(operand) => (operand, B8, Or, Relaxed),
+        AArch64Inst::LdsmaxbVar(operand) => (operand, B8, Smax, Relaxed),
+        AArch64Inst::LdsminbVar(operand) => (operand, B8, Smin, Relaxed),
+        AArch64Inst::LdumaxbVar(operand) => (operand, B8, Umax, Relaxed),
+        AArch64Inst::LduminbVar(operand) => (operand, B8, Umin, Relaxed),
+        AArch64Inst::SwpbVar(operand) => (operand, B8, Swap, Relaxed),
+
+        AArch64Inst::LdaddabVar(operand) => (operand, B8, Add, Acquire),
+        AArch64Inst::LdclrabVar(operand) => (operand, B8, AndNot, Acquire),
+        AArch64Inst::LdeorabVar(operand) => (operand, B8, Xor, Acquire),
+        AArch64Inst::LdsetabVar(operand) => (operand, B8, Or, Acquire),
+        AArch64Inst::LdsmaxabVar(operand) => (operand, B8, Smax, Acquire),
+        AArch64Inst::LdsminabVar(operand) => (operand, B8, Smin, Acquire),
+        AArch64Inst::LdumaxabVar(operand) => (operand, B8, Umax, Acquire),
+        AArch64Inst::LduminabVar(operand) => (operand, B8, Umin, Acquire),
+        AArch64Inst::SwpabVar(operand) => (operand, B8, Swap, Acquire),
+
+        AArch64Inst::LdaddlbVar(operand) => (operand, B8, Add, Release),
+        AArch64Inst::LdclrlbVar(operand) => (operand, B8, AndNot, Release),
+        AArch64Inst::LdeorlbVar(operand) => (operand, B8, Xor, Release),
+        AArch64Inst::LdsetlbVar(operand) => (operand, B8, Or, Release),
+        AArch64Inst::LdsmaxlbVar(operand) => (operand, B8, Smax, Release),
+        AArch64Inst::LdsminlbVar(operand) => (operand, B8, Smin, Release),
+        AArch64Inst::LdumaxlbVar(operand) => (operand, B8, Umax, Release),
+        AArch64Inst::LduminlbVar(operand) => (operand, B8, Umin, Release),
+        AArch64Inst::SwplbVar(operand) => (operand, B8, Swap, Release),
+
+        AArch64Inst::LdaddalbVar(operand) => (operand, B8, Add, SeqCst),
+        AArch64Inst::LdclralbVar(operand) => (operand, B8, AndNot, SeqCst),
+        AArch64Inst::LdeoralbVar(operand) => (operand, B8, Xor, SeqCst),
+        AArch64Inst::LdsetalbVar(operand) => (operand, B8, Or, SeqCst),
+        AArch64Inst::LdsmaxalbVar(operand) => (operand, B8, Smax, SeqCst),
+        AArch64Inst::LdsminalbVar(operand) => (operand, B8, Smin, SeqCst),
+        AArch64Inst::LdumaxalbVar(operand) => (operand, B8, Umax, SeqCst),
+        AArch64Inst::LduminalbVar(operand) => (operand, B8, Umin, SeqCst),
+        AArch64Inst::SwpalbVar(operand) => (operand, B8, Swap, SeqCst),
+
+        AArch64Inst::LdaddhVar(operand) => (operand, B16, Add, Relaxed),
+        AArch64Inst::LdclrhVar(operand) => (operand, B16, AndNot, Relaxed),
+        AArch64Inst::LdeorhVar(operand) => (operand, B16, Xor, Relaxed),
+        AArch64Inst::LdsethVar(operand) => (operand, B16, Or, Relaxed),
+        AArch64Inst::LdsmaxhVar(operand) => (operand, B16, Smax, Relaxed),
+        AArch64Inst::LdsminhVar(operand) => (operand, B16, Smin, Relaxed),
+        AArch64Inst::LdumaxhVar(operand) => (operand, B16, Umax, Relaxed),
+        AArch64Inst::LduminhVar(operand) => (operand, B16, Umin, Relaxed),
+        AArch64Inst::SwphVar(operand) => (operand, B16, Swap, Relaxed),
+
+        AArch64Inst::LdaddahVar(operand) => (operand, B16, Add, Acquire),
+        AArch64Inst::LdclrahVar(operand) => (operand, B16, AndNot, Acquire),
+        AArch64Inst::LdeorahVar(operand) => (operand, B16, Xor, Acquire),
+        AArch64Inst::LdsetahVar(operand) => (operand, B16, Or, Acquire),
+        AArch64Inst::LdsmaxahVar(operand) => (operand, B16, Smax, Acquire),
+        AArch64Inst::LdsminahVar(operand) => (operand, B16, Smin, Acquire),
+        AArch64Inst::LdumaxahVar(operand) => (operand, B16, Umax, Acquire),
+        AArch64Inst::LduminahVar(operand) => (operand, B16, Umin, Acquire),
+        AArch64Inst::SwpahVar(operand) => (operand, B16, Swap, Acquire),
+
+        AArch64Inst::LdaddlhVar(operand) => (operand, B16, Add, Release),
+        AArch64Inst::LdclrlhVar(operand) => (operand, B16, AndNot, Release),
+        AArch64Inst::LdeorlhVar(operand) => (operand, B16, Xor, Release),
+        AArch64Inst::LdsetlhVar(operand) => (operand, B16, Or, Release),
+        AArch64Inst::LdsmaxlhVar(operand) => (operand, B16, Smax, Release),
+        AArch64Inst::LdsminlhVar(operand) => (operand, B16, Smin, Release),
+        AArch64Inst::LdumaxlhVar(operand) => (operand, B16, Umax, Release),
+        AArch64Inst::LduminlhVar(operand) => (operand, B16, Umin, Release),
+        AArch64Inst::SwplhVar(operand) => (operand, B16, Swap, Release),
+
+        AArch64Inst::LdaddalhVar(operand) => (operand, B16, Add, SeqCst),
+        AArch64Inst::LdclralhVar(operand) => (operand, B16, AndNot, SeqCst),
+        AArch64Inst::LdeoralhVar(operand) => (operand, B16, Xor, SeqCst),
+        AArch64Inst::LdsetalhVar(operand) => (operand, B16, Or, SeqCst),
+        AArch64Inst::LdsmaxalhVar(operand) => (operand, B16, Smax, SeqCst),
+        AArch64Inst::LdsminalhVar(operand) => (operand, B16, Smin, SeqCst),
+        AArch64Inst::LdumaxalhVar(operand) => (operand, B16, Umax, SeqCst),
+        AArch64Inst::LduminalhVar(operand) => (operand, B16, Umin, SeqCst),
+        AArch64Inst::SwpalhVar(operand) => (operand, B16, Swap, SeqCst),
+
+        AArch64Inst::LdaddVar32(operand) => (operand, B32, Add, Relaxed),
+        AArch64Inst::LdclrVar32(operand) => (operand, B32, AndNot, Relaxed),
+        AArch64Inst::LdeorVar32(operand) => (operand, B32, Xor, Relaxed),
+        AArch64Inst::LdsetVar32(operand) => (operand, B32, Or, Relaxed),
+        AArch64Inst::LdsmaxVar32(operand) => (operand, B32, Smax, Relaxed),
+        AArch64Inst::LdsminVar32(operand) => (operand, B32, Smin, Relaxed),
+        AArch64Inst::LdumaxVar32(operand) => (operand, B32, Umax, Relaxed),
+        AArch64Inst::LduminVar32(operand) => (operand, B32, Umin, Relaxed),
+        AArch64Inst::SwpVar32(operand) => (operand, B32, Swap, Relaxed),
+
+        AArch64Inst::LdaddaVar32(operand) => (operand, B32, Add, Acquire),
+        AArch64Inst::LdclraVar32(operand) => (operand, B32, AndNot, Acquire),
+        AArch64Inst::LdeoraVar32(operand) => (operand, B32, Xor, Acquire),
+        AArch64Inst::LdsetaVar32(operand) => (operand, B32, Or, Acquire),
+        AArch64Inst::LdsmaxaVar32(operand) => (operand, B32, Smax, Acquire),
+        AArch64Inst::LdsminaVar32(operand) => (operand, B32, Smin, Acquire),
+        AArch64Inst::LdumaxaVar32(operand) => (operand, B32, Umax, Acquire),
+        AArch64Inst::LduminaVar32(operand) => (operand, B32, Umin, Acquire),
+        AArch64Inst::SwpaVar32(operand) => (operand, B32, Swap, Acquire),
+
+        AArch64Inst::LdaddlVar32(operand) => (operand, B32, Add, Release),
+        AArch64Inst::LdclrlVar32(operand) => (operand, B32, AndNot, Release),
+        AArch64Inst::LdeorlVar32(operand) => (operand, B32, Xor, Release),
+        AArch64Inst::LdsetlVar32(operand) => (operand, B32, Or, Release),
+        AArch64Inst::LdsmaxlVar32(operand) => (operand, B32, Smax, Release),
+        AArch64Inst::LdsminlVar32(operand) => (operand, B32, Smin, Release),
+        AArch64Inst::LdumaxlVar32(operand) => (operand, B32, Umax, Release),
+        AArch64Inst::LduminlVar32(operand) => (operand, B32, Umin, Release),
+        AArch64Inst::SwplVar32(operand) => (operand, B32, Swap, Release),
+
+        AArch64Inst::LdaddalVar32(operand) => (operand, B32, Add, SeqCst),
+        AArch64Inst::LdclralVar32(operand) => (operand, B32, AndNot, SeqCst),
+        AArch64Inst::LdeoralVar32(operand) => (operand, B32, Xor, SeqCst),
+        AArch64Inst::LdsetalVar32(operand) => (operand, B32, Or, SeqCst),
+        AArch64Inst::LdsmaxalVar32(operand) => (operand, B32, Smax, SeqCst),
+        AArch64Inst::LdsminalVar32(operand) => (operand, B32, Smin, SeqCst),
+        AArch64Inst::LdumaxalVar32(operand) => (operand, B32, Umax, SeqCst),
+        AArch64Inst::LduminalVar32(operand) => (operand, B32, Umin, SeqCst),
+        AArch64Inst::SwpalVar32(operand) => (operand, B32, Swap, SeqCst),
+
+        AArch64Inst::LdaddVar64(operand) => (operand, B64, Add, Relaxed),
+        AArch64Inst::LdclrVar64(operand) => (operand, B64, AndNot, Relaxed),
+        AArch64Inst::LdeorVar64(operand) => (operand, B64, Xor, Relaxed),
+        AArch64Inst::LdsetVar64(operand) => (operand, B64, Or, Relaxed),
+        AArch64Inst::LdsmaxVar64(operand) => (operand, B64, Smax, Relaxed),
+        AArch64Inst::LdsminVar64(operand) => (operand, B64, Smin, Relaxed),
+        AArch64Inst::LdumaxVar64(operand) => (operand, B64, Umax, Relaxed),
+        AArch64Inst::LduminVar64(operand) => (operand, B64, Umin, Relaxed),
+        AArch64Inst::SwpVar64(operand) => (operand, B64, Swap, Relaxed),
+
+        AArch64Inst::LdaddaVar64(operand) => (operand, B64, Add, Acquire),
+        AArch64Inst::LdclraVar64(operand) => (operand, B64, AndNot, Acquire),
+        AArch64Inst::LdeoraVar64(operand) => (operand, B64, Xor, Acquire),
+        AArch64Inst::LdsetaVar64(operand) => (operand, B64, Or, Acquire),
+        AArch64Inst::LdsmaxaVar64(operand) => (operand, B64, Smax, Acquire),
+        AArch64Inst::LdsminaVar64(operand) => (operand, B64, Smin, Acquire),
+        AArch64Inst::LdumaxaVar64(operand) => (operand, B64, Umax, Acquire),
+        AArch64Inst::LduminaVar64(operand) => (operand, B64, Umin, Acquire),
+        AArch64Inst::SwpaVar64(operand) => (operand, B64, Swap, Acquire),
+
+        AArch64Inst::LdaddlVar64(operand) => (operand, B64, Add, Release),
+        AArch64Inst::LdclrlVar64(operand) => (operand, B64, AndNot, Release),
+        AArch64Inst::LdeorlVar64(operand) => (operand, B64, Xor, Release),
+        AArch64Inst::LdsetlVar64(operand) => (operand, B64, Or, Release),
+        AArch64Inst::LdsmaxlVar64(operand) => (operand, B64, Smax, Release),
+        AArch64Inst::LdsminlVar64(operand) => (operand, B64, Smin, Release),
+        AArch64Inst::LdumaxlVar64(operand) => (operand, B64, Umax, Release),
+        AArch64Inst::LduminlVar64(operand) => (operand, B64, Umin, Release),
+        AArch64Inst::SwplVar64(operand) => (operand, B64, Swap, Release),
+
+        AArch64Inst::LdaddalVar64(operand) => (operand, B64, Add, SeqCst),
+        AArch64Inst::LdclralVar64(operand) => (operand, B64, AndNot, SeqCst),
+        AArch64Inst::LdeoralVar64(operand) => (operand, B64, Xor, SeqCst),
+        AArch64Inst::LdsetalVar64(operand) => (operand, B64, Or, SeqCst),
+        AArch64Inst::LdsmaxalVar64(operand) => (operand, B64, Smax, SeqCst),
+        AArch64Inst::LdsminalVar64(operand) => (operand, B64, Smin, SeqCst),
+        AArch64Inst::LdumaxalVar64(operand) => (operand, B64, Umax, SeqCst),
+        AArch64Inst::LduminalVar64(operand) => (operand, B64, Umin, SeqCst),
+        AArch64Inst::SwpalVar64(operand) => (operand, B64, Swap, SeqCst),
+        _ => return None,
+    })
+}
+
+/// `LD<op>` and `SWP`, the `ST<op>` aliases discard the old value in XZR.
+fn compile_atomic_memory_op(
+    bb: &mut BasicBlock,
+    operand: &RsRnRt,
+    size: IrType,
+    op: AtomicOp,
+    ordering: Reordering,
+) -> Result<(), Error> {
+    let old = bb.new_variable(size);
+    bb.push_inst(IrInst::AtomicRmw {
+        dst: old,
+        addr: read_reg(operand.rn, IrType::B64),
+        src: read_reg(operand.rs, size),
+        op,
+        ordering,
+    });
+    gen_write_reg(bb, operand.rt, old);
+
+    Ok(())
+}
+
+fn compile_cas(
+    bb: &mut BasicBlock,
+    operand: &RsRnRt,
+    size: IrType,
+    acquire: bool,
+    release: bool,
+) -> Result<(), Error> {
+    let old = bb.new_variable(size);
+    bb.push_inst(IrInst::CompareExchange {
+        dst: old,
+        addr: read_reg(operand.rn, IrType::B64),
+        expected: read_reg(operand.rs, size),
+        new: read_reg(operand.rt, size),
+        ordering: atomic_ordering(acquire, release),
+    });
+    gen_write_reg(bb, operand.rs, old);
+
+    Ok(())
+}
+
+/// The pairs are compared and swapped as a single value like exclusive pairs, with the even
+/// register in the lower half.
+fn compile_casp(
+    bb: &mut BasicBlock,
+    operand: &CompareAndSwapPair,
+    size: IrType,
+    acquire: bool,
+    release: bool,
+) -> Result<(), Error> {
+    let reg = |n| AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, n);
+    let (rs, rs2) = (reg(operand.rs), reg(operand.rs + 1));
+    let (rt, rt2) = (reg(operand.rt), reg(operand.rt + 1));
+
+    let expected = gen_pair(bb, read_reg(rs, size), read_reg(rs2, size));
+    let new = gen_pair(bb, read_reg(rt, size), read_reg(rt2, size));
+    let old = bb.new_variable(expected.ty());
+    bb.push_inst(IrInst::CompareExchange {
+        dst: old,
+        addr: read_reg(operand.rn, IrType::B64),
+        expected,
+        new,
+        ordering: atomic_ordering(acquire, release),
+    });
+
+    let (low, high) = gen_split_pair(bb, old);
+    gen_write_reg(bb, rs, low);
+    gen_write_reg(bb, rs2, high);
+
+    Ok(())
+}
+
+fn compile_add_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) -> Result<(), Error> {
+    let imm = if operand.sh == 0b0 {
+        operand.imm12 as u32
+    } else {
+        (operand.imm12 as u32) << 12
+    };
+
+    let result = bb.new_variable(ty);
+    bb.push_inst(IrInst::Add {
+        dst: result,
+        lhs: read_reg(operand.rn, ty),
+        rhs: IrValue::Constant(IrConstant::new(ty, imm)),
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_sub_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) -> Result<(), Error> {
+    let imm = if operand.sh == 0b0 {
+        operand.imm12 as u32
+    } else {
+        (operand.imm12 as u32) << 12
+    };
+
+    let result = bb.new_variable(ty);
+    bb.push_inst(IrInst::Sub {
+        dst: result,
+        lhs: read_reg(operand.rn, ty),
+        rhs: IrValue::Constant(IrConstant::new(ty, imm)),
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// `lhs + rhs` or `lhs - rhs`, updating NZCV if `set_flags` is set.
+fn gen_add_sub(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    ty: IrType,
+    sub: bool,
+    set_flags: bool,
+) -> IrValue {
+    if set_flags {
+        let (result, nzcv) = gen_add_sub_flags(bb, lhs, rhs, ty, sub);
+        gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+        return result;
+    }
+
+    let result = bb.new_variable(ty);
+    bb.push_inst(if sub {
+        IrInst::Sub {
+            dst: result,
+            lhs,
+            rhs,
+        }
+    } else {
+        IrInst::Add {
+            dst: result,
+            lhs,
+            rhs,
+        }
+    });
+
+    result
+}
+
+/// `lhs + rhs` or `lhs - rhs`, along with the NZCV value of the result.
+fn gen_add_sub_flags(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    ty: IrType,
+    sub: bool,
+) -> (IrValue, IrValue) {
+    let result = gen_add_sub(bb, lhs, rhs, ty, sub, false);
+
+    (result, gen_nzcv(bb, sub))
+}
+
+fn compile_adds_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) -> Result<(), Error> {
+    let imm = if operand.sh == 0b0 {
+        operand.imm12 as u32
+    } else {
+        (operand.imm12 as u32) << 12
+    };
+
+    let rn = read_reg(operand.rn, ty);
+    let imm = IrValue::Constant(IrConstant::new(ty, imm));
+    let (result, nzcv) = gen_add_sub_flags(bb, rn, imm, ty, false);
+    gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_subs_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) -> Result<(), Error> {
+    let imm = if operand.sh == 0b0 {
+        operand.imm12 as u32
+    } else {
+        (operand.imm12 as u32) << 12
+    };
+
+    let rn = read_reg(operand.rn, ty);
+    let imm = IrValue::Constant(IrConstant::new(ty, imm));
+    let (result, nzcv) = gen_add_sub_flags(bb, rn, imm, ty, true);
+    gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_add_sub_shifted_reg(
+    bb: &mut BasicBlock,
+    operand: &ShiftRmImm6RnRd,
+    ty: IrType,
+    sub: bool,
+    set_flags: bool,
+) -> Result<(), Error> {
+    let operand2 = bb.new_variable(ty);
+    bb.push_inst(shift_reg(
+        read_reg(operand.rm, ty),
+        decode_shift(operand.shift),
+        IrValue::Constant(IrConstant::B8(operand.imm6)),
+        ty,
+        operand2,
+    ));
+
+    let rn = read_reg(operand.rn, ty);
+    let result = gen_add_sub(bb, rn, operand2, ty, sub, set_flags);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_add_sub_ext_reg(
+    bb: &mut BasicBlock,
+    operand: &AddSubtractExtReg,
+    ty: IrType,
+    sub: bool,
+    set_flags: bool,
+) -> Result<(), Error> {
+    // UXTB, UXTH, UXTW, UXTX and their signed counterparts, never wider than the operation
+    let src_ty = match operand.option & 0b11 {
+        0b00 => IrType::B8,
+        0b01 => IrType::B16,
+        0b10 => IrType::B32,
+        _ => IrType::B64,
+    };
+    let src_ty = if src_ty.size_of() < ty.size_of() {
+        src_ty
+    } else {
+        ty
+    };
+    let signed = operand.option & 0b100 != 0;
+
+    let operand2 = bb.new_variable(ty);
+    let rm = read_reg(operand.rm, src_ty);
+    bb.push_inst(if src_ty == ty {
+        IrInst::Assign {
+            dst: operand2,
+            src: rm,
+        }
+    } else if signed {
+        IrInst::SextCast {
+            dst: operand2,
+            src: rm,
+        }
+    } else {
+        IrInst::ZextCast {
+            dst: operand2,
+            src: rm,
+        }
+    });
+    bb.push_inst(IrInst::Shl {
+        dst: operand2,
+        lhs: operand2,
+        rhs: IrValue::Constant(IrConstant::B8(operand.imm3)),
+    });
+
+    let rn = read_reg(operand.rn, ty);
+    let result = gen_add_sub(bb, rn, operand2, ty, sub, set_flags);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_adc(
+    bb: &mut BasicBlock,
+    operand: &RmRnRd,
+    ty: IrType,
+    sub: bool,
+    set_flags: bool,
+) -> Result<(), Error> {
+    let rm = read_reg(operand.rm, ty);
+    let operand2 = if sub {
+        let inverted = bb.new_variable(ty);
+        bb.push_inst(IrInst::Not {
+            dst: inverted,
+            src: rm,
+        });
+        inverted
+    } else {
+        rm
+    };
+
+    let result = gen_add_with_carry(bb, read_reg(operand.rn, ty), operand2, set_flags);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_div(
+    bb: &mut BasicBlock,
+    operand: &DataProc2Src,
+    ty: IrType,
+    signed: bool,
+) -> Result<(), Error> {
+    let rn = read_reg(operand.rn, ty);
+    let rm = read_reg(operand.rm, ty);
+
+    // Dividing by zero yields zero instead of trapping
+    let is_zero = bb.new_variable(IrType::Bool);
+    bb.push_inst(IrInst::Cmp {
+        dst: is_zero,
+        lhs: rm,
+        rhs: IrValue::Constant(IrConstant::new(ty, 0)),
+        cond: Comparison::Eq,
+    });
+    let divisor = gen_select(bb, is_zero, IrValue::Constant(IrConstant::new(ty, 1)), rm);
+
+    let quotient = bb.new_variable(ty);
+    bb.push_inst(if signed {
+        IrInst::Sdiv {
+            dst: quotient,
+            lhs: rn,
+            rhs: divisor,
+        }
+    } else {
+        IrInst::Div {
+            dst: quotient,
+            lhs: rn,
+            rhs: divisor,
+        }
+    });
+
+    let result = gen_select(
+        bb,
+        is_zero,
+        IrValue::Constant(IrConstant::new(ty, 0)),
+        quotient,
+    );
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_madd(
+    bb: &mut BasicBlock,
+    operand: &DataProc3Src,
+    ty: IrType,
+    sub: bool,
+) -> Result<(), Error> {
+    let product = bb.new_variable(ty);
+    bb.push_inst(IrInst::Mul {
+        dst: product,
+        lhs: read_reg(operand.rn, ty),
+        rhs: read_reg(operand.rm, ty),
+    });
+
+    gen_accumulate(bb, operand, product, sub);
+
+    Ok(())
+}
+
+fn compile_maddl(
+    bb: &mut BasicBlock,
+    operand: &DataProc3Src,
+    signed: bool,
+    sub: bool,
+) -> Result<(), Error> {
+    // The 32 bit operands are extended first so the product can't overflow
+    let extend = |bb: &mut BasicBlock, reg| {
+        let dst = bb.new_variable(IrType::B64);
+        let src = read_reg(reg, IrType::B32);
+        bb.push_inst(if signed {
+            IrInst::SextCast { dst, src }
+        } else {
+            IrInst::ZextCast { dst, src }
+        });
+        dst
+    };
+    let rn = extend(bb, operand.rn);
+    let rm = extend(bb, operand.rm);
+
+    let product = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Mul {
+        dst: product,
+        lhs: rn,
+        rhs: rm,
+    });
+
+    gen_accumulate(bb, operand, product, sub);
+
+    Ok(())
+}
+
+/// Write `ra + product`, or `ra - product` for the subtracting forms, to `rd`.
+fn gen_accumulate(bb: &mut BasicBlock, operand: &DataProc3Src, product: IrValue, sub: bool) {
+    let ty = product.ty();
+    let ra = read_reg(operand.ra, ty);
+    let result = bb.new_variable(ty);
+    bb.push_inst(if sub {
+        IrInst::Sub {
+            dst: result,
+            lhs: ra,
+            rhs: product,
+        }
+    } else {
+        IrInst::Add {
+            dst: result,
+            lhs: ra,
+            rhs: product,
+        }
+    });
+    gen_write_reg(bb, operand.rd, result);
+}
+
+fn compile_mulh(bb: &mut BasicBlock, operand: &DataProc3Src, signed: bool) -> Result<(), Error> {
+    let dst = bb.new_variable(IrType::B64);
+    let lhs = read_reg(operand.rn, IrType::B64);
+    let rhs = read_reg(operand.rm, IrType::B64);
+    bb.push_inst(if signed {
+        IrInst::Smulh { dst, lhs, rhs }
+    } else {
+        IrInst::Umulh { dst, lhs, rhs }
+    });
+    gen_write_reg(bb, operand.rd, dst);
+
+    Ok(())
+}
+
+fn compile_orr_shifted_reg(
+    bb: &mut BasicBlock,
+    operand: &ShiftRmImm6RnRd,
+    ty: IrType,
+) -> Result<(), Error> {
+    let shift_type = decode_shift(operand.shift);
+    let shift_amount = operand.imm6;
+
+    let operand1 = IrValue::Register(ty, operand.rn.raw());
+    let operand2 = bb.new_variable(ty);
+
+    bb.push_inst(shift_reg(
+        IrValue::Register(ty, operand.rm.raw()),
+        shift_type,
+        IrValue::Constant(IrConstant::B8(shift_amount)),
+        ty,
+        operand2,
+    ));
+
+    bb.push_inst(IrInst::Or {
+        dst: IrValue::Register(ty, operand.rd.raw()),
+        lhs: operand1,
+        rhs: operand2,
+    });
+
+    Ok(())
+}
+
+/// Operation of a logical instruction.
+#[derive(Clone, Copy)]
+enum LogicalOp {
+    And,
+    Orr,
+    Eor,
+}
+
+impl LogicalOp {
+    fn inst(self, dst: IrValue, lhs: IrValue, rhs: IrValue) -> IrInst {
+        match self {
+            LogicalOp::And => IrInst::And { dst, lhs, rhs },
+            LogicalOp::Orr => IrInst::Or { dst, lhs, rhs },
+            LogicalOp::Eor => IrInst::Xor { dst, lhs, rhs },
+        }
+    }
+}
+
+fn compile_logical_imm(
+    bb: &mut BasicBlock,
+    operand: &LogicalImm,
+    ty: IrType,
+    op: LogicalOp,
+    set_flags: bool,
+) -> Result<(), Error> {
+    let datasize = ty.size_of() as u32 * 8;
+    let (imm, _) = decode_bit_masks(operand.n, operand.imms, operand.immr, true, datasize)
+        .expect("reserved immediates are rejected by the decoder");
+
+    let result = bb.new_variable(ty);
+    bb.push_inst(op.inst(
+        result,
+        read_reg(operand.rn, ty),
+        IrValue::Constant(IrConstant::new(ty, imm)),
+    ));
+    if set_flags {
+        let nzcv = gen_logical_nzcv(bb);
+        gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+    }
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// How a bitfield move fills the bits around the moved field.
+#[derive(Clone, Copy)]
+enum BitfieldMove {
+    /// SBFM, copies of the top bit of the field
+    Signed,
+    /// UBFM, zeros
+    Unsigned,
+    /// BFM, the bits already in the destination
+    Insert,
+}
+
+fn compile_bfm(
+    bb: &mut BasicBlock,
+    operand: &Bitfield,
+    ty: IrType,
+    op: BitfieldMove,
+) -> Result<(), Error> {
+    let datasize = ty.size_of() as u32 * 8;
+    let (wmask, tmask) = decode_bit_masks(operand.n, operand.imms, operand.immr, false, datasize)
+        .expect("reserved encodings are rejected by the decoder");
+    let mask = |mask: u64| IrValue::Constant(IrConstant::new(ty, mask));
+    let inverted =
+        |mask: u64| IrValue::Constant(IrConstant::new(ty, !mask & ones::<u64>(datasize)));
+
+    let src = read_reg(operand.rn, ty);
+    let dst = read_reg(operand.rd, ty);
+
+    // The field is rotated into place and cut out with wmask
+    let bot = bb.new_variable(ty);
+    if operand.immr == 0 {
+        bb.push_inst(IrInst::And {
+            dst: bot,
+            lhs: src,
+            rhs: mask(wmask),
+        });
+    } else {
+        bb.push_inst(IrInst::Rotr {
+            dst: bot,
+            lhs: src,
+            rhs: IrValue::Constant(IrConstant::B8(operand.immr)),
+        });
+        bb.push_inst(IrInst::And {
+            dst: bot,
+            lhs: bot,
+            rhs: mask(wmask),
+        });
+    }
+    if let BitfieldMove::Insert = op {
+        let kept = bb.new_variable(ty);
+        bb.push_inst(IrInst::And {
+            dst: kept,
+            lhs: dst,
+            rhs: inverted(wmask),
+        });
+        bb.push_inst(IrInst::Or {
+            dst: bot,
+            lhs: bot,
+            rhs: kept,
+        });
+    }
+
+    // Bits outside of tmask come from top
+    let top = match op {
+        BitfieldMove::Signed => {
+            let top = bb.new_variable(ty);
+            bb.push_inst(IrInst::Shl {
+                dst: top,
+                lhs: src,
+                rhs: IrValue::Constant(IrConstant::B8((datasize - 1) as u8 - operand.imms)),
+            });
+            bb.push_inst(IrInst::Ashr {
+                dst: top,
+                lhs: top,
+                rhs: IrValue::Constant(IrConstant::B8((datasize - 1) as u8)),
+            });
+            Some(top)
+        }
+        BitfieldMove::Unsigned => None,
+        BitfieldMove::Insert => Some(dst),
+    };
+
+    let result = bb.new_variable(ty);
+    bb.push_inst(IrInst::And {
+        dst: result,
+        lhs: bot,
+        rhs: mask(tmask),
+    });
+    if let Some(top) = top {
+        let outside = bb.new_variable(ty);
+        bb.push_inst(IrInst::And {
+            dst: outside,
+            lhs: top,
+            rhs: inverted(tmask),
+        });
+        bb.push_inst(IrInst::Or {
+            dst: result,
+            lhs: result,
+            rhs: outside,
+        });
+    }
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_extr(bb: &mut BasicBlock, operand: &ExtractImm, ty: IrType) -> Result<(), Error> {
+    let datasize = ty.size_of() as u8 * 8;
+    let rm = read_reg(operand.rm, ty);
+    if operand.imms == 0 {
+        gen_write_reg(bb, operand.rd, rm);
+        return Ok(());
+    }
+
+    // The low bits come from rm and the high bits from rn
+    let low = bb.new_variable(ty);
+    let high = bb.new_variable(ty);
+    bb.push_inst(IrInst::Lshr {
+        dst: low,
+        lhs: rm,
+        rhs: IrValue::Constant(IrConstant::B8(operand.imms)),
+    });
+    bb.push_inst(IrInst::Shl {
+        dst: high,
+        lhs: read_reg(operand.rn, ty),
+        rhs: IrValue::Constant(IrConstant::B8(datasize - operand.imms)),
+    });
+    bb.push_inst(IrInst::Or {
+        dst: low,
+        lhs: low,
+        rhs: high,
+    });
+    gen_write_reg(bb, operand.rd, low);
+
+    Ok(())
+}
+
+/// Compile a data processing instruction that maps to a single unary IR instruction.
+fn compile_bit_op(
+    bb: &mut BasicBlock,
+    operand: &RnRd,
+    ty: IrType,
+    op: fn(IrValue, IrValue) -> IrInst,
+) -> Result<(), Error> {
+    let result = bb.new_variable(ty);
+    bb.push_inst(op(result, read_reg(operand.rn, ty)));
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn compile_rev16(bb: &mut BasicBlock, operand: &RnRd, ty: IrType) -> Result<(), Error> {
+    let src = read_reg(operand.rn, ty);
+    let low_bytes = 0x00ff_00ff_00ff_00ffu64 & ones::<u64>(ty.size_of() as u32 * 8);
+
+    // Swap the bytes within every halfword
+    let low = bb.new_variable(ty);
+    let high = bb.new_variable(ty);
+    bb.push_inst(IrInst::Lshr {
+        dst: low,
+        lhs: src,
+        rhs: IrValue::Constant(IrConstant::B8(8)),
+    });
+    bb.push_inst(IrInst::And {
+        dst: low,
+        lhs: low,
+        rhs: IrValue::Constant(IrConstant::new(ty, low_bytes)),
+    });
+    bb.push_inst(IrInst::And {
+        dst: high,
+        lhs: src,
+        rhs: IrValue::Constant(IrConstant::new(ty, low_bytes)),
+    });
+    bb.push_inst(IrInst::Shl {
+        dst: high,
+        lhs: high,
+        rhs: IrValue::Constant(IrConstant::B8(8)),
+    });
+    bb.push_inst(IrInst::Or {
+        dst: low,
+        lhs: low,
+        rhs: high,
+    });
+    gen_write_reg(bb, operand.rd, low);
+
+    Ok(())
+}
+
+fn compile_rev32(bb: &mut BasicBlock, operand: &RnRd) -> Result<(), Error> {
+    // Reversing all bytes also swaps the words, which the rotation undoes
+    let result = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Bswap {
+        dst: result,
+        src: read_reg(operand.rn, IrType::B64),
+    });
+    bb.push_inst(IrInst::Rotr {
+        dst: result,
+        lhs: result,
+        rhs: IrValue::Constant(IrConstant::B8(32)),
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// `pc + offset`, the target of a PC relative branch
+fn gen_branch_target(bb: &mut BasicBlock, offset: i64) -> IrValue {
+    gen_pc_rel(bb, IrValue::Constant(IrConstant::B64(offset as u64)))
+}
+
+fn gen_link(bb: &mut BasicBlock) {
+    let return_address = gen_branch_target(bb, 4);
+    gen_write_reg(bb, AArch64Register::X(30), return_address);
+}
+
+fn compile_b_imm(bb: &mut BasicBlock, operand: &Imm26) -> Result<(), Error> {
+    let target = gen_branch_target(bb, sign_extend((operand.imm26 as i64) << 2, 28));
+    bb.set_terminator(BasicBlockTerminator::Branch(target));
+
+    Ok(())
+}
+
+fn compile_bl_imm(bb: &mut BasicBlock, operand: &Imm26) -> Result<(), Error> {
+    let target = gen_branch_target(bb, sign_extend((operand.imm26 as i64) << 2, 28));
+    gen_link(bb);
+    bb.set_terminator(BasicBlockTerminator::Branch(target));
+
+    Ok(())
+}
+
+fn compile_br(bb: &mut BasicBlock, operand: &UncondBranchReg) -> Result<(), Error> {
+    let target = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Assign {
+        dst: target,
+        src: read_reg(operand.rn, IrType::B64),
+    });
+    bb.set_terminator(BasicBlockTerminator::Branch(target));
+
+    Ok(())
+}
+
+fn compile_blr(bb: &mut BasicBlock, operand: &UncondBranchReg) -> Result<(), Error> {
+    // Read the target first, `blr x30` branches to the old value of the link register
+    let target = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Assign {
+        dst: target,
+        src: read_reg(operand.rn, IrType::B64),
+    });
+    gen_link(bb);
+    bb.set_terminator(BasicBlockTerminator::Branch(target));
+
+    Ok(())
+}
+
+fn gen_branch_cond(bb: &mut BasicBlock, cond: IrValue, offset: i64) {
+    let target_true = gen_branch_target(bb, offset);
+    let target_false = gen_branch_target(bb, 4);
+    bb.set_terminator(BasicBlockTerminator::BranchCond {
+        cond,
+        target_true,
+        target_false,
+    });
+}
+
+fn compile_b_cond(bb: &mut BasicBlock, operand: &Imm19Cond) -> Result<(), Error> {
+    let cond = gen_condition_holds(bb, operand.cond);
+    gen_branch_cond(bb, cond, sign_extend((operand.imm19 as i64) << 2, 21));
+
+    Ok(())
+}
+
+fn compile_cbz(
+    bb: &mut BasicBlock,
+    operand: &Imm19Rt,
+    ty: IrType,
+    nonzero: bool,
+) -> Result<(), Error> {
+    let cond = bb.new_variable(IrType::Bool);
+    bb.push_inst(IrInst::Cmp {
+        dst: cond,
+        lhs: read_reg(operand.rt, ty),
+        rhs: IrValue::Constant(IrConstant::new(ty, 0)),
+        cond: if nonzero {
+            Comparison::Ne
+        } else {
+            Comparison::Eq
+        },
+    });
+
+    gen_branch_cond(bb, cond, sign_extend((operand.imm19 as i64) << 2, 21));
+
+    Ok(())
+}
+
+fn compile_tbz(bb: &mut BasicBlock, operand: &B5B40Imm14Rt, nonzero: bool) -> Result<(), Error> {
+    let bit = operand.b5 << 5 | operand.b40;
+
+    let tested = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::And {
+        dst: tested,
+        lhs: read_reg(operand.rt, IrType::B64),
+        rhs: IrValue::Constant(IrConstant::B64(1 << bit)),
+    });
+
+    let cond = bb.new_variable(IrType::Bool);
+    bb.push_inst(IrInst::Cmp {
+        dst: cond,
+        lhs: tested,
+        rhs: IrValue::Constant(IrConstant::B64(0)),
+        cond: if nonzero {
+            Comparison::Ne
+        } else {
+            Comparison::Eq
+        },
+    });
+
+    gen_branch_cond(bb, cond, sign_extend((operand.imm14 as i64) << 2, 16));
+
+    Ok(())
+}
+
+fn gen_select(bb: &mut BasicBlock, cond: IrValue, if_true: IrValue, if_false: IrValue) -> IrValue {
+    let result = bb.new_variable(if_true.ty());
+    bb.push_inst(IrInst::Select {
+        dst: result,
+        cond,
+        if_true,
+        if_false,
+    });
+
+    result
+}
+
+/// What a conditional select does to its second operand when the condition fails.
+#[derive(Clone, Copy)]
+enum CondSelect {
+    Csel,
+    Csinc,
+    Csinv,
+    Csneg,
+}
+
+fn compile_csel(
+    bb: &mut BasicBlock,
+    operand: &RmCondRnRd,
+    ty: IrType,
+    op: CondSelect,
+) -> Result<(), Error> {
+    let cond = gen_condition_holds(bb, operand.cond);
+
+    let rm = read_reg(operand.rm, ty);
+    let operand2 = match op {
+        CondSelect::Csel => rm,
+        CondSelect::Csinc => {
+            let operand2 = bb.new_variable(ty);
+            bb.push_inst(IrInst::Add {
+                dst: operand2,
+                lhs: rm,
+                rhs: IrValue::Constant(IrConstant::new(ty, 1)),
+            });
+            operand2
+        }
+        CondSelect::Csinv => {
+            let operand2 = bb.new_variable(ty);
+            bb.push_inst(IrInst::Not {
+                dst: operand2,
+                src: rm,
+            });
+            operand2
+        }
+        CondSelect::Csneg => {
+            let operand2 = bb.new_variable(ty);
+            bb.push_inst(IrInst::Sub {
+                dst: operand2,
+                lhs: IrValue::Constant(IrConstant::new(ty, 0)),
+                rhs: rm,
+            });
+            operand2
+        }
+    };
+
+    let result = gen_select(bb, cond, read_reg(operand.rn, ty), operand2);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// Set NZCV to the flags of `rn - operand2` (or `rn + operand2` for CCMN) if `cond` holds,
+/// and to the immediate `nzcv` otherwise.
+fn gen_ccmp(
+    bb: &mut BasicBlock,
+    cond: u8,
+    rn: AArch64Register,
+    operand2: IrValue,
+    nzcv: u8,
+    ty: IrType,
+    sub: bool,
+) {
+    let holds = gen_condition_holds(bb, cond);
+    let (_, flags) = gen_add_sub_flags(bb, read_reg(rn, ty), operand2, ty, sub);
+
+    let nzcv = IrValue::Constant(IrConstant::B64((nzcv as u64) << 28));
+    let nzcv = gen_select(bb, holds, flags, nzcv);
+    gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+}
+
+fn compile_ccmp_reg(
+    bb: &mut BasicBlock,
+    operand: &CondCmpReg,
+    ty: IrType,
+    sub: bool,
+) -> Result<(), Error> {
+    let rm = read_reg(operand.rm, ty);
+    gen_ccmp(bb, operand.cond, operand.rn, rm, operand.nzcv, ty, sub);
+
+    Ok(())
+}
+
+fn compile_ccmp_imm(
+    bb: &mut BasicBlock,
+    operand: &CondCmpImm,
+    ty: IrType,
+    sub: bool,
+) -> Result<(), Error> {
+    let imm = IrValue::Constant(IrConstant::new(ty, operand.imm5));
+    gen_ccmp(bb, operand.cond, operand.rn, imm, operand.nzcv, ty, sub);
+
+    Ok(())
+}
+
+fn compile_udf(_bb: &mut BasicBlock, operand: &Imm16) -> Result<(), Error> {
+    // UDF is permanently undefined, its encoding is the immediate itself
+    Err(Error::UndefinedInstruction(operand.imm16 as u32))
+}
+
+fn compile_svc(bb: &mut BasicBlock, operand: &ExceptionGen) -> Result<(), Error> {
+    bb.push_inst(IrInst::Interrupt(Interrupt::SystemCall(
+        operand.imm16 as u64,
+    )));
+
+    Ok(())
+}
+
+/// Log2 of the number of words DC ZVA zeroes, as DCZID_EL0 reports it.
+const DC_ZVA_BLOCK_SIZE: u8 = 4;
+
+fn compile_sys(bb: &mut BasicBlock, operand: &SystemInstructions) -> Result<(), Error> {
+    match (operand.op1, operand.crn, operand.crm, operand.op2) {
+        // IC IVAU, DC CVAU
+        (0b011, 0b0111, 0b0101, 0b001) | (0b011, 0b0111, 0b1011, 0b001) => {
+            bb.push_inst(IrInst::Intrinsic(IrIntrinsic::InvalidateCode {
+                addr: IrValue::Register(IrType::B64, operand.rt.raw()),
+            }));
+        }
+        // IC IALLUIS, IC IALLU
+        (0b000, 0b0111, 0b0001 | 0b0101, 0b000) => {
+            bb.push_inst(IrInst::Intrinsic(IrIntrinsic::InvalidateAllCode));
+        }
+        // DC ZVA zeroes the naturally aligned block that contains the address
+        (0b011, 0b0111, 0b0100, 0b001) => {
+            let block_size = 4u64 << DC_ZVA_BLOCK_SIZE;
+            let base = bb.new_variable(IrType::B64);
+            bb.push_inst(IrInst::And {
+                dst: base,
+                lhs: read_reg(operand.rt, IrType::B64),
+                rhs: IrValue::Constant(IrConstant::B64(!(block_size - 1))),
+            });
+
+            for offset in (0..block_size).step_by(8) {
+                let address = bb.new_variable(IrType::B64);
+                bb.push_inst(IrInst::Add {
+                    dst: address,
+                    lhs: base,
+                    rhs: IrValue::Constant(IrConstant::B64(offset)),
+                });
+                bb.push_inst(IrInst::Store {
+                    dst: address,
+                    src: IrValue::Constant(IrConstant::B64(0)),
+                });
+            }
+        }
+        // Data cache maintenance by VA, memory is always coherent to us.
+        (0b011, 0b0111, 0b1010 | 0b1100 | 0b1101 | 0b1110, 0b001) => {}
+        // Everything else is only available to the kernel
+        _ => return Err(reserved_encoding()),
+    }
+
+    Ok(())
+}
+
+/// The register a system register move accesses, only NZCV, FPCR, FPSR and TPIDR_EL0 are
+/// modelled.
+fn system_register(operand: &SysRegMov) -> Option<AArch64Register> {
+    match (
+        operand.o0,
+        operand.op1,
+        operand.crn,
+        operand.crm,
+        operand.op2,
+    ) {
+        (1, 0b011, 0b0100, 0b0010, 0b000) => Some(AArch64Register::Pstate),
+        (1, 0b011, 0b0100, 0b0100, 0b000) => Some(AArch64Register::Fpcr),
+        (1, 0b011, 0b0100, 0b0100, 0b001) => Some(AArch64Register::Fpsr),
+        (1, 0b011, 0b1101, 0b0000, 0b010) => Some(AArch64Register::Tpidr),
+        _ => None,
+    }
+}
+
+/// The value of a read-only identification register, as the emulated CPU reports it.
+fn constant_system_register(operand: &SysRegMov) -> Option<u64> {
+    match (
+        operand.o0,
+        operand.op1,
+        operand.crn,
+        operand.crm,
+        operand.op2,
+    ) {
+        // MIDR_EL1, which Linux emulates for userland: no implementer, no part number and the
+        // architecture defined by the ID registers
+        (1, 0b000, 0b0000, 0b0000, 0b000) => Some(0x000F_0000),
+        // CTR_EL0: 64 byte cache lines and writeback granules, PIPT instruction cache
+        (1, 0b011, 0b0000, 0b0000, 0b001) => Some(0x8444_C004),
+        // DCZID_EL0: DC ZVA is allowed
+        (1, 0b011, 0b0000, 0b0000, 0b111) => Some(DC_ZVA_BLOCK_SIZE as u64),
+        _ => None,
+    }
+}
+
+/// Move the `width` bits of `value` at `from` to `to`, clearing all other bits.
+fn gen_move_field(bb: &mut BasicBlock, value: IrValue, from: u8, width: u8, to: u8) -> IrValue {
+    let field = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Lshr {
+        dst: field,
+        lhs: value,
+        rhs: IrValue::Constant(IrConstant::B8(from)),
+    });
+    bb.push_inst(IrInst::And {
+        dst: field,
+        lhs: field,
+        rhs: IrValue::Constant(IrConstant::B64((1 << width) - 1)),
+    });
+    bb.push_inst(IrInst::Shl {
+        dst: field,
+        lhs: field,
+        rhs: IrValue::Constant(IrConstant::B8(to)),
+    });
+
+    field
+}
+
+fn compile_mrs(bb: &mut BasicBlock, operand: &SysRegMov) -> Result<(), Error> {
+    let value = match system_register(operand) {
+        Some(AArch64Register::Fpcr) => {
+            // RMode, FZ, DN and AHP are FPCR[26:22] and FZ16 is FPCR[19]
+            let fpcr = read_reg(AArch64Register::Fpcr, IrType::B64);
+            let controls = gen_move_field(bb, fpcr, 0, 5, 22);
+            let fz16 = gen_move_field(bb, fpcr, 5, 1, 19);
+
+            let value = bb.new_variable(IrType::B64);
+            bb.push_inst(IrInst::Or {
+                dst: value,
+                lhs: controls,
+                rhs: fz16,
+            });
+            value
+        }
+        Some(reg) => read_reg(reg, IrType::B64),
+        None => match constant_system_register(operand) {
+            Some(value) => IrValue::Constant(IrConstant::B64(value)),
+            None => {
+                return Err(Error::UnimplementedInstruction(format!(
+                    "system register read {operand:?}"
+                )))
+            }
+        },
+    };
+    gen_write_reg(bb, operand.rt, value);
+
+    Ok(())
+}
+
+fn compile_msr_reg(bb: &mut BasicBlock, operand: &SysRegMov) -> Result<(), Error> {
+    let value = read_reg(operand.rt, IrType::B64);
+    match system_register(operand) {
+        Some(AArch64Register::Pstate) => {
+            // Only bits 31:28 exist, the rest of the register reads as zero
+            bb.push_inst(IrInst::And {
+                dst: IrValue::Register(IrType::B64, AArch64Register::Pstate.raw()),
+                lhs: value,
+                rhs: IrValue::Constant(IrConstant::B64(0xF000_0000)),
+            });
+        }
+        Some(AArch64Register::Fpcr) => {
+            // Exceptions are never trapped, so the trap enables read as zero like the other
+            // bits that are not modelled
+            let controls = gen_move_field(bb, value, 22, 5, 0);
+            let fz16 = gen_move_field(bb, value, 19, 1, 5);
+            bb.push_inst(IrInst::Or {
+                dst: IrValue::Register(IrType::B64, AArch64Register::Fpcr.raw()),
+                lhs: controls,
+                rhs: fz16,
+            });
+        }
+        Some(AArch64Register::Fpsr) => {
+            // QC and the cumulative exception bits
+            bb.push_inst(IrInst::And {
+                dst: IrValue::Register(IrType::B64, AArch64Register::Fpsr.raw()),
+                lhs: value,
+                rhs: IrValue::Constant(IrConstant::B64(0x0800_009F)),
+            });
+        }
+        Some(reg @ AArch64Register::Tpidr) => gen_write_reg(bb, reg, value),
+        _ => {
+            return Err(Error::UnimplementedInstruction(format!(
+                "system register write {operand:?}"
+            )))
         }
-        _ => unimplemented!(),
     }
+
+    Ok(())
+}
+
+/// Compile a floating-point instruction that maps to a single binary IR instruction.
+fn compile_fp_binary(
+    bb: &mut BasicBlock,
+    operand: &RmRnRd,
+    ty: IrType,
+    op: fn(IrValue, IrValue, IrValue) -> IrInst,
+) -> Result<(), Error> {
+    let result = bb.new_variable(ty);
+    bb.push_inst(op(
+        result,
+        read_reg(operand.rn, ty),
+        read_reg(operand.rm, ty),
+    ));
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+fn gen_fneg(bb: &mut BasicBlock, value: IrValue) -> IrValue {
+    let result = bb.new_variable(value.ty());
+    bb.push_inst(IrInst::Fneg {
+        dst: result,
+        src: value,
+    });
+
+    result
 }
 
-fn compile_movz(bb: &mut BasicBlock, operand: &HwImm16Rd) {
-    let rd = operand.rd.raw();
+fn gen_fabs(bb: &mut BasicBlock, value: IrValue) -> IrValue {
+    let result = bb.new_variable(value.ty());
+    bb.push_inst(IrInst::Fabs {
+        dst: result,
+        src: value,
+    });
 
-    let pos = operand.hw << 4;
-    bb.push_inst(IrInst::Assign {
-        dst: IrValue::Register(IrType::B64, rd),
-        src: IrValue::Constant(IrConstant::B64((operand.imm16 as u64) << pos)),
+    result
+}
+
+/// FABD, the absolute value of `rn - rm`.
+fn compile_fabd(bb: &mut BasicBlock, operand: &RmRnRd, ty: IrType) -> Result<(), Error> {
+    let difference = bb.new_variable(ty);
+    bb.push_inst(IrInst::Fsub {
+        dst: difference,
+        lhs: read_reg(operand.rn, ty),
+        rhs: read_reg(operand.rm, ty),
     });
 
-    compiler_prelude::gen_move_pc(bb);
+    let result = gen_fabs(bb, difference);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_movn(bb: &mut BasicBlock, operand: &HwImm16Rd, ty: IrType) {
-    let rd = operand.rd.raw();
+fn compile_fnmul(bb: &mut BasicBlock, operand: &RmRnRd, ty: IrType) -> Result<(), Error> {
+    let product = bb.new_variable(ty);
+    bb.push_inst(IrInst::Fmul {
+        dst: product,
+        lhs: read_reg(operand.rn, ty),
+        rhs: read_reg(operand.rm, ty),
+    });
+
+    let result = gen_fneg(bb, product);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// `ra + rn * rm` with a single rounding, FMSUB, FNMADD and FNMSUB negate the product or the
+/// addend. Negating `rn` negates the product exactly.
+fn compile_fmadd(
+    bb: &mut BasicBlock,
+    operand: &RmRaRnRd,
+    ty: IrType,
+    negate_product: bool,
+    negate_addend: bool,
+) -> Result<(), Error> {
+    let mut lhs = read_reg(operand.rn, ty);
+    if negate_product {
+        lhs = gen_fneg(bb, lhs);
+    }
+    let mut addend = read_reg(operand.ra, ty);
+    if negate_addend {
+        addend = gen_fneg(bb, addend);
+    }
+
+    let result = bb.new_variable(ty);
+    bb.push_inst(IrInst::Fma {
+        dst: result,
+        lhs,
+        rhs: read_reg(operand.rm, ty),
+        addend,
+    });
+    gen_write_reg(bb, operand.rd, result);
 
+    Ok(())
+}
+
+/// Materialize a positive floating-point zero of type `ty`, all of its bits are clear.
+fn gen_fp_zero(bb: &mut BasicBlock, ty: IrType) -> IrValue {
+    let zero = bb.new_variable(ty);
     bb.push_inst(IrInst::ZextCast {
-        dst: IrValue::Register(IrType::B64, rd),
-        src: IrValue::Constant(IrConstant::new(ty, {
-            let pos = operand.hw << 4;
-            !((operand.imm16 as u64) << pos)
-        })),
+        dst: zero,
+        src: IrValue::Constant(IrConstant::B8(0)),
     });
 
-    compiler_prelude::gen_move_pc(bb);
+    zero
 }
 
-fn compile_movk(bb: &mut BasicBlock, operand: &HwImm16Rd, ty: IrType) {
-    let rd = operand.rd.raw();
+/// The bits of the FMOV immediate `imm8`, expanded like VFPExpandImm does to `ty`.
+fn vfp_expand_imm(imm8: u8, ty: IrType) -> IrConstant {
+    let imm8 = imm8 as u64;
+    let (exponent_bits, fraction_bits) = match ty {
+        IrType::F16 => (5, 10),
+        IrType::F32 => (8, 23),
+        _ => (11, 52),
+    };
 
-    let pos = operand.hw << 4;
-    let mask = !(ones::<u64>(pos as u32) << pos);
+    let sign = imm8 >> 7;
+    let b6 = (imm8 >> 6) & 1;
+    let exponent = ((b6 ^ 1) << (exponent_bits - 1))
+        | ((b6 * ((1 << (exponent_bits - 3)) - 1)) << 2)
+        | ((imm8 >> 4) & 0b11);
+    let fraction = (imm8 & 0b1111) << (fraction_bits - 4);
+
+    let bits = (sign << (exponent_bits + fraction_bits)) | (exponent << fraction_bits) | fraction;
+    match ty {
+        IrType::F16 => IrConstant::B16(bits as u16),
+        IrType::F32 => IrConstant::B32(bits as u32),
+        _ => IrConstant::B64(bits),
+    }
+}
 
-    // t1 = rd & !(ones(pos) << pos)
-    // t2 = t1 | (imm16 << pos)
-    // rd = zext(t2)
-    let t1 = bb.new_variable(ty);
-    let t2 = bb.new_variable(ty);
-    bb.push_inst(IrInst::And {
-        dst: t1,
-        lhs: IrValue::Register(ty, rd),
-        rhs: IrValue::Constant(IrConstant::new(ty, mask)),
+fn compile_fmov_imm(
+    bb: &mut BasicBlock,
+    operand: &FloatingPointImmediate,
+    ty: IrType,
+) -> Result<(), Error> {
+    let imm = IrValue::Constant(vfp_expand_imm(operand.imm8, ty));
+    gen_write_reg(bb, operand.rd, imm);
+
+    Ok(())
+}
+
+fn compile_fcvt_precision(
+    bb: &mut BasicBlock,
+    operand: &RnRd,
+    src_ty: IrType,
+    dst_ty: IrType,
+) -> Result<(), Error> {
+    let result = bb.new_variable(dst_ty);
+    bb.push_inst(IrInst::FpCast {
+        dst: result,
+        src: read_reg(operand.rn, src_ty),
+        rounding: FpRounding::Current,
     });
-    bb.push_inst(IrInst::Or {
-        dst: t2,
-        lhs: t1,
-        rhs: IrValue::Constant(IrConstant::new(ty, (operand.imm16 as u64) << pos)),
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// Convert a floating-point register to an integer in a general-purpose register.
+fn compile_fcvt_to_int(
+    bb: &mut BasicBlock,
+    operand: &RnRd,
+    src_ty: IrType,
+    dst_ty: IrType,
+    signed: bool,
+    rounding: FpRounding,
+) -> Result<(), Error> {
+    let result = bb.new_variable(dst_ty);
+    bb.push_inst(IrInst::FpToInt {
+        dst: result,
+        src: read_reg(operand.rn, src_ty),
+        signed,
+        frac_bits: 0,
+        rounding,
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// Convert a general-purpose register to a floating-point one.
+fn compile_int_to_fp(
+    bb: &mut BasicBlock,
+    operand: &RnRd,
+    src_ty: IrType,
+    dst_ty: IrType,
+    signed: bool,
+) -> Result<(), Error> {
+    let result = bb.new_variable(dst_ty);
+    bb.push_inst(IrInst::IntToFp {
+        dst: result,
+        src: read_reg(operand.rn, src_ty),
+        signed,
+        frac_bits: 0,
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// Convert a fixed-point general-purpose register with `64 - scale` fraction bits to a
+/// floating-point one.
+fn compile_fixed_to_fp(
+    bb: &mut BasicBlock,
+    operand: &ConvBetweenFloatAndFixedPoint,
+    src_ty: IrType,
+    dst_ty: IrType,
+    signed: bool,
+) -> Result<(), Error> {
+    let result = bb.new_variable(dst_ty);
+    bb.push_inst(IrInst::IntToFp {
+        dst: result,
+        src: read_reg(operand.rn, src_ty),
+        signed,
+        frac_bits: 64 - operand.scale,
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// Convert a floating-point register to a fixed-point general-purpose register with
+/// `64 - scale` fraction bits, rounding toward zero.
+fn compile_fp_to_fixed(
+    bb: &mut BasicBlock,
+    operand: &ConvBetweenFloatAndFixedPoint,
+    src_ty: IrType,
+    dst_ty: IrType,
+    signed: bool,
+) -> Result<(), Error> {
+    let result = bb.new_variable(dst_ty);
+    bb.push_inst(IrInst::FpToInt {
+        dst: result,
+        src: read_reg(operand.rn, src_ty),
+        signed,
+        frac_bits: 64 - operand.scale,
+        rounding: FpRounding::TowardZero,
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
+
+/// Move the upper 64 bits of a 128 bit register to a general-purpose register.
+fn compile_fmov_from_top_half(bb: &mut BasicBlock, operand: &RnRd) -> Result<(), Error> {
+    let high = bb.new_variable(IrType::B128);
+    bb.push_inst(IrInst::Lshr {
+        dst: high,
+        lhs: read_reg(operand.rn, IrType::B128),
+        rhs: IrValue::Constant(IrConstant::B8(64)),
     });
+    gen_write_reg(bb, operand.rd, high);
+
+    Ok(())
+}
+
+/// Move a general-purpose register to the upper 64 bits of a 128 bit register, the lower
+/// half is kept.
+fn compile_fmov_to_top_half(bb: &mut BasicBlock, operand: &RnRd) -> Result<(), Error> {
+    let low = bb.new_variable(IrType::B128);
     bb.push_inst(IrInst::ZextCast {
-        dst: IrValue::Register(IrType::B64, rd),
-        src: t2,
+        dst: low,
+        src: read_reg(operand.rd, IrType::B64),
+    });
+
+    let high = bb.new_variable(IrType::B128);
+    bb.push_inst(IrInst::ZextCast {
+        dst: high,
+        src: read_reg(operand.rn, IrType::B64),
+    });
+    bb.push_inst(IrInst::Shl {
+        dst: high,
+        lhs: high,
+        rhs: IrValue::Constant(IrConstant::B8(64)),
+    });
+
+    bb.push_inst(IrInst::Or {
+        dst: IrValue::Register(IrType::B128, operand.rd.raw()),
+        lhs: high,
+        rhs: low,
     });
 
-    compiler_prelude::gen_move_pc(bb);
+    Ok(())
 }
 
-fn compile_movi(bb: &mut BasicBlock, operand: &AdvSimdModifiedImm) {
-    todo!()
+fn gen_fcmp(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    cond: FpComparison,
+    signaling: bool,
+) -> IrValue {
+    let result = bb.new_variable(IrType::Bool);
+    bb.push_inst(IrInst::Fcmp {
+        dst: result,
+        lhs,
+        rhs,
+        cond,
+        signaling,
+    });
+
+    result
 }
 
-fn compile_adr(bb: &mut BasicBlock, operand: &PcRelAddressing) {
-    let rd = operand.rd.raw();
-    let imm = sign_extend((operand.immhi as i64) << 2 | (operand.immlo as i64), 21);
-    let off = gen_pc_rel(bb, IrValue::Constant(IrConstant::new(IrType::B64, imm)));
+/// NZCV of a floating-point comparison, laid out like the NZCV register.
+///
+/// Less than sets N, equal sets Z and C, greater than sets C and unordered sets C and V.
+fn gen_fp_nzcv(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, signaling: bool) -> IrValue {
+    let unordered = gen_fcmp(bb, lhs, rhs, FpComparison::Unordered, signaling);
+    let lt = gen_fcmp(bb, lhs, rhs, FpComparison::Lt, signaling);
+    let eq = gen_fcmp(bb, lhs, rhs, FpComparison::Eq, signaling);
+
+    let flags = |nzcv: u64| IrValue::Constant(IrConstant::B64(nzcv << 28));
+    let nzcv = gen_select(bb, eq, flags(0b0110), flags(0b0010));
+    let nzcv = gen_select(bb, lt, flags(0b1000), nzcv);
+    gen_select(bb, unordered, flags(0b0011), nzcv)
+}
 
-    bb.push_inst(IrInst::Assign {
-        dst: IrValue::Register(IrType::B64, rd),
-        src: off,
+/// Type of the operands of a floating-point compare.
+fn fp_compare_type(ptype: u8) -> Result<IrType, Error> {
+    match ptype {
+        0b00 => Ok(IrType::F32),
+        0b01 => Ok(IrType::F64),
+        0b11 => Ok(IrType::F16),
+        _ => Err(reserved_encoding()),
+    }
+}
+
+/// FCMP and FCMPE, the latter raises the invalid exception for quiet NaNs too.
+fn compile_fcmp(
+    bb: &mut BasicBlock,
+    operand: &FloatingPointCompare,
+    signaling: bool,
+) -> Result<(), Error> {
+    let ty = fp_compare_type(operand.ptype)?;
+
+    let lhs = read_reg(operand.rn, ty);
+    // The compare with zero forms ignore rm
+    let rhs = if operand.opcode2 & 0b01000 != 0 {
+        gen_fp_zero(bb, ty)
+    } else {
+        read_reg(operand.rm, ty)
+    };
+
+    let nzcv = gen_fp_nzcv(bb, lhs, rhs, signaling);
+    gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+
+    Ok(())
+}
+
+/// Set NZCV to the flags of comparing `rn` with `rm` if `cond` holds, and to the immediate
+/// `nzcv` otherwise.
+fn compile_fccmp(
+    bb: &mut BasicBlock,
+    operand: &CondCmpReg,
+    ty: IrType,
+    signaling: bool,
+) -> Result<(), Error> {
+    let holds = gen_condition_holds(bb, operand.cond);
+
+    // Nothing is compared if the condition does not hold, zeroes raise no exceptions
+    let zero = gen_fp_zero(bb, ty);
+    let lhs = gen_select(bb, holds, read_reg(operand.rn, ty), zero);
+    let rhs = gen_select(bb, holds, read_reg(operand.rm, ty), zero);
+    let flags = gen_fp_nzcv(bb, lhs, rhs, signaling);
+
+    let nzcv = IrValue::Constant(IrConstant::B64((operand.nzcv as u64) << 28));
+    let nzcv = gen_select(bb, holds, flags, nzcv);
+    gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+
+    Ok(())
+}
+
+/// Set `rd` to a mask of `mask_ty` with every bit set if `lhs cond rhs` holds, and to zero
+/// otherwise. Only the equality compare is quiet.
+fn gen_fp_compare_mask(
+    bb: &mut BasicBlock,
+    rd: AArch64Register,
+    lhs: IrValue,
+    rhs: IrValue,
+    cond: FpComparison,
+    mask_ty: IrType,
+) {
+    let holds = gen_fcmp(bb, lhs, rhs, cond, cond != FpComparison::Eq);
+
+    let ones = u64::MAX >> (64 - mask_ty.size_of() * 8);
+    let ones = IrValue::Constant(IrConstant::new(mask_ty, ones));
+    let zero = IrValue::Constant(IrConstant::new(mask_ty, 0u64));
+    let mask = gen_select(bb, holds, ones, zero);
+    gen_write_reg(bb, rd, mask);
+}
+
+/// Scalar FCMEQ, FCMGE and FCMGT, the greater forms compare with swapped operands. FACGE and
+/// FACGT compare the absolute values.
+fn compile_fcm_reg(
+    bb: &mut BasicBlock,
+    operand: &RmRnRd,
+    ty: IrType,
+    mask_ty: IrType,
+    cond: FpComparison,
+    absolute: bool,
+) -> Result<(), Error> {
+    let mut lhs = read_reg(operand.rn, ty);
+    let mut rhs = read_reg(operand.rm, ty);
+    if absolute {
+        lhs = gen_fabs(bb, lhs);
+        rhs = gen_fabs(bb, rhs);
+    }
+
+    match cond {
+        FpComparison::Eq => gen_fp_compare_mask(bb, operand.rd, lhs, rhs, cond, mask_ty),
+        _ => gen_fp_compare_mask(bb, operand.rd, rhs, lhs, cond, mask_ty),
+    }
+
+    Ok(())
+}
+
+/// Scalar FCMxx with zero, `swap` compares zero with `rn` to get the greater forms.
+fn compile_fcm_zero(
+    bb: &mut BasicBlock,
+    operand: &RnRd,
+    ty: IrType,
+    mask_ty: IrType,
+    cond: FpComparison,
+    swap: bool,
+) -> Result<(), Error> {
+    let value = read_reg(operand.rn, ty);
+    let zero = gen_fp_zero(bb, ty);
+
+    match swap {
+        true => gen_fp_compare_mask(bb, operand.rd, zero, value, cond, mask_ty),
+        false => gen_fp_compare_mask(bb, operand.rd, value, zero, cond, mask_ty),
+    }
+
+    Ok(())
+}
+
+/// The vector of `8 << size` bit lanes filling 64 bits, or 128 bits if `q` is set.
+fn arrangement(q: u8, size: u8) -> IrType {
+    IrType::Vector(vec_lane(8 << size), (8 << q) >> size)
+}
+
+/// Like `arrangement`, but only the sizes in `sizes` are allowed and a single 64-bit lane is
+/// reserved.
+fn vec_arrangement(q: u8, size: u8, sizes: RangeInclusive<u8>) -> Result<IrType, Error> {
+    if !sizes.contains(&size) || (size == 3 && q == 0) {
+        return Err(reserved_encoding());
+    }
+
+    Ok(arrangement(q, size))
+}
+
+/// Error of a reserved encoding the decoder lets through, the operand fields do not tell the
+/// encoding so the runtime reports the one it fetched.
+fn reserved_encoding() -> Error {
+    Error::UndefinedInstruction(0)
+}
+
+fn vec_lane(bits: u32) -> VecTy {
+    match bits {
+        8 => VecTy::U8,
+        16 => VecTy::U16,
+        32 => VecTy::U32,
+        64 => VecTy::U64,
+        _ => VecTy::U128,
+    }
+}
+
+fn lane_bits(ty: IrType) -> u32 {
+    match ty {
+        IrType::Vector(lane, _) => lane.size_of() as u32 * 8,
+        _ => unreachable!(),
+    }
+}
+
+/// `ty` with the same number of lanes, each `bits` wide.
+fn with_lane_bits(ty: IrType, bits: u32) -> IrType {
+    match ty {
+        IrType::Vector(_, lanes) => IrType::Vector(vec_lane(bits), lanes),
+        _ => unreachable!(),
+    }
+}
+
+/// The integer type of `size` bytes.
+fn int_type(size: usize) -> IrType {
+    match size {
+        1 => IrType::B8,
+        2 => IrType::B16,
+        4 => IrType::B32,
+        8 => IrType::B64,
+        _ => IrType::B128,
+    }
+}
+
+fn const_shift(amount: u32) -> IrValue {
+    IrValue::Constant(IrConstant::B8(amount as u8))
+}
+
+/// Push the instruction `inst` builds around a new variable of type `ty`, return the variable.
+fn gen_op(bb: &mut BasicBlock, ty: IrType, inst: impl FnOnce(IrValue) -> IrInst) -> IrValue {
+    let dst = bb.new_variable(ty);
+    bb.push_inst(inst(dst));
+
+    dst
+}
+
+fn gen_vec_add(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::Add { dst, lhs, rhs })
+}
+
+fn gen_vec_sub(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::Sub { dst, lhs, rhs })
+}
+
+fn gen_vec_mul(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::Mul { dst, lhs, rhs })
+}
+
+fn gen_vec_and(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::And { dst, lhs, rhs })
+}
+
+fn gen_vec_or(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::Or { dst, lhs, rhs })
+}
+
+fn gen_vec_xor(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::Xor { dst, lhs, rhs })
+}
+
+fn gen_vec_not(bb: &mut BasicBlock, src: IrValue) -> IrValue {
+    gen_op(bb, src.ty(), |dst| IrInst::Not { dst, src })
+}
+
+fn gen_vec_shl(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::Shl { dst, lhs, rhs })
+}
+
+/// Arithmetic shift right if `signed`, logical otherwise.
+fn gen_vec_shr(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, signed: bool) -> IrValue {
+    match signed {
+        true => gen_op(bb, lhs.ty(), |dst| IrInst::Ashr { dst, lhs, rhs }),
+        false => gen_op(bb, lhs.ty(), |dst| IrInst::Lshr { dst, lhs, rhs }),
+    }
+}
+
+fn gen_vec_cmp(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, cond: Comparison) -> IrValue {
+    gen_op(bb, lhs.ty(), |dst| IrInst::Cmp {
+        dst,
+        lhs,
+        rhs,
+        cond,
+    })
+}
+
+fn gen_vec_max_min(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    signed: bool,
+    max: bool,
+) -> IrValue {
+    match max {
+        true => gen_op(bb, lhs.ty(), |dst| IrInst::Max {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        }),
+        false => gen_op(bb, lhs.ty(), |dst| IrInst::Min {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        }),
+    }
+}
+
+/// Saturating subtraction if `sub`, saturating addition otherwise.
+fn gen_vec_add_sub_sat(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    signed: bool,
+    sub: bool,
+) -> IrValue {
+    match sub {
+        true => gen_op(bb, lhs.ty(), |dst| IrInst::SubSat {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        }),
+        false => gen_op(bb, lhs.ty(), |dst| IrInst::AddSat {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        }),
+    }
+}
+
+/// A vector of type `ty` with `value` in every lane.
+fn gen_vec_const(bb: &mut BasicBlock, ty: IrType, value: u64) -> IrValue {
+    let lane_ty = int_type((lane_bits(ty) / 8).min(8) as usize);
+    let src = IrValue::Constant(IrConstant::new(lane_ty, value));
+    gen_op(bb, ty, |dst| IrInst::Splat { dst, src })
+}
+
+/// Reinterpret the bits of `src` as `ty`, or truncate its lanes if both have as many lanes.
+fn gen_vec_cast(bb: &mut BasicBlock, src: IrValue, ty: IrType) -> IrValue {
+    gen_op(bb, ty, |dst| IrInst::ZextCast { dst, src })
+}
+
+/// Extend the lanes of `src` to the wider lanes of `ty`.
+fn gen_vec_extend(bb: &mut BasicBlock, src: IrValue, ty: IrType, signed: bool) -> IrValue {
+    match signed {
+        true => gen_op(bb, ty, |dst| IrInst::SextCast { dst, src }),
+        false => gen_op(bb, ty, |dst| IrInst::ZextCast { dst, src }),
+    }
+}
+
+/// Truncate the lanes of `src` to the narrower lanes of `ty`, or clamp them to their range if
+/// `saturate` gives the signedness of `src` and of the result.
+fn gen_vec_narrow(
+    bb: &mut BasicBlock,
+    src: IrValue,
+    ty: IrType,
+    saturate: Option<(bool, bool)>,
+) -> IrValue {
+    match saturate {
+        Some((src_signed, dst_signed)) => gen_op(bb, ty, |dst| IrInst::TruncSat {
+            dst,
+            src,
+            src_signed,
+            dst_signed,
+        }),
+        None => gen_vec_cast(bb, src, ty),
+    }
+}
+
+/// Add `value` to the vector in `rd`, or subtract it if `sub`.
+fn gen_vec_accumulate(
+    bb: &mut BasicBlock,
+    rd: AArch64Register,
+    value: IrValue,
+    sub: bool,
+) -> IrValue {
+    let acc = read_reg(rd, value.ty());
+    match sub {
+        true => gen_vec_sub(bb, acc, value),
+        false => gen_vec_add(bb, acc, value),
+    }
+}
+
+/// The lower or the upper half of the lanes of `value`.
+fn gen_vec_half(bb: &mut BasicBlock, value: IrValue, upper: bool) -> IrValue {
+    let IrType::Vector(lane, lanes) = value.ty() else {
+        unreachable!()
+    };
+    let half_ty = IrType::Vector(lane, lanes / 2);
+    if !upper {
+        return gen_vec_cast(bb, value, half_ty);
+    }
+
+    let size = value.ty().size_of();
+    let bits = gen_vec_cast(bb, value, int_type(size));
+    let high = gen_op(bb, bits.ty(), |dst| IrInst::Lshr {
+        dst,
+        lhs: bits,
+        rhs: const_shift(size as u32 * 4),
     });
+    gen_vec_cast(bb, high, half_ty)
 }
 
-fn compile_adrp(bb: &mut BasicBlock, operand: &PcRelAddressing) {
-    let rd = operand.rd.raw();
-    let pc = AArch64Architecture::get_pc_register().raw();
+/// The vector with the lanes of `low` followed by the lanes of `high`.
+fn gen_vec_concat(bb: &mut BasicBlock, low: IrValue, high: IrValue) -> IrValue {
+    let IrType::Vector(lane, lanes) = low.ty() else {
+        unreachable!()
+    };
+    let size = low.ty().size_of();
+    let low = gen_vec_cast(bb, low, int_type(size * 2));
+    let high = gen_vec_cast(bb, high, int_type(size * 2));
+    let high = gen_op(bb, high.ty(), |dst| IrInst::Shl {
+        dst,
+        lhs: high,
+        rhs: const_shift(size as u32 * 8),
+    });
+    let bits = gen_op(bb, low.ty(), |dst| IrInst::Or {
+        dst,
+        lhs: low,
+        rhs: high,
+    });
 
-    let imm = sign_extend(
-        ((operand.immhi as i64) << 2 | (operand.immlo as i64)) << 12,
-        33,
-    );
+    gen_vec_cast(bb, bits, IrType::Vector(lane, lanes * 2))
+}
+
+/// Read the lower or the upper 64 bits of `reg` as the 64-bit vector `ty`.
+fn read_vec_half(bb: &mut BasicBlock, reg: AArch64Register, ty: IrType, upper: bool) -> IrValue {
+    let IrType::Vector(lane, lanes) = ty else {
+        unreachable!()
+    };
+    match upper {
+        true => gen_vec_half(bb, read_reg(reg, IrType::Vector(lane, lanes * 2)), true),
+        false => read_reg(reg, ty),
+    }
+}
+
+/// Write the 64-bit `value` to the lower half of `rd` clearing the upper one, or to the upper
+/// half of `rd` keeping the lower one.
+fn gen_write_vec_half(bb: &mut BasicBlock, rd: AArch64Register, value: IrValue, upper: bool) {
+    let value = match upper {
+        true => gen_vec_concat(bb, read_reg(rd, value.ty()), value),
+        false => value,
+    };
+    gen_write_reg(bb, rd, value);
+}
+
+/// Apply `op` to `operands`, vectors of the same size, or to each of their 64-bit halves if
+/// they are wider. This leaves `op` room to extend the lanes to twice their width.
+fn gen_by_halves(
+    bb: &mut BasicBlock,
+    operands: &[IrValue],
+    op: impl Fn(&mut BasicBlock, &[IrValue]) -> IrValue,
+) -> IrValue {
+    if operands[0].ty().size_of() <= 8 {
+        return op(bb, operands);
+    }
+
+    let [low, high] = [false, true].map(|upper| {
+        let halves: Vec<_> = operands
+            .iter()
+            .map(|&operand| gen_vec_half(bb, operand, upper))
+            .collect();
+        op(bb, &halves)
+    });
+    gen_vec_concat(bb, low, high)
+}
+
+/// `(lhs + rhs) >> 1` without losing the carry, rounded up if `rounding`.
+fn gen_halving_add(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    signed: bool,
+    rounding: bool,
+) -> IrValue {
+    let diff = gen_vec_xor(bb, lhs, rhs);
+    let half_diff = gen_vec_shr(bb, diff, const_shift(1), signed);
+    if rounding {
+        let either = gen_vec_or(bb, lhs, rhs);
+        gen_vec_sub(bb, either, half_diff)
+    } else {
+        let both = gen_vec_and(bb, lhs, rhs);
+        gen_vec_add(bb, both, half_diff)
+    }
+}
+
+/// `(lhs - rhs) >> 1` without losing the borrow.
+fn gen_halving_sub(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, signed: bool) -> IrValue {
+    let diff = gen_vec_xor(bb, lhs, rhs);
+    let half_diff = gen_vec_shr(bb, diff, const_shift(1), signed);
+    let not_lhs = gen_vec_not(bb, lhs);
+    let borrow = gen_vec_and(bb, not_lhs, rhs);
+    gen_vec_sub(bb, half_diff, borrow)
+}
+
+fn gen_abs_diff(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, signed: bool) -> IrValue {
+    let max = gen_vec_max_min(bb, lhs, rhs, signed, true);
+    let min = gen_vec_max_min(bb, lhs, rhs, signed, false);
+    gen_vec_sub(bb, max, min)
+}
+
+/// The shifts by register, shift the lanes of `value` left by the signed low byte of the lanes
+/// of `amount`, a negative amount shifts right. Right shifts are rounded if `rounding`, as by
+/// SRSHL and URSHL, and left shifts saturate if `saturating`, as by SQSHL and UQSHL.
+fn gen_shl_by_lanes(
+    bb: &mut BasicBlock,
+    value: IrValue,
+    amount: IrValue,
+    signed: bool,
+    rounding: bool,
+    saturating: bool,
+) -> IrValue {
+    let ty = value.ty();
+    let bits = lane_bits(ty);
+    let amount = match bits {
+        8 => amount,
+        _ => {
+            let amount = gen_vec_shl(bb, amount, const_shift(bits - 8));
+            gen_vec_shr(bb, amount, const_shift(bits - 8), true)
+        }
+    };
+
+    let zero = gen_vec_const(bb, ty, 0);
+    let right_mask = gen_vec_cmp(bb, amount, zero, Comparison::Slt);
+    let left_mask = gen_vec_not(bb, right_mask);
+    let negated = gen_vec_sub(bb, zero, amount);
+
+    // Lanes shifted by at least their width are cleared, or filled with their sign
+    let left = gen_vec_shl(bb, value, amount);
+    let mut right = gen_vec_shr(bb, value, negated, signed);
+    if rounding {
+        // The last bit shifted out rounds, it cancels the sign filling signed lanes shifted by
+        // more than their width
+        let one = gen_vec_const(bb, ty, 1);
+        let last = gen_vec_sub(bb, negated, one);
+        let last = gen_vec_shr(bb, value, last, signed);
+        let round = gen_vec_and(bb, last, one);
+        right = gen_vec_add(bb, right, round);
+    }
+    let shifted_left = gen_vec_and(bb, left, left_mask);
+    let right = gen_vec_and(bb, right, right_mask);
+    let result = gen_vec_or(bb, shifted_left, right);
+    if !saturating {
+        return result;
+    }
+
+    // A left shift overflowed if shifting the lane back does not give it again
+    let back = gen_vec_shr(bb, left, amount, signed);
+    let overflow = gen_vec_cmp(bb, back, value, Comparison::Ne);
+    let overflow = gen_vec_and(bb, overflow, left_mask);
+    let limit = match signed {
+        // The largest lane for positive lanes and the smallest one for negative ones
+        true => {
+            let sign = gen_vec_shr(bb, value, const_shift(bits - 1), true);
+            let max = gen_vec_const(bb, ty, (1 << (bits - 1)) - 1);
+            gen_vec_xor(bb, sign, max)
+        }
+        false => gen_vec_not(bb, zero),
+    };
+    let limit = gen_vec_and(bb, limit, overflow);
+    let kept = gen_vec_not(bb, overflow);
+    let kept = gen_vec_and(bb, result, kept);
+    let result = gen_vec_or(bb, kept, limit);
+    // Adding the saturated lanes to themselves keeps them and sets FPSR.QC
+    gen_vec_add_sub_sat(bb, result, limit, signed, false)
+}
+
+/// `value >> amount` rounded to nearest with ties upwards, it cannot overflow.
+fn gen_rounding_shr(bb: &mut BasicBlock, value: IrValue, amount: u32, signed: bool) -> IrValue {
+    let shifted = gen_vec_shr(bb, value, const_shift(amount), signed);
+    let below = gen_vec_shr(bb, value, const_shift(amount - 1), false);
+    let one = gen_vec_const(bb, value.ty(), 1);
+    let round = gen_vec_and(bb, below, one);
+    gen_vec_add(bb, shifted, round)
+}
+
+/// Apply `op` to the even and the odd lanes of `value`.
+fn gen_vec_pairs(
+    bb: &mut BasicBlock,
+    value: IrValue,
+    op: &impl Fn(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> IrValue {
+    let IrType::Vector(lane, lanes) = value.ty() else {
+        unreachable!()
+    };
+    let bits = lane_bits(value.ty());
+    let half_ty = IrType::Vector(lane, lanes / 2);
+
+    let pairs = gen_vec_cast(bb, value, IrType::Vector(vec_lane(bits * 2), lanes / 2));
+    let even = gen_vec_cast(bb, pairs, half_ty);
+    let odd = gen_vec_shr(bb, pairs, const_shift(bits), false);
+    let odd = gen_vec_cast(bb, odd, half_ty);
+    op(bb, even, odd)
+}
+
+/// The pairwise instructions, `op` combines adjacent lanes of `lhs` into the lower half of the
+/// result and adjacent lanes of `rhs` into the upper one.
+fn gen_pairwise(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    op: impl Fn(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> IrValue {
+    let low = gen_vec_pairs(bb, lhs, &op);
+    let high = gen_vec_pairs(bb, rhs, &op);
+    gen_vec_concat(bb, low, high)
+}
+
+/// The sums of adjacent lanes of `value` as lanes of twice their width.
+fn gen_add_pairs_long(bb: &mut BasicBlock, value: IrValue, signed: bool) -> IrValue {
+    let IrType::Vector(_, lanes) = value.ty() else {
+        unreachable!()
+    };
+    let bits = lane_bits(value.ty());
+
+    let pairs = gen_vec_cast(bb, value, IrType::Vector(vec_lane(bits * 2), lanes / 2));
+    let even = gen_vec_shl(bb, pairs, const_shift(bits));
+    let even = gen_vec_shr(bb, even, const_shift(bits), signed);
+    let odd = gen_vec_shr(bb, pairs, const_shift(bits), signed);
+    gen_vec_add(bb, even, odd)
+}
+
+/// SQDMULH and SQRDMULH, the high halves of the doubled products of the lanes, saturated.
+fn gen_sqdmulh(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, rounding: bool) -> IrValue {
+    gen_by_halves(bb, &[lhs, rhs], |bb, halves| {
+        let ty = halves[0].ty();
+        let bits = lane_bits(ty);
+        let wide_ty = with_lane_bits(ty, bits * 2);
+
+        let lhs = gen_vec_extend(bb, halves[0], wide_ty, true);
+        let rhs = gen_vec_extend(bb, halves[1], wide_ty, true);
+        let mut product = gen_vec_mul(bb, lhs, rhs);
+        if rounding {
+            let round = gen_vec_const(bb, wide_ty, 1 << (bits - 2));
+            product = gen_vec_add(bb, product, round);
+        }
+
+        // Shifting the undoubled product one bit less leaves just -1 * -1 out of range
+        let high = gen_vec_shr(bb, product, const_shift(bits - 1), true);
+        gen_vec_narrow(bb, high, ty, Some((true, true)))
+    })
+}
+
+/// SQDMULL, the doubled products of the extended lanes of `lhs` and `rhs`, saturated. They only
+/// overflow for -1 * -1 of the narrow lanes.
+fn gen_sqdmull(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue) -> IrValue {
+    let product = gen_vec_mul(bb, lhs, rhs);
+    gen_vec_add_sub_sat(bb, product, product, true, false)
+}
+
+/// SQDMLAL and SQDMLSL, add the saturated doubled products of `lhs` and `rhs` to the lanes of
+/// `rd`, or subtract them if `sub`, saturating again.
+fn gen_sqdmlal(
+    bb: &mut BasicBlock,
+    rd: AArch64Register,
+    lhs: IrValue,
+    rhs: IrValue,
+    sub: bool,
+) -> IrValue {
+    let product = gen_sqdmull(bb, lhs, rhs);
+    let acc = read_reg(rd, product.ty());
+    gen_vec_add_sub_sat(bb, acc, product, true, sub)
+}
+
+/// SUQADD and USQADD, add the lanes of `value` to the ones of `rd` read with the opposite
+/// signedness, saturating to the range of the lanes of `rd` which are signed if `signed`.
+fn gen_mixed_sign_accumulate(
+    bb: &mut BasicBlock,
+    rd: AArch64Register,
+    value: IrValue,
+    signed: bool,
+) -> IrValue {
+    let acc = read_reg(rd, value.ty());
+    gen_by_halves(bb, &[acc, value], |bb, halves| {
+        let ty = halves[0].ty();
+        let wide_ty = with_lane_bits(ty, lane_bits(ty) * 2);
+
+        let acc = gen_vec_extend(bb, halves[0], wide_ty, signed);
+        let value = gen_vec_extend(bb, halves[1], wide_ty, !signed);
+        // The sums of the extended lanes are in the range of the signed wide lanes
+        let sum = gen_vec_add(bb, acc, value);
+        gen_vec_narrow(bb, sum, ty, Some((true, signed)))
+    })
+}
+
+/// The carry-less products of the lanes of `lhs` and `rhs`, whose lowest `bits` bits are
+/// multiplied.
+fn gen_polynomial_mul(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, bits: u32) -> IrValue {
+    let ty = lhs.ty();
+    let zero = gen_vec_const(bb, ty, 0);
+
+    (0..bits).fold(zero, |product, i| {
+        let bit = gen_vec_const(bb, ty, 1 << i);
+        let bit = gen_vec_and(bb, rhs, bit);
+        let mask = gen_vec_cmp(bb, bit, zero, Comparison::Ne);
+        let term = gen_vec_shl(bb, lhs, const_shift(i));
+        let term = gen_vec_and(bb, term, mask);
+        gen_vec_xor(bb, product, term)
+    })
+}
+
+/// Reverse the order of the lanes of `value` within every `container` bits.
+fn gen_vec_rev(bb: &mut BasicBlock, value: IrValue, container: u32) -> IrValue {
+    let ty = value.ty();
+    let containers = ty.size_of() as u32 * 8 / container;
+
+    // Swapping the bytes of the containers leaves the bytes of each lane swapped as well
+    let src = gen_vec_cast(bb, value, IrType::Vector(vec_lane(container), containers));
+    let swapped = gen_op(bb, src.ty(), |dst| IrInst::Bswap { dst, src });
+    let src = gen_vec_cast(bb, swapped, ty);
+    match lane_bits(ty) {
+        8 => src,
+        _ => gen_op(bb, ty, |dst| IrInst::Bswap { dst, src }),
+    }
+}
 
-    let t1 = bb.new_variable(IrType::B64);
-    bb.push_inst(IrInst::And {
-        dst: t1,
-        lhs: IrValue::Register(IrType::B64, pc),
-        rhs: IrValue::Constant(IrConstant::new(IrType::B64, 0xFFFF_FFFF_FFFF_F000u64)),
-    });
-    bb.push_inst(IrInst::Add {
-        dst: IrValue::Register(IrType::B64, rd),
-        lhs: t1,
-        rhs: IrValue::Constant(IrConstant::new(IrType::B64, imm)),
-    });
+/// Advanced SIMD three same instructions, `op` gets the vectors of `rn` and `rm`.
+fn compile_vec_three_same(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    sizes: RangeInclusive<u8>,
+    op: impl FnOnce(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let ty = vec_arrangement(operand.q, operand.size, sizes)?;
+    let result = op(bb, read_reg(operand.rn, ty), read_reg(operand.rm, ty));
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_rev_var(bb: &mut BasicBlock, operand: &RnRd, ty: IrType) {
-    todo!()
+/// The bitwise three same instructions, whose size field is part of the opcode. `op` gets the
+/// vectors of `rd`, `rn` and `rm`.
+fn compile_vec_bitwise(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    op: impl FnOnce(&mut BasicBlock, IrValue, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let ty = arrangement(operand.q, 0);
+    let result = op(
+        bb,
+        read_reg(operand.rd, ty),
+        read_reg(operand.rn, ty),
+        read_reg(operand.rm, ty),
+    );
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_ldr_imm(bb: &mut BasicBlock, operand: &OpcSizeImm12RnRt, ty: IrType) {
-    let (wback, post_index, offset) = decode_operand_for_ld_st_reg_imm(operand, false);
+/// Advanced SIMD three different instructions with wide results. `op` gets the lanes of the
+/// halves of `rn` and `rm` selected by `q` extended to twice their width, or all of `rn` if it
+/// is `wide`.
+fn compile_vec_long(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    sizes: RangeInclusive<u8>,
+    signed: bool,
+    wide: bool,
+    op: impl FnOnce(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    if !sizes.contains(&operand.size) {
+        return Err(reserved_encoding());
+    }
 
-    let address = bb.new_variable(ty);
+    let ty = arrangement(0, operand.size);
+    let wide_ty = with_lane_bits(ty, lane_bits(ty) * 2);
+    let upper = operand.q == 1;
 
-    let offset_temp = if !post_index { offset } else { 0 };
+    let lhs = match wide {
+        true => read_reg(operand.rn, wide_ty),
+        false => {
+            let lhs = read_vec_half(bb, operand.rn, ty, upper);
+            gen_vec_extend(bb, lhs, wide_ty, signed)
+        }
+    };
+    let rhs = read_vec_half(bb, operand.rm, ty, upper);
+    let rhs = gen_vec_extend(bb, rhs, wide_ty, signed);
 
-    bb.push_inst(IrInst::Add {
-        dst: address,
-        lhs: IrValue::Constant(IrConstant::B64(offset_temp as u64)),
-        rhs: IrValue::Register(ty, operand.rn.raw()),
-    });
+    let result = op(bb, lhs, rhs);
+    gen_write_reg(bb, operand.rd, result);
 
-    let data = bb.new_variable(ty);
+    Ok(())
+}
 
-    bb.push_inst(IrInst::Load {
-        dst: data,
-        src: address,
-    });
+/// ADDHN, SUBHN and their rounding forms, the high halves of the wide sums or differences go to
+/// the half of `rd` selected by `q`.
+fn compile_vec_add_sub_high(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    sub: bool,
+    rounding: bool,
+) -> Result<(), Error> {
+    if operand.size == 3 {
+        return Err(reserved_encoding());
+    }
 
-    bb.push_inst(IrInst::ZextCast {
-        dst: IrValue::Register(ty, operand.rt.raw()),
-        src: data,
-    });
+    let ty = arrangement(0, operand.size);
+    let bits = lane_bits(ty);
+    let wide_ty = with_lane_bits(ty, bits * 2);
 
-    if wback {
-        bb.push_inst(IrInst::Assign {
-            dst: IrValue::Register(ty, operand.rn.raw()),
-            src: address,
-        })
+    let lhs = read_reg(operand.rn, wide_ty);
+    let rhs = read_reg(operand.rm, wide_ty);
+    let mut result = match sub {
+        true => gen_vec_sub(bb, lhs, rhs),
+        false => gen_vec_add(bb, lhs, rhs),
+    };
+    if rounding {
+        let round = gen_vec_const(bb, wide_ty, 1 << (bits - 1));
+        result = gen_vec_add(bb, result, round);
     }
-}
 
-fn compile_ldr_imm_simd_fp(bb: &mut BasicBlock, operand: &OpcSizeImm12RnRt, ty: IrType) {
-    todo!()
-}
+    let high = gen_vec_shr(bb, result, const_shift(bits), false);
+    let high = gen_vec_narrow(bb, high, ty, None);
+    gen_write_vec_half(bb, operand.rd, high, operand.q == 1);
 
-fn compile_ldr_lit_var64(bb: &mut BasicBlock, operand: &Imm19Rt) {
-    todo!()
+    Ok(())
 }
 
-fn compile_ldrh_imm(bb: &mut BasicBlock, operand: &OpcSizeImm12RnRt) {
-    todo!()
-}
+fn compile_pmull(bb: &mut BasicBlock, operand: &QSizeRmRnRd) -> Result<(), Error> {
+    if !matches!(operand.size, 0 | 3) {
+        return Err(reserved_encoding());
+    }
 
-fn compile_ldrb_imm(bb: &mut BasicBlock, operand: &OpcSizeImm12RnRt) {
-    todo!()
+    compile_vec_long(bb, operand, 0..=3, false, false, |bb, lhs, rhs| {
+        gen_polynomial_mul(bb, lhs, rhs, 8 << operand.size)
+    })
 }
 
-fn compile_ldr_reg(bb: &mut BasicBlock, operand: &LoadStoreRegRegOffset, ty: IrType) {
-    todo!()
+/// The operands of the Advanced SIMD by element instructions. The scalar forms only compute the
+/// lowest lane.
+struct ElementOperand {
+    q: u8,
+    scalar: bool,
+    size: u8,
+    l: u8,
+    m: u8,
+    h: u8,
+    rm: AArch64Register,
+    rn: AArch64Register,
+    rd: AArch64Register,
 }
 
-fn compile_ldrb_reg_shifted_reg(bb: &mut BasicBlock, operand: &LoadStoreRegRegOffset) {
-    todo!()
+impl From<&AdvSimdXIndexedElem> for ElementOperand {
+    fn from(operand: &AdvSimdXIndexedElem) -> Self {
+        ElementOperand {
+            q: operand.q,
+            scalar: false,
+            size: operand.size,
+            l: operand.l,
+            m: operand.m,
+            h: operand.h,
+            rm: operand.rm,
+            rn: operand.rn,
+            rd: operand.rd,
+        }
+    }
 }
 
-fn compile_ldp(bb: &mut BasicBlock, operand: &LoadStoreRegPair, ty: IrType) {
-    todo!()
+impl From<&AdvSimdScalarXIndexedElem> for ElementOperand {
+    fn from(operand: &AdvSimdScalarXIndexedElem) -> Self {
+        ElementOperand {
+            q: 1,
+            scalar: true,
+            size: operand.size,
+            l: operand.l,
+            m: operand.m,
+            h: operand.h,
+            rm: operand.rm,
+            rn: operand.rn,
+            rd: operand.rd,
+        }
+    }
 }
 
-fn compile_ldrsh_reg(bb: &mut BasicBlock, operand: &LoadStoreRegRegOffset, ty: IrType) {
-    todo!()
-}
+impl ElementOperand {
+    /// The vector type `ty`, or its lowest lane for the scalar forms.
+    fn lanes(&self, ty: IrType) -> IrType {
+        match (self.scalar, ty) {
+            (true, IrType::Vector(lane, _)) => IrType::Vector(lane, 1),
+            _ => ty,
+        }
+    }
 
-fn compile_ldaxr(bb: &mut BasicBlock, operand: &RsRt2RnRt, ty: IrType) {
-    todo!()
-}
+    /// Whether the long forms read the upper half of `rn`, as the second part forms do.
+    fn upper(&self) -> bool {
+        !self.scalar && self.q == 1
+    }
 
-fn compile_ldar(bb: &mut BasicBlock, operand: &RsRt2RnRt, ty: IrType) {
-    todo!()
+    /// A vector of type `ty` with the element of `rm` in every lane. 16-bit elements are indexed
+    /// by H:L:M in V0-V15, 32-bit ones by H:L and 64-bit ones by H.
+    fn gen_element(&self, bb: &mut BasicBlock, ty: IrType) -> Result<IrValue, Error> {
+        let AArch64Register::V(rm) = self.rm else {
+            unreachable!()
+        };
+        let bits = lane_bits(ty);
+        let (rm, index) = match bits {
+            16 => (rm, self.h << 2 | self.l << 1 | self.m),
+            32 => (self.m << 4 | rm, self.h << 1 | self.l),
+            64 if self.l == 0 => (self.m << 4 | rm, self.h),
+            _ => return Err(reserved_encoding()),
+        };
+
+        let size = (bits / 8).trailing_zeros() as u8;
+        let src = gen_extract_lane(bb, AArch64Register::V(rm), size, index);
+        Ok(gen_op(bb, ty, |dst| IrInst::Splat { dst, src }))
+    }
 }
 
-fn compile_ldur(bb: &mut BasicBlock, operand: &LdStRegUnscaledImm, ty: IrType) {
-    todo!()
+/// The integer by element instructions, `op` gets the lanes of `rn` and a vector with the
+/// element of `rm` in every lane.
+fn compile_vec_by_elem(
+    bb: &mut BasicBlock,
+    operand: ElementOperand,
+    op: impl FnOnce(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let ty = operand.lanes(vec_arrangement(operand.q, operand.size, 1..=2)?);
+    let element = operand.gen_element(bb, ty)?;
+    let result = op(bb, read_reg(operand.rn, ty), element);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_ldp_simd_fp(bb: &mut BasicBlock, operand: &LoadStoreRegPair, ty: IrType) {
-    todo!()
-}
+/// The integer by element instructions with wide results. `op` gets the lanes of the half of
+/// `rn` selected by `q` and the element of `rm`, extended to twice their width.
+fn compile_vec_long_by_elem(
+    bb: &mut BasicBlock,
+    operand: ElementOperand,
+    signed: bool,
+    op: impl FnOnce(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    if !(1..=2).contains(&operand.size) {
+        return Err(reserved_encoding());
+    }
 
-fn compile_ldr_reg_simd_fp(bb: &mut BasicBlock, operand: &LoadStoreRegRegOffset) {
-    todo!()
-}
+    let ty = operand.lanes(arrangement(0, operand.size));
+    let wide_ty = with_lane_bits(ty, lane_bits(ty) * 2);
 
-fn compile_ldxr(bb: &mut BasicBlock, operand: &RsRt2RnRt, ty: IrType) {
-    todo!()
+    let lhs = read_vec_half(bb, operand.rn, ty, operand.upper());
+    let lhs = gen_vec_extend(bb, lhs, wide_ty, signed);
+    let rhs = operand.gen_element(bb, ty)?;
+    let rhs = gen_vec_extend(bb, rhs, wide_ty, signed);
+
+    let result = op(bb, lhs, rhs);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_str_imm(bb: &mut BasicBlock, operand: &OpcSizeImm12RnRt, ty: IrType) {
-    let (wback, post_index, offset) = decode_operand_for_ld_st_reg_imm(operand, false);
+/// The size field and the raw shift of an Advanced SIMD shift by immediate, the size is given
+/// by the highest set bit of `immh` and the shift by `immh:immb`.
+fn shift_imm_size(operand: &AdvSimdShiftByImm) -> (u8, u32) {
+    let size = 7 - operand.immh.leading_zeros() as u8;
+    (size, (operand.immh as u32) << 3 | operand.immb as u32)
+}
 
-    let address = bb.new_variable(ty);
+/// SSHR, USHR, their rounding forms, and the accumulating SSRA, USRA, SRSRA and URSRA.
+fn compile_vec_shr_imm(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdShiftByImm,
+    signed: bool,
+    rounding: bool,
+    accumulate: bool,
+) -> Result<(), Error> {
+    let (size, imm) = shift_imm_size(operand);
+    let ty = vec_arrangement(operand.q, size, 0..=3)?;
+    let shift = (16 << size) - imm;
+
+    let value = read_reg(operand.rn, ty);
+    let mut result = match rounding {
+        true => gen_rounding_shr(bb, value, shift, signed),
+        false => gen_vec_shr(bb, value, const_shift(shift), signed),
+    };
+    if accumulate {
+        result = gen_vec_accumulate(bb, operand.rd, result, false);
+    }
+    gen_write_reg(bb, operand.rd, result);
 
-    let offset_temp = if !post_index { offset } else { 0 };
+    Ok(())
+}
 
-    let rn = IrValue::Register(ty, operand.rn.raw());
+fn compile_vec_shl_imm(bb: &mut BasicBlock, operand: &AdvSimdShiftByImm) -> Result<(), Error> {
+    let (size, imm) = shift_imm_size(operand);
+    let ty = vec_arrangement(operand.q, size, 0..=3)?;
+    let shift = imm - (8 << size);
 
-    bb.push_inst(IrInst::Add {
-        dst: address,
-        lhs: rn,
-        rhs: IrValue::Constant(IrConstant::new(ty, offset_temp)),
-    });
+    let result = gen_vec_shl(bb, read_reg(operand.rn, ty), const_shift(shift));
+    gen_write_reg(bb, operand.rd, result);
 
-    bb.push_inst(IrInst::Fence(Reordering::Relaxed));
+    Ok(())
+}
 
-    bb.push_inst(IrInst::Store {
-        dst: address,
-        src: IrValue::Register(ty, operand.rt.raw()),
+/// SQSHL, UQSHL and SQSHLU by an immediate, the lanes are shifted at twice their width and
+/// saturated back.
+fn compile_vec_qshl_imm(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdShiftByImm,
+    src_signed: bool,
+    dst_signed: bool,
+) -> Result<(), Error> {
+    let (size, imm) = shift_imm_size(operand);
+    let ty = vec_arrangement(operand.q, size, 0..=3)?;
+    let bits = 8 << size;
+    let shift = imm - bits;
+
+    let value = read_reg(operand.rn, ty);
+    let result = gen_by_halves(bb, &[value], |bb, halves| {
+        let ty = halves[0].ty();
+        let wide = gen_vec_extend(bb, halves[0], with_lane_bits(ty, bits * 2), src_signed);
+        let shifted = gen_vec_shl(bb, wide, const_shift(shift));
+        gen_vec_narrow(bb, shifted, ty, Some((src_signed, dst_signed)))
     });
+    gen_write_reg(bb, operand.rd, result);
 
-    if wback {
-        bb.push_inst(IrInst::Assign {
-            dst: rn,
-            src: address,
-        })
-    }
+    Ok(())
 }
 
-fn compile_stp_var(bb: &mut BasicBlock, operand: &LoadStoreRegPair, ty: IrType) {
-    todo!()
+/// SRI and SLI, the shifted lanes of `rn` replace the bits of `rd` they cover.
+fn compile_vec_shift_insert(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdShiftByImm,
+    left: bool,
+) -> Result<(), Error> {
+    let (size, imm) = shift_imm_size(operand);
+    let ty = vec_arrangement(operand.q, size, 0..=3)?;
+    let bits = 8 << size;
+
+    let value = read_reg(operand.rn, ty);
+    let ones = gen_vec_const(bb, ty, u64::MAX >> (64 - bits));
+    let (shifted, mask) = match left {
+        true => {
+            let shift = const_shift(imm - bits);
+            (gen_vec_shl(bb, value, shift), gen_vec_shl(bb, ones, shift))
+        }
+        false => {
+            let shift = const_shift(2 * bits - imm);
+            (
+                gen_vec_shr(bb, value, shift, false),
+                gen_vec_shr(bb, ones, shift, false),
+            )
+        }
+    };
+
+    let mask = gen_vec_not(bb, mask);
+    let kept = gen_vec_and(bb, read_reg(operand.rd, ty), mask);
+    let result = gen_vec_or(bb, kept, shifted);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_strb_imm(bb: &mut BasicBlock, operand: &OpcSizeImm12RnRt) {
-    todo!()
+/// The shift right narrow instructions, the shifted lanes of `rn` narrowed to half their width
+/// go to the half of `rd` selected by `q`. `saturate` gives the signedness of the result if it
+/// saturates rather than truncates.
+fn compile_vec_shr_narrow(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdShiftByImm,
+    signed: bool,
+    rounding: bool,
+    saturate: Option<bool>,
+) -> Result<(), Error> {
+    let (size, imm) = shift_imm_size(operand);
+    if size == 3 {
+        return Err(reserved_encoding());
+    }
+
+    let ty = arrangement(0, size);
+    let bits = lane_bits(ty);
+    let shift = 2 * bits - imm;
+
+    let value = read_reg(operand.rn, with_lane_bits(ty, bits * 2));
+    let shifted = match rounding {
+        true => gen_rounding_shr(bb, value, shift, signed),
+        false => gen_vec_shr(bb, value, const_shift(shift), signed),
+    };
+    let result = gen_vec_narrow(bb, shifted, ty, saturate.map(|dst| (signed, dst)));
+    gen_write_vec_half(bb, operand.rd, result, operand.q == 1);
+
+    Ok(())
 }
 
-fn compile_sturb_imm(bb: &mut BasicBlock, operand: &LdStRegUnscaledImm) {
-    todo!()
+/// SSHLL, USHLL and SHLL, the lanes of the half of `rn` selected by `q` are extended to twice
+/// their width and shifted left by `shift`.
+fn gen_shift_left_long(
+    bb: &mut BasicBlock,
+    rn: AArch64Register,
+    rd: AArch64Register,
+    q: u8,
+    size: u8,
+    shift: u32,
+    signed: bool,
+) {
+    let ty = arrangement(0, size);
+    let value = read_vec_half(bb, rn, ty, q == 1);
+    let value = gen_vec_extend(bb, value, with_lane_bits(ty, lane_bits(ty) * 2), signed);
+    let result = gen_vec_shl(bb, value, const_shift(shift));
+    gen_write_reg(bb, rd, result);
 }
 
-fn compile_str_reg(bb: &mut BasicBlock, operand: &LoadStoreRegRegOffset, ty: IrType) {
-    todo!()
+fn compile_vec_shll_imm(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdShiftByImm,
+    signed: bool,
+) -> Result<(), Error> {
+    let (size, imm) = shift_imm_size(operand);
+    if size == 3 {
+        return Err(reserved_encoding());
+    }
+
+    let shift = imm - (8 << size);
+    gen_shift_left_long(bb, operand.rn, operand.rd, operand.q, size, shift, signed);
+
+    Ok(())
 }
 
-fn compile_stur(bb: &mut BasicBlock, operand: &LdStRegUnscaledImm, ty: IrType) {
-    todo!()
+fn compile_shll(bb: &mut BasicBlock, operand: &QSizeRnRd) -> Result<(), Error> {
+    if operand.size == 3 {
+        return Err(reserved_encoding());
+    }
+
+    let shift = 8 << operand.size;
+    gen_shift_left_long(
+        bb,
+        operand.rn,
+        operand.rd,
+        operand.q,
+        operand.size,
+        shift,
+        false,
+    );
+
+    Ok(())
 }
 
-fn compile_stur_simd_fp(bb: &mut BasicBlock, operand: &LdStRegUnscaledImm, ty: IrType) {
-    todo!()
+/// Advanced SIMD two-register miscellaneous instructions, `op` gets the vector of `rn`.
+fn compile_vec_two_reg(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    sizes: RangeInclusive<u8>,
+    op: impl FnOnce(&mut BasicBlock, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let ty = vec_arrangement(operand.q, operand.size, sizes)?;
+    let result = op(bb, read_reg(operand.rn, ty));
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_stp_simd_fp(bb: &mut BasicBlock, operand: &LoadStoreRegPair, ty: IrType) {
-    todo!()
+/// NOT and RBIT, whose size field is part of the opcode.
+fn compile_vec_bitwise_unary(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    op: impl FnOnce(&mut BasicBlock, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let result = op(bb, read_reg(operand.rn, arrangement(operand.q, 0)));
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_str_imm_simd_fp(bb: &mut BasicBlock, operand: &OpcSizeImm12RnRt, ty: IrType) {
-    todo!()
+/// XTN and the saturating extract narrow instructions, the lanes of `rn` narrowed to half their
+/// width go to the half of `rd` selected by `q`.
+fn compile_vec_narrow(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    saturate: Option<(bool, bool)>,
+) -> Result<(), Error> {
+    if operand.size == 3 {
+        return Err(reserved_encoding());
+    }
+
+    let ty = arrangement(0, operand.size);
+    let value = read_reg(operand.rn, with_lane_bits(ty, lane_bits(ty) * 2));
+    let result = gen_vec_narrow(bb, value, ty, saturate);
+    gen_write_vec_half(bb, operand.rd, result, operand.q == 1);
+
+    Ok(())
 }
 
-fn compile_str_reg_simd_fp(bb: &mut BasicBlock, operand: &LoadStoreRegRegOffset) {
-    todo!()
+/// Advanced SIMD across lanes instructions, `op` combines the lanes of `rn` into the scalar
+/// written to `rd`. The long forms first add adjacent lanes at twice their width, signed if
+/// `long` is `Some(true)`.
+fn compile_vec_reduce(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    long: Option<bool>,
+    op: impl Fn(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    if operand.size == 3 || (operand.size == 2 && operand.q == 0) {
+        return Err(reserved_encoding());
+    }
+
+    let mut value = read_reg(operand.rn, arrangement(operand.q, operand.size));
+    if let Some(signed) = long {
+        value = gen_add_pairs_long(bb, value, signed);
+    }
+    while let IrType::Vector(_, 2..) = value.ty() {
+        let low = gen_vec_half(bb, value, false);
+        let high = gen_vec_half(bb, value, true);
+        value = op(bb, low, high);
+    }
+    gen_write_reg(bb, operand.rd, value);
+
+    Ok(())
 }
 
-fn compile_stlxr(bb: &mut BasicBlock, operand: &RsRt2RnRt, ty: IrType) {
-    todo!()
+fn compile_addp_scalar(bb: &mut BasicBlock, operand: &AdvSimdScalarPairwise) -> Result<(), Error> {
+    if operand.size != 3 {
+        return Err(reserved_encoding());
+    }
+
+    let value = read_reg(operand.rn, IrType::Vector(VecTy::U64, 2));
+    let low = gen_vec_half(bb, value, false);
+    let high = gen_vec_half(bb, value, true);
+    let result = gen_vec_add(bb, low, high);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_stxr(bb: &mut BasicBlock, operand: &RsRt2RnRt, ty: IrType) {
-    todo!()
+/// The immediate `a:b:c:d:e:f:g:h` of an Advanced SIMD modified immediate.
+fn adv_simd_imm8(operand: &AdvSimdModifiedImm) -> u8 {
+    [
+        operand.a, operand.b, operand.c, operand.d, operand.e, operand.f, operand.g, operand.h,
+    ]
+    .into_iter()
+    .fold(0, |imm8, bit| imm8 << 1 | bit)
 }
 
-fn compile_strb_reg(bb: &mut BasicBlock, operand: &LoadStoreRegRegOffset) {
-    todo!()
+/// AdvSIMDExpandImm, the 64-bit pattern an Advanced SIMD modified immediate stands for.
+fn adv_simd_expand_imm(operand: &AdvSimdModifiedImm) -> u64 {
+    let imm8 = adv_simd_imm8(operand) as u64;
+    let replicate32 = |imm: u64| imm << 32 | imm;
+    let replicate16 = |imm: u64| imm * 0x0001_0001_0001_0001;
+
+    match operand.cmode {
+        0b0000 | 0b0001 => replicate32(imm8),
+        0b0010 | 0b0011 => replicate32(imm8 << 8),
+        0b0100 | 0b0101 => replicate32(imm8 << 16),
+        0b0110 | 0b0111 => replicate32(imm8 << 24),
+        0b1000 | 0b1001 => replicate16(imm8),
+        0b1010 | 0b1011 => replicate16(imm8 << 8),
+        0b1100 => replicate32(imm8 << 8 | 0xFF),
+        0b1101 => replicate32(imm8 << 16 | 0xFFFF),
+        _ if operand.op == 0 => imm8 * 0x0101_0101_0101_0101,
+        // Every bit of imm8 stands for a byte
+        _ => (0..8)
+            .filter(|i| imm8 >> i & 1 != 0)
+            .fold(0, |imm, i| imm | 0xFF << (i * 8)),
+    }
 }
 
-fn compile_dup_general(bb: &mut BasicBlock, operand: &AdvancedSimdCopy) {
-    todo!()
+/// MOVI, and MVNI if `invert`, which writes the inverted pattern.
+fn compile_movi(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdModifiedImm,
+    invert: bool,
+) -> Result<(), Error> {
+    let imm = match invert {
+        true => !adv_simd_expand_imm(operand),
+        false => adv_simd_expand_imm(operand),
+    };
+
+    let ty = IrType::Vector(VecTy::U64, 1 + operand.q as u32);
+    let value = gen_vec_const(bb, ty, imm);
+    gen_write_reg(bb, operand.rd, value);
+
+    Ok(())
 }
 
-fn compile_add_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) {
-    let imm = if operand.sh == 0b0 {
-        operand.imm12 as u32
-    } else {
-        (operand.imm12 as u32) << 12
+/// ORR with an immediate, or BIC if `bic`, which clears the bits of the pattern.
+fn compile_vec_orr_bic_imm(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdModifiedImm,
+    bic: bool,
+) -> Result<(), Error> {
+    let ty = IrType::Vector(VecTy::U64, 1 + operand.q as u32);
+    let value = read_reg(operand.rd, ty);
+    let imm = adv_simd_expand_imm(operand);
+
+    let result = match bic {
+        true => {
+            let imm = gen_vec_const(bb, ty, !imm);
+            gen_vec_and(bb, value, imm)
+        }
+        false => {
+            let imm = gen_vec_const(bb, ty, imm);
+            gen_vec_or(bb, value, imm)
+        }
     };
+    gen_write_reg(bb, operand.rd, result);
 
-    let operand1 = IrValue::Register(ty, operand.rn.raw());
+    Ok(())
+}
 
-    let result = bb.new_variable(ty);
+/// DUP from a general-purpose register, the lowest set bit of `imm5` gives the size of the
+/// lanes.
+fn compile_dup_general(bb: &mut BasicBlock, operand: &AdvancedSimdCopy) -> Result<(), Error> {
+    let size = operand.imm5.trailing_zeros() as u8;
+    let ty = vec_arrangement(operand.q, size, 0..=3)?;
 
-    if operand.rd == AArch64Register::Sp {
-        bb.push_inst(IrInst::ZextCast {
-            dst: result,
-            src: result,
-        })
-    }
+    let src = read_reg(operand.rn, int_type(1 << size));
+    let value = gen_op(bb, ty, |dst| IrInst::Splat { dst, src });
+    gen_write_reg(bb, operand.rd, value);
 
-    bb.push_inst(IrInst::Add {
-        dst: IrValue::Register(ty, operand.rd.raw()),
-        lhs: operand1,
-        rhs: IrValue::Constant(IrConstant::new(ty, imm)),
-    });
+    Ok(())
 }
 
-fn compile_adds_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) {
-    todo!()
+/// The size of the lanes and the index that `imm5` of the Advanced SIMD copy instructions
+/// encodes.
+fn copy_lane(operand: &AdvancedSimdCopy) -> Result<(u8, u8), Error> {
+    match operand.imm5.trailing_zeros() as u8 {
+        size @ 0..=3 => Ok((size, operand.imm5 >> (size + 1))),
+        _ => Err(reserved_encoding()),
+    }
 }
 
-fn compile_add_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    todo!()
+fn gen_extract_lane(bb: &mut BasicBlock, reg: AArch64Register, size: u8, lane: u8) -> IrValue {
+    let src = read_reg(reg, arrangement(1, size));
+    gen_op(bb, int_type(1 << size), |dst| IrInst::ExtractLane {
+        dst,
+        src,
+        lane,
+    })
 }
 
-fn compile_adds_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    todo!()
+/// Replace the lane `lane` of `rd` with the scalar `value`, keeping the other lanes.
+fn gen_insert_lane(bb: &mut BasicBlock, rd: AArch64Register, value: IrValue, lane: u8) {
+    let ty = arrangement(1, value.ty().size_of().trailing_zeros() as u8);
+    let src = read_reg(rd, ty);
+    let result = gen_op(bb, ty, |dst| IrInst::InsertLane {
+        dst,
+        src,
+        value,
+        lane,
+    });
+    gen_write_reg(bb, rd, result);
 }
 
-fn compile_add_ext_reg(bb: &mut BasicBlock, operand: &AddSubtractExtReg, ty: IrType) {
-    todo!()
+fn compile_dup_element(bb: &mut BasicBlock, operand: &AdvancedSimdCopy) -> Result<(), Error> {
+    let (size, lane) = copy_lane(operand)?;
+    let ty = vec_arrangement(operand.q, size, 0..=3)?;
+
+    let src = gen_extract_lane(bb, operand.rn, size, lane);
+    let value = gen_op(bb, ty, |dst| IrInst::Splat { dst, src });
+    gen_write_reg(bb, operand.rd, value);
+
+    Ok(())
 }
 
-fn compile_sub_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) {
-    let imm = if operand.sh == 0b0 {
-        operand.imm12 as u32
-    } else {
-        (operand.imm12 as u32) << 12
+/// SMOV and UMOV, move a lane to a W register, or to a X register if `q` is set.
+fn compile_mov_to_general(
+    bb: &mut BasicBlock,
+    operand: &AdvancedSimdCopy,
+    signed: bool,
+) -> Result<(), Error> {
+    let (size, lane) = copy_lane(operand)?;
+    // SMOV sign extends lanes narrower than the register, UMOV moves lanes narrower than a W
+    // register or as wide as a X register
+    let reserved = match (signed, operand.q) {
+        (true, q) => size > q + 1,
+        (false, 0) => size > 2,
+        (false, _) => size != 3,
     };
+    if reserved {
+        return Err(reserved_encoding());
+    }
 
-    let rn = IrValue::Register(ty, operand.rn.raw());
+    let value = gen_extract_lane(bb, operand.rn, size, lane);
+    let value = match signed {
+        true => {
+            let ty = [IrType::B32, IrType::B64][operand.q as usize];
+            gen_op(bb, ty, |dst| IrInst::SextCast { dst, src: value })
+        }
+        false => value,
+    };
+    gen_write_reg(bb, operand.rd, value);
 
-    let result = bb.new_variable(ty);
-    bb.push_inst(IrInst::Sub {
-        dst: result,
-        lhs: rn,
-        rhs: IrValue::Constant(IrConstant::new(ty, imm)),
-    });
+    Ok(())
+}
 
-    if operand.rd == AArch64Register::Sp {
-        bb.push_inst(IrInst::ZextCast {
-            dst: rn,
-            src: result,
-        })
-    } else {
-        bb.push_inst(IrInst::Assign {
-            dst: rn,
-            src: result,
-        })
-    }
+fn compile_ins_general(bb: &mut BasicBlock, operand: &AdvancedSimdCopy) -> Result<(), Error> {
+    let (size, lane) = copy_lane(operand)?;
+
+    let value = read_reg(operand.rn, int_type(1 << size));
+    gen_insert_lane(bb, operand.rd, value, lane);
+
+    Ok(())
 }
 
-fn compile_sub_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    let shift_type = decode_shift(operand.shift);
-    let shift_amount = operand.imm6;
+fn compile_ins_element(bb: &mut BasicBlock, operand: &AdvancedSimdCopy) -> Result<(), Error> {
+    let (size, lane) = copy_lane(operand)?;
 
-    let operand1 = IrValue::Register(ty, operand.rn.raw());
-    let operand2 = bb.new_variable(ty);
+    let value = gen_extract_lane(bb, operand.rn, size, operand.imm4 >> size);
+    gen_insert_lane(bb, operand.rd, value, lane);
 
-    bb.push_inst(shift_reg(
-        IrValue::Register(ty, operand.rm.raw()),
-        shift_type,
-        IrValue::Constant(IrConstant::B8(shift_amount)),
-        ty,
-        operand2,
-    ));
+    Ok(())
+}
 
-    bb.push_inst(IrInst::Not {
-        dst: operand2,
-        src: operand2,
+/// ZIP, UZP and TRN, `lane` maps the number of lanes and a lane of the result to the lane of
+/// `rn` followed by `rm` that it takes.
+fn compile_vec_permute(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    lane: impl Fn(u8, u8) -> u8,
+) -> Result<(), Error> {
+    let ty = vec_arrangement(operand.q, operand.size, 0..=3)?;
+    let IrType::Vector(_, lanes) = ty else {
+        unreachable!()
+    };
+    let lanes = lanes as u8;
+
+    let result = gen_op(bb, ty, |dst| IrInst::Shuffle {
+        dst,
+        lhs: read_reg(operand.rn, ty),
+        rhs: read_reg(operand.rm, ty),
+        lanes: (0..lanes).map(|i| lane(lanes, i)).collect(),
     });
+    gen_write_reg(bb, operand.rd, result);
 
-    bb.push_inst(IrInst::Sub {
-        dst: IrValue::Register(ty, operand.rd.raw()),
-        lhs: operand1,
-        rhs: operand2,
-    })
+    Ok(())
 }
 
-fn compile_subs_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    todo!()
-}
+/// EXT, the bytes of `rn` followed by those of `rm`, starting from byte `imm4`.
+fn compile_ext(bb: &mut BasicBlock, operand: &AdvancedSimdExtract) -> Result<(), Error> {
+    let bytes = 8 << operand.q;
+    if operand.imm4 >= bytes {
+        return Err(reserved_encoding());
+    }
 
-fn compile_subs_ext_reg(bb: &mut BasicBlock, operand: &AddSubtractExtReg, ty: IrType) {
-    todo!()
-}
+    let ty = IrType::Vector(VecTy::U8, bytes as u32);
+    let result = gen_op(bb, ty, |dst| IrInst::Shuffle {
+        dst,
+        lhs: read_reg(operand.rn, ty),
+        rhs: read_reg(operand.rm, ty),
+        lanes: (operand.imm4..operand.imm4 + bytes).collect(),
+    });
+    gen_write_reg(bb, operand.rd, result);
 
-fn compile_subs_imm(bb: &mut BasicBlock, operand: &ShImm12RnRd, ty: IrType) {
-    todo!()
+    Ok(())
 }
 
-fn compile_madd(bb: &mut BasicBlock, operand: &DataProc3Src, ty: IrType) {
-    todo!()
+/// TBL and TBX, look up the bytes of `rm` in the table of `len + 1` consecutive registers from
+/// `rn`. Bytes out of the table are zero, or kept from `rd` for TBX.
+fn compile_table_lookup(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdTableLookup,
+    keep: bool,
+) -> Result<(), Error> {
+    let ty = IrType::Vector(VecTy::U8, 8 << operand.q);
+    let indices = read_reg(operand.rm, ty);
+
+    // Every register of the table looks up the indices moved down to its first byte, those
+    // below wrap around out of its range
+    let mut result = None;
+    for i in 0..=operand.len {
+        let table = read_reg(
+            AArch64Register::V((operand.rn + i) % 32),
+            IrType::Vector(VecTy::U8, 16),
+        );
+        let indices = match i {
+            0 => indices,
+            _ => {
+                let offset = gen_vec_const(bb, ty, i as u64 * 16);
+                gen_vec_sub(bb, indices, offset)
+            }
+        };
+        let bytes = gen_op(bb, ty, |dst| IrInst::Swizzle {
+            dst,
+            src: table,
+            indices,
+        });
+        result = Some(match result {
+            Some(result) => gen_vec_or(bb, result, bytes),
+            None => bytes,
+        });
+    }
+    let mut result = result.unwrap();
+
+    if keep {
+        let table_size = gen_vec_const(bb, ty, (operand.len as u64 + 1) * 16);
+        let outside = gen_vec_cmp(bb, indices, table_size, Comparison::Uge);
+        let kept = gen_vec_and(bb, read_reg(operand.rd, ty), outside);
+        result = gen_vec_or(bb, result, kept);
+    }
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_msub(bb: &mut BasicBlock, operand: &DataProc3Src, ty: IrType) {
-    todo!()
+/// The vector of single-precision lanes filling 64 bits, or 128 bits if `q` is set, or of
+/// double-precision lanes if the low bit of `size` is set. A single double-precision lane is
+/// reserved. `half` selects the 4H and 8H arrangements of the half-precision forms, which do
+/// not encode them in `size`.
+fn fp_arrangement(q: u8, size: u8, half: bool) -> Result<IrType, Error> {
+    match (half, size & 1, q) {
+        (true, _, _) => Ok(IrType::Vector(VecTy::F16, 4 << q)),
+        (false, 0, _) => Ok(IrType::Vector(VecTy::F32, 2 << q)),
+        (false, _, 1) => Ok(IrType::Vector(VecTy::F64, 2)),
+        _ => Err(reserved_encoding()),
+    }
 }
 
-fn compile_div(bb: &mut BasicBlock, operand: &DataProc2Src, ty: IrType) {
-    todo!()
+/// Whether `inst` is the half-precision form of an Advanced SIMD floating-point instruction,
+/// which shares the lowering of the single and double-precision one.
+fn half_precision(inst: &AArch64Inst) -> bool {
+    matches!(
+        inst,
+        AArch64Inst::FaddVecHalfPrecision(_)
+            | AArch64Inst::FsubVecHalfPrecision(_)
+            | AArch64Inst::FmulVecHalfPrecision(_)
+            | AArch64Inst::FdivVecHalfPrecision(_)
+            | AArch64Inst::FmaxVecHalfPrecision(_)
+            | AArch64Inst::FminVecHalfPrecision(_)
+            | AArch64Inst::FmaxnmVecHalfPrecision(_)
+            | AArch64Inst::FminnmVecHalfPrecision(_)
+            | AArch64Inst::FrecpsVecHalfPrecision(_)
+            | AArch64Inst::FrsqrtsVecHalfPrecision(_)
+            | AArch64Inst::FmlaVecHalfPrecision(_)
+            | AArch64Inst::FmlsVecHalfPrecision(_)
+            | AArch64Inst::FabdVecHalfPrecision(_)
+            | AArch64Inst::FcmeqRegVecHalfPrecision(_)
+            | AArch64Inst::FcmgeRegVecHalfPrecision(_)
+            | AArch64Inst::FcmgtRegVecHalfPrecision(_)
+            | AArch64Inst::FacgeVecHalfPrecision(_)
+            | AArch64Inst::FacgtVecHalfPrecision(_)
+            | AArch64Inst::FaddpVecHalfPrecision(_)
+            | AArch64Inst::FmaxpVecHalfPrecision(_)
+            | AArch64Inst::FminpVecHalfPrecision(_)
+            | AArch64Inst::FmaxnmpVecHalfPrecision(_)
+            | AArch64Inst::FminnmpVecHalfPrecision(_)
+            | AArch64Inst::FabsVecHalfPrecision(_)
+            | AArch64Inst::FnegVecHalfPrecision(_)
+            | AArch64Inst::FsqrtVecHalfPrecision(_)
+            | AArch64Inst::FrecpeVecHalfPrecision(_)
+            | AArch64Inst::FrsqrteVecHalfPrecision(_)
+            | AArch64Inst::FrintnVecHalfPrecision(_)
+            | AArch64Inst::FrintpVecHalfPrecision(_)
+            | AArch64Inst::FrintmVecHalfPrecision(_)
+            | AArch64Inst::FrintzVecHalfPrecision(_)
+            | AArch64Inst::FrintaVecHalfPrecision(_)
+            | AArch64Inst::FrintxVecHalfPrecision(_)
+            | AArch64Inst::FrintiVecHalfPrecision(_)
+            | AArch64Inst::FcvtnsVecHalfPrecision(_)
+            | AArch64Inst::FcvtnuVecHalfPrecision(_)
+            | AArch64Inst::FcvtasVecHalfPrecision(_)
+            | AArch64Inst::FcvtauVecHalfPrecision(_)
+            | AArch64Inst::FcvtpsVecHalfPrecision(_)
+            | AArch64Inst::FcvtpuVecHalfPrecision(_)
+            | AArch64Inst::FcvtmsVecHalfPrecision(_)
+            | AArch64Inst::FcvtmuVecHalfPrecision(_)
+            | AArch64Inst::FcvtzsVecHalfPrecision(_)
+            | AArch64Inst::FcvtzuVecHalfPrecision(_)
+            | AArch64Inst::ScvtfVecHalfPrecision(_)
+            | AArch64Inst::UcvtfVecHalfPrecision(_)
+            | AArch64Inst::FcmeqZeroVecHalfPrecision(_)
+            | AArch64Inst::FcmgeZeroVecHalfPrecision(_)
+            | AArch64Inst::FcmgtZeroVecHalfPrecision(_)
+            | AArch64Inst::FcmleZeroVecHalfPrecision(_)
+            | AArch64Inst::FcmltZeroVecHalfPrecision(_)
+            | AArch64Inst::FaddpScalarHalfPrecision(_)
+            | AArch64Inst::FmaxpScalarHalfPrecision(_)
+            | AArch64Inst::FminpScalarHalfPrecision(_)
+            | AArch64Inst::FmaxnmpScalarHalfPrecision(_)
+            | AArch64Inst::FminnmpScalarHalfPrecision(_)
+            | AArch64Inst::FmulxVecHalfPrecision(_)
+            | AArch64Inst::FmaxvHalfPrecision(_)
+            | AArch64Inst::FminvHalfPrecision(_)
+            | AArch64Inst::FmaxnmvHalfPrecision(_)
+            | AArch64Inst::FminnmvHalfPrecision(_)
+    )
 }
 
-fn compile_ubfm(bb: &mut BasicBlock, operand: &Bitfield, ty: IrType) {
-    todo!()
+/// The vector of integer lanes as wide as the floating-point lanes of `ty`.
+fn fp_mask_type(ty: IrType) -> IrType {
+    with_lane_bits(ty, lane_bits(ty))
 }
 
-fn compile_sbfm(bb: &mut BasicBlock, operand: &Bitfield, ty: IrType) {
-    todo!()
+/// `rd + lhs * rhs` with a single rounding, or `rd - lhs * rhs` if `sub`, which negates `lhs`.
+fn gen_fp_vec_fma(
+    bb: &mut BasicBlock,
+    rd: AArch64Register,
+    lhs: IrValue,
+    rhs: IrValue,
+    sub: bool,
+) -> IrValue {
+    let lhs = match sub {
+        true => gen_fneg(bb, lhs),
+        false => lhs,
+    };
+    let addend = read_reg(rd, lhs.ty());
+    gen_op(bb, lhs.ty(), |dst| IrInst::Fma {
+        dst,
+        lhs,
+        rhs,
+        addend,
+    })
 }
 
-fn compile_and_imm(bb: &mut BasicBlock, operand: &LogicalImm, ty: IrType) {
-    todo!()
+/// Compare the lanes of `lhs` with those of `rhs` into a mask, only the equality compare is
+/// quiet.
+fn gen_fp_vec_mask(bb: &mut BasicBlock, lhs: IrValue, rhs: IrValue, cond: FpComparison) -> IrValue {
+    gen_op(bb, fp_mask_type(lhs.ty()), |dst| IrInst::Fcmp {
+        dst,
+        lhs,
+        rhs,
+        cond,
+        signaling: cond != FpComparison::Eq,
+    })
 }
 
-fn compile_ands_imm(bb: &mut BasicBlock, operand: &LogicalImm, ty: IrType) {
-    todo!()
+fn compile_fp_vec_three_same(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    half: bool,
+    op: impl FnOnce(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let ty = fp_arrangement(operand.q, operand.size, half)?;
+    let result = op(bb, read_reg(operand.rn, ty), read_reg(operand.rm, ty));
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_ands_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    todo!()
+/// The floating-point three same instructions that are a single instruction of the IR.
+fn compile_fp_vec_binary(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    half: bool,
+    op: fn(IrValue, IrValue, IrValue) -> IrInst,
+) -> Result<(), Error> {
+    compile_fp_vec_three_same(bb, operand, half, |bb, lhs, rhs| {
+        gen_op(bb, lhs.ty(), |dst| op(dst, lhs, rhs))
+    })
 }
 
-fn compile_and_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    todo!()
+/// FCMEQ, FCMGE and FCMGT of vectors, the greater forms compare with swapped operands. FACGE
+/// and FACGT compare the absolute values.
+fn compile_fp_vec_compare(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    half: bool,
+    cond: FpComparison,
+    absolute: bool,
+) -> Result<(), Error> {
+    compile_fp_vec_three_same(bb, operand, half, |bb, mut lhs, mut rhs| {
+        if absolute {
+            lhs = gen_fabs(bb, lhs);
+            rhs = gen_fabs(bb, rhs);
+        }
+
+        match cond {
+            FpComparison::Eq => gen_fp_vec_mask(bb, lhs, rhs, cond),
+            _ => gen_fp_vec_mask(bb, rhs, lhs, cond),
+        }
+    })
 }
 
-fn compile_orr_imm(bb: &mut BasicBlock, operand: &LogicalImm, ty: IrType) {
-    todo!()
+/// FCMxx of vectors with zero, `swap` compares zero with `rn` to get the greater forms.
+fn compile_fp_vec_compare_zero(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    half: bool,
+    cond: FpComparison,
+    swap: bool,
+) -> Result<(), Error> {
+    compile_fp_vec_unary_with(bb, operand, half, |bb, value| {
+        let zero = gen_fp_zero(bb, value.ty());
+        match swap {
+            true => gen_fp_vec_mask(bb, zero, value, cond),
+            false => gen_fp_vec_mask(bb, value, zero, cond),
+        }
+    })
 }
 
-fn compile_orr_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    let shift_type = decode_shift(operand.shift);
-    let shift_amount = operand.imm6;
+/// The floating-point pairwise instructions, `op` combines adjacent lanes of `rn` into the
+/// lower half of the result and adjacent lanes of `rm` into the upper one.
+fn compile_fp_vec_pairwise(
+    bb: &mut BasicBlock,
+    operand: &QSizeRmRnRd,
+    half: bool,
+    op: fn(IrValue, IrValue, IrValue) -> IrInst,
+) -> Result<(), Error> {
+    compile_fp_vec_three_same(bb, operand, half, |bb, lhs, rhs| {
+        gen_pairwise(bb, lhs, rhs, |bb, even, odd| {
+            gen_op(bb, even.ty(), |dst| op(dst, even, odd))
+        })
+    })
+}
 
-    let operand1 = IrValue::Register(ty, operand.rn.raw());
-    let operand2 = bb.new_variable(ty);
+/// FMLA, FMLS, FMUL and FMULX by element, `op` gets the lanes of `rn` and a vector with the
+/// element of `rm` in every lane.
+fn compile_fp_vec_by_elem(
+    bb: &mut BasicBlock,
+    operand: ElementOperand,
+    op: impl FnOnce(&mut BasicBlock, IrValue, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let ty = operand.lanes(fp_arrangement(operand.q, operand.size, false)?);
+    let element = operand.gen_element(bb, ty)?;
+    let result = op(bb, read_reg(operand.rn, ty), element);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
 
-    bb.push_inst(shift_reg(
-        IrValue::Register(ty, operand.rm.raw()),
-        shift_type,
-        IrValue::Constant(IrConstant::B8(shift_amount)),
-        ty,
-        operand2,
-    ));
+fn compile_fp_vec_unary_with(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    half: bool,
+    op: impl FnOnce(&mut BasicBlock, IrValue) -> IrValue,
+) -> Result<(), Error> {
+    let ty = fp_arrangement(operand.q, operand.size, half)?;
+    let result = op(bb, read_reg(operand.rn, ty));
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
+}
 
-    bb.push_inst(IrInst::Or {
-        dst: IrValue::Register(ty, operand.rd.raw()),
-        lhs: operand1,
-        rhs: operand2,
+/// The floating-point two-register miscellaneous instructions that are a single instruction
+/// of the IR.
+fn compile_fp_vec_unary(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    half: bool,
+    op: fn(IrValue, IrValue) -> IrInst,
+) -> Result<(), Error> {
+    compile_fp_vec_unary_with(bb, operand, half, |bb, src| {
+        gen_op(bb, src.ty(), |dst| op(dst, src))
     })
 }
 
-fn compile_orn_shifted_reg(bb: &mut BasicBlock, operand: &ShiftRmImm6RnRd, ty: IrType) {
-    todo!()
+/// Convert the floating-point lanes of `rn` to integers of the same width.
+fn compile_fp_vec_to_int(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    half: bool,
+    signed: bool,
+    rounding: FpRounding,
+) -> Result<(), Error> {
+    compile_fp_vec_unary_with(bb, operand, half, |bb, src| {
+        gen_op(bb, fp_mask_type(src.ty()), |dst| IrInst::FpToInt {
+            dst,
+            src,
+            signed,
+            frac_bits: 0,
+            rounding,
+        })
+    })
 }
 
-fn compile_lslv(bb: &mut BasicBlock, operand: &DataProc2Src, ty: IrType) {
-    todo!()
+/// Convert the integer lanes of `rn` to floating-point values of the same width.
+fn compile_int_to_fp_vec(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    half: bool,
+    signed: bool,
+) -> Result<(), Error> {
+    let ty = fp_arrangement(operand.q, operand.size, half)?;
+    let result = gen_op(bb, ty, |dst| IrInst::IntToFp {
+        dst,
+        src: read_reg(operand.rn, fp_mask_type(ty)),
+        signed,
+        frac_bits: 0,
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_bl_imm(bb: &mut BasicBlock, operand: &Imm26) {
-    let offset = sign_extend((operand.imm26 as i64) << 2, 28) as u64;
+/// The floating-point arrangement of the fixed-point conversions and their number of fraction
+/// bits, `immh` gives the size of the lanes.
+fn fixed_point_arrangement(operand: &AdvSimdShiftByImm) -> Result<(IrType, u8), Error> {
+    let (size, imm) = shift_imm_size(operand);
+    let ty = match (size, operand.q) {
+        (2, q) => IrType::Vector(VecTy::F32, 2 << q),
+        (3, 1) => IrType::Vector(VecTy::F64, 2),
+        _ => return Err(reserved_encoding()),
+    };
 
-    let x_30 = IrValue::Register(IrType::B64, AArch64Register::X(30).raw());
-    bb.push_inst(IrInst::Assign {
-        dst: x_30,
-        src: IrValue::Register(IrType::B64, AArch64Register::Pc.raw()),
-    });
+    Ok((ty, ((16 << size) - imm) as u8))
+}
 
-    let temp = bb.new_variable(IrType::B64);
-    bb.push_inst(IrInst::Add {
-        dst: temp,
-        lhs: IrValue::Register(IrType::B64, AArch64Register::Pc.raw()),
-        rhs: IrValue::Constant(IrConstant::B64(offset)),
+/// FCVTZS and FCVTZU of vectors to fixed-point lanes, rounding toward zero.
+fn compile_fp_to_fixed_vec(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdShiftByImm,
+    signed: bool,
+) -> Result<(), Error> {
+    let (ty, frac_bits) = fixed_point_arrangement(operand)?;
+    let result = gen_op(bb, fp_mask_type(ty), |dst| IrInst::FpToInt {
+        dst,
+        src: read_reg(operand.rn, ty),
+        signed,
+        frac_bits,
+        rounding: FpRounding::TowardZero,
     });
+    gen_write_reg(bb, operand.rd, result);
 
-    bb.set_terminator(BasicBlockTerminator::Branch(temp));
+    Ok(())
 }
 
-fn compile_b_imm(bb: &mut BasicBlock, operand: &Imm26) {
-    todo!()
+/// SCVTF and UCVTF of vectors of fixed-point lanes.
+fn compile_fixed_to_fp_vec(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdShiftByImm,
+    signed: bool,
+) -> Result<(), Error> {
+    let (ty, frac_bits) = fixed_point_arrangement(operand)?;
+    let result = gen_op(bb, ty, |dst| IrInst::IntToFp {
+        dst,
+        src: read_reg(operand.rn, fp_mask_type(ty)),
+        signed,
+        frac_bits,
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_br(bb: &mut BasicBlock, operand: &UncondBranchReg) {
-    todo!()
+/// The half-precision or single-precision lanes of FCVTL and FCVTN, and the lanes twice as
+/// wide they convert from or to.
+fn fcvt_long_arrangement(size: u8) -> (IrType, IrType) {
+    match size & 1 {
+        0 => (IrType::Vector(VecTy::F16, 4), IrType::Vector(VecTy::F32, 4)),
+        _ => (IrType::Vector(VecTy::F32, 2), IrType::Vector(VecTy::F64, 2)),
+    }
 }
 
-fn compile_blr(bb: &mut BasicBlock, operand: &UncondBranchReg) {
-    todo!()
+/// FCVTL, the lanes of the half of `rn` selected by `q` converted to twice their width.
+fn compile_fcvtl(bb: &mut BasicBlock, operand: &QSizeRnRd) -> Result<(), Error> {
+    let (narrow, wide) = fcvt_long_arrangement(operand.size);
+    let value = read_vec_half(bb, operand.rn, narrow, operand.q == 1);
+    let result = gen_op(bb, wide, |dst| IrInst::FpCast {
+        dst,
+        src: value,
+        rounding: FpRounding::Current,
+    });
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_b_cond(bb: &mut BasicBlock, operand: &Imm19Cond) {
-    todo!()
+/// FCVTN and FCVTXN, the lanes of `rn` converted to half their width with `rounding` go to the
+/// half of `rd` selected by `q`.
+fn compile_fcvtn(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    rounding: FpRounding,
+) -> Result<(), Error> {
+    let (narrow, wide) = fcvt_long_arrangement(operand.size);
+    let result = gen_op(bb, narrow, |dst| IrInst::FpCast {
+        dst,
+        src: read_reg(operand.rn, wide),
+        rounding,
+    });
+    gen_write_vec_half(bb, operand.rd, result, operand.q == 1);
+
+    Ok(())
 }
 
-fn compile_cbz(bb: &mut BasicBlock, operand: &Imm19Rt, ty: IrType) {
-    todo!()
+/// Combine the adjacent lanes of `value` with `op` until one is left, in the order of the
+/// Reduce pseudocode. NaNs are propagated as if lanes were combined one pair at a time.
+fn gen_fp_reduce(
+    bb: &mut BasicBlock,
+    mut value: IrValue,
+    op: fn(IrValue, IrValue, IrValue) -> IrInst,
+) -> IrValue {
+    while let IrType::Vector(_, 2..) = value.ty() {
+        value = gen_vec_pairs(bb, value, &|bb, even, odd| {
+            gen_op(bb, even.ty(), |dst| op(dst, even, odd))
+        });
+    }
+
+    value
 }
 
-fn compile_cbnz(bb: &mut BasicBlock, operand: &Imm19Rt, ty: IrType) {
-    todo!()
+/// FMAXV, FMINV, FMAXNMV and FMINNMV, which only have the 4S arrangement besides the
+/// half-precision ones.
+fn compile_fp_vec_reduce(
+    bb: &mut BasicBlock,
+    operand: &QSizeRnRd,
+    half: bool,
+    op: fn(IrValue, IrValue, IrValue) -> IrInst,
+) -> Result<(), Error> {
+    if !half && (operand.q == 0 || operand.size & 1 == 1) {
+        return Err(reserved_encoding());
+    }
+
+    let value = read_reg(operand.rn, fp_arrangement(operand.q, operand.size, half)?);
+    let result = gen_fp_reduce(bb, value, op);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_ret(bb: &mut BasicBlock, operand: &UncondBranchReg) {
-    todo!()
+/// The floating-point scalar pairwise instructions, which combine the two lanes of `rn`.
+fn compile_fp_pairwise_scalar(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdScalarPairwise,
+    half: bool,
+    op: fn(IrValue, IrValue, IrValue) -> IrInst,
+) -> Result<(), Error> {
+    let lane = match (half, operand.size & 1) {
+        (true, _) => VecTy::F16,
+        (false, 0) => VecTy::F32,
+        (false, _) => VecTy::F64,
+    };
+
+    let value = read_reg(operand.rn, IrType::Vector(lane, 2));
+    let result = gen_fp_reduce(bb, value, op);
+    gen_write_reg(bb, operand.rd, result);
+
+    Ok(())
 }
 
-fn compile_tbz(bb: &mut BasicBlock, operand: &B5B40Imm14Rt) {
-    todo!()
+/// FMOV of an immediate to every lane of a vector, `imm8` expands like the scalar FMOV does.
+fn compile_fmov_vec_imm(
+    bb: &mut BasicBlock,
+    operand: &AdvSimdModifiedImm,
+    lane: VecTy,
+) -> Result<(), Error> {
+    let (ty, lanes) = match lane {
+        VecTy::F64 => (IrType::F64, 2),
+        _ => (IrType::F32, 2 << operand.q),
+    };
+
+    let src = IrValue::Constant(vfp_expand_imm(adv_simd_imm8(operand), ty));
+    let value = gen_op(bb, IrType::Vector(lane, lanes), |dst| IrInst::Splat {
+        dst,
+        src,
+    });
+    gen_write_reg(bb, operand.rd, value);
+
+    Ok(())
 }
 
-fn compile_tbnz(bb: &mut BasicBlock, operand: &B5B40Imm14Rt) {
-    todo!()
+/// The operands of the Advanced SIMD structure loads and stores. `post_index` is the register
+/// the post-index forms advance `rn` by.
+struct StructureOperand {
+    q: u8,
+    s: u8,
+    size: u8,
+    rn: AArch64Register,
+    rt: u8,
+    post_index: Option<AArch64Register>,
 }
 
-fn compile_ccmp_imm(bb: &mut BasicBlock, operand: &CondCmpImm, ty: IrType) {
-    todo!()
+impl From<&AdvSimdLdStMultiStructures> for StructureOperand {
+    fn from(operand: &AdvSimdLdStMultiStructures) -> Self {
+        StructureOperand {
+            q: operand.q,
+            s: 0,
+            size: operand.size,
+            rn: operand.rn,
+            rt: operand.rt,
+            post_index: None,
+        }
+    }
 }
 
-fn compile_ccmp_reg(bb: &mut BasicBlock, operand: &CondCmpReg, ty: IrType) {
-    todo!()
+impl From<&AdvSimdLdStMultiStructuresPostIndexed> for StructureOperand {
+    fn from(operand: &AdvSimdLdStMultiStructuresPostIndexed) -> Self {
+        StructureOperand {
+            q: operand.q,
+            s: 0,
+            size: operand.size,
+            rn: operand.rn,
+            rt: operand.rt,
+            post_index: Some(operand.rm),
+        }
+    }
 }
 
-fn compile_ccmn_imm(bb: &mut BasicBlock, operand: &CondCmpImm, ty: IrType) {
-    todo!()
+impl From<&AdvSimdLdStSingleStructure> for StructureOperand {
+    fn from(operand: &AdvSimdLdStSingleStructure) -> Self {
+        StructureOperand {
+            q: operand.q,
+            s: operand.s,
+            size: operand.size,
+            rn: operand.rn,
+            rt: operand.rt,
+            post_index: None,
+        }
+    }
 }
 
-fn compile_csel(bb: &mut BasicBlock, operand: &RmCondRnRd, ty: IrType) {
-    todo!()
+impl From<&AdvSimdLdStSingleStructurePostIndexed> for StructureOperand {
+    fn from(operand: &AdvSimdLdStSingleStructurePostIndexed) -> Self {
+        StructureOperand {
+            q: operand.q,
+            s: operand.s,
+            size: operand.size,
+            rn: operand.rn,
+            rt: operand.rt,
+            post_index: Some(operand.rm),
+        }
+    }
 }
 
-fn compile_csinv(bb: &mut BasicBlock, operand: &RmCondRnRd, ty: IrType) {
-    todo!()
+impl StructureOperand {
+    /// Register `i` of the list of registers from `rt`, which wraps around after V31.
+    fn reg(&self, i: u8) -> AArch64Register {
+        AArch64Register::V((self.rt + i) % 32)
+    }
+
+    /// How the instruction indexes `rn`, XZR post-indexes by the `size` bytes it transfers.
+    fn indexing(&self, size: usize) -> Indexing {
+        match self.post_index {
+            None => Indexing::Offset(0),
+            Some(AArch64Register::Xzr) => Indexing::PostIndex(size as i64),
+            Some(rm) => Indexing::PostIndexReg(rm),
+        }
+    }
 }
 
-fn compile_svc(bb: &mut BasicBlock, operand: &ExceptionGen) {
-    todo!()
+fn gen_offset_address(bb: &mut BasicBlock, address: IrValue, offset: usize) -> IrValue {
+    match offset {
+        0 => address,
+        _ => gen_op(bb, IrType::B64, |dst| IrInst::Add {
+            dst,
+            lhs: address,
+            rhs: IrValue::Constant(IrConstant::B64(offset as u64)),
+        }),
+    }
 }
 
-fn compile_brk(bb: &mut BasicBlock, operand: &ExceptionGen) {
-    todo!()
+/// The vector whose lane `i` is lane `lane(i).1` of `sources[lane(i).0]`, vectors of the same
+/// type.
+fn gen_gather_lanes(
+    bb: &mut BasicBlock,
+    sources: &[IrValue],
+    lane: impl Fn(u8) -> (usize, u8),
+) -> IrValue {
+    let ty = sources[0].ty();
+    let IrType::Vector(_, lanes) = ty else {
+        unreachable!()
+    };
+    let lanes = lanes as u8;
+
+    // Every source fills its lanes into the result so far, the first one is taken as it is
+    let mut result = sources[0];
+    for (k, &source) in sources.iter().enumerate().skip(1) {
+        let shuffle = (0..lanes)
+            .map(|i| match lane(i) {
+                (from, j) if from == k => lanes + j,
+                (0, j) if k == 1 => j,
+                _ => i,
+            })
+            .collect();
+        result = gen_op(bb, ty, |dst| IrInst::Shuffle {
+            dst,
+            lhs: result,
+            rhs: source,
+            lanes: shuffle,
+        });
+    }
+
+    result
 }
 
-fn compile_mrs(bb: &mut BasicBlock, operand: &SysRegMov) {
-    todo!()
+/// LD1-LD4 and ST1-ST4 (multiple structures). `rpt` times, `selem` registers are loaded from or
+/// stored to consecutive structures of `selem` elements, one element of every register each.
+fn compile_ld_st_multi_structures(
+    bb: &mut BasicBlock,
+    operand: StructureOperand,
+    rpt: u8,
+    selem: u8,
+    load: bool,
+) -> Result<(), Error> {
+    if selem > 1 && operand.size == 3 && operand.q == 0 {
+        return Err(reserved_encoding());
+    }
+    let ty = arrangement(operand.q, operand.size);
+    let IrType::Vector(_, lanes) = ty else {
+        unreachable!()
+    };
+    let lanes = lanes as u8;
+    let bytes = ty.size_of();
+
+    let indexing = operand.indexing(bytes * (rpt * selem) as usize);
+    let address = gen_address(bb, operand.rn, indexing);
+
+    for r in 0..rpt {
+        let regs = (0..selem).map(|i| operand.reg(r + i)).collect::<Vec<_>>();
+        let addresses = (0..selem)
+            .map(|i| gen_offset_address(bb, address, (r + i) as usize * bytes))
+            .collect::<Vec<_>>();
+
+        // Element `e` of register `i` is element `e * selem + i` of the memory it spans
+        if load {
+            let memory = addresses
+                .iter()
+                .map(|&src| {
+                    let data = gen_op(bb, int_type(bytes), |dst| IrInst::Load { dst, src });
+                    gen_vec_cast(bb, data, ty)
+                })
+                .collect::<Vec<_>>();
+            for (i, &reg) in regs.iter().enumerate() {
+                let value = gen_gather_lanes(bb, &memory, |e| {
+                    let j = e * selem + i as u8;
+                    ((j / lanes) as usize, j % lanes)
+                });
+                gen_write_reg(bb, reg, value);
+            }
+        } else {
+            let values = regs
+                .iter()
+                .map(|&reg| read_reg(reg, ty))
+                .collect::<Vec<_>>();
+            for (k, &dst) in addresses.iter().enumerate() {
+                let value = gen_gather_lanes(bb, &values, |i| {
+                    let j = k as u8 * lanes + i;
+                    ((j % selem) as usize, j / selem)
+                });
+                let src = gen_vec_cast(bb, value, int_type(bytes));
+                bb.push_inst(IrInst::Store { dst, src });
+            }
+        }
+    }
+
+    gen_writeback(bb, operand.rn, address, indexing);
+
+    Ok(())
 }
 
-fn compile_msr_reg(bb: &mut BasicBlock, operand: &SysRegMov) {
-    todo!()
+/// LD1-LD4 and ST1-ST4 (single structure), a structure of `selem` elements of `1 << scale`
+/// bytes from or to the same lane of `selem` registers.
+fn compile_ld_st_single_structure(
+    bb: &mut BasicBlock,
+    operand: StructureOperand,
+    selem: u8,
+    scale: u8,
+    load: bool,
+) -> Result<(), Error> {
+    // The lane is indexed by Q:S:size, with the bits the element size needs dropped
+    let index = (operand.q << 3 | operand.s << 2 | operand.size) >> scale;
+    let bytes = 1 << scale;
+
+    let indexing = operand.indexing(bytes * selem as usize);
+    let address = gen_address(bb, operand.rn, indexing);
+
+    for i in 0..selem {
+        let reg = operand.reg(i);
+        let address = gen_offset_address(bb, address, i as usize * bytes);
+        if load {
+            let value = gen_op(bb, int_type(bytes), |dst| IrInst::Load {
+                dst,
+                src: address,
+            });
+            gen_insert_lane(bb, reg, value, index);
+        } else {
+            let src = gen_extract_lane(bb, reg, scale, index);
+            bb.push_inst(IrInst::Store { dst: address, src });
+        }
+    }
+
+    gen_writeback(bb, operand.rn, address, indexing);
+
+    Ok(())
 }
 
-fn compile_msr_imm(bb: &mut BasicBlock, operand: &PstateOp) {
-    todo!()
+/// LD1R-LD4R, a structure of `selem` elements copied to every lane of `selem` registers.
+fn compile_ld_replicate(
+    bb: &mut BasicBlock,
+    operand: StructureOperand,
+    selem: u8,
+) -> Result<(), Error> {
+    let ty = arrangement(operand.q, operand.size);
+    let bytes = 1 << operand.size;
+
+    let indexing = operand.indexing(bytes * selem as usize);
+    let address = gen_address(bb, operand.rn, indexing);
+
+    for i in 0..selem {
+        let address = gen_offset_address(bb, address, i as usize * bytes);
+        let src = gen_op(bb, int_type(bytes), |dst| IrInst::Load {
+            dst,
+            src: address,
+        });
+        let value = gen_op(bb, ty, |dst| IrInst::Splat { dst, src });
+        gen_write_reg(bb, operand.reg(i), value);
+    }
+
+    gen_writeback(bb, operand.rn, address, indexing);
+
+    Ok(())
 }
@@ -1,12 +1,12 @@
 use core::{
-    ir::{BasicBlock, IrConstant, IrInst, IrType, IrValue},
+    ir::{BasicBlock, Comparison, Flag, IrConstant, IrInst, IrType, IrValue, TypeOf},
     Architecture, Register,
 };
 use std::ops::{BitAnd, BitOr, Range, Shl};
 
 use num_traits::{Bounded, One, Zero};
 
-use super::{AArch64Architecture, OpcSizeImm12RnRt};
+use super::{AArch64Architecture, AArch64Register, OpcSizeImm12RnRt};
 
 pub fn gen_move_pc(bb: &mut BasicBlock) {
     bb.push_inst(IrInst::Add {
@@ -27,17 +27,381 @@ pub fn gen_pc_rel(bb: &mut BasicBlock, offset: IrValue) -> IrValue {
     rel
 }
 
+/// Read `reg` as `ty`, XZR always reads as zero.
+pub fn read_reg(reg: AArch64Register, ty: IrType) -> IrValue {
+    match reg {
+        AArch64Register::Xzr => IrValue::Constant(IrConstant::new(ty, 0u64)),
+        _ => IrValue::Register(ty, reg.raw()),
+    }
+}
+
+/// Write `value` to the 64 bit register `reg`, zero extending narrower values like every
+/// write to a W register does. Writes to XZR are discarded.
+///
+/// A SIMD&FP register is written as a whole 128 bits, so a scalar result clears the rest of it.
+pub fn gen_write_reg(bb: &mut BasicBlock, reg: AArch64Register, value: IrValue) {
+    if reg == AArch64Register::Xzr {
+        return;
+    }
+
+    let ty = match reg {
+        AArch64Register::V(_) => IrType::B128,
+        _ => IrType::B64,
+    };
+    let dst = IrValue::Register(ty, reg.raw());
+    if value.ty() == ty {
+        bb.push_inst(IrInst::Assign { dst, src: value });
+    } else {
+        bb.push_inst(IrInst::ZextCast { dst, src: value });
+    }
+}
+
+/// Collect the flags set by the preceding `Add` or `Sub` into the layout of the NZCV register.
+///
+/// AArch64 sets C when a subtraction does not borrow, so it is inverted for `sub`.
+pub fn gen_nzcv(bb: &mut BasicBlock, sub: bool) -> IrValue {
+    // Every flag is read before the instructions combining them overwrite it
+    let flags = [
+        (Flag::NF, 31),
+        (Flag::ZF, 30),
+        (Flag::CF, 29),
+        (Flag::OF, 28),
+    ]
+    .map(|(flag, dst_pos)| {
+        let dst = bb.new_variable(IrType::B64);
+        bb.push_inst(IrInst::MoveFlag { dst, dst_pos, flag });
+        dst
+    });
+
+    let nzcv = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Or {
+        dst: nzcv,
+        lhs: flags[0],
+        rhs: flags[1],
+    });
+    bb.push_inst(IrInst::Or {
+        dst: nzcv,
+        lhs: nzcv,
+        rhs: flags[2],
+    });
+    bb.push_inst(IrInst::Or {
+        dst: nzcv,
+        lhs: nzcv,
+        rhs: flags[3],
+    });
+    if sub {
+        bb.push_inst(IrInst::Xor {
+            dst: nzcv,
+            lhs: nzcv,
+            rhs: IrValue::Constant(IrConstant::B64(1 << 29)),
+        });
+    }
+
+    nzcv
+}
+
+/// `lhs + rhs + C`, the `AddWithCarry` of the reference manual, updating NZCV if `set_flags`
+/// is set. Subtracting with carry is adding the inverted `rhs`.
+pub fn gen_add_with_carry(
+    bb: &mut BasicBlock,
+    lhs: IrValue,
+    rhs: IrValue,
+    set_flags: bool,
+) -> IrValue {
+    let ty = lhs.ty();
+
+    let carry_in = bb.new_variable(ty);
+    bb.push_inst(IrInst::Lshr {
+        dst: carry_in,
+        lhs: read_reg(AArch64Register::Pstate, ty),
+        rhs: IrValue::Constant(IrConstant::B8(29)),
+    });
+    bb.push_inst(IrInst::And {
+        dst: carry_in,
+        lhs: carry_in,
+        rhs: IrValue::Constant(IrConstant::new(ty, 1)),
+    });
+
+    let partial = bb.new_variable(ty);
+    bb.push_inst(IrInst::Add {
+        dst: partial,
+        lhs,
+        rhs,
+    });
+    let result = bb.new_variable(ty);
+    if !set_flags {
+        bb.push_inst(IrInst::Add {
+            dst: result,
+            lhs: partial,
+            rhs: carry_in,
+        });
+        return result;
+    }
+
+    // At most one of the two additions can carry out
+    let partial_carry = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::MoveFlag {
+        dst: partial_carry,
+        dst_pos: 29,
+        flag: Flag::CF,
+    });
+    bb.push_inst(IrInst::Add {
+        dst: result,
+        lhs: partial,
+        rhs: carry_in,
+    });
+    let flags = [(Flag::NF, 31), (Flag::ZF, 30), (Flag::CF, 29)].map(|(flag, dst_pos)| {
+        let dst = bb.new_variable(IrType::B64);
+        bb.push_inst(IrInst::MoveFlag { dst, dst_pos, flag });
+        dst
+    });
+
+    // The sum overflowed if both operands have a different sign than the result
+    let lhs_sign = bb.new_variable(ty);
+    bb.push_inst(IrInst::Xor {
+        dst: lhs_sign,
+        lhs,
+        rhs: result,
+    });
+    let rhs_sign = bb.new_variable(ty);
+    bb.push_inst(IrInst::Xor {
+        dst: rhs_sign,
+        lhs: rhs,
+        rhs: result,
+    });
+    let overflow = bb.new_variable(ty);
+    bb.push_inst(IrInst::And {
+        dst: overflow,
+        lhs: lhs_sign,
+        rhs: rhs_sign,
+    });
+    bb.push_inst(IrInst::Lshr {
+        dst: overflow,
+        lhs: overflow,
+        rhs: IrValue::Constant(IrConstant::B8(ty.size_of() as u8 * 8 - 1)),
+    });
+    let v = bb.new_variable(IrType::B64);
+    if ty == IrType::B64 {
+        bb.push_inst(IrInst::Assign {
+            dst: v,
+            src: overflow,
+        });
+    } else {
+        bb.push_inst(IrInst::ZextCast {
+            dst: v,
+            src: overflow,
+        });
+    }
+    bb.push_inst(IrInst::Shl {
+        dst: v,
+        lhs: v,
+        rhs: IrValue::Constant(IrConstant::B8(28)),
+    });
+
+    let nzcv = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Or {
+        dst: nzcv,
+        lhs: partial_carry,
+        rhs: v,
+    });
+    for flag in flags {
+        bb.push_inst(IrInst::Or {
+            dst: nzcv,
+            lhs: nzcv,
+            rhs: flag,
+        });
+    }
+
+    gen_write_reg(bb, AArch64Register::Pstate, nzcv);
+
+    result
+}
+
+/// Collect the flags set by the preceding logical instruction into the layout of the NZCV
+/// register. Logical instructions always clear C and V.
+pub fn gen_logical_nzcv(bb: &mut BasicBlock) -> IrValue {
+    let n = bb.new_variable(IrType::B64);
+    let z = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::MoveFlag {
+        dst: n,
+        dst_pos: 31,
+        flag: Flag::NF,
+    });
+    bb.push_inst(IrInst::MoveFlag {
+        dst: z,
+        dst_pos: 30,
+        flag: Flag::ZF,
+    });
+
+    let nzcv = bb.new_variable(IrType::B64);
+    bb.push_inst(IrInst::Or {
+        dst: nzcv,
+        lhs: n,
+        rhs: z,
+    });
+
+    nzcv
+}
+
+/// Evaluate the condition code `cond` against NZCV as a `IrType::Bool`.
+pub fn gen_condition_holds(bb: &mut BasicBlock, cond: u8) -> IrValue {
+    let ty = IrType::B64;
+    let mut flag = |pos: u8| {
+        let bit = bb.new_variable(ty);
+        bb.push_inst(IrInst::Lshr {
+            dst: bit,
+            lhs: IrValue::Register(ty, AArch64Register::Pstate.raw()),
+            rhs: IrValue::Constant(IrConstant::B8(pos)),
+        });
+        bb.push_inst(IrInst::And {
+            dst: bit,
+            lhs: bit,
+            rhs: IrValue::Constant(IrConstant::new(ty, 1)),
+        });
+        bit
+    };
+
+    let (n, z, c, v) = (31, 30, 29, 28);
+    let result = match cond >> 1 {
+        // EQ
+        0b000 => flag(z),
+        // CS
+        0b001 => flag(c),
+        // MI
+        0b010 => flag(n),
+        // VS
+        0b011 => flag(v),
+        // HI, C == 1 && Z == 0
+        0b100 => {
+            let (c, z) = (flag(c), flag(z));
+            let result = bb.new_variable(ty);
+            bb.push_inst(IrInst::Xor {
+                dst: result,
+                lhs: z,
+                rhs: IrValue::Constant(IrConstant::new(ty, 1)),
+            });
+            bb.push_inst(IrInst::And {
+                dst: result,
+                lhs: result,
+                rhs: c,
+            });
+            result
+        }
+        // GE, N == V
+        0b101 => {
+            let (n, v) = (flag(n), flag(v));
+            let result = bb.new_variable(ty);
+            bb.push_inst(IrInst::Xor {
+                dst: result,
+                lhs: n,
+                rhs: v,
+            });
+            bb.push_inst(IrInst::Xor {
+                dst: result,
+                lhs: result,
+                rhs: IrValue::Constant(IrConstant::new(ty, 1)),
+            });
+            result
+        }
+        // GT, N == V && Z == 0
+        0b110 => {
+            let (n, z, v) = (flag(n), flag(z), flag(v));
+            let result = bb.new_variable(ty);
+            bb.push_inst(IrInst::Xor {
+                dst: result,
+                lhs: n,
+                rhs: v,
+            });
+            bb.push_inst(IrInst::Or {
+                dst: result,
+                lhs: result,
+                rhs: z,
+            });
+            bb.push_inst(IrInst::Xor {
+                dst: result,
+                lhs: result,
+                rhs: IrValue::Constant(IrConstant::new(ty, 1)),
+            });
+            result
+        }
+        // AL and NV both always hold
+        _ => IrValue::Constant(IrConstant::new(ty, 1)),
+    };
+
+    // The low bit inverts the condition, except for NV
+    let comparison = if cond & 0b1 == 0b1 && cond >> 1 != 0b111 {
+        Comparison::Eq
+    } else {
+        Comparison::Ne
+    };
+
+    let holds = bb.new_variable(IrType::Bool);
+    bb.push_inst(IrInst::Cmp {
+        dst: holds,
+        lhs: result,
+        rhs: IrValue::Constant(IrConstant::new(ty, 0)),
+        cond: comparison,
+    });
+
+    holds
+}
+
 pub fn ones<T>(len: u32) -> T
 where
     T: Zero + One + Shl<u32, Output = T> + BitOr<T, Output = T>,
 {
-    assert!(len <= 8);
+    assert!(len as usize <= std::mem::size_of::<T>() * 8);
     (0..len).fold(T::zero(), |state, _| {
         let state = state << 1;
         state | T::one()
     })
 }
 
+/// AArch64 `DecodeBitMasks`, the `(wmask, tmask)` of a logical immediate or bitfield move of
+/// `datasize` bits. Returns `None` for reserved encodings.
+pub fn decode_bit_masks(
+    n: u8,
+    imms: u8,
+    immr: u8,
+    immediate: bool,
+    datasize: u32,
+) -> Option<(u64, u64)> {
+    // The element size is given by the highest set bit of N:NOT(imms)
+    let len = match ((n as u32) << 6 | (!imms & 0b111111) as u32).checked_ilog2() {
+        Some(len) if len >= 1 => len,
+        _ => return None,
+    };
+    let esize = 1 << len;
+    if esize > datasize {
+        return None;
+    }
+
+    let levels: u8 = ones(len);
+    if immediate && imms & levels == levels {
+        return None;
+    }
+
+    let s = (imms & levels) as u32;
+    let r = (immr & levels) as u32;
+    let d = s.wrapping_sub(r) & (esize - 1);
+
+    let element_mask: u64 = ones(esize);
+    let welem: u64 = ones(s + 1);
+    let telem: u64 = ones(d + 1);
+    let welem = if r == 0 {
+        welem
+    } else {
+        (welem >> r | welem << (esize - r)) & element_mask
+    };
+
+    let replicate = |elem: u64| {
+        (0..datasize)
+            .step_by(esize as usize)
+            .fold(0, |mask, i| mask | elem << i)
+    };
+    Some((replicate(welem), replicate(telem)))
+}
+
 pub fn sign_extend<T>(val: T, len: u32) -> T
 where
     T: Zero
@@ -136,7 +500,7 @@ pub fn decode_operand_for_ld_st_reg_imm(
 
 #[cfg(test)]
 mod tests {
-    use crate::aarch64::compiler_prelude::sign_extend;
+    use crate::aarch64::compiler_prelude::{decode_bit_masks, sign_extend};
 
     #[test]
     fn test_sign_extend() {
@@ -146,4 +510,55 @@ mod tests {
             assert_eq!(rust_sext, val);
         }
     }
+
+    #[test]
+    fn logical_immediates() {
+        // orr x0, xzr, #1
+        assert_eq!(decode_bit_masks(1, 0b000000, 0, true, 64).unwrap().0, 1);
+        // orr x0, xzr, #0x5555555555555555, a 2-bit element
+        assert_eq!(
+            decode_bit_masks(0, 0b111100, 0, true, 64).unwrap().0,
+            0x5555_5555_5555_5555
+        );
+        // orr x0, xzr, #0xf0f0f0f0f0f0f0f0, an 8-bit element rotated by 4
+        assert_eq!(
+            decode_bit_masks(0, 0b110011, 0b000100, true, 64).unwrap().0,
+            0xf0f0_f0f0_f0f0_f0f0
+        );
+        // orr w0, wzr, #0x7fffffff
+        assert_eq!(
+            decode_bit_masks(0, 0b011110, 0, true, 32).unwrap().0,
+            0x7fff_ffff
+        );
+    }
+
+    #[test]
+    fn reserved_logical_immediates() {
+        // No element size
+        assert_eq!(decode_bit_masks(0, 0b111111, 0, true, 64), None);
+        // An element of all ones
+        assert_eq!(decode_bit_masks(1, 0b111111, 0, true, 64), None);
+        assert_eq!(decode_bit_masks(0, 0b011111, 0, true, 32), None);
+        // A 64-bit element in a W register
+        assert_eq!(decode_bit_masks(1, 0b000000, 0, true, 32), None);
+    }
+
+    #[test]
+    fn bitfield_masks() {
+        // lsr x0, x1, #4 is ubfm x0, x1, #4, #63
+        assert_eq!(
+            decode_bit_masks(1, 63, 4, false, 64),
+            Some((u64::MAX, 0x0fff_ffff_ffff_ffff))
+        );
+        // lsl x0, x1, #4 is ubfm x0, x1, #60, #59
+        assert_eq!(
+            decode_bit_masks(1, 59, 60, false, 64),
+            Some((0xffff_ffff_ffff_fff0, u64::MAX))
+        );
+        // ubfx w0, w1, #8, #4 is ubfm w0, w1, #8, #11
+        assert_eq!(
+            decode_bit_masks(0, 11, 8, false, 32),
+            Some((0xff00_000f, 0xf))
+        );
+    }
 }
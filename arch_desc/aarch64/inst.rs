@@ -1,5 +1,5 @@
 use core::ir::BasicBlock;
-use core::Instruction;
+use core::{Error, Instruction};
 use std::fmt::Debug;
 
 use crate::aarch64::inst_operand::*;
@@ -516,6 +516,80 @@ pub enum AArch64Inst {
     Ld2MulStructuresImmOffsetVar(AdvSimdLdStMultiStructuresPostIndexed),
     Ld1MulStructures2RegImmOffsetVar(AdvSimdLdStMultiStructuresPostIndexed),
 
+    St1SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St1SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St1SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St1SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    St1SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St1SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St1SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St1SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St3SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St2SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    St4SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+
+    Ld1SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1rRegOffset(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3rRegOffset(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureRegOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureRegOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureRegOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureRegOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2rRegOffset(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4rRegOffset(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld1rImmOffset(AdvSimdLdStSingleStructurePostIndexed),
+    Ld3rImmOffset(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureImmOffsetVar8(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureImmOffsetVar16(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureImmOffsetVar32(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4SingleStructureImmOffsetVar64(AdvSimdLdStSingleStructurePostIndexed),
+    Ld2rImmOffset(AdvSimdLdStSingleStructurePostIndexed),
+    Ld4rImmOffset(AdvSimdLdStSingleStructurePostIndexed),
+
     FcvtnsScalarSinglePrecisionTo32(RnRd),
     FcvtnuScalarSinglePrecisionTo32(RnRd),
     ScvtfScalarInt32ToSinglePrecision(RnRd),
@@ -543,6 +617,20 @@ pub enum AArch64Inst {
     FcvtzsScalarIntDoublePrecisionTo32(RnRd),
     FcvtzuScalarIntDoublePrecisionTo32(RnRd),
     Fjcvtzs(RnRd),
+    FcvtnsScalarHalfPrecisionTo32(RnRd),
+    FcvtnuScalarHalfPrecisionTo32(RnRd),
+    ScvtfScalarInt32ToHalfPrecision(RnRd),
+    UcvtfScalarInt32ToHalfPrecision(RnRd),
+    FcvtasScalarHalfPrecisionTo32(RnRd),
+    FcvtauScalarHalfPrecisionTo32(RnRd),
+    FmovGeneralHalfPrecisionTo32(RnRd),
+    FmovGeneral32ToHalfPrecision(RnRd),
+    FcvtpsScalarHalfPrecisionTo32(RnRd),
+    FcvtpuScalarHalfPrecisionTo32(RnRd),
+    FcvtmsScalarHalfPrecisionTo32(RnRd),
+    FcvtmuScalarHalfPrecisionTo32(RnRd),
+    FcvtzsScalarIntHalfPrecisionTo32(RnRd),
+    FcvtzuScalarIntHalfPrecisionTo32(RnRd),
     FcvtnsScalarSinglePrecisionTo64(RnRd),
     FcvtnuScalarSinglePrecisionTo64(RnRd),
     ScvtfScalarInt64ToSinglePrecision(RnRd),
@@ -569,6 +657,20 @@ pub enum AArch64Inst {
     FcvtmuScalarDoublePrecisionTo64(RnRd),
     FcvtzsScalarIntDoublePrecisionTo64(RnRd),
     FcvtzuScalarIntDoublePrecisionTo64(RnRd),
+    FcvtnsScalarHalfPrecisionTo64(RnRd),
+    FcvtnuScalarHalfPrecisionTo64(RnRd),
+    ScvtfScalarInt64ToHalfPrecision(RnRd),
+    UcvtfScalarInt64ToHalfPrecision(RnRd),
+    FcvtasScalarHalfPrecisionTo64(RnRd),
+    FcvtauScalarHalfPrecisionTo64(RnRd),
+    FmovGeneralHalfPrecisionTo64(RnRd),
+    FmovGeneral64ToHalfPrecision(RnRd),
+    FcvtpsScalarHalfPrecisionTo64(RnRd),
+    FcvtpuScalarHalfPrecisionTo64(RnRd),
+    FcvtmsScalarHalfPrecisionTo64(RnRd),
+    FcvtmuScalarHalfPrecisionTo64(RnRd),
+    FcvtzsScalarIntHalfPrecisionTo64(RnRd),
+    FcvtzuScalarIntHalfPrecisionTo64(RnRd),
     FmovGeneralTopHalfOf128To64(RnRd),
     FmovGeneral64toTopHalfOf128(RnRd),
 
@@ -590,6 +692,9 @@ pub enum AArch64Inst {
 
     Ext(AdvancedSimdExtract),
 
+    Tbl(AdvSimdTableLookup),
+    Tbx(AdvSimdTableLookup),
+
     Shadd(QSizeRmRnRd),
     Sqadd(QSizeRmRnRd),
     Srhadd(QSizeRmRnRd),
@@ -668,6 +773,58 @@ pub enum AArch64Inst {
     Bit(QSizeRmRnRd),
     Bif(QSizeRmRnRd),
 
+    Saddl(QSizeRmRnRd),
+    Saddw(QSizeRmRnRd),
+    Ssubl(QSizeRmRnRd),
+    Ssubw(QSizeRmRnRd),
+    Addhn(QSizeRmRnRd),
+    Sabal(QSizeRmRnRd),
+    Subhn(QSizeRmRnRd),
+    Sabdl(QSizeRmRnRd),
+    SmlalVec(QSizeRmRnRd),
+    SqdmlalVec(QSizeRmRnRd),
+    SmlslVec(QSizeRmRnRd),
+    SqdmlslVec(QSizeRmRnRd),
+    SmullVec(QSizeRmRnRd),
+    SqdmullVec(QSizeRmRnRd),
+    Pmull(QSizeRmRnRd),
+    Uaddl(QSizeRmRnRd),
+    Uaddw(QSizeRmRnRd),
+    Usubl(QSizeRmRnRd),
+    Usubw(QSizeRmRnRd),
+    Raddhn(QSizeRmRnRd),
+    Uabal(QSizeRmRnRd),
+    Rsubhn(QSizeRmRnRd),
+    Uabdl(QSizeRmRnRd),
+    UmlalVec(QSizeRmRnRd),
+    UmlslVec(QSizeRmRnRd),
+    UmullVec(QSizeRmRnRd),
+
+    FmaxnmVecHalfPrecision(QSizeRmRnRd),
+    FmlaVecHalfPrecision(QSizeRmRnRd),
+    FaddVecHalfPrecision(QSizeRmRnRd),
+    FmulxVecHalfPrecision(QSizeRmRnRd),
+    FcmeqRegVecHalfPrecision(QSizeRmRnRd),
+    FmaxVecHalfPrecision(QSizeRmRnRd),
+    FrecpsVecHalfPrecision(QSizeRmRnRd),
+    FminnmVecHalfPrecision(QSizeRmRnRd),
+    FmlsVecHalfPrecision(QSizeRmRnRd),
+    FsubVecHalfPrecision(QSizeRmRnRd),
+    FminVecHalfPrecision(QSizeRmRnRd),
+    FrsqrtsVecHalfPrecision(QSizeRmRnRd),
+    FmaxnmpVecHalfPrecision(QSizeRmRnRd),
+    FaddpVecHalfPrecision(QSizeRmRnRd),
+    FmulVecHalfPrecision(QSizeRmRnRd),
+    FcmgeRegVecHalfPrecision(QSizeRmRnRd),
+    FacgeVecHalfPrecision(QSizeRmRnRd),
+    FmaxpVecHalfPrecision(QSizeRmRnRd),
+    FdivVecHalfPrecision(QSizeRmRnRd),
+    FminnmpVecHalfPrecision(QSizeRmRnRd),
+    FabdVecHalfPrecision(QSizeRmRnRd),
+    FcmgtRegVecHalfPrecision(QSizeRmRnRd),
+    FacgtVecHalfPrecision(QSizeRmRnRd),
+    FminpVecHalfPrecision(QSizeRmRnRd),
+
     Sshr(AdvSimdShiftByImm),
     Ssra(AdvSimdShiftByImm),
     Srshr(AdvSimdShiftByImm),
@@ -724,12 +881,62 @@ pub enum AArch64Inst {
     FrintxScalarDoublePrecisionVar(RnRd),
     FrintiScalarDoublePrecisionVar(RnRd),
 
+    FmovRegHalfPrecisionVar(RnRd),
+    FabsScalarHalfPrecisionVar(RnRd),
+    FnegScalarHalfPrecisionVar(RnRd),
+    FsqrtScalarHalfPrecisionVar(RnRd),
+    FcvtHalfToSinglePrecisionVar(RnRd),
+    FcvtHalfToDoublePrecisionVar(RnRd),
+    FrintnScalarHalfPrecisionVar(RnRd),
+    FrintpScalarHalfPrecisionVar(RnRd),
+    FrintmScalarHalfPrecisionVar(RnRd),
+    FrintzScalarHalfPrecisionVar(RnRd),
+    FrintaScalarHalfPrecisionVar(RnRd),
+    FrintxScalarHalfPrecisionVar(RnRd),
+    FrintiScalarHalfPrecisionVar(RnRd),
+
     AddpScalar(AdvSimdScalarPairwise),
     FmaxnmpScalarEncoding(AdvSimdScalarPairwise),
     FaddpScalarEncoding(AdvSimdScalarPairwise),
     FmaxpScalarEncoding(AdvSimdScalarPairwise),
     FminnmpScalarEncoding(AdvSimdScalarPairwise),
     FminpScalarEncoding(AdvSimdScalarPairwise),
+    FmaxnmpScalarHalfPrecision(AdvSimdScalarPairwise),
+    FaddpScalarHalfPrecision(AdvSimdScalarPairwise),
+    FmaxpScalarHalfPrecision(AdvSimdScalarPairwise),
+    FminnmpScalarHalfPrecision(AdvSimdScalarPairwise),
+    FminpScalarHalfPrecision(AdvSimdScalarPairwise),
+
+    FmulxScalarHalfPrecision(RmRnRd),
+    FcmeqRegScalarHalfPrecision(RmRnRd),
+    FrecpsScalarHalfPrecision(RmRnRd),
+    FrsqrtsScalarHalfPrecision(RmRnRd),
+    FcmgeRegScalarHalfPrecision(RmRnRd),
+    FacgeScalarHalfPrecision(RmRnRd),
+    FabdScalarHalfPrecision(RmRnRd),
+    FcmgtRegScalarHalfPrecision(RmRnRd),
+    FacgtScalarHalfPrecision(RmRnRd),
+
+    FcvtnsScalarHalfPrecision(RnRd),
+    FcvtmsScalarHalfPrecision(RnRd),
+    FcvtasScalarHalfPrecision(RnRd),
+    ScvtfScalarHalfPrecision(RnRd),
+    FcmgtZeroScalarHalfPrecision(RnRd),
+    FcmeqZeroScalarHalfPrecision(RnRd),
+    FcmltZeroScalarHalfPrecision(RnRd),
+    FcvtpsScalarHalfPrecision(RnRd),
+    FcvtzsScalarHalfPrecision(RnRd),
+    FrecpeScalarHalfPrecision(RnRd),
+    FrecpxScalarHalfPrecision(RnRd),
+    FcvtnuScalarHalfPrecision(RnRd),
+    FcvtmuScalarHalfPrecision(RnRd),
+    FcvtauScalarHalfPrecision(RnRd),
+    UcvtfScalarHalfPrecision(RnRd),
+    FcmgeZeroScalarHalfPrecision(RnRd),
+    FcmleZeroScalarHalfPrecision(RnRd),
+    FcvtpuScalarHalfPrecision(RnRd),
+    FcvtzuScalarHalfPrecision(RnRd),
+    FrsqrteScalarHalfPrecision(RnRd),
 
     Rev64(QSizeRnRd),
     Rev16Vec(QSizeRnRd),
@@ -794,6 +1001,36 @@ pub enum AArch64Inst {
     Frsqrte(QSizeRnRd),
     FsqrtVec(QSizeRnRd),
 
+    FrintnVecHalfPrecision(QSizeRnRd),
+    FrintmVecHalfPrecision(QSizeRnRd),
+    FcvtnsVecHalfPrecision(QSizeRnRd),
+    FcvtmsVecHalfPrecision(QSizeRnRd),
+    FcvtasVecHalfPrecision(QSizeRnRd),
+    ScvtfVecHalfPrecision(QSizeRnRd),
+    FcmgtZeroVecHalfPrecision(QSizeRnRd),
+    FcmeqZeroVecHalfPrecision(QSizeRnRd),
+    FcmltZeroVecHalfPrecision(QSizeRnRd),
+    FabsVecHalfPrecision(QSizeRnRd),
+    FrintpVecHalfPrecision(QSizeRnRd),
+    FrintzVecHalfPrecision(QSizeRnRd),
+    FcvtpsVecHalfPrecision(QSizeRnRd),
+    FcvtzsVecHalfPrecision(QSizeRnRd),
+    FrecpeVecHalfPrecision(QSizeRnRd),
+    FrintaVecHalfPrecision(QSizeRnRd),
+    FrintxVecHalfPrecision(QSizeRnRd),
+    FcvtnuVecHalfPrecision(QSizeRnRd),
+    FcvtmuVecHalfPrecision(QSizeRnRd),
+    FcvtauVecHalfPrecision(QSizeRnRd),
+    UcvtfVecHalfPrecision(QSizeRnRd),
+    FcmgeZeroVecHalfPrecision(QSizeRnRd),
+    FcmleZeroVecHalfPrecision(QSizeRnRd),
+    FnegVecHalfPrecision(QSizeRnRd),
+    FrintiVecHalfPrecision(QSizeRnRd),
+    FcvtpuVecHalfPrecision(QSizeRnRd),
+    FcvtzuVecHalfPrecision(QSizeRnRd),
+    FrsqrteVecHalfPrecision(QSizeRnRd),
+    FsqrtVecHalfPrecision(QSizeRnRd),
+
     Saddlv(QSizeRnRd),
     Smaxv(QSizeRnRd),
     Sminv(QSizeRnRd),
@@ -801,10 +1038,14 @@ pub enum AArch64Inst {
     Uaddlv(QSizeRnRd),
     Umaxv(QSizeRnRd),
     Uminv(QSizeRnRd),
-    FmaxnvmEncoding(QSizeRnRd),
+    FmaxnmvEncoding(QSizeRnRd),
     FmaxvEncoding(QSizeRnRd),
     FminnmvEncoding(QSizeRnRd),
     FminvEncoding(QSizeRnRd),
+    FmaxnmvHalfPrecision(QSizeRnRd),
+    FmaxvHalfPrecision(QSizeRnRd),
+    FminnmvHalfPrecision(QSizeRnRd),
+    FminvHalfPrecision(QSizeRnRd),
 
     Udf(Imm16),
 
@@ -1030,8 +1271,19 @@ pub enum AArch64Inst {
     FminnmScalarDoublePrecisionVar(RmRnRd),
     FnmulScalarDoublePrecisionVar(RmRnRd),
 
+    FmulScalarHalfPrecisionVar(RmRnRd),
+    FdivScalarHalfPrecisionVar(RmRnRd),
+    FaddScalarHalfPrecisionVar(RmRnRd),
+    FsubScalarHalfPrecisionVar(RmRnRd),
+    FmaxScalarHalfPrecisionVar(RmRnRd),
+    FminScalarHalfPrecisionVar(RmRnRd),
+    FmaxnmScalarHalfPrecisionVar(RmRnRd),
+    FminnmScalarHalfPrecisionVar(RmRnRd),
+    FnmulScalarHalfPrecisionVar(RmRnRd),
+
     FmovScalarImmSinglePrecisionVar(FloatingPointImmediate),
     FmovScalarImmDoublePrecisionVar(FloatingPointImmediate),
+    FmovScalarImmHalfPrecisionVar(FloatingPointImmediate),
 
     ScvtfScalarFixedPt32ToSinglePrecision(ConvBetweenFloatAndFixedPoint),
     UcvtfScalarFixedPt32ToSinglePrecision(ConvBetweenFloatAndFixedPoint),
@@ -1053,8 +1305,26 @@ pub enum AArch64Inst {
     FcvtzsScalarFixedPtDoublePrecisionTo64(ConvBetweenFloatAndFixedPoint),
     FcvtzuScalarFixedPtDoublePrecisionTo64(ConvBetweenFloatAndFixedPoint),
 
+    ScvtfScalarFixedPt32ToHalfPrecision(ConvBetweenFloatAndFixedPoint),
+    UcvtfScalarFixedPt32ToHalfPrecision(ConvBetweenFloatAndFixedPoint),
+    FcvtzsScalarFixedPtHalfPrecisionTo32(ConvBetweenFloatAndFixedPoint),
+    FcvtzuScalarFixedPtHalfPrecisionTo32(ConvBetweenFloatAndFixedPoint),
+
+    ScvtfScalarFixedPt64ToHalfPrecision(ConvBetweenFloatAndFixedPoint),
+    UcvtfScalarFixedPt64ToHalfPrecision(ConvBetweenFloatAndFixedPoint),
+    FcvtzsScalarFixedPtHalfPrecisionTo64(ConvBetweenFloatAndFixedPoint),
+    FcvtzuScalarFixedPtHalfPrecisionTo64(ConvBetweenFloatAndFixedPoint),
+
+    FccmpSinglePrecisionVar(CondCmpReg),
+    FccmpeSinglePrecisionVar(CondCmpReg),
+    FccmpDoublePrecisionVar(CondCmpReg),
+    FccmpeDoublePrecisionVar(CondCmpReg),
+    FccmpHalfPrecisionVar(CondCmpReg),
+    FccmpeHalfPrecisionVar(CondCmpReg),
+
     FcselSinglePrecisionVar(RmCondRnRd),
     FcselDoublePrecisionVar(RmCondRnRd),
+    FcselHalfPrecisionVar(RmCondRnRd),
 
     SmlalByElem(AdvSimdXIndexedElem),
     SqdmlalByElem(AdvSimdXIndexedElem),
@@ -1076,19 +1346,37 @@ pub enum AArch64Inst {
     UmlslByElem(AdvSimdXIndexedElem),
     UmullByElem(AdvSimdXIndexedElem),
     FmulxByElemEncoding(AdvSimdXIndexedElem),
+
+    SqdmlalByElemScalar(AdvSimdScalarXIndexedElem),
+    SqdmlslByElemScalar(AdvSimdScalarXIndexedElem),
+    SqdmullByElemScalar(AdvSimdScalarXIndexedElem),
+    SqdmulhByElemScalar(AdvSimdScalarXIndexedElem),
+    SqrdmulhByElemScalar(AdvSimdScalarXIndexedElem),
+    FmlaByElemScalar(AdvSimdScalarXIndexedElem),
+    FmlsByElemScalar(AdvSimdScalarXIndexedElem),
+    FmulByElemScalar(AdvSimdScalarXIndexedElem),
+    FmulxByElemScalar(AdvSimdScalarXIndexedElem),
 }
 
 impl Instruction for AArch64Inst {
+    const ALIGNMENT: u64 = 4;
+
     fn size(&self) -> u64 {
         4
     }
 
     /// decode the instruction from raw bytes
-    fn decode(raw_inst: &[u8]) -> Option<Self> {
+    fn decode(raw_inst: &[u8]) -> Result<Self, Error> {
         decode_aarch64_inst(raw_inst)
     }
 
-    fn compile_to_ir(&self, basic_block: &mut BasicBlock) {
-        compile_aarch64_to_ir(self, basic_block)
+    fn compile_to_ir(&self, basic_block: &mut BasicBlock) -> Result<(), Error> {
+        // An instruction can fail after emitting part of its translation
+        let len = basic_block.inst().len();
+        let terminator = basic_block.terminator();
+        compile_aarch64_to_ir(self, basic_block).inspect_err(|_| {
+            basic_block.truncate(len);
+            basic_block.set_terminator(terminator);
+        })
     }
 }
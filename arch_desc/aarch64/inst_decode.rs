@@ -1,6 +1,7 @@
-use core::Architecture;
+use core::{Architecture, Error};
 use std::str::Chars;
 
+use crate::aarch64::compiler_prelude::decode_bit_masks;
 use crate::aarch64::inst::AArch64Inst;
 use crate::aarch64::inst_operand::*;
 use crate::aarch64::AArch64Architecture;
@@ -20,29 +21,42 @@ fn to_le(pat: impl AsRef<str>) -> String {
     pat.chunks(8).rev().flatten().collect()
 }
 
-pub(crate) fn decode_aarch64_inst(raw: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<Option<AArch64Inst>>> = Lazy::new(|| {
+/// Error for an encoding that is not an instruction.
+fn undefined(raw_instr: &[u8]) -> Error {
+    let mut encoding = [0; 4];
+    let len = raw_instr.len().min(4);
+    encoding[..len].copy_from_slice(&raw_instr[..len]);
+    Error::UndefinedInstruction(u32::from_le_bytes(encoding))
+}
+
+/// Error for an instruction class the decoder does not handle yet.
+fn unimplemented(class: &str) -> Error {
+    Error::UnimplementedInstruction(class.into())
+}
+
+pub(crate) fn decode_aarch64_inst(raw: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_xx_0000_xxxxxxxxxxxxxxxxxxxxxxxxx"),
             |raw_instr: &[u8],
              Extract(op0): Extract<u8, 29, 31>,
-             Extract(op1): Extract<u8, 16, 25>,
+             Extract(op1): Extract<u16, 16, 25>,
              Extract(imm16): Extract<u16, 0, 16>| {
                 let imm16 = Imm16 { imm16 };
-                match (op0, op1) {
-                    (0b00, 0b000000000) => Some(AArch64Inst::Udf(imm16)),
-                    _ => todo!("Unknown reserved instruction {:?}", raw_instr),
-                }
+                Ok(match (op0, op1) {
+                    (0b00, 0b000000000) => AArch64Inst::Udf(imm16),
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         )
         .bind(
             to_le("1_xx_0000_xxxxxxxxxxxxxxxxxxxxxxxxx"),
-            |_raw_instr: &[u8]| todo!("SME encodings"),
+            |_raw_instr: &[u8]| Err(unimplemented("SME encodings")),
         )
         .bind(
             to_le("x_xx_0010_xxxxxxxxxxxxxxxxxxxxxxxxx"),
-            |_raw_instr: &[u8]| todo!("SVE encodings"),
+            |_raw_instr: &[u8]| Err(unimplemented("SVE encodings")),
         )
         .bind(
             to_le("x_xx_100x_xxxxxxxxxxxxxxxxxxxxxxxxx"),
@@ -68,12 +82,19 @@ pub(crate) fn decode_aarch64_inst(raw: &[u8]) -> Option<AArch64Inst> {
         m
     });
 
-    MATCHER.try_match(raw).flatten()
+    // Patterns only test the bytes they cover, a truncated instruction would match anything
+    if raw.len() < 4 {
+        return Err(undefined(raw));
+    }
+
+    MATCHER
+        .try_match(raw)
+        .unwrap_or_else(|| Err(undefined(raw)))
 }
 
 // parse DPI(Data Processing Immediate) instructions in AArch64
-fn parse_aarch64_d_p_i(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<Option<AArch64Inst>>> = Lazy::new(|| {
+fn parse_aarch64_d_p_i(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xxx_100_00x_xxxxxxxxxxxxxxxxxxxxxxx"),
@@ -101,12 +122,14 @@ fn parse_aarch64_d_p_i(raw_instr: &[u8]) -> Option<AArch64Inst> {
         m
     });
 
-    MATCHER.try_match(raw_instr).flatten()
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
 // parse DPI(Data Processing Register) instructions in AArch64
-fn parse_aarch64_d_p_r(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<Option<AArch64Inst>>> = Lazy::new(|| {
+fn parse_aarch64_d_p_r(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_1_101_0110_xxxxx_xxxxxx_xxxxxxxxxx"),
@@ -160,260 +183,266 @@ fn parse_aarch64_d_p_r(raw_instr: &[u8]) -> Option<AArch64Inst> {
         m
     });
 
-    MATCHER.try_match(raw_instr).flatten()
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_aarch64_dp_sfp_adv_simd(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<Option<AArch64Inst>>> = Lazy::new(|| {
+fn parse_aarch64_dp_sfp_adv_simd(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0100"),
                 to_le("0x"),
                 to_le("x101"),
                 "00xxxxx10"
-            ),
-            |_raw_instr: &[u8]| todo!("Cryptographic AES"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Cryptographic AES")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0101"),
                 to_le("0x"),
                 to_le("x0xx"),
                 "xxx0xxx00"
-            ),
-            |_raw_instr: &[u8]| todo!("Cryptographic three-register SHA"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Cryptographic three-register SHA")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0101"),
                 to_le("0x"),
                 to_le("x101"),
                 "00xxxxx10"
-            ),
-            |_raw_instr: &[u8]| todo!("Cryptographic two-register SHA"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Cryptographic two-register SHA")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("00"),
                 to_le("00xx"),
                 "xxx0xxxx1"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar copy"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Advanced SIMD scalar copy")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("0x"),
                 to_le("10xx"),
                 "xxx00xxx1"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar three same FP16"),
+            )),
+            parse_adv_simd_scalar_three_same_fp16,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("0x"),
                 to_le("1111"),
                 "00xxxxx10"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar two-register miscellaneous FP16"),
+            )),
+            parse_adv_simd_scalar_2reg_misc_fp16,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("0x"),
                 to_le("x0xx"),
                 "xxx1xxxx1"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar three same extra"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Advanced SIMD scalar three same extra")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("0x"),
                 to_le("x100"),
                 "00xxxxx10"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar two-register miscellaneous"),
+            )),
+            |_raw_instr: &[u8]| {
+                Err(unimplemented(
+                    "Advanced SIMD scalar two-register miscellaneous",
+                ))
+            },
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("0x"),
                 to_le("x110"),
                 "00xxxxx10"
-            ),
+            )),
             parse_adv_simd_scalar_pairwise,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxxx00"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar three different"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Advanced SIMD scalar three different")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxxxx1"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar three same"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Advanced SIMD scalar three same")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("10"),
                 to_le("xxxx"),
                 "xxxxxxxx1"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD scalar shifted by immediate"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Advanced SIMD scalar shifted by immediate")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("01x1"),
                 to_le("1x"),
                 to_le("xxxx"),
                 "xxxxxxxx0"
-            ),
+            )),
             parse_adv_simd_scalar_x_indexed_elem,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0x00"),
                 to_le("0x"),
                 to_le("x0xx"),
                 "xxx0xxx00"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD table lookup"),
+            )),
+            parse_advanced_simd_table_lookup,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0x00"),
                 to_le("0x"),
                 to_le("x0xx"),
                 "xxx0xxx10"
-            ),
+            )),
             parse_advanced_simd_permute,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0x10"),
                 to_le("0x"),
                 to_le("x0xx"),
                 "xxx0xxxx0"
-            ),
+            )),
             parse_advanced_simd_extract,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("00"),
                 to_le("00xx"),
                 "xxx0xxxx1"
-            ),
+            )),
             parse_advanced_simd_copy,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("0x"),
                 to_le("10xx"),
                 "xxx00xxx1"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD three same (FP16)"),
+            )),
+            parse_advanced_simd_three_same_fp16,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("0x"),
                 to_le("1111"),
                 "00xxxxx10"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD two-register miscellaneous (FP16)"),
+            )),
+            parse_adv_simd_2reg_misc_fp16,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("0x"),
                 to_le("x0xx"),
                 "xxx1xxxx1"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD three-register extension"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Advanced SIMD three-register extension")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("0x"),
                 to_le("x100"),
                 "00xxxxx10"
-            ),
+            )),
             parse_adv_simd_2reg_miscellaneous,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("0x"),
                 to_le("x110"),
                 "00xxxxx10"
-            ),
+            )),
             parse_adv_simd_across_lanes,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxxx00"
-            ),
-            |_raw_instr: &[u8]| todo!("Advanced SIMD three different"),
+            )),
+            parse_adv_simd_three_different,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxxxx1"
-            ),
+            )),
             parse_advanced_simd_three_same,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("10"),
                 to_le("xxxx"),
                 "xxxxxxxx1"
-            ),
+            )),
             |raw_instr: &[u8], Extract(op2): Extract<u8, 19, 23>| {
                 if op2 == 0b0000 {
                     parse_adv_simd_modified_imm(raw_instr)
@@ -423,165 +452,167 @@ fn parse_aarch64_dp_sfp_adv_simd(raw_instr: &[u8]) -> Option<AArch64Inst> {
             },
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("0xx0"),
                 to_le("1x"),
                 to_le("xxxx"),
                 "xxxxxxxx0"
-            ),
+            )),
             parse_adv_simd_vec_x_indexed_elem,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("1100"),
                 to_le("00"),
                 to_le("10xx"),
                 "xxx10xxxx"
-            ),
-            |_raw_instr: &[u8]| todo!("Cryptographic three-register, imm2"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Cryptographic three-register, imm2")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("1100"),
                 to_le("00"),
                 to_le("11xx"),
                 "xxx1x00xx"
-            ),
-            |_raw_instr: &[u8]| todo!("Cryptographic three-reigster SHA 512"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Cryptographic three-reigster SHA 512")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("1100"),
                 to_le("00"),
                 to_le("xxxx"),
                 "xxx0xxxxx"
-            ),
-            |_raw_instr: &[u8]| todo!("Cryptographic four-register"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Cryptographic four-register")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("1100"),
                 to_le("01"),
                 to_le("00xx"),
                 "xxxxxxxxx"
-            ),
-            |_raw_instr: &[u8]| todo!("XAR"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("XAR")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("1100"),
                 to_le("01"),
                 to_le("1000"),
                 "0001000xx"
-            ),
-            |_raw_instr: &[u8]| todo!("Cryptographic two-register SHA 512"),
+            )),
+            |_raw_instr: &[u8]| Err(unimplemented("Cryptographic two-register SHA 512")),
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x0xx"),
                 "xxxxxxxxx"
-            ),
+            )),
             parse_conv_between_float_and_fixed_point,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxx000000"
-            ),
+            )),
             parse_conv_between_float_and_int,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxx10000"
-            ),
+            )),
             parse_float_data_proc_1src,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxx1000"
-            ),
+            )),
             parse_floating_point_compare,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxx100"
-            ),
+            )),
             parse_floating_point_immediate,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxxx01"
-            ),
-            |_raw_instr: &[u8]| todo!("Floating-point conditional compare"),
+            )),
+            parse_floating_point_conditional_compare,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxxx10"
-            ),
+            )),
             parse_float_data_proc_2src,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("0x"),
                 to_le("x1xx"),
                 "xxxxxxx11"
-            ),
+            )),
             parse_floating_point_conditional_select,
         )
         .bind(
-            &format!(
+            &to_le(format!(
                 "{}_xxx_{}_{}_{}_xxxxxxxxxx",
                 to_le("x0x1"),
                 to_le("1x"),
                 to_le("xxxx"),
                 "xxxxxxxxx"
-            ),
+            )),
             parse_fp_data_processing_3src,
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr).flatten()
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
 // parse Load and stores instructions i pairn AArch64
-fn parse_aarch64_load_and_stores(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<Option<AArch64Inst>>> = Lazy::new(|| {
+fn parse_aarch64_load_and_stores(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0x00_1_0_0_00_x_1xxxxx_xxxx_xx_xxxxxxxxxx"),
@@ -601,9 +632,7 @@ fn parse_aarch64_load_and_stores(raw_instr: &[u8]) -> Option<AArch64Inst> {
         )
         .bind(
             to_le("0x00_1_1_0_11_x_xxxxxx_xxxx_xx_xxxxxxxxxx"),
-            |_raw_instr: &[u8]| {
-                todo!("Advanced SIMD Load/Store single structure(post-indexed)");
-            },
+            parse_adv_simd_ld_st_single_structure_post_indexed,
         )
         .bind(
             to_le("1101_1_0_0_1x_x_1xxxxx_xxxx_xx_xxxxxxxxxx"),
@@ -635,9 +664,7 @@ fn parse_aarch64_load_and_stores(raw_instr: &[u8]) -> Option<AArch64Inst> {
         )
         .bind(
             to_le("xx01_1_x_0_1x_x_0xxxxx_xxxx_01_xxxxxxxxxx"),
-            |_raw_instr: &[u8]| {
-                todo!("Memory Copy and Memory Set");
-            },
+            |_raw_instr: &[u8]| Err(unimplemented("Memory Copy and Memory Set")),
         )
         .bind(
             to_le("xx10_1_x_0_00_x_xxxxxx_xxxx_xx_xxxxxxxxxx"),
@@ -681,9 +708,8 @@ fn parse_aarch64_load_and_stores(raw_instr: &[u8]) -> Option<AArch64Inst> {
         )
         .bind(
             to_le("xx11_1_x_0_0x_x_1xxxxx_xxxx_x1_xxxxxxxxxx"),
-            |_raw_instr: &[u8]| {
-                todo!("Load/Store register (pac)"); // Need to do FEAT_PAuth feature instructions
-            },
+            // Need to do FEAT_PAuth feature instructions
+            |_raw_instr: &[u8]| Err(unimplemented("Load/Store register (pac)")),
         )
         .bind(
             to_le("xx11_1_x_0_1x_x_xxxxxx_xxxx_xx_xxxxxxxxxx"),
@@ -693,11 +719,15 @@ fn parse_aarch64_load_and_stores(raw_instr: &[u8]) -> Option<AArch64Inst> {
         m
     });
 
-    MATCHER.try_match(raw_instr).flatten()
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_aarch64_branches_exception_gen_and_sys_instr(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<Option<AArch64Inst>>> = Lazy::new(|| {
+fn parse_aarch64_branches_exception_gen_and_sys_instr(
+    raw_instr: &[u8],
+) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         //--------------------------------------------
         //      |op1|101|      op2     |       | op3 |
@@ -751,11 +781,13 @@ fn parse_aarch64_branches_exception_gen_and_sys_instr(raw_instr: &[u8]) -> Optio
         m
     });
 
-    MATCHER.try_match(raw_instr).flatten()
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_add_sub_shifted_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_add_sub_shifted_reg(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xxx_01011_xx_0_xxxxxxxxxxxxxxxxxxxxx"),
@@ -774,7 +806,12 @@ fn parse_add_sub_shifted_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     imm6,
                 };
 
-                match (sf_op_s, shift, imm6) {
+                // ROR is reserved, and so are shifts by 32 or more for 32 bit registers
+                if shift == 0b11 || (sf_op_s >> 2 == 0b0 && imm6 >= 32) {
+                    return Err(undefined(raw_instr));
+                }
+
+                Ok(match (sf_op_s, shift, imm6) {
                     (0b000, _, _) => AArch64Inst::AddShiftedReg32(data),
                     (0b001, _, _) => AArch64Inst::AddsShiftedReg32(data),
                     (0b010, _, _) => AArch64Inst::SubShiftedReg32(data),
@@ -784,19 +821,21 @@ fn parse_add_sub_shifted_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b110, _, _) => AArch64Inst::SubShiftedReg64(data),
                     (0b111, _, _) => AArch64Inst::SubsShiftedReg64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_add_sub_immediate(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_add_sub_immediate(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_100010_x_xxxxxxxxxxxx_xxxxx_xxxxx"),
@@ -806,6 +845,12 @@ fn parse_add_sub_immediate(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(imm12): Extract<u16, 10, 22>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rd): Extract<u8, 0, 5>| {
+                // ADD and SUB can write SP, the flag setting forms write XZR instead
+                let rd_hint = if sf_op_s & 0b1 == 0 {
+                    AArch64MnemonicHint::X_SP
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = ShImm12RnRd {
                     sh,
                     imm12,
@@ -813,10 +858,10 @@ fn parse_add_sub_immediate(raw_instr: &[u8]) -> Option<AArch64Inst> {
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
+                    rd: AArch64Architecture::get_register_by_mnemonic(rd_hint, rd),
                 };
 
-                match sf_op_s {
+                Ok(match sf_op_s {
                     0b000 => AArch64Inst::AddImm32(data),
                     0b001 => AArch64Inst::AddsImm32(data),
                     0b010 => AArch64Inst::SubImm32(data),
@@ -825,19 +870,21 @@ fn parse_add_sub_immediate(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     0b101 => AArch64Inst::AddsImm64(data),
                     0b110 => AArch64Inst::SubImm64(data),
                     0b111 => AArch64Inst::SubsImm64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_fp_data_processing_3src(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_fp_data_processing_3src(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11111_xx_x_xxxxx_x_xxxxx_xxxxx_xxxxx"),
@@ -858,7 +905,7 @@ fn parse_fp_data_processing_3src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (m, s, ptype, o1, o0) {
+                Ok(match (m, s, ptype, o1, o0) {
                     (0b0, 0b0, 0b00, 0b0, 0b0) => AArch64Inst::FmAddSinglePrecision(data),
                     (0b0, 0b0, 0b00, 0b0, 0b1) => AArch64Inst::FmSubSinglePrecision(data),
                     (0b0, 0b0, 0b00, 0b1, 0b0) => AArch64Inst::FnmAddSinglePrecision(data),
@@ -871,19 +918,21 @@ fn parse_fp_data_processing_3src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b0, 0b0, 0b11, 0b0, 0b1) => AArch64Inst::FmSubHalfPrecision(data),
                     (0b0, 0b0, 0b11, 0b1, 0b0) => AArch64Inst::FnmAddHalfPrecision(data),
                     (0b0, 0b0, 0b11, 0b1, 0b1) => AArch64Inst::FnmSubHalfPrecision(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_unsigned_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_unsigned_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_111_x_01_xx_xxxxxxxxxxxx_xxxxx_xxxxx"),
@@ -895,6 +944,11 @@ fn parse_load_store_reg_unsigned_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(imm12): Extract<u16, 10, 22>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = OpcSizeImm12RnRt {
                     idxt,
                     opc,
@@ -904,13 +958,10 @@ fn parse_load_store_reg_unsigned_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(
-                        AArch64MnemonicHint::X_SP,
-                        rt,
-                    ),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (size, v, opc) {
+                Ok(match (size, v, opc) {
                     (0b00, 0b0, 0b00) => AArch64Inst::StrbImm(data),
                     (0b00, 0b0, 0b01) => AArch64Inst::LdrbImm(data),
                     (0b00, 0b0, 0b10) => AArch64Inst::LdrsbImm64(data),
@@ -935,19 +986,21 @@ fn parse_load_store_reg_unsigned_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b0, 0b10) => AArch64Inst::PrfmImm(data),
                     (0b11, 0b1, 0b00) => AArch64Inst::StrImmSimdFP64(data),
                     (0b11, 0b1, 0b01) => AArch64Inst::LdrImmSimdFP64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_move_wide_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_move_wide_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_xx_100101_xx_xxxxxxxxxxxxxxxx_xxxxx"),
@@ -962,26 +1015,28 @@ fn parse_move_wide_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf_opc, hw) {
+                Ok(match (sf_opc, hw) {
                     (0b000, 0b00 | 0b01) => AArch64Inst::MovnVar32(data),
                     (0b010, 0b00 | 0b01) => AArch64Inst::MovzVar32(data),
                     (0b011, 0b00 | 0b01) => AArch64Inst::MovkVar32(data),
                     (0b100, _) => AArch64Inst::MovnVar64(data),
                     (0b110, _) => AArch64Inst::MovzVar64(data),
                     (0b111, _) => AArch64Inst::MovkVar64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_uncond_branch_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_uncond_branch_reg(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("1101011_xxxx_xxxxx_xxxxxx_xxxxx_xxxxx"),
@@ -1009,61 +1064,79 @@ fn parse_uncond_branch_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     ),
                 };
 
-                match (opc, op2, op3, rn, op4) {
+                Ok(match (opc, op2, op3, rn, op4) {
                     (0b0000, 0b11111, 0b000000, _, 0b00000) => AArch64Inst::Br(data),
                     (0b0000, 0b11111, 0b000010, _, 0b11111) => {
-                        todo!("BRAA, BRAAZ, BRAB, BRABZ. Key A, zero modifier")
+                        return Err(unimplemented(
+                            "BRAA, BRAAZ, BRAB, BRABZ. Key A, zero modifier",
+                        ))
                     }
                     (0b0000, 0b11111, 0b000011, _, 0b11111) => {
-                        todo!("BRAA, BRAAZ, BRAB, BRABZ. Key B, zero modifier")
+                        return Err(unimplemented(
+                            "BRAA, BRAAZ, BRAB, BRABZ. Key B, zero modifier",
+                        ))
                     }
                     (0b0001, 0b11111, 0b000000, _, 0b00000) => AArch64Inst::Blr(data),
                     (0b0001, 0b11111, 0b000010, _, 0b11111) => {
-                        todo!("BLRAA, BLRAAZ, BLRAB, BLRABZ. Key A, zero modifier")
+                        return Err(unimplemented(
+                            "BLRAA, BLRAAZ, BLRAB, BLRABZ. Key A, zero modifier",
+                        ))
                     }
                     (0b0001, 0b11111, 0b000011, _, 0b11111) => {
-                        todo!("BLRAA, BLRAAZ, BLRAB, BLRABZ. Key B, zero modifier")
+                        return Err(unimplemented(
+                            "BLRAA, BLRAAZ, BLRAB, BLRABZ. Key B, zero modifier",
+                        ))
                     }
                     (0b0010, 0b11111, 0b000000, _, 0b00000) => AArch64Inst::Ret(data),
                     (0b0010, 0b11111, 0b000010, 0b11111, 0b11111) => {
-                        todo!("RETAA, RETAB - RETAA variant")
+                        return Err(unimplemented("RETAA, RETAB - RETAA variant"))
                     }
                     (0b0010, 0b11111, 0b000011, 0b11111, 0b11111) => {
-                        todo!("RETAA, RETAB - RETAB variant")
+                        return Err(unimplemented("RETAA, RETAB - RETAB variant"))
                     }
                     (0b0100, 0b11111, 0b000000, 0b11111, 0b00000) => AArch64Inst::ERet(data),
                     (0b0100, 0b11111, 0b000010, 0b11111, 0b11111) => {
-                        todo!("ERETAA, ERETAB - ERETAA variant")
+                        return Err(unimplemented("ERETAA, ERETAB - ERETAA variant"))
                     }
                     (0b0100, 0b11111, 0b000011, 0b11111, 0b11111) => {
-                        todo!("ERETAA, ERETAB - ERETAB variant")
+                        return Err(unimplemented("ERETAA, ERETAB - ERETAB variant"))
                     }
                     (0b0101, 0b11111, 0b000000, 0b11111, 0b00000) => AArch64Inst::Drps(data),
                     (0b1000, 0b11111, 0b000010, _, _) => {
-                        todo!("BRAA, BRAAZ, BRAB, BRABZ - Key A, register modifier")
+                        return Err(unimplemented(
+                            "BRAA, BRAAZ, BRAB, BRABZ - Key A, register modifier",
+                        ))
                     }
                     (0b1000, 0b11111, 0b000011, _, _) => {
-                        todo!("BRAA, BRAAZ, BRAB, BRABZ - Key B, register modifier")
+                        return Err(unimplemented(
+                            "BRAA, BRAAZ, BRAB, BRABZ - Key B, register modifier",
+                        ))
                     }
                     (0b1001, 0b11111, 0b000010, _, _) => {
-                        todo!("BLRAA, BLRAAZ, BLRAB, BLRABZ - Key A, register modifier")
+                        return Err(unimplemented(
+                            "BLRAA, BLRAAZ, BLRAB, BLRABZ - Key A, register modifier",
+                        ))
                     }
                     (0b1001, 0b11111, 0b000011, _, _) => {
-                        todo!("BLRAA, BLRAAZ, BLRAB, BLRABZ - Key B, register modifier")
+                        return Err(unimplemented(
+                            "BLRAA, BLRAAZ, BLRAB, BLRABZ - Key B, register modifier",
+                        ))
                     }
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_uncond_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_uncond_branch_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_00101_xxxxxxxxxxxxxxxxxxxxxxxxxx"),
@@ -1072,22 +1145,24 @@ fn parse_uncond_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(imm26): Extract<u32, 0, 26>| {
                 let data = Imm26 { imm26 };
 
-                match op {
+                Ok(match op {
                     0b0 => AArch64Inst::BImm(data),
                     0b1 => AArch64Inst::BlImm(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_cond_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_cond_branch_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0101010_x_xxxxxxxxxxxxxxxxxxx_x_xxxx"),
@@ -1098,22 +1173,24 @@ fn parse_cond_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(cond): Extract<u8, 0, 4>| {
                 let data = Imm19Cond { imm19, cond };
 
-                match (o1, o0) {
+                Ok(match (o1, o0) {
                     (0b0, 0b0) => AArch64Inst::BCond(data),
                     (0b0, 0b1) => AArch64Inst::BcCond(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_cond_sel(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_cond_sel(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_11010100_xxxxx_xxxx_xx_xxxxx_xxxxx"),
@@ -1131,7 +1208,7 @@ fn parse_cond_sel(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf_op_s, op2) {
+                Ok(match (sf_op_s, op2) {
                     (0b000, 0b00) => AArch64Inst::Csel32(data),
                     (0b000, 0b01) => AArch64Inst::Csinc32(data),
                     (0b010, 0b00) => AArch64Inst::Csinv32(data),
@@ -1140,19 +1217,21 @@ fn parse_cond_sel(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b100, 0b01) => AArch64Inst::Csinc64(data),
                     (0b110, 0b00) => AArch64Inst::Csinv64(data),
                     (0b110, 0b01) => AArch64Inst::Csneg64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_test_and_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_test_and_branch_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_011011_x_xxxxx_xxxxxxxxxxxxxx_xxxxx"),
@@ -1169,22 +1248,24 @@ fn parse_test_and_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match op {
+                Ok(match op {
                     0b0 => AArch64Inst::Tbz(data),
                     0b1 => AArch64Inst::Tbnz(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_logical_shifted_register(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_logical_shifted_register(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_xx_01010_xx_x_xxxxx_xxxxxx_xxxxx_xxxxx"),
@@ -1205,8 +1286,8 @@ fn parse_logical_shifted_register(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf, opc, n) {
-                    (0b0, _, _) if imm6 & 0b100000 == 0b100000 => unreachable!(),
+                Ok(match (sf, opc, n) {
+                    (0b0, _, _) if imm6 & 0b100000 == 0b100000 => return Err(undefined(raw_instr)),
                     (0b0, 0b00, 0b0) => AArch64Inst::AndShiftedReg32(data),
                     (0b0, 0b00, 0b1) => AArch64Inst::BicShiftedReg32(data),
                     (0b0, 0b01, 0b0) => AArch64Inst::OrrShiftedReg32(data),
@@ -1223,58 +1304,65 @@ fn parse_logical_shifted_register(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b10, 0b1) => AArch64Inst::EonShiftedReg64(data),
                     (0b1, 0b11, 0b0) => AArch64Inst::AndsShiftedReg64(data),
                     (0b1, 0b11, 0b1) => AArch64Inst::BicsShiftedReg64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_hints(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_hints(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("11010101000000110010_xxxx_xxx_11111"),
-            |raw_instr: &[u8],
+            |_raw_instr: &[u8],
              Extract(crm): Extract<u8, 8, 12>,
-             Extract(op2): Extract<u8, 5, 8>| match (crm, op2) {
-                (0b0000, 0b000) => AArch64Inst::Nop,
-                (0b0000, 0b001) => AArch64Inst::Yield,
-                (0b0000, 0b010) => AArch64Inst::Wfe,
-                (0b0000, 0b011) => AArch64Inst::Wfi,
-                (0b0000, 0b100) => AArch64Inst::Sev,
-                (0b0000, 0b101) => AArch64Inst::Sevl,
-
-                (0b0000, 0b111) => AArch64Inst::Xpaclri,
-                (0b0001, 0b000) => AArch64Inst::Pacia1716Var,
-                (0b0001, 0b010) => AArch64Inst::Pacib1716Var,
-                (0b0001, 0b100) => AArch64Inst::Autia1716Var,
-                (0b0001, 0b110) => AArch64Inst::Autib1716Var,
-
-                (0b0011, 0b000) => AArch64Inst::PaciazVar,
-                (0b0011, 0b001) => AArch64Inst::PaciaspVar,
-                (0b0011, 0b010) => AArch64Inst::PacibzVar,
-                (0b0011, 0b011) => AArch64Inst::PacibspVar,
-                (0b0011, 0b100) => AArch64Inst::AutiazVar,
-                (0b0011, 0b101) => AArch64Inst::AutiaspVar,
-                (0b0011, 0b110) => AArch64Inst::AutibzVar,
-                (0b0011, 0b111) => AArch64Inst::AutibspVar,
-                _ => todo!("Unknown instruction {:?}", raw_instr),
+             Extract(op2): Extract<u8, 5, 8>| {
+                Ok(match (crm, op2) {
+                    (0b0000, 0b000) => AArch64Inst::Nop,
+                    (0b0000, 0b001) => AArch64Inst::Yield,
+                    (0b0000, 0b010) => AArch64Inst::Wfe,
+                    (0b0000, 0b011) => AArch64Inst::Wfi,
+                    (0b0000, 0b100) => AArch64Inst::Sev,
+                    (0b0000, 0b101) => AArch64Inst::Sevl,
+
+                    (0b0000, 0b111) => AArch64Inst::Xpaclri,
+                    (0b0001, 0b000) => AArch64Inst::Pacia1716Var,
+                    (0b0001, 0b010) => AArch64Inst::Pacib1716Var,
+                    (0b0001, 0b100) => AArch64Inst::Autia1716Var,
+                    (0b0001, 0b110) => AArch64Inst::Autib1716Var,
+
+                    (0b0011, 0b000) => AArch64Inst::PaciazVar,
+                    (0b0011, 0b001) => AArch64Inst::PaciaspVar,
+                    (0b0011, 0b010) => AArch64Inst::PacibzVar,
+                    (0b0011, 0b011) => AArch64Inst::PacibspVar,
+                    (0b0011, 0b100) => AArch64Inst::AutiazVar,
+                    (0b0011, 0b101) => AArch64Inst::AutiaspVar,
+                    (0b0011, 0b110) => AArch64Inst::AutibzVar,
+                    (0b0011, 0b111) => AArch64Inst::AutibspVar,
+                    // Unallocated hints execute as NOP
+                    _ => AArch64Inst::Nop,
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_pc_rel_addressing(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_pc_rel_addressing(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_xx_10000_xxxxxxxxxxxxxxxxxxx_xxxxx"),
@@ -1289,22 +1377,24 @@ fn parse_pc_rel_addressing(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match op {
+                Ok(match op {
                     0b0 => AArch64Inst::Adr(data),
                     0b1 => AArch64Inst::Adrp(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_exception_gen(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_exception_gen(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("11010100_xxx_xxxxxxxxxxxxxxxx_xxx_xx"),
@@ -1320,7 +1410,7 @@ fn parse_exception_gen(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     ll,
                 };
 
-                match (opc, op2, ll) {
+                Ok(match (opc, op2, ll) {
                     (0b000, 0b000, 0b01) => AArch64Inst::Svc(data),
                     (0b000, 0b000, 0b10) => AArch64Inst::Hvc(data),
                     (0b000, 0b000, 0b11) => AArch64Inst::Smc(data),
@@ -1330,19 +1420,21 @@ fn parse_exception_gen(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b101, 0b000, 0b01) => AArch64Inst::DcpS1(data),
                     (0b101, 0b000, 0b10) => AArch64Inst::DcpS2(data),
                     (0b101, 0b000, 0b11) => AArch64Inst::DcpS3(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_reg_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_reg_offset(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_111_x_00_xx_1_xxxxx_xxx_x_10_xxxxx_xxxxx"),
@@ -1355,6 +1447,11 @@ fn parse_load_store_reg_reg_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(s): Extract<u8, 12, 13>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = LoadStoreRegRegOffset {
                     size,
                     v,
@@ -1366,10 +1463,15 @@ fn parse_load_store_reg_reg_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (size, v, opc, option) {
+                // Extending with UXTB, UXTH, SXTB or SXTH is unallocated
+                if option & 0b010 == 0 {
+                    return Err(undefined(raw_instr));
+                }
+
+                Ok(match (size, v, opc, option) {
                     (0b00, 0b0, 0b00, _) if option != 0b011 => AArch64Inst::StrbRegExtReg(data),
                     (0b00, 0b0, 0b00, 0b011) => AArch64Inst::StrbRegShiftedReg(data),
                     (0b00, 0b0, 0b01, _) if option != 0b011 => AArch64Inst::LdrbRegExtReg(data),
@@ -1390,19 +1492,21 @@ fn parse_load_store_reg_reg_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b0, 0b00, _) => AArch64Inst::StrReg64(data),
                     (0b11, 0b0, 0b01, _) => AArch64Inst::LdrReg64(data),
                     (0b11, 0b0, 0b10, _) => AArch64Inst::PrfmReg(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_add_sub_ext_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_add_sub_ext_reg(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_01011_xx_1_xxxxx_xxx_xxx_xxxxx_xxxxx"),
@@ -1414,6 +1518,12 @@ fn parse_add_sub_ext_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(imm3): Extract<u8, 10, 13>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rd): Extract<u8, 0, 5>| {
+                // ADD and SUB can write SP, ADDS and SUBS write XZR instead
+                let rd_hint = if sf_op_s & 0b1 == 0b1 {
+                    AArch64MnemonicHint::X
+                } else {
+                    AArch64MnemonicHint::X_SP
+                };
                 let data = AddSubtractExtReg {
                     rm: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rm),
                     option,
@@ -1422,13 +1532,15 @@ fn parse_add_sub_ext_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rd: AArch64Architecture::get_register_by_mnemonic(
-                        AArch64MnemonicHint::X_SP,
-                        rd,
-                    ),
+                    rd: AArch64Architecture::get_register_by_mnemonic(rd_hint, rd),
                 };
 
-                match (sf_op_s, opt) {
+                // The extended register can only be shifted left by up to 4
+                if imm3 > 4 {
+                    return Err(undefined(raw_instr));
+                }
+
+                Ok(match (sf_op_s, opt) {
                     (0b000, 0b00) => AArch64Inst::AddExtReg32(data),
                     (0b001, 0b00) => AArch64Inst::AddsExtReg32(data),
                     (0b010, 0b00) => AArch64Inst::SubExtReg32(data),
@@ -1437,19 +1549,21 @@ fn parse_add_sub_ext_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b101, 0b00) => AArch64Inst::AddsExtReg64(data),
                     (0b110, 0b00) => AArch64Inst::SubExtReg64(data),
                     (0b111, 0b00) => AArch64Inst::SubsExtReg64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_bitfield(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_bitfield(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_xx_100110_x_xxxxxx_xxxxxx_xxxxx_xxxxx"),
@@ -1469,7 +1583,11 @@ fn parse_bitfield(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf, opc, n) {
+                if sf == 0b0 && (immr | imms) & 0b100000 != 0 {
+                    return Err(undefined(raw_instr));
+                }
+
+                Ok(match (sf, opc, n) {
                     (0b0, 0b00, 0b0) => AArch64Inst::Sbfm32(data),
                     (0b0, 0b01, 0b0) => AArch64Inst::Bfm32(data),
                     (0b0, 0b10, 0b0) => AArch64Inst::Ubfm32(data),
@@ -1477,19 +1595,21 @@ fn parse_bitfield(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b01, 0b1) => AArch64Inst::Bfm64(data),
                     (0b1, 0b10, 0b1) => AArch64Inst::Ubfm64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_logical_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_logical_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_xx_100100_x_xxxxxx_xxxxxx_xxxxx_xxxxx"),
@@ -1501,18 +1621,26 @@ fn parse_logical_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(imms): Extract<u8, 10, 16>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rd): Extract<u8, 0, 5>| {
+                // AND, ORR and EOR can write SP, ANDS writes XZR instead
+                let rd_hint = if opc == 0b11 {
+                    AArch64MnemonicHint::X
+                } else {
+                    AArch64MnemonicHint::X_SP
+                };
                 let data = LogicalImm {
                     n,
                     immr,
                     imms,
                     rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rn),
-                    rd: AArch64Architecture::get_register_by_mnemonic(
-                        AArch64MnemonicHint::X_SP,
-                        rd,
-                    ),
+                    rd: AArch64Architecture::get_register_by_mnemonic(rd_hint, rd),
                 };
 
-                match (sf, opc, n) {
+                let datasize = if sf == 0b1 { 64 } else { 32 };
+                if decode_bit_masks(n, imms, immr, true, datasize).is_none() {
+                    return Err(undefined(raw_instr));
+                }
+
+                Ok(match (sf, opc, n) {
                     (0b0, 0b00, 0b0) => AArch64Inst::AndImm32(data),
                     (0b0, 0b01, 0b0) => AArch64Inst::OrrImm32(data),
                     (0b0, 0b10, 0b0) => AArch64Inst::EorImm32(data),
@@ -1522,19 +1650,21 @@ fn parse_logical_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b10, _) => AArch64Inst::EorImm64(data),
                     (0b1, 0b11, _) => AArch64Inst::AndsImm64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_pair_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_pair_offset(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_101_x_010_x_xxxxxxx_xxxxx_xxxxx_xxxxx"),
@@ -1546,6 +1676,11 @@ fn parse_load_store_reg_pair_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(rt2): Extract<u8, 10, 15>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = LoadStoreRegPair {
                     opc,
                     imm7,
@@ -1555,10 +1690,10 @@ fn parse_load_store_reg_pair_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (opc, v, l) {
+                Ok(match (opc, v, l) {
                     (0b00, 0b0, 0b0) => AArch64Inst::StpVar32(data),
                     (0b00, 0b0, 0b1) => AArch64Inst::LdpVar32(data),
                     (0b00, 0b1, 0b0) => AArch64Inst::StpSimdFPVar32(data),
@@ -1571,19 +1706,21 @@ fn parse_load_store_reg_pair_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b10, 0b0, 0b1) => AArch64Inst::LdpVar64(data),
                     (0b10, 0b1, 0b0) => AArch64Inst::StpSimdFpVar128(data),
                     (0b10, 0b1, 0b1) => AArch64Inst::LdpSimdFpVar128(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_add_sub_imm_with_tags(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_add_sub_imm_with_tags(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_100011_x_xxxxxx_xx_xxxx_xxxxx_xxxxx"),
@@ -1610,22 +1747,24 @@ fn parse_add_sub_imm_with_tags(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     ),
                 };
 
-                match (sf_op_s, o2) {
+                Ok(match (sf_op_s, o2) {
                     (0b100, 0b0) => AArch64Inst::Addg(data),
                     (0b110, 0b0) => AArch64Inst::Subg(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_extract(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_extract(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_xx_100111_x_x_xxxxx_xxxxxx_xxxxx_xxxxx"),
@@ -1644,24 +1783,26 @@ fn parse_extract(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf_op21, n, o0, imms) {
+                Ok(match (sf_op21, n, o0, imms) {
                     (0b000, 0b0, 0b0, imms) if (imms & 0b100000) == 0b000000 => {
                         AArch64Inst::Extr32(data)
                     }
                     (0b100, 1, 0, _) => AArch64Inst::Extr64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_data_proc_1src(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_data_proc_1src(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_1_x_11010110_xxxxx_xxxxxx_xxxxx_xxxxx"),
@@ -1677,7 +1818,7 @@ fn parse_data_proc_1src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf, s, opcode2, opcode) {
+                Ok(match (sf, s, opcode2, opcode) {
                     (0b0, 0b0, 0b00000, 0b000000) => AArch64Inst::RbitVar32(data),
                     (0b0, 0b0, 0b00000, 0b000001) => AArch64Inst::Rev16Var32(data),
                     (0b0, 0b0, 0b00000, 0b000010) => AArch64Inst::RevVar32(data),
@@ -1689,19 +1830,21 @@ fn parse_data_proc_1src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b0, 0b00000, 0b000011) => AArch64Inst::RevVar64(data),
                     (0b1, 0b0, 0b00000, 0b000100) => AArch64Inst::ClzVar64(data),
                     (0b1, 0b0, 0b00000, 0b000101) => AArch64Inst::ClsVar64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_cmp_and_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_cmp_and_branch_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_011010_x_xxxxxxxxxxxxxxxxxxx_xxxxx"),
@@ -1715,24 +1858,26 @@ fn parse_cmp_and_branch_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (sf, op) {
+                Ok(match (sf, op) {
                     (0b0, 0b0) => AArch64Inst::Cbz32(data),
                     (0b0, 0b1) => AArch64Inst::Cbnz32(data),
                     (0b1, 0b0) => AArch64Inst::Cbz64(data),
                     (0b1, 0b1) => AArch64Inst::Cbnz64(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_data_proccessing_3src(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_data_proccessing_3src(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_xx_11011_xxx_xxxxx_x_xxxxx_xxxxx_xxxxx"),
@@ -1752,7 +1897,7 @@ fn parse_data_proccessing_3src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf, op54, op31, o0) {
+                Ok(match (sf, op54, op31, o0) {
                     (0b0, 0b00, 0b000, 0b0) => AArch64Inst::Madd32(data),
                     (0b0, 0b00, 0b000, 0b1) => AArch64Inst::Msub32(data),
                     (0b1, 0b00, 0b000, 0b0) => AArch64Inst::Madd64(data),
@@ -1763,19 +1908,21 @@ fn parse_data_proccessing_3src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b00, 0b101, 0b0) => AArch64Inst::Umaddl(data),
                     (0b1, 0b00, 0b101, 0b1) => AArch64Inst::Umsubl(data),
                     (0b1, 0b00, 0b110, 0b0) => AArch64Inst::Umulh(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_unscaled_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_unscaled_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_111_x_00_xx_0_xxxxxxxxx_00_xxxxx_xxxxx"),
@@ -1786,16 +1933,21 @@ fn parse_load_store_reg_unscaled_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(imm9): Extract<u16, 12, 21>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = LdStRegUnscaledImm {
                     imm9,
                     rn: AArch64Architecture::get_register_by_mnemonic(
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (size, v, opc) {
+                Ok(match (size, v, opc) {
                     (0b00, 0b0, 0b00) => AArch64Inst::Sturb(data),
                     (0b00, 0b0, 0b01) => AArch64Inst::Ldurb(data),
                     (0b00, 0b0, 0b10) => AArch64Inst::Ldursb64(data),
@@ -1821,19 +1973,21 @@ fn parse_load_store_reg_unscaled_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b1, 0b00) => AArch64Inst::SturSimdFP64(data),
                     (0b11, 0b1, 0b01) => AArch64Inst::LdurSimdFP64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_sys_reg_mov(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_sys_reg_mov(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("1101010100_x_1_x_xxx_xxxx_xxxx_xxx_xxxxx"),
@@ -1854,22 +2008,24 @@ fn parse_sys_reg_mov(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match l {
+                Ok(match l {
                     0 => AArch64Inst::MsrReg(data),
                     1 => AArch64Inst::Mrs(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_pair_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_pair_pre_indexed(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_101_x_011_x_xxxxxxx_xxxxx_xxxxx_xxxxx"),
@@ -1881,6 +2037,11 @@ fn parse_load_store_reg_pair_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst
              Extract(rt2): Extract<u8, 10, 15>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = LoadStoreRegPair {
                     opc,
                     o: 0b011,
@@ -1890,10 +2051,10 @@ fn parse_load_store_reg_pair_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (opc, v, l) {
+                Ok(match (opc, v, l) {
                     (0b00, 0b0, 0b0) => AArch64Inst::StpVar32(data),
                     (0b00, 0b0, 0b1) => AArch64Inst::LdpVar32(data),
                     (0b00, 0b1, 0b0) => AArch64Inst::StpSimdFPVar32(data),
@@ -1906,19 +2067,21 @@ fn parse_load_store_reg_pair_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst
                     (0b10, 0b0, 0b1) => AArch64Inst::LdpVar64(data),
                     (0b10, 0b1, 0b0) => AArch64Inst::StpSimdFpVar128(data),
                     (0b10, 0b1, 0b1) => AArch64Inst::LdpSimdFpVar128(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_pair_post_indexed(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_pair_post_indexed(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_101_x_001_x_xxxxxxx_xxxxx_xxxxx_xxxxx"),
@@ -1930,6 +2093,11 @@ fn parse_load_store_reg_pair_post_indexed(raw_instr: &[u8]) -> Option<AArch64Ins
              Extract(rt2): Extract<u8, 10, 15>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = LoadStoreRegPair {
                     opc,
                     o: 0b001,
@@ -1939,10 +2107,10 @@ fn parse_load_store_reg_pair_post_indexed(raw_instr: &[u8]) -> Option<AArch64Ins
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (opc, v, l) {
+                Ok(match (opc, v, l) {
                     (0b00, 0b0, 0b0) => AArch64Inst::StpVar32(data),
                     (0b00, 0b0, 0b1) => AArch64Inst::LdpVar32(data),
                     (0b00, 0b1, 0b0) => AArch64Inst::StpSimdFPVar32(data),
@@ -1955,19 +2123,21 @@ fn parse_load_store_reg_pair_post_indexed(raw_instr: &[u8]) -> Option<AArch64Ins
                     (0b10, 0b0, 0b1) => AArch64Inst::LdpVar64(data),
                     (0b10, 0b1, 0b0) => AArch64Inst::StpSimdFpVar128(data),
                     (0b10, 0b1, 0b1) => AArch64Inst::LdpSimdFpVar128(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_data_proc_2src(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_data_proc_2src(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11010110_xxxxx_xxxxxx_xxxxx_xxxxx"),
@@ -1984,7 +2154,7 @@ fn parse_data_proc_2src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match (sf, s, opcode) {
+                Ok(match (sf, s, opcode) {
                     (0b0, 0b0, 0b000010) => AArch64Inst::UdivVar32(data),
                     (0b0, 0b0, 0b000011) => AArch64Inst::SdivVar32(data),
                     (0b0, 0b0, 0b001000) => AArch64Inst::LslvVar32(data),
@@ -1999,19 +2169,21 @@ fn parse_data_proc_2src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b0, 0b001011) => AArch64Inst::RorvVar64(data),
 
                     (0b1, 0b0, 0b001100) => AArch64Inst::Pacga(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_imm_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_imm_pre_indexed(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_111_x_00_xx_0_xxxxxxxxx_11_xxxxx_xxxxx"),
@@ -2023,6 +2195,11 @@ fn parse_load_store_reg_imm_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst>
              Extract(imm12): Extract<u16, 10, 22>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = OpcSizeImm12RnRt {
                     idxt,
                     opc,
@@ -2032,10 +2209,10 @@ fn parse_load_store_reg_imm_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst>
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (size, v, opc) {
+                Ok(match (size, v, opc) {
                     (0b00, 0b0, 0b00) => AArch64Inst::StrbImm(data),
                     (0b00, 0b0, 0b01) => AArch64Inst::LdrbImm(data),
                     (0b00, 0b0, 0b10) => AArch64Inst::LdrsbImm64(data),
@@ -2061,19 +2238,21 @@ fn parse_load_store_reg_imm_pre_indexed(raw_instr: &[u8]) -> Option<AArch64Inst>
                     (0b11, 0b1, 0b00) => AArch64Inst::StrImmSimdFP64(data),
                     (0b11, 0b1, 0b01) => AArch64Inst::LdrImmSimdFP64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_imm_post_indexed(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_imm_post_indexed(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_111_x_00_xx_0_xxxxxxxxx_01_xxxxx_xxxxx"),
@@ -2085,6 +2264,11 @@ fn parse_load_store_reg_imm_post_indexed(raw_instr: &[u8]) -> Option<AArch64Inst
              Extract(imm12): Extract<u16, 10, 21>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = OpcSizeImm12RnRt {
                     idxt,
                     opc,
@@ -2094,10 +2278,10 @@ fn parse_load_store_reg_imm_post_indexed(raw_instr: &[u8]) -> Option<AArch64Inst
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (size, v, opc) {
+                Ok(match (size, v, opc) {
                     (0b00, 0b0, 0b00) => AArch64Inst::StrbImm(data),
                     (0b00, 0b0, 0b01) => AArch64Inst::LdrbImm(data),
                     (0b00, 0b0, 0b10) => AArch64Inst::LdrsbImm64(data),
@@ -2123,19 +2307,21 @@ fn parse_load_store_reg_imm_post_indexed(raw_instr: &[u8]) -> Option<AArch64Inst
                     (0b11, 0b1, 0b00) => AArch64Inst::StrImmSimdFP64(data),
                     (0b11, 0b1, 0b01) => AArch64Inst::LdrImmSimdFP64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_barriers(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_barriers(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("11010101000000110011_xxxx_xxx_xxxxx"),
@@ -2145,25 +2331,27 @@ fn parse_barriers(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(rt): Extract<u8, 0, 5>| {
                 let data = Barriers { crm };
 
-                match (crm, op2, rt) {
+                Ok(match (crm, op2, rt) {
                     (_, 0b010, 0b11111) => AArch64Inst::Clrex(data),
                     (_, 0b100, 0b11111) => AArch64Inst::DsbEncoding(data),
                     (_, 0b101, 0b11111) => AArch64Inst::Dmb(data),
                     (_, 0b110, 0b11111) => AArch64Inst::Isb(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_advanced_simd_copy(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_advanced_simd_copy(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_x_01110000_xxxxx_0_xxxx_1_xxxxx_xxxxx"),
@@ -2182,28 +2370,55 @@ fn parse_advanced_simd_copy(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (q, op, imm5, imm4) {
+                Ok(match (q, op, imm5, imm4) {
                     (_, 0b0, _, 0b0000) => AArch64Inst::DupElement(data),
-                    (_, 0b0, _, 0b0001) => AArch64Inst::DupGeneral(data),
-                    (0b0 | 0b1, 0b0, _, 0b0101) => AArch64Inst::Smov(data),
-                    (0b0, 0b0, _, 0b0111) => AArch64Inst::Umov(data),
-                    (0b1, 0b0, 0b01000 | 0b11000, 0b0111) => AArch64Inst::Umov(data),
-                    (0b1, 0b0, _, 0b0011) => AArch64Inst::InsGeneral(data),
+                    (_, 0b0, _, 0b0001) => AArch64Inst::DupGeneral(AdvancedSimdCopy {
+                        rn: AArch64Architecture::get_register_by_mnemonic(
+                            AArch64MnemonicHint::X,
+                            rn,
+                        ),
+                        ..data
+                    }),
+                    (0b0 | 0b1, 0b0, _, 0b0101) => AArch64Inst::Smov(AdvancedSimdCopy {
+                        rd: AArch64Architecture::get_register_by_mnemonic(
+                            AArch64MnemonicHint::X,
+                            rd,
+                        ),
+                        ..data
+                    }),
+                    (0b0, 0b0, _, 0b0111) | (0b1, 0b0, 0b01000 | 0b11000, 0b0111) => {
+                        AArch64Inst::Umov(AdvancedSimdCopy {
+                            rd: AArch64Architecture::get_register_by_mnemonic(
+                                AArch64MnemonicHint::X,
+                                rd,
+                            ),
+                            ..data
+                        })
+                    }
+                    (0b1, 0b0, _, 0b0011) => AArch64Inst::InsGeneral(AdvancedSimdCopy {
+                        rn: AArch64Architecture::get_register_by_mnemonic(
+                            AArch64MnemonicHint::X,
+                            rn,
+                        ),
+                        ..data
+                    }),
                     (0b1, 0b1, _, _) => AArch64Inst::InsElement(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_cond_cmp_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_cond_cmp_reg(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_11010010_xxxxx_xxxx_0_x_xxxxx_x_xxxx"),
@@ -2222,25 +2437,27 @@ fn parse_cond_cmp_reg(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     nzcv,
                 };
 
-                match (sf_op_s, o2, o3) {
+                Ok(match (sf_op_s, o2, o3) {
                     (0b001, 0b0, 0b0) => AArch64Inst::CcmnRegVar32(data),
                     (0b011, 0b0, 0b0) => AArch64Inst::CcmpRegVar32(data),
                     (0b101, 0b0, 0b0) => AArch64Inst::CcmnRegVar64(data),
                     (0b111, 0b0, 0b0) => AArch64Inst::CcmpRegVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_ld_st_multi_structures(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_ld_st_multi_structures(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_0011000_x_000000_xxxx_xx_xxxxx_xxxxx"),
@@ -2261,7 +2478,7 @@ fn parse_adv_simd_ld_st_multi_structures(raw_instr: &[u8]) -> Option<AArch64Inst
                     rt,
                 };
 
-                match (l, opcode) {
+                Ok(match (l, opcode) {
                     (0b0, 0b0000) => AArch64Inst::St4MulStructures(data),
                     (0b0, 0b0010) => AArch64Inst::St1MulStructures4RegsVar(data),
                     (0b0, 0b0100) => AArch64Inst::St3MulStructures(data),
@@ -2278,19 +2495,21 @@ fn parse_adv_simd_ld_st_multi_structures(raw_instr: &[u8]) -> Option<AArch64Inst
                     (0b1, 0b1000) => AArch64Inst::Ld2MulStructures(data),
                     (0b1, 0b1010) => AArch64Inst::Ld1MulStructures2RegsVar(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_advanced_simd_extract(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_advanced_simd_extract(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_101110_xx_0_xxxxx_0_xxxx_0_xxxxx_xxxxx"),
@@ -2309,21 +2528,25 @@ fn parse_advanced_simd_extract(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match op2 {
+                Ok(match op2 {
                     0b00 => AArch64Inst::Ext(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_ld_st_multi_structures_post_indexed(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_ld_st_multi_structures_post_indexed(
+    raw_instr: &[u8],
+) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_0011001_x_0_xxxxx_xxxx_xx_xxxxx_xxxxx"),
@@ -2346,7 +2569,7 @@ fn parse_adv_simd_ld_st_multi_structures_post_indexed(raw_instr: &[u8]) -> Optio
                     rt,
                 };
 
-                match (l, rm, opcode) {
+                Ok(match (l, rm, opcode) {
                     (0b0, rm, 0b0000) if rm != 0b11111 => {
                         AArch64Inst::St4MulStructuresRegOffsetVar(data)
                     }
@@ -2406,19 +2629,21 @@ fn parse_adv_simd_ld_st_multi_structures_post_indexed(raw_instr: &[u8]) -> Optio
                     (0b1, 0b11111, 0b0111) => AArch64Inst::Ld1MulStructures1RegImmOffsetVar(data),
                     (0b1, 0b11111, 0b1000) => AArch64Inst::Ld2MulStructuresImmOffsetVar(data),
                     (0b1, 0b11111, 0b1010) => AArch64Inst::Ld1MulStructures2RegImmOffsetVar(data),
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_conv_between_float_and_int(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_conv_between_float_and_int(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11110_xx_1_xx_xxx_000000_xxxxx_xxxxx"),
@@ -2430,12 +2655,17 @@ fn parse_conv_between_float_and_int(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(opcode): Extract<u8, 16, 19>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rd): Extract<u8, 0, 5>| {
+                // SCVTF, UCVTF and the FMOVs into a SIMD&FP register read a general-purpose one.
+                let (rn_hint, rd_hint) = match opcode {
+                    0b010 | 0b011 | 0b111 => (AArch64MnemonicHint::X, AArch64MnemonicHint::V),
+                    _ => (AArch64MnemonicHint::V, AArch64MnemonicHint::X),
+                };
                 let data = RnRd {
-                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
-                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
+                    rn: AArch64Architecture::get_register_by_mnemonic(rn_hint, rn),
+                    rd: AArch64Architecture::get_register_by_mnemonic(rd_hint, rd),
                 };
 
-                match (sf, s, ptype, rmode, opcode) {
+                Ok(match (sf, s, ptype, rmode, opcode) {
                     (0b0, 0b0, 0b00, 0b00, 0b000) => {
                         AArch64Inst::FcvtnsScalarSinglePrecisionTo32(data)
                     }
@@ -2514,10 +2744,53 @@ fn parse_conv_between_float_and_int(raw_instr: &[u8]) -> Option<AArch64Inst> {
                         AArch64Inst::FcvtzsScalarIntDoublePrecisionTo32(data)
                     }
                     (0b0, 0b0, 0b01, 0b11, 0b001) => {
-                        AArch64Inst::FcvtzsScalarIntDoublePrecisionTo32(data)
+                        AArch64Inst::FcvtzuScalarIntDoublePrecisionTo32(data)
                     }
                     (0b0, 0b0, 0b01, 0b11, 0b110) => AArch64Inst::Fjcvtzs(data),
 
+                    (0b0, 0b0, 0b11, 0b00, 0b000) => {
+                        AArch64Inst::FcvtnsScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b001) => {
+                        AArch64Inst::FcvtnuScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b010) => {
+                        AArch64Inst::ScvtfScalarInt32ToHalfPrecision(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b011) => {
+                        AArch64Inst::UcvtfScalarInt32ToHalfPrecision(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b100) => {
+                        AArch64Inst::FcvtasScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b101) => {
+                        AArch64Inst::FcvtauScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b110) => {
+                        AArch64Inst::FmovGeneralHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b111) => {
+                        AArch64Inst::FmovGeneral32ToHalfPrecision(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b01, 0b000) => {
+                        AArch64Inst::FcvtpsScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b01, 0b001) => {
+                        AArch64Inst::FcvtpuScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b10, 0b000) => {
+                        AArch64Inst::FcvtmsScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b10, 0b001) => {
+                        AArch64Inst::FcvtmuScalarHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b11, 0b000) => {
+                        AArch64Inst::FcvtzsScalarIntHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b11, 0b001) => {
+                        AArch64Inst::FcvtzuScalarIntHalfPrecisionTo32(data)
+                    }
+
                     (0b1, 0b0, 0b00, 0b00, 0b000) => {
                         AArch64Inst::FcvtnsScalarSinglePrecisionTo64(data)
                     }
@@ -2596,25 +2869,70 @@ fn parse_conv_between_float_and_int(raw_instr: &[u8]) -> Option<AArch64Inst> {
                         AArch64Inst::FcvtzsScalarIntDoublePrecisionTo64(data)
                     }
                     (0b1, 0b0, 0b01, 0b11, 0b001) => {
-                        AArch64Inst::FcvtzsScalarIntDoublePrecisionTo64(data)
+                        AArch64Inst::FcvtzuScalarIntDoublePrecisionTo64(data)
+                    }
+
+                    (0b1, 0b0, 0b11, 0b00, 0b000) => {
+                        AArch64Inst::FcvtnsScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b001) => {
+                        AArch64Inst::FcvtnuScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b010) => {
+                        AArch64Inst::ScvtfScalarInt64ToHalfPrecision(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b011) => {
+                        AArch64Inst::UcvtfScalarInt64ToHalfPrecision(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b100) => {
+                        AArch64Inst::FcvtasScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b101) => {
+                        AArch64Inst::FcvtauScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b110) => {
+                        AArch64Inst::FmovGeneralHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b111) => {
+                        AArch64Inst::FmovGeneral64ToHalfPrecision(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b01, 0b000) => {
+                        AArch64Inst::FcvtpsScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b01, 0b001) => {
+                        AArch64Inst::FcvtpuScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b10, 0b000) => {
+                        AArch64Inst::FcvtmsScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b10, 0b001) => {
+                        AArch64Inst::FcvtmuScalarHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b11, 0b000) => {
+                        AArch64Inst::FcvtzsScalarIntHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b11, 0b001) => {
+                        AArch64Inst::FcvtzuScalarIntHalfPrecisionTo64(data)
                     }
 
                     (0b1, 0b0, 0b10, 0b01, 0b110) => AArch64Inst::FmovGeneralTopHalfOf128To64(data),
                     (0b1, 0b0, 0b10, 0b01, 0b111) => AArch64Inst::FmovGeneral64toTopHalfOf128(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_modified_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_modified_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_x_01111_00000_x_x_x_xxxx_x_1_x_x_x_x_x_xxxxx"),
@@ -2651,7 +2969,7 @@ fn parse_adv_simd_modified_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (q, op, cmode0, cmode1, cmode2, cmode3, o2) {
+                Ok(match (q, op, cmode0, cmode1, cmode2, cmode3, o2) {
                     (_, 0b0, 0, _, _, 0, 0b0) => AArch64Inst::MoviShiftedImmVar32(data),
                     (_, 0b0, 0, _, _, 1, 0b0) => AArch64Inst::OrrVecImmVar32(data),
                     (_, 0b0, 1, 0, _, 0, 0b0) => AArch64Inst::MoviShiftedImmVar16(data),
@@ -2671,19 +2989,21 @@ fn parse_adv_simd_modified_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b1, 1, 1, 1, 0, 0b0) => AArch64Inst::MoviVectorVar64(data),
                     (0b1, 0b1, 1, 1, 1, 1, 0b0) => AArch64Inst::FmovVecImmDoublePrecisionVar(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_cond_cmp_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_cond_cmp_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_11010010_xxxxx_xxxx_1_x_xxxxx_x_xxxx"),
@@ -2702,25 +3022,27 @@ fn parse_cond_cmp_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     nzcv,
                 };
 
-                match (sf_op_s, o2, o3) {
+                Ok(match (sf_op_s, o2, o3) {
                     (0b001, 0b0, 0b0) => AArch64Inst::CcmnImmVar32(data),
                     (0b011, 0b0, 0b0) => AArch64Inst::CcmpImmVar32(data),
                     (0b101, 0b0, 0b0) => AArch64Inst::CcmnImmVar64(data),
                     (0b111, 0b0, 0b0) => AArch64Inst::CcmpImmVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_exclusive_register(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_exclusive_register(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_0010000_x_0_xxxxx_x_xxxxx_xxxxx_xxxxx"),
@@ -2742,7 +3064,7 @@ fn parse_load_store_exclusive_register(raw_instr: &[u8]) -> Option<AArch64Inst>
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (size, l, o0) {
+                Ok(match (size, l, o0) {
                     (0b00, 0b0, 0b0) => AArch64Inst::Stxrb(data),
                     (0b00, 0b1, 0b0) => AArch64Inst::Ldxrb(data),
                     (0b01, 0b0, 0b0) => AArch64Inst::Stxrh(data),
@@ -2761,19 +3083,21 @@ fn parse_load_store_exclusive_register(raw_instr: &[u8]) -> Option<AArch64Inst>
                     (0b11, 0b0, 0b1) => AArch64Inst::StlxrVar64(data),
                     (0b11, 0b1, 0b1) => AArch64Inst::LdaxrVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_ordered(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_ordered(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_0010001_x_0_xxxxx_x_xxxxx_xxxxx_xxxxx"),
@@ -2795,7 +3119,7 @@ fn parse_load_store_ordered(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (size, l, o0) {
+                Ok(match (size, l, o0) {
                     (0b00, 0b0, 0b1) => AArch64Inst::Stlrb(data),
                     (0b00, 0b1, 0b1) => AArch64Inst::Ldarb(data),
                     (0b01, 0b0, 0b1) => AArch64Inst::Stlrh(data),
@@ -2805,19 +3129,21 @@ fn parse_load_store_ordered(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b0, 0b1) => AArch64Inst::StlrVar64(data),
                     (0b11, 0b1, 0b1) => AArch64Inst::LdarVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_advanced_simd_three_same(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_advanced_simd_three_same(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_x_01110_xx_1_xxxxx_xxxxx_1_xxxxx_xxxxx"),
@@ -2837,7 +3163,7 @@ fn parse_advanced_simd_three_same(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (u, size, opcode) {
+                Ok(match (u, size, opcode) {
                     (0b0, _, 0b00000) => AArch64Inst::Shadd(data),
                     (0b0, _, 0b00001) => AArch64Inst::Sqadd(data),
                     (0b0, _, 0b00010) => AArch64Inst::Srhadd(data),
@@ -2871,7 +3197,7 @@ fn parse_advanced_simd_three_same(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b0, 0b00 | 0b01, 0b11111) => AArch64Inst::Frecps(data),
 
                     (0b0, 0b00, 0b00011) => AArch64Inst::AndVec(data),
-                    (0b0, 0b01, 0b00011) => AArch64Inst::AndVec(data),
+                    (0b0, 0b01, 0b00011) => AArch64Inst::BicVecReg(data),
 
                     (0b0, 0b10 | 0b11, 0b11000) => AArch64Inst::FminnmVec(data),
                     (0b0, 0b10 | 0b11, 0b11001) => AArch64Inst::FmlsVec(data),
@@ -2926,37 +3252,165 @@ fn parse_advanced_simd_three_same(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b10, 0b00011) => AArch64Inst::Bit(data),
                     (0b1, 0b11, 0b00011) => AArch64Inst::Bif(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
+
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_advanced_simd_three_same_fp16(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("0_x_x_01110_x_10_xxxxx_00_xxx_1_xxxxx_xxxxx"),
+            |raw_instr: &[u8],
+             Extract(q): Extract<u8, 30, 31>,
+             Extract(u): Extract<u8, 29, 30>,
+             Extract(size): Extract<u8, 22, 24>,
+             Extract(rm): Extract<u8, 16, 21>,
+             Extract(opcode): Extract<u8, 11, 14>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(rd): Extract<u8, 0, 5>| {
+                let data = QSizeRmRnRd {
+                    q,
+                    size,
+                    rm: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rm),
+                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
+                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
+                };
+
+                // The low bit of size is always set, the high one is the `a` bit of the opcode
+                Ok(match (u, size, opcode) {
+                    (0b0, 0b01, 0b000) => AArch64Inst::FmaxnmVecHalfPrecision(data),
+                    (0b0, 0b01, 0b001) => AArch64Inst::FmlaVecHalfPrecision(data),
+                    (0b0, 0b01, 0b010) => AArch64Inst::FaddVecHalfPrecision(data),
+                    (0b0, 0b01, 0b011) => AArch64Inst::FmulxVecHalfPrecision(data),
+                    (0b0, 0b01, 0b100) => AArch64Inst::FcmeqRegVecHalfPrecision(data),
+                    (0b0, 0b01, 0b110) => AArch64Inst::FmaxVecHalfPrecision(data),
+                    (0b0, 0b01, 0b111) => AArch64Inst::FrecpsVecHalfPrecision(data),
+                    (0b0, 0b11, 0b000) => AArch64Inst::FminnmVecHalfPrecision(data),
+                    (0b0, 0b11, 0b001) => AArch64Inst::FmlsVecHalfPrecision(data),
+                    (0b0, 0b11, 0b010) => AArch64Inst::FsubVecHalfPrecision(data),
+                    (0b0, 0b11, 0b110) => AArch64Inst::FminVecHalfPrecision(data),
+                    (0b0, 0b11, 0b111) => AArch64Inst::FrsqrtsVecHalfPrecision(data),
+                    (0b1, 0b01, 0b000) => AArch64Inst::FmaxnmpVecHalfPrecision(data),
+                    (0b1, 0b01, 0b010) => AArch64Inst::FaddpVecHalfPrecision(data),
+                    (0b1, 0b01, 0b011) => AArch64Inst::FmulVecHalfPrecision(data),
+                    (0b1, 0b01, 0b100) => AArch64Inst::FcmgeRegVecHalfPrecision(data),
+                    (0b1, 0b01, 0b101) => AArch64Inst::FacgeVecHalfPrecision(data),
+                    (0b1, 0b01, 0b110) => AArch64Inst::FmaxpVecHalfPrecision(data),
+                    (0b1, 0b01, 0b111) => AArch64Inst::FdivVecHalfPrecision(data),
+                    (0b1, 0b11, 0b000) => AArch64Inst::FminnmpVecHalfPrecision(data),
+                    (0b1, 0b11, 0b010) => AArch64Inst::FabdVecHalfPrecision(data),
+                    (0b1, 0b11, 0b100) => AArch64Inst::FcmgtRegVecHalfPrecision(data),
+                    (0b1, 0b11, 0b101) => AArch64Inst::FacgtVecHalfPrecision(data),
+                    (0b1, 0b11, 0b110) => AArch64Inst::FminpVecHalfPrecision(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
+
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_adv_simd_three_different(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("0_x_x_01110_xx_1_xxxxx_xxxx_00_xxxxx_xxxxx"),
+            |raw_instr: &[u8],
+             Extract(q): Extract<u8, 30, 31>,
+             Extract(u): Extract<u8, 29, 30>,
+             Extract(size): Extract<u8, 22, 24>,
+             Extract(rm): Extract<u8, 16, 21>,
+             Extract(opcode): Extract<u8, 12, 16>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(rd): Extract<u8, 0, 5>| {
+                let data = QSizeRmRnRd {
+                    q,
+                    size,
+                    rm: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rm),
+                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
+                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
+                };
+
+                Ok(match (u, opcode) {
+                    (0b0, 0b0000) => AArch64Inst::Saddl(data),
+                    (0b0, 0b0001) => AArch64Inst::Saddw(data),
+                    (0b0, 0b0010) => AArch64Inst::Ssubl(data),
+                    (0b0, 0b0011) => AArch64Inst::Ssubw(data),
+                    (0b0, 0b0100) => AArch64Inst::Addhn(data),
+                    (0b0, 0b0101) => AArch64Inst::Sabal(data),
+                    (0b0, 0b0110) => AArch64Inst::Subhn(data),
+                    (0b0, 0b0111) => AArch64Inst::Sabdl(data),
+                    (0b0, 0b1000) => AArch64Inst::SmlalVec(data),
+                    (0b0, 0b1001) => AArch64Inst::SqdmlalVec(data),
+                    (0b0, 0b1010) => AArch64Inst::SmlslVec(data),
+                    (0b0, 0b1011) => AArch64Inst::SqdmlslVec(data),
+                    (0b0, 0b1100) => AArch64Inst::SmullVec(data),
+                    (0b0, 0b1101) => AArch64Inst::SqdmullVec(data),
+                    (0b0, 0b1110) => AArch64Inst::Pmull(data),
+
+                    (0b1, 0b0000) => AArch64Inst::Uaddl(data),
+                    (0b1, 0b0001) => AArch64Inst::Uaddw(data),
+                    (0b1, 0b0010) => AArch64Inst::Usubl(data),
+                    (0b1, 0b0011) => AArch64Inst::Usubw(data),
+                    (0b1, 0b0100) => AArch64Inst::Raddhn(data),
+                    (0b1, 0b0101) => AArch64Inst::Uabal(data),
+                    (0b1, 0b0110) => AArch64Inst::Rsubhn(data),
+                    (0b1, 0b0111) => AArch64Inst::Uabdl(data),
+                    (0b1, 0b1000) => AArch64Inst::UmlalVec(data),
+                    (0b1, 0b1010) => AArch64Inst::UmlslVec(data),
+                    (0b1, 0b1100) => AArch64Inst::UmullVec(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_shift_by_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_shift_by_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_x_011110_xxxx_xxx_xxxxx_1_xxxxx_xxxxx"),
             |raw_instr: &[u8],
              Extract(q): Extract<u8, 30, 31>,
              Extract(u): Extract<u8, 29, 30>,
+             Extract(immh): Extract<u8, 19, 23>,
              Extract(immb): Extract<u8, 16, 19>,
              Extract(opcode): Extract<u8, 11, 16>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rd): Extract<u8, 0, 5>| {
                 let data = AdvSimdShiftByImm {
                     q,
+                    immh,
                     immb,
                     rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (u, opcode) {
+                Ok(match (u, opcode) {
                     (0b0, 0b00000) => AArch64Inst::Sshr(data),
                     (0b0, 0b00010) => AArch64Inst::Ssra(data),
                     (0b0, 0b00100) => AArch64Inst::Srshr(data),
@@ -2990,19 +3444,21 @@ fn parse_adv_simd_shift_by_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b11100) => AArch64Inst::UcvtfVecFixedPt(data),
                     (0b1, 0b11111) => AArch64Inst::FcvtzuVecFixedPt(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_float_data_proc_1src(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_float_data_proc_1src(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11110_xx_1_xxxxxx_10000_xxxxx_xxxxx"),
@@ -3018,7 +3474,7 @@ fn parse_float_data_proc_1src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (m, s, ptype, opcode) {
+                Ok(match (m, s, ptype, opcode) {
                     (0b0, 0b0, 0b00, 0b000000) => AArch64Inst::FmovRegSinglePrecisionVar(data),
                     (0b0, 0b0, 0b00, 0b000001) => AArch64Inst::FabsScalarSinglePrecisionVar(data),
                     (0b0, 0b0, 0b00, 0b000010) => AArch64Inst::FnegScalarSinglePrecisionVar(data),
@@ -3051,19 +3507,37 @@ fn parse_float_data_proc_1src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b0, 0b0, 0b01, 0b001110) => AArch64Inst::FrintxScalarDoublePrecisionVar(data),
                     (0b0, 0b0, 0b01, 0b001111) => AArch64Inst::FrintiScalarDoublePrecisionVar(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    (0b0, 0b0, 0b11, 0b000000) => AArch64Inst::FmovRegHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b000001) => AArch64Inst::FabsScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b000010) => AArch64Inst::FnegScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b000011) => AArch64Inst::FsqrtScalarHalfPrecisionVar(data),
+
+                    (0b0, 0b0, 0b11, 0b000100) => AArch64Inst::FcvtHalfToSinglePrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b000101) => AArch64Inst::FcvtHalfToDoublePrecisionVar(data),
+
+                    (0b0, 0b0, 0b11, 0b001000) => AArch64Inst::FrintnScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b001001) => AArch64Inst::FrintpScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b001010) => AArch64Inst::FrintmScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b001011) => AArch64Inst::FrintzScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b001100) => AArch64Inst::FrintaScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b001110) => AArch64Inst::FrintxScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b001111) => AArch64Inst::FrintiScalarHalfPrecisionVar(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_scalar_pairwise(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_scalar_pairwise(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("01_x_11110_xx_11000_xxxxx_10_xxxxx_xxxxx"),
@@ -3079,27 +3553,127 @@ fn parse_adv_simd_scalar_pairwise(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (u, size, opcode) {
+                Ok(match (u, size, opcode) {
                     (0b0, _, 0b11011) => AArch64Inst::AddpScalar(data),
-                    (0b0, 0b00 | 0b01, 0b01100) => AArch64Inst::FmaxnmpScalarEncoding(data),
-                    (0b0, 0b00 | 0b01, 0b01101) => AArch64Inst::FaddpScalarEncoding(data),
-                    (0b0, 0b00 | 0b01, 0b01111) => AArch64Inst::FmaxpScalarEncoding(data),
-                    (0b0, 0b10 | 0b11, 0b01100) => AArch64Inst::FminnmpScalarEncoding(data),
-                    (0b0, 0b10 | 0b11, 11) => AArch64Inst::FminpScalarEncoding(data),
+                    (0b0, 0b00, 0b01100) => AArch64Inst::FmaxnmpScalarHalfPrecision(data),
+                    (0b0, 0b00, 0b01101) => AArch64Inst::FaddpScalarHalfPrecision(data),
+                    (0b0, 0b00, 0b01111) => AArch64Inst::FmaxpScalarHalfPrecision(data),
+                    (0b0, 0b10, 0b01100) => AArch64Inst::FminnmpScalarHalfPrecision(data),
+                    (0b0, 0b10, 0b01111) => AArch64Inst::FminpScalarHalfPrecision(data),
+                    (0b1, 0b00 | 0b01, 0b01100) => AArch64Inst::FmaxnmpScalarEncoding(data),
+                    (0b1, 0b00 | 0b01, 0b01101) => AArch64Inst::FaddpScalarEncoding(data),
+                    (0b1, 0b00 | 0b01, 0b01111) => AArch64Inst::FmaxpScalarEncoding(data),
+                    (0b1, 0b10 | 0b11, 0b01100) => AArch64Inst::FminnmpScalarEncoding(data),
+                    (0b1, 0b10 | 0b11, 0b01111) => AArch64Inst::FminpScalarEncoding(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_adv_simd_scalar_three_same_fp16(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("01_x_11110_x_10_xxxxx_00_xxx_1_xxxxx_xxxxx"),
+            |raw_instr: &[u8],
+             Extract(u): Extract<u8, 29, 30>,
+             Extract(a): Extract<u8, 23, 24>,
+             Extract(rm): Extract<u8, 16, 21>,
+             Extract(opcode): Extract<u8, 11, 14>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(rd): Extract<u8, 0, 5>| {
+                let data = RmRnRd {
+                    rm: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rm),
+                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
+                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
+                };
+
+                Ok(match (u, a, opcode) {
+                    (0b0, 0b0, 0b011) => AArch64Inst::FmulxScalarHalfPrecision(data),
+                    (0b0, 0b0, 0b100) => AArch64Inst::FcmeqRegScalarHalfPrecision(data),
+                    (0b0, 0b0, 0b111) => AArch64Inst::FrecpsScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b111) => AArch64Inst::FrsqrtsScalarHalfPrecision(data),
+                    (0b1, 0b0, 0b100) => AArch64Inst::FcmgeRegScalarHalfPrecision(data),
+                    (0b1, 0b0, 0b101) => AArch64Inst::FacgeScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b010) => AArch64Inst::FabdScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b100) => AArch64Inst::FcmgtRegScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b101) => AArch64Inst::FacgtScalarHalfPrecision(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_ld_st_single_structure(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_scalar_2reg_misc_fp16(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("01_x_11110_x_1111_00_xxxxx_10_xxxxx_xxxxx"),
+            |raw_instr: &[u8],
+             Extract(u): Extract<u8, 29, 30>,
+             Extract(a): Extract<u8, 23, 24>,
+             Extract(opcode): Extract<u8, 12, 17>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(rd): Extract<u8, 0, 5>| {
+                let data = RnRd {
+                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
+                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
+                };
+
+                Ok(match (u, a, opcode) {
+                    (0b0, 0b0, 0b11010) => AArch64Inst::FcvtnsScalarHalfPrecision(data),
+                    (0b0, 0b0, 0b11011) => AArch64Inst::FcvtmsScalarHalfPrecision(data),
+                    (0b0, 0b0, 0b11100) => AArch64Inst::FcvtasScalarHalfPrecision(data),
+                    (0b0, 0b0, 0b11101) => AArch64Inst::ScvtfScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b01100) => AArch64Inst::FcmgtZeroScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b01101) => AArch64Inst::FcmeqZeroScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b01110) => AArch64Inst::FcmltZeroScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b11010) => AArch64Inst::FcvtpsScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b11011) => AArch64Inst::FcvtzsScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b11101) => AArch64Inst::FrecpeScalarHalfPrecision(data),
+                    (0b0, 0b1, 0b11111) => AArch64Inst::FrecpxScalarHalfPrecision(data),
+                    (0b1, 0b0, 0b11010) => AArch64Inst::FcvtnuScalarHalfPrecision(data),
+                    (0b1, 0b0, 0b11011) => AArch64Inst::FcvtmuScalarHalfPrecision(data),
+                    (0b1, 0b0, 0b11100) => AArch64Inst::FcvtauScalarHalfPrecision(data),
+                    (0b1, 0b0, 0b11101) => AArch64Inst::UcvtfScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b01100) => AArch64Inst::FcmgeZeroScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b01101) => AArch64Inst::FcmleZeroScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b11010) => AArch64Inst::FcvtpuScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b11011) => AArch64Inst::FcvtzuScalarHalfPrecision(data),
+                    (0b1, 0b1, 0b11101) => AArch64Inst::FrsqrteScalarHalfPrecision(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
+
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_adv_simd_ld_st_single_structure(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_0011010_x_x_00000_xxx_x_xx_xxxxx_xxxxx"),
@@ -3123,7 +3697,7 @@ fn parse_adv_simd_ld_st_single_structure(raw_instr: &[u8]) -> Option<AArch64Inst
                     rt,
                 };
 
-                match (l, r, opcode, s, size) {
+                Ok(match (l, r, opcode, s, size) {
                     (0b0, 0b0, 0b000, _, _) => AArch64Inst::St1SingleStructureVar8(data),
                     (0b0, 0b0, 0b001, _, _) => AArch64Inst::St3SingleStructureVar8(data),
                     (0b0, 0b0, 0b010, _, 0b00 | 0b10) => AArch64Inst::St1SingleStructureVar16(data),
@@ -3171,21 +3745,268 @@ fn parse_adv_simd_ld_st_single_structure(raw_instr: &[u8]) -> Option<AArch64Inst
                     (0b1, 0b1, 0b101, 0b0, 0b01) => AArch64Inst::Ld4SingleStructureVar64(data),
 
                     (0b1, 0b1, 0b110, 0b0, _) => AArch64Inst::Ld2r(data),
-                    (0b1, 0b1, 0b111, 0b0, _) => AArch64Inst::Ld2r(data),
+                    (0b1, 0b1, 0b111, 0b0, _) => AArch64Inst::Ld4r(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
+
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_adv_simd_ld_st_single_structure_post_indexed(
+    raw_instr: &[u8],
+) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("0_x_0011011_x_x_xxxxx_xxx_x_xx_xxxxx_xxxxx"),
+            |raw_instr: &[u8],
+             Extract(q): Extract<u8, 30, 31>,
+             Extract(l): Extract<u8, 22, 23>,
+             Extract(r): Extract<u8, 21, 22>,
+             Extract(rm): Extract<u8, 16, 21>,
+             Extract(opcode): Extract<u8, 13, 16>,
+             Extract(s): Extract<u8, 12, 13>,
+             Extract(size): Extract<u8, 10, 12>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(rt): Extract<u8, 0, 5>| {
+                let data = AdvSimdLdStSingleStructurePostIndexed {
+                    q,
+                    rm: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rm),
+                    s,
+                    size,
+                    rn: AArch64Architecture::get_register_by_mnemonic(
+                        AArch64MnemonicHint::X_SP,
+                        rn,
+                    ),
+                    rt,
+                };
+
+                Ok(match (l, r, rm, opcode, s, size) {
+                    (0b0, 0b0, 0b11111, 0b000, _, _) => {
+                        AArch64Inst::St1SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b0, 0b0, 0b11111, 0b001, _, _) => {
+                        AArch64Inst::St3SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b0, 0b0, 0b11111, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::St1SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b0, 0b0, 0b11111, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::St3SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b0, 0b0, 0b11111, 0b100, _, 0b00) => {
+                        AArch64Inst::St1SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b0, 0b0, 0b11111, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::St1SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b0, 0b0, 0b11111, 0b101, _, 0b00) => {
+                        AArch64Inst::St3SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b0, 0b0, 0b11111, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::St3SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b000, _, _) => {
+                        AArch64Inst::St2SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b001, _, _) => {
+                        AArch64Inst::St4SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::St2SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::St4SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b100, _, 0b00) => {
+                        AArch64Inst::St2SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::St2SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b101, _, 0b00) => {
+                        AArch64Inst::St4SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b0, 0b1, 0b11111, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::St4SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b000, _, _) => {
+                        AArch64Inst::Ld1SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b001, _, _) => {
+                        AArch64Inst::Ld3SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld1SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld3SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b100, _, 0b00) => {
+                        AArch64Inst::Ld1SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::Ld1SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b101, _, 0b00) => {
+                        AArch64Inst::Ld3SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::Ld3SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b1, 0b0, 0b11111, 0b110, 0b0, _) => AArch64Inst::Ld1rImmOffset(data),
+                    (0b1, 0b0, 0b11111, 0b111, 0b0, _) => AArch64Inst::Ld3rImmOffset(data),
+                    (0b1, 0b1, 0b11111, 0b000, _, _) => {
+                        AArch64Inst::Ld2SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b001, _, _) => {
+                        AArch64Inst::Ld4SingleStructureImmOffsetVar8(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld2SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld4SingleStructureImmOffsetVar16(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b100, _, 0b00) => {
+                        AArch64Inst::Ld2SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::Ld2SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b101, _, 0b00) => {
+                        AArch64Inst::Ld4SingleStructureImmOffsetVar32(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::Ld4SingleStructureImmOffsetVar64(data)
+                    }
+                    (0b1, 0b1, 0b11111, 0b110, 0b0, _) => AArch64Inst::Ld2rImmOffset(data),
+                    (0b1, 0b1, 0b11111, 0b111, 0b0, _) => AArch64Inst::Ld4rImmOffset(data),
+
+                    (0b0, 0b0, _, 0b000, _, _) => {
+                        AArch64Inst::St1SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b0, 0b0, _, 0b001, _, _) => {
+                        AArch64Inst::St3SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b0, 0b0, _, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::St1SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b0, 0b0, _, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::St3SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b0, 0b0, _, 0b100, _, 0b00) => {
+                        AArch64Inst::St1SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b0, 0b0, _, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::St1SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b0, 0b0, _, 0b101, _, 0b00) => {
+                        AArch64Inst::St3SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b0, 0b0, _, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::St3SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b0, 0b1, _, 0b000, _, _) => {
+                        AArch64Inst::St2SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b0, 0b1, _, 0b001, _, _) => {
+                        AArch64Inst::St4SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b0, 0b1, _, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::St2SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b0, 0b1, _, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::St4SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b0, 0b1, _, 0b100, _, 0b00) => {
+                        AArch64Inst::St2SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b0, 0b1, _, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::St2SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b0, 0b1, _, 0b101, _, 0b00) => {
+                        AArch64Inst::St4SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b0, 0b1, _, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::St4SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b1, 0b0, _, 0b000, _, _) => {
+                        AArch64Inst::Ld1SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b1, 0b0, _, 0b001, _, _) => {
+                        AArch64Inst::Ld3SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b1, 0b0, _, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld1SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b1, 0b0, _, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld3SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b1, 0b0, _, 0b100, _, 0b00) => {
+                        AArch64Inst::Ld1SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b1, 0b0, _, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::Ld1SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b1, 0b0, _, 0b101, _, 0b00) => {
+                        AArch64Inst::Ld3SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b1, 0b0, _, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::Ld3SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b1, 0b0, _, 0b110, 0b0, _) => AArch64Inst::Ld1rRegOffset(data),
+                    (0b1, 0b0, _, 0b111, 0b0, _) => AArch64Inst::Ld3rRegOffset(data),
+                    (0b1, 0b1, _, 0b000, _, _) => {
+                        AArch64Inst::Ld2SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b1, 0b1, _, 0b001, _, _) => {
+                        AArch64Inst::Ld4SingleStructureRegOffsetVar8(data)
+                    }
+                    (0b1, 0b1, _, 0b010, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld2SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b1, 0b1, _, 0b011, _, 0b00 | 0b10) => {
+                        AArch64Inst::Ld4SingleStructureRegOffsetVar16(data)
+                    }
+                    (0b1, 0b1, _, 0b100, _, 0b00) => {
+                        AArch64Inst::Ld2SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b1, 0b1, _, 0b100, 0b0, 0b01) => {
+                        AArch64Inst::Ld2SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b1, 0b1, _, 0b101, _, 0b00) => {
+                        AArch64Inst::Ld4SingleStructureRegOffsetVar32(data)
+                    }
+                    (0b1, 0b1, _, 0b101, 0b0, 0b01) => {
+                        AArch64Inst::Ld4SingleStructureRegOffsetVar64(data)
+                    }
+                    (0b1, 0b1, _, 0b110, 0b0, _) => AArch64Inst::Ld2rRegOffset(data),
+                    (0b1, 0b1, _, 0b111, 0b0, _) => AArch64Inst::Ld4rRegOffset(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_2reg_miscellaneous(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_2reg_miscellaneous(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_x_01110_xx_10000_xxxxx_10_xxxxx_xxxxx"),
@@ -3203,7 +4024,7 @@ fn parse_adv_simd_2reg_miscellaneous(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (u, size, opcode) {
+                Ok(match (u, size, opcode) {
                     (0b0, _, 0b00000) => AArch64Inst::Rev64(data),
                     (0b0, _, 0b00001) => AArch64Inst::Rev16Vec(data),
                     (0b0, _, 0b00010) => AArch64Inst::Saddlp(data),
@@ -3273,19 +4094,85 @@ fn parse_adv_simd_2reg_miscellaneous(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b10 | 0b11, 0b11101) => AArch64Inst::Frsqrte(data),
                     (0b1, 0b10 | 0b11, 0b11111) => AArch64Inst::FsqrtVec(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
+
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_adv_simd_2reg_misc_fp16(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("0_x_x_01110_x_1111_00_xxxxx_10_xxxxx_xxxxx"),
+            |raw_instr: &[u8],
+             Extract(q): Extract<u8, 30, 31>,
+             Extract(u): Extract<u8, 29, 30>,
+             Extract(size): Extract<u8, 22, 24>,
+             Extract(opcode): Extract<u8, 12, 17>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(rd): Extract<u8, 0, 5>| {
+                let data = QSizeRnRd {
+                    q,
+                    size,
+                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
+                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
+                };
+
+                // The low bit of size is always set, the high one is the `a` bit of the opcode
+                Ok(match (u, size, opcode) {
+                    (0b0, 0b01, 0b11000) => AArch64Inst::FrintnVecHalfPrecision(data),
+                    (0b0, 0b01, 0b11001) => AArch64Inst::FrintmVecHalfPrecision(data),
+                    (0b0, 0b01, 0b11010) => AArch64Inst::FcvtnsVecHalfPrecision(data),
+                    (0b0, 0b01, 0b11011) => AArch64Inst::FcvtmsVecHalfPrecision(data),
+                    (0b0, 0b01, 0b11100) => AArch64Inst::FcvtasVecHalfPrecision(data),
+                    (0b0, 0b01, 0b11101) => AArch64Inst::ScvtfVecHalfPrecision(data),
+                    (0b0, 0b11, 0b01100) => AArch64Inst::FcmgtZeroVecHalfPrecision(data),
+                    (0b0, 0b11, 0b01101) => AArch64Inst::FcmeqZeroVecHalfPrecision(data),
+                    (0b0, 0b11, 0b01110) => AArch64Inst::FcmltZeroVecHalfPrecision(data),
+                    (0b0, 0b11, 0b01111) => AArch64Inst::FabsVecHalfPrecision(data),
+                    (0b0, 0b11, 0b11000) => AArch64Inst::FrintpVecHalfPrecision(data),
+                    (0b0, 0b11, 0b11001) => AArch64Inst::FrintzVecHalfPrecision(data),
+                    (0b0, 0b11, 0b11010) => AArch64Inst::FcvtpsVecHalfPrecision(data),
+                    (0b0, 0b11, 0b11011) => AArch64Inst::FcvtzsVecHalfPrecision(data),
+                    (0b0, 0b11, 0b11101) => AArch64Inst::FrecpeVecHalfPrecision(data),
+                    (0b1, 0b01, 0b11000) => AArch64Inst::FrintaVecHalfPrecision(data),
+                    (0b1, 0b01, 0b11001) => AArch64Inst::FrintxVecHalfPrecision(data),
+                    (0b1, 0b01, 0b11010) => AArch64Inst::FcvtnuVecHalfPrecision(data),
+                    (0b1, 0b01, 0b11011) => AArch64Inst::FcvtmuVecHalfPrecision(data),
+                    (0b1, 0b01, 0b11100) => AArch64Inst::FcvtauVecHalfPrecision(data),
+                    (0b1, 0b01, 0b11101) => AArch64Inst::UcvtfVecHalfPrecision(data),
+                    (0b1, 0b11, 0b01100) => AArch64Inst::FcmgeZeroVecHalfPrecision(data),
+                    (0b1, 0b11, 0b01101) => AArch64Inst::FcmleZeroVecHalfPrecision(data),
+                    (0b1, 0b11, 0b01111) => AArch64Inst::FnegVecHalfPrecision(data),
+                    (0b1, 0b11, 0b11001) => AArch64Inst::FrintiVecHalfPrecision(data),
+                    (0b1, 0b11, 0b11010) => AArch64Inst::FcvtpuVecHalfPrecision(data),
+                    (0b1, 0b11, 0b11011) => AArch64Inst::FcvtzuVecHalfPrecision(data),
+                    (0b1, 0b11, 0b11101) => AArch64Inst::FrsqrteVecHalfPrecision(data),
+                    (0b1, 0b11, 0b11111) => AArch64Inst::FsqrtVecHalfPrecision(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_across_lanes(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_across_lanes(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_x_01110_xx_11000_xxxxx_10_xxxxx_xxxxx"),
@@ -3303,35 +4190,43 @@ fn parse_adv_simd_across_lanes(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (u, size, opcode) {
+                Ok(match (u, size, opcode) {
                     (0b0, _, 0b00011) => AArch64Inst::Saddlv(data),
                     (0b0, _, 0b01010) => AArch64Inst::Smaxv(data),
                     (0b0, _, 0b11010) => AArch64Inst::Sminv(data),
                     (0b0, _, 0b11011) => AArch64Inst::Addv(data),
 
                     (0b1, _, 0b00011) => AArch64Inst::Uaddlv(data),
-                    (0b1, _, 0b01010) => AArch64Inst::Uaddlv(data),
+                    (0b1, _, 0b01010) => AArch64Inst::Umaxv(data),
                     (0b1, _, 0b11010) => AArch64Inst::Uminv(data),
 
-                    (0b1, 0b00 | 0b01, 0b01100) => AArch64Inst::FmaxnvmEncoding(data),
+                    (0b0, 0b00, 0b01100) => AArch64Inst::FmaxnmvHalfPrecision(data),
+                    (0b0, 0b00, 0b01111) => AArch64Inst::FmaxvHalfPrecision(data),
+
+                    (0b0, 0b10, 0b01100) => AArch64Inst::FminnmvHalfPrecision(data),
+                    (0b0, 0b10, 0b01111) => AArch64Inst::FminvHalfPrecision(data),
+
+                    (0b1, 0b00 | 0b01, 0b01100) => AArch64Inst::FmaxnmvEncoding(data),
                     (0b1, 0b00 | 0b01, 0b01111) => AArch64Inst::FmaxvEncoding(data),
 
                     (0b1, 0b10 | 0b11, 0b01100) => AArch64Inst::FminnmvEncoding(data),
                     (0b1, 0b10 | 0b11, 0b01111) => AArch64Inst::FminvEncoding(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_compare_and_swap(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_compare_and_swap(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_0010001_x_1_xxxxx_x_xxxxx_xxxxx_xxxxx"),
@@ -3352,7 +4247,7 @@ fn parse_compare_and_swap(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (size, l, o0, rt2) {
+                Ok(match (size, l, o0, rt2) {
                     (0b00, 0b0, 0b0, 0b11111) => AArch64Inst::Casb(data),
                     (0b00, 0b0, 0b1, 0b11111) => AArch64Inst::Caslb(data),
                     (0b00, 0b1, 0b0, 0b11111) => AArch64Inst::Casab(data),
@@ -3373,19 +4268,21 @@ fn parse_compare_and_swap(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b1, 0b0, 0b11111) => AArch64Inst::CasaVar64(data),
                     (0b11, 0b1, 0b1, 0b11111) => AArch64Inst::CasalVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_atomic_memory_operations(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_atomic_memory_operations(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_111_x_00_x_x_1_xxxxx_x_xxx_00_xxxxx_xxxxx"),
@@ -3408,7 +4305,7 @@ fn parse_atomic_memory_operations(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (size, v, a, r, rs, o3, opc) {
+                Ok(match (size, v, a, r, rs, o3, opc) {
                     (0b00, 0b0, 0b0, 0b0, _, 0b0, 0b000) => AArch64Inst::LdaddbVar(data),
                     (0b00, 0b0, 0b0, 0b0, _, 0b0, 0b001) => AArch64Inst::LdclrbVar(data),
                     (0b00, 0b0, 0b0, 0b0, _, 0b0, 0b010) => AArch64Inst::LdeorbVar(data),
@@ -3580,19 +4477,21 @@ fn parse_atomic_memory_operations(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b0, 0b1, 0b1, _, 0b0, 0b111) => AArch64Inst::LduminalVar64(data),
                     (0b11, 0b0, 0b1, 0b1, _, 0b1, 0b000) => AArch64Inst::SwpalVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_add_sub_with_carry(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_add_sub_with_carry(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_11010000_xxxxx_000000_xxxxx_xxxxx"),
@@ -3607,7 +4506,7 @@ fn parse_add_sub_with_carry(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rd),
                 };
 
-                match sf_op_s {
+                Ok(match sf_op_s {
                     0b000 => AArch64Inst::AdcVar32(data),
                     0b001 => AArch64Inst::AdcsVar32(data),
                     0b010 => AArch64Inst::SbcVar32(data),
@@ -3618,19 +4517,21 @@ fn parse_add_sub_with_carry(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     0b110 => AArch64Inst::SbcVar64(data),
                     0b111 => AArch64Inst::SbcsVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_floating_point_compare(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_floating_point_compare(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11110_xx_1_xxxxx_xx_1000_xxxxx_xxxxx"),
@@ -3649,28 +4550,68 @@ fn parse_floating_point_compare(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     opcode2,
                 };
 
-                match (m, s, ptype, op, opcode2) {
+                Ok(match (m, s, ptype, op, opcode2) {
                     (0b0, 0b0, 0b00, 0b00, 0b00000 | 0b01000)
                     | (0b0, 0b0, 0b01, 0b00, 0b00000 | 0b01000)
-                    | (0b0, 0b0, 0b11, 0b01, 0b00000 | 0b01000) => AArch64Inst::Fcmp(data),
+                    | (0b0, 0b0, 0b11, 0b00, 0b00000 | 0b01000) => AArch64Inst::Fcmp(data),
 
                     (0b0, 0b0, 0b00, 0b00, 0b10000 | 0b11000)
                     | (0b0, 0b0, 0b01, 0b00, 0b10000 | 0b11000)
-                    | (0b0, 0b0, 0b11, 0b01, 0b10000 | 0b11000) => AArch64Inst::Fcmp(data),
+                    | (0b0, 0b0, 0b11, 0b00, 0b10000 | 0b11000) => AArch64Inst::Fcmpe(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
+
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_advanced_simd_table_lookup(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("0_x_001110_xx_0_xxxxx_0_xx_x_00_xxxxx_xxxxx"),
+            |raw_instr: &[u8],
+             Extract(q): Extract<u8, 30, 31>,
+             Extract(op2): Extract<u8, 22, 24>,
+             Extract(rm): Extract<u8, 16, 21>,
+             Extract(len): Extract<u8, 13, 15>,
+             Extract(op): Extract<u8, 12, 13>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(rd): Extract<u8, 0, 5>| {
+                let data = AdvSimdTableLookup {
+                    q,
+                    rm: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rm),
+                    len,
+                    rn,
+                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
+                };
+
+                Ok(match (op2, op) {
+                    (0b00, 0b0) => AArch64Inst::Tbl(data),
+                    (0b00, 0b1) => AArch64Inst::Tbx(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_advanced_simd_permute(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_advanced_simd_permute(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_001110_xx_0_xxxxx_0_xxx_10_xxxxx_xxxxx"),
@@ -3689,7 +4630,7 @@ fn parse_advanced_simd_permute(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match opcode {
+                Ok(match opcode {
                     0b001 => AArch64Inst::Uzp1(data),
                     0b010 => AArch64Inst::Trn1(data),
                     0b011 => AArch64Inst::Zip1(data),
@@ -3698,19 +4639,21 @@ fn parse_advanced_simd_permute(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     0b110 => AArch64Inst::Trn2(data),
                     0b111 => AArch64Inst::Zip2(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_float_data_proc_2src(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_float_data_proc_2src(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11110_xx_1_xxxxx_xxxx_10_xxxxx_xxxxx"),
@@ -3728,7 +4671,7 @@ fn parse_float_data_proc_2src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (m, s, ptype, opcode) {
+                Ok(match (m, s, ptype, opcode) {
                     (0b0, 0b0, 0b00, 0b0000) => AArch64Inst::FmulScalarSinglePrecisionVar(data),
                     (0b0, 0b0, 0b00, 0b0001) => AArch64Inst::FdivScalarSinglePrecisionVar(data),
                     (0b0, 0b0, 0b00, 0b0010) => AArch64Inst::FaddScalarSinglePrecisionVar(data),
@@ -3749,19 +4692,31 @@ fn parse_float_data_proc_2src(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b0, 0b0, 0b01, 0b0111) => AArch64Inst::FminnmScalarDoublePrecisionVar(data),
                     (0b0, 0b0, 0b01, 0b1000) => AArch64Inst::FnmulScalarDoublePrecisionVar(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    (0b0, 0b0, 0b11, 0b0000) => AArch64Inst::FmulScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0001) => AArch64Inst::FdivScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0010) => AArch64Inst::FaddScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0011) => AArch64Inst::FsubScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0100) => AArch64Inst::FmaxScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0101) => AArch64Inst::FminScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0110) => AArch64Inst::FmaxnmScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0111) => AArch64Inst::FminnmScalarHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b1000) => AArch64Inst::FnmulScalarHalfPrecisionVar(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_floating_point_immediate(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_floating_point_immediate(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11110_xx_1_xxxxxxxx_100_xxxxx_xxxxx"),
@@ -3777,23 +4732,26 @@ fn parse_floating_point_immediate(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (m, s, ptype, imm5) {
+                Ok(match (m, s, ptype, imm5) {
                     (0b0, 0b0, 0b00, 0b00000) => AArch64Inst::FmovScalarImmSinglePrecisionVar(data),
                     (0b0, 0b0, 0b01, 0b00000) => AArch64Inst::FmovScalarImmDoublePrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b00000) => AArch64Inst::FmovScalarImmHalfPrecisionVar(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_conv_between_float_and_fixed_point(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_conv_between_float_and_fixed_point(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11110_xx_0_xx_xxx_xxxxxx_xxxxx_xxxxx"),
@@ -3806,13 +4764,17 @@ fn parse_conv_between_float_and_fixed_point(raw_instr: &[u8]) -> Option<AArch64I
              Extract(scale): Extract<u8, 10, 16>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rd): Extract<u8, 0, 5>| {
+                let (rn_hint, rd_hint) = match opcode {
+                    0b010 | 0b011 => (AArch64MnemonicHint::X, AArch64MnemonicHint::V),
+                    _ => (AArch64MnemonicHint::V, AArch64MnemonicHint::X),
+                };
                 let data = ConvBetweenFloatAndFixedPoint {
                     scale,
-                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rn),
-                    rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
+                    rn: AArch64Architecture::get_register_by_mnemonic(rn_hint, rn),
+                    rd: AArch64Architecture::get_register_by_mnemonic(rd_hint, rd),
                 };
 
-                match (sf, s, ptype, rmode, opcode, scale) {
+                Ok(match (sf, s, ptype, rmode, opcode, scale) {
                     (0b0, 0b0, 0b00, 0b00, 0b010, _) => {
                         AArch64Inst::ScvtfScalarFixedPt32ToSinglePrecision(data)
                     }
@@ -3839,6 +4801,19 @@ fn parse_conv_between_float_and_fixed_point(raw_instr: &[u8]) -> Option<AArch64I
                         AArch64Inst::FcvtzuScalarFixedPtDoublePrecisionTo32(data)
                     }
 
+                    (0b0, 0b0, 0b11, 0b00, 0b010, _) => {
+                        AArch64Inst::ScvtfScalarFixedPt32ToHalfPrecision(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b00, 0b011, _) => {
+                        AArch64Inst::UcvtfScalarFixedPt32ToHalfPrecision(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b11, 0b000, _) => {
+                        AArch64Inst::FcvtzsScalarFixedPtHalfPrecisionTo32(data)
+                    }
+                    (0b0, 0b0, 0b11, 0b11, 0b001, _) => {
+                        AArch64Inst::FcvtzuScalarFixedPtHalfPrecisionTo32(data)
+                    }
+
                     (0b1, 0b0, 0b00, 0b00, 0b010, _) => {
                         AArch64Inst::ScvtfScalarFixedPt64ToSinglePrecision(data)
                     }
@@ -3865,19 +4840,76 @@ fn parse_conv_between_float_and_fixed_point(raw_instr: &[u8]) -> Option<AArch64I
                         AArch64Inst::FcvtzuScalarFixedPtDoublePrecisionTo64(data)
                     }
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    (0b1, 0b0, 0b11, 0b00, 0b010, _) => {
+                        AArch64Inst::ScvtfScalarFixedPt64ToHalfPrecision(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b00, 0b011, _) => {
+                        AArch64Inst::UcvtfScalarFixedPt64ToHalfPrecision(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b11, 0b000, _) => {
+                        AArch64Inst::FcvtzsScalarFixedPtHalfPrecisionTo64(data)
+                    }
+                    (0b1, 0b0, 0b11, 0b11, 0b001, _) => {
+                        AArch64Inst::FcvtzuScalarFixedPtHalfPrecisionTo64(data)
+                    }
+
+                    _ => return Err(undefined(raw_instr)),
+                })
+            },
+        );
+
+        m
+    });
+
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
+}
+
+fn parse_floating_point_conditional_compare(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
+        let mut m = BitPatternMatcher::new();
+        m.bind(
+            to_le("x_0_x_11110_xx_1_xxxxx_xxxx_01_xxxxx_x_xxxx"),
+            |raw_instr: &[u8],
+             Extract(m): Extract<u8, 31, 32>,
+             Extract(s): Extract<u8, 29, 30>,
+             Extract(ptype): Extract<u8, 22, 24>,
+             Extract(rm): Extract<u8, 16, 21>,
+             Extract(cond): Extract<u8, 12, 16>,
+             Extract(rn): Extract<u8, 5, 10>,
+             Extract(op): Extract<u8, 4, 5>,
+             Extract(nzcv): Extract<u8, 0, 4>| {
+                let data = CondCmpReg {
+                    rm: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rm),
+                    cond,
+                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rn),
+                    nzcv,
+                };
+
+                Ok(match (m, s, ptype, op) {
+                    (0b0, 0b0, 0b00, 0b0) => AArch64Inst::FccmpSinglePrecisionVar(data),
+                    (0b0, 0b0, 0b00, 0b1) => AArch64Inst::FccmpeSinglePrecisionVar(data),
+                    (0b0, 0b0, 0b01, 0b0) => AArch64Inst::FccmpDoublePrecisionVar(data),
+                    (0b0, 0b0, 0b01, 0b1) => AArch64Inst::FccmpeDoublePrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b0) => AArch64Inst::FccmpHalfPrecisionVar(data),
+                    (0b0, 0b0, 0b11, 0b1) => AArch64Inst::FccmpeHalfPrecisionVar(data),
+
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_floating_point_conditional_select(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_floating_point_conditional_select(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_0_x_11110_xx_1_xxxxx_xxxx_11_xxxxx_xxxxx"),
@@ -3896,28 +4928,31 @@ fn parse_floating_point_conditional_select(raw_instr: &[u8]) -> Option<AArch64In
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (m, s, ptype) {
+                Ok(match (m, s, ptype) {
                     (0b0, 0b0, 0b00) => AArch64Inst::FcselSinglePrecisionVar(data),
                     (0b0, 0b0, 0b01) => AArch64Inst::FcselDoublePrecisionVar(data),
+                    (0b0, 0b0, 0b11) => AArch64Inst::FcselHalfPrecisionVar(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_vec_x_indexed_elem(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_vec_x_indexed_elem(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_x_01111_xx_x_x_xxxx_xxxx_x_0_xxxxx_xxxxx"),
             |raw_instr: &[u8],
-             Extract(q): Extract<u8, 31, 32>,
+             Extract(q): Extract<u8, 30, 31>,
              Extract(u): Extract<u8, 29, 30>,
              Extract(size): Extract<u8, 22, 24>,
              Extract(l): Extract<u8, 21, 22>,
@@ -3938,7 +4973,7 @@ fn parse_adv_simd_vec_x_indexed_elem(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (u, size, opcode) {
+                Ok(match (u, size, opcode) {
                     (0b0, _, 0b0010) => AArch64Inst::SmlalByElem(data),
                     (0b0, _, 0b0011) => AArch64Inst::SqdmlalByElem(data),
                     (0b0, _, 0b0110) => AArch64Inst::SmlslByElem(data),
@@ -3961,19 +4996,21 @@ fn parse_adv_simd_vec_x_indexed_elem(raw_instr: &[u8]) -> Option<AArch64Inst> {
 
                     (0b1, 0b10 | 0b11, 0b1001) => AArch64Inst::FmulxByElemEncoding(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_adv_simd_scalar_x_indexed_elem(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_adv_simd_scalar_x_indexed_elem(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("01_x_11111_xx_x_x_xxxx_xxxx_x_0_xxxxx_xxxxx"),
@@ -3987,8 +5024,7 @@ fn parse_adv_simd_scalar_x_indexed_elem(raw_instr: &[u8]) -> Option<AArch64Inst>
              Extract(h): Extract<u8, 11, 12>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rd): Extract<u8, 0, 5>| {
-                let data = AdvSimdXIndexedElem {
-                    q: 0b1,
+                let data = AdvSimdScalarXIndexedElem {
                     size,
                     l,
                     m,
@@ -3998,32 +5034,34 @@ fn parse_adv_simd_scalar_x_indexed_elem(raw_instr: &[u8]) -> Option<AArch64Inst>
                     rd: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::V, rd),
                 };
 
-                match (u, size, opcode) {
-                    (0b0, _, 0b0011) => AArch64Inst::SqdmlalByElem(data),
-                    (0b0, _, 0b0111) => AArch64Inst::SqdmlslByElem(data),
-                    (0b0, _, 0b1011) => AArch64Inst::SqdmullByElem(data),
-                    (0b0, _, 0b1100) => AArch64Inst::SqdmulhByElem(data),
-                    (0b0, _, 0b1101) => AArch64Inst::SqrdmulhByElem(data),
+                Ok(match (u, size, opcode) {
+                    (0b0, _, 0b0011) => AArch64Inst::SqdmlalByElemScalar(data),
+                    (0b0, _, 0b0111) => AArch64Inst::SqdmlslByElemScalar(data),
+                    (0b0, _, 0b1011) => AArch64Inst::SqdmullByElemScalar(data),
+                    (0b0, _, 0b1100) => AArch64Inst::SqdmulhByElemScalar(data),
+                    (0b0, _, 0b1101) => AArch64Inst::SqrdmulhByElemScalar(data),
 
-                    (0b0, 0b10 | 0b11, 0b0001) => AArch64Inst::FmlaByElemEncoding(data),
-                    (0b0, 0b10 | 0b11, 0b0101) => AArch64Inst::FmlsByElemEncoding(data),
-                    (0b0, 0b10 | 0b11, 0b1001) => AArch64Inst::FmulByElemEncoding(data),
+                    (0b0, 0b10 | 0b11, 0b0001) => AArch64Inst::FmlaByElemScalar(data),
+                    (0b0, 0b10 | 0b11, 0b0101) => AArch64Inst::FmlsByElemScalar(data),
+                    (0b0, 0b10 | 0b11, 0b1001) => AArch64Inst::FmulByElemScalar(data),
 
-                    (0b1, 0b10 | 0b11, 0b1001) => AArch64Inst::FmulxByElemEncoding(data),
+                    (0b1, 0b10 | 0b11, 0b1001) => AArch64Inst::FmulxByElemScalar(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_sys_instr_with_reg_arg(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_sys_instr_with_reg_arg(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("11010101000000110001_xxxx_xxx_xxxxx"),
@@ -4035,23 +5073,25 @@ fn parse_sys_instr_with_reg_arg(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (crm, op2) {
+                Ok(match (crm, op2) {
                     (0b0000, 0b000) => AArch64Inst::Wfet(data),
                     (0b0000, 0b001) => AArch64Inst::Wfit(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_pstate(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_pstate(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("1101010100000_xxx_0100_xxxx_xxx_xxxxx"),
@@ -4062,25 +5102,27 @@ fn parse_pstate(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(rt): Extract<u8, 0, 5>| {
                 let data = PstateOp { op1, crm, op2 };
 
-                match (op1, op2, rt) {
+                Ok(match (op1, op2, rt) {
                     (0b000, 0b000, 0b11111) => AArch64Inst::Cfinv(data),
                     (0b000, 0b001, 0b11111) => AArch64Inst::Xaflag(data),
                     (0b000, 0b010, 0b11111) => AArch64Inst::Axflag(data),
                     (_, _, 0b11111) => AArch64Inst::MsrImm(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_sys_with_result(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_sys_with_result(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("1101010100000_xxx_0100_xxxx_xxx_xxxxx"),
@@ -4094,26 +5136,28 @@ fn parse_sys_with_result(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (op1, crn, crm, op2) {
+                Ok(match (op1, crn, crm, op2) {
                     (0b011, 0b0011, 0b0000, 0b011) => AArch64Inst::Tstart(data),
                     (0b011, 0b0011, 0b0001, 0b011) => AArch64Inst::Ttest(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_sys_instr(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_sys_instr(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
-            to_le("1101010100000_xxx_0100_xxxx_xxx_xxxxx"),
+            to_le("1101010100_x_01_xxx_xxxx_xxxx_xxx_xxxxx"),
             |raw_instr: &[u8],
              Extract(l): Extract<u8, 21, 22>,
              Extract(op1): Extract<u8, 16, 19>,
@@ -4129,23 +5173,25 @@ fn parse_sys_instr(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match l {
+                Ok(match l {
                     0b0 => AArch64Inst::Sys(data),
                     0b1 => AArch64Inst::Sysl(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_rot_right_into_flags(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_rot_right_into_flags(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_11010000_xxxxxx_00001_xxxxx_x_xxxx"),
@@ -4161,22 +5207,24 @@ fn parse_rot_right_into_flags(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     mask,
                 };
 
-                match (sf_op_s, o2) {
+                Ok(match (sf_op_s, o2) {
                     (0b101, 0b0) => AArch64Inst::Rmif(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_eval_into_flags(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_eval_into_flags(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("x_x_x_11010000_xxxxxx_x_0010_xxxxx_x_xxxx"),
@@ -4191,23 +5239,25 @@ fn parse_eval_into_flags(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rn),
                 };
 
-                match (sf_op_s, opcode2, sz, o3, mask) {
+                Ok(match (sf_op_s, opcode2, sz, o3, mask) {
                     (0b001, 0b000000, 0b0, 0b0, 0b1101) => AArch64Inst::SetfVar8(data),
                     (0b001, 0b000000, 0b1, 0b0, 0b1101) => AArch64Inst::SetfVar16(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_register_literal(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_register_literal(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_011_x_00_xxxxxxxxxxxxxxxxxxx_xxxxx"),
@@ -4216,12 +5266,17 @@ fn parse_load_register_literal(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(v): Extract<u8, 26, 27>,
              Extract(imm19): Extract<u32, 5, 24>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = Imm19Rt {
                     imm19,
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (opc, v) {
+                Ok(match (opc, v) {
                     (0b00, 0b0) => AArch64Inst::LdrLitVar32(data),
                     (0b00, 0b1) => AArch64Inst::LdrLitSimdFPVar32(data),
                     (0b01, 0b0) => AArch64Inst::LdrLitVar64(data),
@@ -4230,19 +5285,21 @@ fn parse_load_register_literal(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b10, 0b1) => AArch64Inst::LdrLitSimdFPVar128(data),
                     (0b11, 0b0) => AArch64Inst::PrfmLit(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_compare_and_swap_pair(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_compare_and_swap_pair(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("0_x_0010000_x_1_xxxxx_x_xxxxx_xxxxx_xxxxx"),
@@ -4254,13 +5311,21 @@ fn parse_compare_and_swap_pair(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(rt2): Extract<u8, 10, 15>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                // The pairs start at even registers
+                if rs & 1 != 0 || rt & 1 != 0 {
+                    return Err(undefined(raw_instr));
+                }
+
                 let data = CompareAndSwapPair {
                     rs,
-                    rn: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rn),
+                    rn: AArch64Architecture::get_register_by_mnemonic(
+                        AArch64MnemonicHint::X_SP,
+                        rn,
+                    ),
                     rt,
                 };
 
-                match (sz, l, o0, rt2) {
+                Ok(match (sz, l, o0, rt2) {
                     (0b0, 0b0, 0b0, 0b11111) => AArch64Inst::CaspVar32(data),
                     (0b0, 0b0, 0b1, 0b11111) => AArch64Inst::CasplVar32(data),
                     (0b0, 0b1, 0b0, 0b11111) => AArch64Inst::CaspaVar32(data),
@@ -4271,19 +5336,21 @@ fn parse_compare_and_swap_pair(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b1, 0b0, 0b11111) => AArch64Inst::CaspaVar64(data),
                     (0b1, 0b1, 0b1, 0b11111) => AArch64Inst::CaspalVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_memory_tags(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_memory_tags(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("11011001_xx_1_xxxxxxxxx_xx_xxxxx_xxxxx"),
@@ -4306,7 +5373,7 @@ fn parse_load_store_memory_tags(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     ),
                 };
 
-                match (opc, imm9, op2) {
+                Ok(match (opc, imm9, op2) {
                     (0b00, _, 0b01 | 0b10 | 0b11) => AArch64Inst::StgEncoding(data),
                     (0b00, 0b000000000, 0b00) => AArch64Inst::Stzgm(data),
                     (0b01, _, 0b00) => AArch64Inst::Ldg(data),
@@ -4316,19 +5383,21 @@ fn parse_load_store_memory_tags(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, _, 0b01 | 0b10 | 0b11) => AArch64Inst::Stz2gEncoding(data),
                     (0b11, 0b000000000, 0b00) => AArch64Inst::Ldgm(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_exclusive_pair(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_exclusive_pair(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("1_x_0010000_x_1_xxxxx_x_xxxxx_xxxxx_xxxxx"),
@@ -4350,7 +5419,7 @@ fn parse_load_store_exclusive_pair(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (sz, l, o0) {
+                Ok(match (sz, l, o0) {
                     (0b0, 0b0, 0b0) => AArch64Inst::StxpVar32(data),
                     (0b0, 0b0, 0b1) => AArch64Inst::StlxpVar32(data),
                     (0b0, 0b1, 0b0) => AArch64Inst::LdxpVar32(data),
@@ -4361,19 +5430,21 @@ fn parse_load_store_exclusive_pair(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b1, 0b1, 0b0) => AArch64Inst::LdxpVar64(data),
                     (0b1, 0b1, 0b1) => AArch64Inst::LdaxpVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_ldapr_stlr_unscaled_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_ldapr_stlr_unscaled_imm(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_011001_xx_0_xxxxxxxxx_00_xxxxx_xxxxx"),
@@ -4392,7 +5463,7 @@ fn parse_ldapr_stlr_unscaled_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (size, opc) {
+                Ok(match (size, opc) {
                     (0b00, 0b00) => AArch64Inst::Stlurb(data),
                     (0b00, 0b01) => AArch64Inst::Ldapurb(data),
                     (0b00, 0b10) => AArch64Inst::LdapursbVar64(data),
@@ -4410,19 +5481,21 @@ fn parse_ldapr_stlr_unscaled_imm(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b00) => AArch64Inst::StlurVar64(data),
                     (0b11, 0b01) => AArch64Inst::LdapurVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_ld_st_no_alloc_pair_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_ld_st_no_alloc_pair_offset(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_101_x_000_x_xxxxxxx_xxxxx_xxxxx_xxxxx"),
@@ -4434,17 +5507,22 @@ fn parse_ld_st_no_alloc_pair_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
              Extract(rt2): Extract<u8, 10, 15>,
              Extract(rn): Extract<u8, 5, 10>,
              Extract(rt): Extract<u8, 0, 5>| {
+                let rt_hint = if v == 1 {
+                    AArch64MnemonicHint::V
+                } else {
+                    AArch64MnemonicHint::X
+                };
                 let data = LdStNoAllocPairOffset {
                     imm7,
-                    rt2: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt2),
+                    rt2: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt2),
                     rn: AArch64Architecture::get_register_by_mnemonic(
                         AArch64MnemonicHint::X_SP,
                         rn,
                     ),
-                    rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
+                    rt: AArch64Architecture::get_register_by_mnemonic(rt_hint, rt),
                 };
 
-                match (opc, v, l) {
+                Ok(match (opc, v, l) {
                     (0b00, 0b0, 0b0) => AArch64Inst::StnpVar32(data),
                     (0b00, 0b0, 0b1) => AArch64Inst::LdnpVar32(data),
                     (0b00, 0b1, 0b0) => AArch64Inst::StnpSimdFPVar32(data),
@@ -4457,19 +5535,21 @@ fn parse_ld_st_no_alloc_pair_offset(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b10, 0b1, 0b0) => AArch64Inst::StnpSimdFPVar128(data),
                     (0b10, 0b1, 0b1) => AArch64Inst::LdnpSimdFPVar128(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
 
-fn parse_load_store_reg_unprivileged(raw_instr: &[u8]) -> Option<AArch64Inst> {
-    pub static MATCHER: Lazy<BitPatternMatcher<AArch64Inst>> = Lazy::new(|| {
+fn parse_load_store_reg_unprivileged(raw_instr: &[u8]) -> Result<AArch64Inst, Error> {
+    pub static MATCHER: Lazy<BitPatternMatcher<Result<AArch64Inst, Error>>> = Lazy::new(|| {
         let mut m = BitPatternMatcher::new();
         m.bind(
             to_le("xx_111_x_00_xx_0_xxxxxxxxx_10_xxxxx_xxxxx"),
@@ -4489,7 +5569,7 @@ fn parse_load_store_reg_unprivileged(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     rt: AArch64Architecture::get_register_by_mnemonic(AArch64MnemonicHint::X, rt),
                 };
 
-                match (size, v, opc) {
+                Ok(match (size, v, opc) {
                     (0b00, 0b0, 0b00) => AArch64Inst::Sttrb(data),
                     (0b00, 0b0, 0b01) => AArch64Inst::Ldtrb(data),
                     (0b00, 0b0, 0b10) => AArch64Inst::LdtrsbVar64(data),
@@ -4507,13 +5587,15 @@ fn parse_load_store_reg_unprivileged(raw_instr: &[u8]) -> Option<AArch64Inst> {
                     (0b11, 0b0, 0b00) => AArch64Inst::SttrVar64(data),
                     (0b11, 0b0, 0b01) => AArch64Inst::LdtrVar64(data),
 
-                    _ => todo!("Unknown instruction {:?}", raw_instr),
-                }
+                    _ => return Err(undefined(raw_instr)),
+                })
             },
         );
 
         m
     });
 
-    MATCHER.try_match(raw_instr)
+    MATCHER
+        .try_match(raw_instr)
+        .unwrap_or_else(|| Err(undefined(raw_instr)))
 }
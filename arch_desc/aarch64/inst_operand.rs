@@ -249,6 +249,15 @@ pub struct AdvancedSimdExtract {
     pub rd: AArch64Register,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdvSimdTableLookup {
+    pub q: u8,
+    pub rm: AArch64Register,
+    pub len: u8,
+    pub rn: u8,
+    pub rd: AArch64Register,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AdvSimdLdStMultiStructuresPostIndexed {
     pub q: u8,
@@ -310,6 +319,7 @@ pub struct QSizeRmRnRd {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AdvSimdShiftByImm {
     pub q: u8,
+    pub immh: u8,
     pub immb: u8,
     pub rn: AArch64Register,
     pub rd: AArch64Register,
@@ -331,6 +341,16 @@ pub struct AdvSimdLdStSingleStructure {
     pub rt: u8,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdvSimdLdStSingleStructurePostIndexed {
+    pub q: u8,
+    pub rm: AArch64Register,
+    pub s: u8,
+    pub size: u8,
+    pub rn: AArch64Register,
+    pub rt: u8,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct QSizeRnRd {
     pub q: u8,
@@ -27,6 +27,11 @@ pub enum AArch64Register {
     Pc,
     Pstate,
     Xzr,
+    /// FPCR, kept in the layout of `ir::FpControl`
+    Fpcr,
+    Fpsr,
+    /// TPIDR_EL0, the thread pointer of userland
+    Tpidr,
 }
 
 impl Register for AArch64Register {
@@ -45,6 +50,9 @@ impl Register for AArch64Register {
             Self::Pc => 0x0801,
             Self::Pstate => 0x0802,
             Self::Xzr => 0x0803,
+            Self::Fpcr => 0x0804,
+            Self::Fpsr => 0x0805,
+            Self::Tpidr => 0x0806,
         };
 
         RawRegisterId::new(raw)
@@ -19,6 +19,14 @@ pub trait Architecture: Default + Clone + Copy + PartialEq + Eq {
     /// This panics if the arch does not have a pc register.
     fn get_pc_register() -> Self::Register;
 
+    /// Get the register holding the `ir::FpControl` of floating-point instructions.
+    /// This panics if the arch does not have floating-point registers.
+    fn get_fp_control_register() -> Self::Register;
+
+    /// Get the register floating-point instructions accumulate their `ir::FpExceptions` in.
+    /// This panics if the arch does not have floating-point registers.
+    fn get_fp_status_register() -> Self::Register;
+
     /// Get register file description.
     fn get_register_file_desc() -> RegisterFileDesc;
 }
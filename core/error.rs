@@ -0,0 +1,65 @@
+use std::fmt;
+
+/// The kind of a guest memory access.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    Read,
+    Write,
+    Execute,
+}
+
+/// Errors raised while decoding, translating or running guest code.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// The encoding is not an instruction of the architecture.
+    UndefinedInstruction(u32),
+    /// The instruction exists but cannot be translated yet.
+    UnimplementedInstruction(String),
+    /// Nothing is mapped at the address.
+    UnmappedAccess { addr: u64, access: Access },
+    /// The mapping at the address does not allow the access.
+    PermissionFault { addr: u64, access: Access },
+    /// The address is not aligned to `size` bytes as the access requires.
+    AlignmentFault { addr: u64, size: u64 },
+    /// The ABI does not implement the system call.
+    UnsupportedSyscall(u64),
+    /// The program cannot be loaded into the guest, for the given reason.
+    LoadFailure(String),
+    /// The host code generator failed to compile a translated block, for the given reason.
+    CodegenFailure(String),
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Write => write!(f, "write"),
+            Self::Execute => write!(f, "execute"),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndefinedInstruction(encoding) => {
+                write!(f, "undefined instruction 0x{encoding:08x}")
+            }
+            Self::UnimplementedInstruction(inst) => write!(f, "unimplemented instruction {inst}"),
+            Self::UnmappedAccess { addr, access } => {
+                write!(f, "{access} access to unmapped address 0x{addr:x}")
+            }
+            Self::PermissionFault { addr, access } => {
+                write!(f, "{access} access to 0x{addr:x} is not permitted")
+            }
+            Self::AlignmentFault { addr, size } => {
+                write!(f, "address 0x{addr:x} is not aligned to {size} bytes")
+            }
+            Self::UnsupportedSyscall(nr) => write!(f, "unsupported system call {nr}"),
+            Self::LoadFailure(reason) => write!(f, "cannot load the program: {reason}"),
+            Self::CodegenFailure(reason) => write!(f, "cannot compile the block: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
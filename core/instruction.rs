@@ -1,12 +1,15 @@
-use crate::ir::BasicBlock;
+use crate::{ir::BasicBlock, Error};
 
 /// The representation of a machine instruction
 pub trait Instruction: Sized {
+    /// Alignment the address of an instruction must have.
+    const ALIGNMENT: u64 = 1;
+
     fn size(&self) -> u64;
 
     /// decode the instruction from raw bytes
-    fn decode(raw_inst: &[u8]) -> Option<Self>;
+    fn decode(raw_inst: &[u8]) -> Result<Self, Error>;
 
-    /// compile the instruction to IR
-    fn compile_to_ir(&self, basic_block: &mut BasicBlock);
+    /// compile the instruction to IR, nothing is emitted if it fails
+    fn compile_to_ir(&self, basic_block: &mut BasicBlock) -> Result<(), Error>;
 }
@@ -10,3 +10,5 @@ mod flag;
 pub use flag::*;
 mod reordering;
 pub use reordering::*;
+mod fp_env;
+pub use fp_env::*;
@@ -21,6 +21,11 @@ impl BasicBlock {
         }
     }
 
+    /// Guest address of the first instruction
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
     pub fn inst(&self) -> &[IrInst] {
         &self.statements
     }
@@ -29,6 +34,11 @@ impl BasicBlock {
         self.statements.push(statement);
     }
 
+    /// Drop every instruction after the first `len`. Variables stay allocated.
+    pub fn truncate(&mut self, len: usize) {
+        self.statements.truncate(len);
+    }
+
     pub fn terminator(&self) -> BasicBlockTerminator {
         self.terminator
     }
@@ -37,6 +47,10 @@ impl BasicBlock {
         self.terminator = terminator;
     }
 
+    pub fn variable_count(&self) -> usize {
+        self.variable_count
+    }
+
     pub fn new_variable(&mut self, ty: IrType) -> IrValue {
         let variable = IrValue::Variable(ty, self.variable_count);
         self.variable_count += 1;
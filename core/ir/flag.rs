@@ -1,3 +1,10 @@
+/// Condition flags set as a side effect of integer instructions.
+///
+/// Instructions that compute a result set `ZF` and `NF` from it. `Add` and `Sub` also set `CF`,
+/// the carry out of an addition or the borrow of a subtraction, and `OF` on signed overflow.
+/// `Mul` sets `CF` if the unsigned product does not fit and `OF` if the signed one does not.
+/// `Rotr`, the bit counting and reordering instructions, `Cmp` and `Select` leave the flags
+/// untouched.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Flag {
     /// Zero flag
@@ -6,6 +13,8 @@ pub enum Flag {
     CF,
     /// Overflow flag
     OF,
+    /// Negative flag, the sign bit of the result
+    NF,
 }
 
 impl Flag {
@@ -14,6 +23,7 @@ impl Flag {
             Self::ZF => 0,
             Self::CF => 1,
             Self::OF => 2,
+            Self::NF => 3,
         }
     }
 
@@ -0,0 +1,96 @@
+use std::ops::{BitOr, BitOrAssign};
+
+use super::FpRounding;
+
+/// Controls of floating-point instructions, kept in the register given by
+/// `Architecture::get_fp_control_register`.
+///
+/// Bits 1:0 select the rounding mode, bit 2 flushes denormal operands and results to zero,
+/// bit 3 replaces every NaN result with the default NaN, bit 4 selects the alternative
+/// half-precision format and bit 5 flushes half-precision denormals to zero.
+///
+/// Half-precision operations only follow bit 5, without raising the input denormal exception
+/// for flushed operands. The alternative format has no infinities or NaNs and only applies to
+/// conversions from and to half precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FpControl(u64);
+
+impl FpControl {
+    pub const FLUSH_TO_ZERO: u64 = 1 << 2;
+    pub const DEFAULT_NAN: u64 = 1 << 3;
+    pub const ALTERNATIVE_HALF: u64 = 1 << 4;
+    pub const FLUSH_HALF_TO_ZERO: u64 = 1 << 5;
+
+    /// Control from the bits of the register, unknown bits are ignored.
+    pub fn from_bits(bits: u64) -> Self {
+        Self(bits & 0x3F)
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// The rounding mode, never `FpRounding::TiesToAway` or `FpRounding::Current`.
+    pub fn rounding(self) -> FpRounding {
+        match self.0 & 0b11 {
+            0b00 => FpRounding::TiesToEven,
+            0b01 => FpRounding::TowardPositive,
+            0b10 => FpRounding::TowardNegative,
+            _ => FpRounding::TowardZero,
+        }
+    }
+
+    pub fn flush_to_zero(self) -> bool {
+        self.0 & Self::FLUSH_TO_ZERO != 0
+    }
+
+    pub fn default_nan(self) -> bool {
+        self.0 & Self::DEFAULT_NAN != 0
+    }
+
+    pub fn alternative_half(self) -> bool {
+        self.0 & Self::ALTERNATIVE_HALF != 0
+    }
+
+    pub fn flush_half_to_zero(self) -> bool {
+        self.0 & Self::FLUSH_HALF_TO_ZERO != 0
+    }
+}
+
+/// Exceptions raised by floating-point instructions, accumulated into the register given by
+/// `Architecture::get_fp_status_register`. The other bits of the register are left untouched.
+///
+/// The saturating integer instructions accumulate their saturation there as well.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FpExceptions(u32);
+
+impl FpExceptions {
+    pub const NONE: Self = Self(0);
+    pub const INVALID: Self = Self(1 << 0);
+    pub const DIVIDE_BY_ZERO: Self = Self(1 << 1);
+    pub const OVERFLOW: Self = Self(1 << 2);
+    pub const UNDERFLOW: Self = Self(1 << 3);
+    pub const INEXACT: Self = Self(1 << 4);
+    /// A denormal operand was flushed to zero
+    pub const INPUT_DENORMAL: Self = Self(1 << 7);
+    /// A saturating integer instruction clamped its result
+    pub const SATURATION: Self = Self(1 << 27);
+
+    pub fn bits(self) -> u64 {
+        self.0 as u64
+    }
+}
+
+impl BitOr for FpExceptions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for FpExceptions {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
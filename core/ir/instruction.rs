@@ -2,6 +2,12 @@ use crate::Interrupt;
 
 use super::{Flag, IrType, IrValue, Reordering, TypeOf};
 
+/// An instruction of the IR.
+///
+/// The integer arithmetic, bitwise, shift, count, compare and cast instructions also take
+/// `IrType::Vector` operands, which they process lane by lane without setting any flag. A vector
+/// is shifted by a scalar amount for every lane or by a vector of amounts, and shifting a lane
+/// by its width or more shifts out every bit, `Ashr` filling it with the sign bit.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum IrInst {
     Add {
@@ -19,16 +25,44 @@ pub enum IrInst {
         lhs: IrValue,
         rhs: IrValue,
     },
+    /// Unsigned division, raises `Interrupt::Exception(0)` if `rhs` is zero
     Div {
         dst: IrValue,
         lhs: IrValue,
         rhs: IrValue,
     },
+    /// Unsigned remainder, raises `Interrupt::Exception(0)` if `rhs` is zero
     Rem {
         dst: IrValue,
         lhs: IrValue,
         rhs: IrValue,
     },
+    /// Signed division rounding towards zero, raises `Interrupt::Exception(0)` if `rhs` is
+    /// zero. Dividing the minimum value by -1 wraps around to the minimum value.
+    Sdiv {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// Signed remainder with the sign of `lhs`, raises `Interrupt::Exception(0)` if `rhs` is
+    /// zero
+    Srem {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// High half of the double width unsigned product
+    Umulh {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// High half of the double width signed product
+    Smulh {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
     And {
         dst: IrValue,
         lhs: IrValue,
@@ -70,6 +104,68 @@ pub enum IrInst {
         lhs: IrValue,
         rhs: IrValue,
     },
+    /// Count leading zero bits
+    Clz {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// Count the leading bits that are equal to the sign bit, not counting the sign bit itself
+    Cls {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// Reverse the order of the bits
+    Bitrev {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// Reverse the order of the bytes
+    Bswap {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// Count the bits that are set
+    Popcnt {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// The larger of `lhs` and `rhs`
+    Max {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        signed: bool,
+    },
+    /// The smaller of `lhs` and `rhs`
+    Min {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        signed: bool,
+    },
+    /// Addition that clamps the result to the range of the type instead of wrapping around.
+    ///
+    /// The saturating instructions raise `FpExceptions::SATURATION` if they clamped any lane.
+    AddSat {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        signed: bool,
+    },
+    /// Subtraction that clamps the result to the range of the type instead of wrapping around
+    SubSat {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        signed: bool,
+    },
+    /// Truncate `src` to the narrower type of `dst`, clamping it to the range of that type
+    TruncSat {
+        dst: IrValue,
+        src: IrValue,
+        src_signed: bool,
+        dst_signed: bool,
+    },
     Assign {
         dst: IrValue,
         src: IrValue,
@@ -82,7 +178,44 @@ pub enum IrInst {
         dst: IrValue,
         src: IrValue,
     },
+    /// Load `dst` from the address `src` and link the address for `StoreConditional`
+    LoadLinked {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// Store `src` to the address `dst` only if it is still linked by the last `LoadLinked`,
+    /// `stored` is a `IrType::Bool` that is true if it was. The link is cleared either way.
+    StoreConditional {
+        dst: IrValue,
+        src: IrValue,
+        stored: IrValue,
+    },
+    /// Clear the link of the last `LoadLinked`
+    ClearLink,
+    /// Atomically replace the value at the address `addr` with `op` applied to it and `src`,
+    /// `dst` is the value it replaced
+    AtomicRmw {
+        dst: IrValue,
+        addr: IrValue,
+        src: IrValue,
+        op: AtomicOp,
+        ordering: Reordering,
+    },
+    /// Atomically replace the value at the address `addr` with `new` if it equals `expected`,
+    /// `dst` is the value found there either way
+    CompareExchange {
+        dst: IrValue,
+        addr: IrValue,
+        expected: IrValue,
+        new: IrValue,
+        ordering: Reordering,
+    },
 
+    /// Zero extend `src` to the type of `dst`, or truncate it if `dst` is narrower.
+    /// Floating-point values are cast as their bit patterns.
+    ///
+    /// Vectors with the same number of lanes are cast lane by lane, any other cast from or to a
+    /// vector is one of its bit pattern.
     ZextCast {
         dst: IrValue,
         src: IrValue,
@@ -91,11 +224,231 @@ pub enum IrInst {
         dst: IrValue,
         src: IrValue,
     },
+    /// Copy the scalar `src` to every lane of the vector `dst`
+    Splat {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// Lane `i` of the vector `dst` is lane `lanes[i]` of the lanes of `lhs` followed by those
+    /// of `rhs`, two vectors with lanes of the same type as `dst`.
+    Shuffle {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        lanes: Vec<u8>,
+    },
+    /// Lane `i` of the vector `dst` is the lane of `src` that lane `i` of the integer vector
+    /// `indices` selects, or zero if `src` has no such lane
+    Swizzle {
+        dst: IrValue,
+        src: IrValue,
+        indices: IrValue,
+    },
+    /// Copy lane `lane` of the vector `src` to the scalar `dst`
+    ExtractLane {
+        dst: IrValue,
+        src: IrValue,
+        lane: u8,
+    },
+    /// Replace lane `lane` of the vector `src` with the scalar `value`
+    InsertLane {
+        dst: IrValue,
+        src: IrValue,
+        value: IrValue,
+        lane: u8,
+    },
     MoveFlag {
         dst: IrValue,
         dst_pos: usize,
         flag: Flag,
     },
+    /// Compare `lhs` with `rhs`, `dst` is a `IrType::Bool`. Vectors are compared into a vector
+    /// of the same type whose lanes are all ones where the condition holds and zero elsewhere.
+    Cmp {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        cond: Comparison,
+    },
+    /// `cond ? if_true : if_false` for a `IrType::Bool` condition
+    Select {
+        dst: IrValue,
+        cond: IrValue,
+        if_true: IrValue,
+        if_false: IrValue,
+    },
+    /// Floating-point addition of `IrType::F16`, `IrType::F32` or `IrType::F64` values.
+    ///
+    /// Floating-point instructions round and treat denormals and NaNs as the `FpControl` of the
+    /// architecture selects, and accumulate the `FpExceptions` they raise. A NaN result is the
+    /// first signaling NaN operand made quiet, otherwise the first quiet NaN operand. NaNs
+    /// created by invalid operations, and every NaN with default NaN enabled, are the positive
+    /// quiet NaN with a zero payload.
+    ///
+    /// The floating-point instructions also take `IrType::Vector` operands of floating-point
+    /// lanes, which they process lane by lane. Conversions of vectors need as many lanes in
+    /// `dst` as in `src`.
+    Fadd {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    Fsub {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    Fmul {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    Fdiv {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// Like `Fmul`, but infinity times zero is 2 with the sign of the product and raises nothing
+    Fmulx {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// The larger of `lhs` and `rhs`, a NaN if either of them is one. +0 is larger than -0.
+    Fmax {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// The smaller of `lhs` and `rhs`, a NaN if either of them is one. -0 is smaller than +0.
+    Fmin {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// Like `Fmax`, but a quiet NaN operand is ignored unless both of them are NaNs
+    Fmaxnm {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// Like `Fmin`, but a quiet NaN operand is ignored unless both of them are NaNs
+    Fminnm {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// `lhs * rhs + addend` with a single rounding
+    Fma {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        addend: IrValue,
+    },
+    /// Flip the sign bit of `src`, NaNs are not processed and no exception is raised
+    Fneg {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// Clear the sign bit of `src`, NaNs are not processed and no exception is raised
+    Fabs {
+        dst: IrValue,
+        src: IrValue,
+    },
+    Fsqrt {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// An estimate of `1 / src` with 8 significant bits, exactly the one of the Arm
+    /// FRECPE instruction
+    FrecipEstimate {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// An estimate of `1 / sqrt(src)` with 8 significant bits, exactly the one of the Arm
+    /// FRSQRTE instruction
+    FrsqrtEstimate {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// The reciprocal estimate of the Arm URECPE instruction, of the 32-bit lanes of `src` read
+    /// as unsigned fractions. Lanes below one half give all ones.
+    UrecipEstimate {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// The reciprocal square root estimate of the Arm URSQRTE instruction, of the 32-bit lanes
+    /// of `src` read as unsigned fractions. Lanes below one quarter give all ones.
+    UrsqrtEstimate {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// `src` with a zero significand and the bits of its exponent inverted, the scale factor of
+    /// the Arm FRECPX instruction. Zeroes and denormals get the largest finite exponent.
+    FrecipExponent {
+        dst: IrValue,
+        src: IrValue,
+    },
+    /// `2 - lhs * rhs` with a single rounding, the Newton-Raphson step refining an estimate of
+    /// a reciprocal. Infinity times zero is 2 and raises nothing.
+    FrecipStep {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// `(3 - lhs * rhs) / 2` with a single rounding, the Newton-Raphson step refining an
+    /// estimate of a reciprocal square root. Infinity times zero is 1.5 and raises nothing.
+    FrsqrtStep {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+    },
+    /// Round `src` to an integral value of the same type, raising the inexact exception if that
+    /// changed the value and `signal_inexact` is set
+    Fround {
+        dst: IrValue,
+        src: IrValue,
+        rounding: FpRounding,
+        signal_inexact: bool,
+    },
+    /// Convert `src` to the floating-point type of `dst` with `rounding`. Half-precision values
+    /// are never flushed to zero by conversions, and are in the alternative format if that is
+    /// selected.
+    FpCast {
+        dst: IrValue,
+        src: IrValue,
+        rounding: FpRounding,
+    },
+    /// Convert the floating-point `src` multiplied by `2^frac_bits` to the integer type of
+    /// `dst`. Values out of range saturate and NaNs convert to zero, both raise the invalid
+    /// exception.
+    FpToInt {
+        dst: IrValue,
+        src: IrValue,
+        signed: bool,
+        frac_bits: u8,
+        rounding: FpRounding,
+    },
+    /// Convert the integer `src` divided by `2^frac_bits` to the floating-point type of `dst`
+    IntToFp {
+        dst: IrValue,
+        src: IrValue,
+        signed: bool,
+        frac_bits: u8,
+    },
+    /// Compare the floating-point `lhs` with `rhs`, `dst` is a `IrType::Bool`. Vectors are
+    /// compared into a vector of integer lanes as wide as theirs, all ones where the condition
+    /// holds and zero elsewhere.
+    ///
+    /// Signaling NaN operands raise the invalid exception, and so do quiet NaNs if `signaling`
+    /// is set.
+    Fcmp {
+        dst: IrValue,
+        lhs: IrValue,
+        rhs: IrValue,
+        cond: FpComparison,
+        signaling: bool,
+    },
     /// A memory fence
     Fence(Reordering),
     Interrupt(Interrupt),
@@ -110,6 +463,10 @@ impl TypeOf for IrInst {
             Self::Mul { dst, .. } => dst.ty(),
             Self::Div { dst, .. } => dst.ty(),
             Self::Rem { dst, .. } => dst.ty(),
+            Self::Sdiv { dst, .. } => dst.ty(),
+            Self::Srem { dst, .. } => dst.ty(),
+            Self::Umulh { dst, .. } => dst.ty(),
+            Self::Smulh { dst, .. } => dst.ty(),
             Self::And { dst, .. } => dst.ty(),
             Self::Or { dst, .. } => dst.ty(),
             Self::Xor { dst, .. } => dst.ty(),
@@ -118,12 +475,59 @@ impl TypeOf for IrInst {
             Self::Lshr { dst, .. } => dst.ty(),
             Self::Ashr { dst, .. } => dst.ty(),
             Self::Rotr { dst, .. } => dst.ty(),
+            Self::Clz { dst, .. } => dst.ty(),
+            Self::Cls { dst, .. } => dst.ty(),
+            Self::Bitrev { dst, .. } => dst.ty(),
+            Self::Bswap { dst, .. } => dst.ty(),
+            Self::Popcnt { dst, .. } => dst.ty(),
+            Self::Max { dst, .. } => dst.ty(),
+            Self::Min { dst, .. } => dst.ty(),
+            Self::AddSat { dst, .. } => dst.ty(),
+            Self::SubSat { dst, .. } => dst.ty(),
+            Self::TruncSat { dst, .. } => dst.ty(),
             Self::Assign { dst, .. } => dst.ty(),
             Self::Load { dst, .. } => dst.ty(),
             Self::Store { dst, .. } => dst.ty(),
+            Self::LoadLinked { dst, .. } => dst.ty(),
+            Self::StoreConditional { dst, .. } => dst.ty(),
+            Self::ClearLink => IrType::Void,
+            Self::AtomicRmw { dst, .. } => dst.ty(),
+            Self::CompareExchange { dst, .. } => dst.ty(),
             Self::ZextCast { dst, .. } => dst.ty(),
             Self::SextCast { dst, .. } => dst.ty(),
+            Self::Splat { dst, .. } => dst.ty(),
+            Self::Shuffle { dst, .. } => dst.ty(),
+            Self::Swizzle { dst, .. } => dst.ty(),
+            Self::ExtractLane { dst, .. } => dst.ty(),
+            Self::InsertLane { dst, .. } => dst.ty(),
             Self::MoveFlag { dst, .. } => dst.ty(),
+            Self::Cmp { dst, .. } => dst.ty(),
+            Self::Select { dst, .. } => dst.ty(),
+            Self::Fadd { dst, .. } => dst.ty(),
+            Self::Fsub { dst, .. } => dst.ty(),
+            Self::Fmul { dst, .. } => dst.ty(),
+            Self::Fdiv { dst, .. } => dst.ty(),
+            Self::Fmulx { dst, .. } => dst.ty(),
+            Self::Fmax { dst, .. } => dst.ty(),
+            Self::Fmin { dst, .. } => dst.ty(),
+            Self::Fmaxnm { dst, .. } => dst.ty(),
+            Self::Fminnm { dst, .. } => dst.ty(),
+            Self::Fma { dst, .. } => dst.ty(),
+            Self::Fneg { dst, .. } => dst.ty(),
+            Self::Fabs { dst, .. } => dst.ty(),
+            Self::Fsqrt { dst, .. } => dst.ty(),
+            Self::FrecipEstimate { dst, .. } => dst.ty(),
+            Self::FrsqrtEstimate { dst, .. } => dst.ty(),
+            Self::UrecipEstimate { dst, .. } => dst.ty(),
+            Self::UrsqrtEstimate { dst, .. } => dst.ty(),
+            Self::FrecipExponent { dst, .. } => dst.ty(),
+            Self::FrecipStep { dst, .. } => dst.ty(),
+            Self::FrsqrtStep { dst, .. } => dst.ty(),
+            Self::Fround { dst, .. } => dst.ty(),
+            Self::FpCast { dst, .. } => dst.ty(),
+            Self::FpToInt { dst, .. } => dst.ty(),
+            Self::IntToFp { dst, .. } => dst.ty(),
+            Self::Fcmp { dst, .. } => dst.ty(),
             Self::Fence { .. } => IrType::Void,
             Self::Interrupt(_) => IrType::Void,
             Self::Intrinsic(_) => IrType::Void,
@@ -131,5 +535,80 @@ impl TypeOf for IrInst {
     }
 }
 
+/// Condition of an integer comparison.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    /// Unsigned less than
+    Ult,
+    /// Unsigned less than or equal
+    Ule,
+    /// Unsigned greater than
+    Ugt,
+    /// Unsigned greater than or equal
+    Uge,
+    /// Signed less than
+    Slt,
+    /// Signed less than or equal
+    Sle,
+    /// Signed greater than
+    Sgt,
+    /// Signed greater than or equal
+    Sge,
+}
+
+/// Condition of a floating-point comparison, every condition is false if an operand is a NaN
+/// except `Unordered`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FpComparison {
+    Eq,
+    Lt,
+    Le,
+    /// Either operand is a NaN
+    Unordered,
+}
+
+/// Rounding mode of a floating-point operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FpRounding {
+    /// To nearest, ties to even
+    TiesToEven,
+    /// To nearest, ties away from zero
+    TiesToAway,
+    TowardPositive,
+    TowardNegative,
+    TowardZero,
+    /// Toward zero, setting the lowest bit of inexact results
+    ToOdd,
+    /// The rounding mode of the `FpControl`
+    Current,
+}
+
+/// Operation of an atomic read-modify-write, applied to the value in memory and the operand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AtomicOp {
+    Add,
+    /// Clear the bits set in the operand
+    AndNot,
+    Or,
+    Xor,
+    /// Signed maximum
+    Smax,
+    /// Signed minimum
+    Smin,
+    /// Unsigned maximum
+    Umax,
+    /// Unsigned minimum
+    Umin,
+    /// Replace the value with the operand
+    Swap,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub enum IrIntrinsic {}
+pub enum IrIntrinsic {
+    /// Discard translated code of the page that contains the given address.
+    InvalidateCode { addr: IrValue },
+    /// Discard all translated code.
+    InvalidateAllCode,
+}
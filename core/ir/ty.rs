@@ -5,6 +5,7 @@ pub enum IrType {
     B32,
     B64,
     B128,
+    F16,
     F32,
     F64,
     Bool,
@@ -16,7 +17,7 @@ impl IrType {
     pub fn size_of(self) -> usize {
         match self {
             IrType::B8 => 1,
-            IrType::B16 => 2,
+            IrType::B16 | IrType::F16 => 2,
             IrType::B32 | IrType::F32 => 4,
             IrType::B64 | IrType::F64 => 8,
             IrType::B128 => 16,
@@ -34,6 +35,7 @@ pub enum VecTy {
     U32,
     U64,
     U128,
+    F16,
     F32,
     F64,
 }
@@ -42,7 +44,7 @@ impl VecTy {
     pub fn size_of(self) -> usize {
         match self {
             VecTy::U8 => 1,
-            VecTy::U16 => 2,
+            VecTy::U16 | VecTy::F16 => 2,
             VecTy::U32 | VecTy::F32 => 4,
             VecTy::U64 | VecTy::F64 => 8,
             VecTy::U128 => 16,
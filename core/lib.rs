@@ -12,4 +12,6 @@ mod interrupt;
 pub use interrupt::*;
 mod compat;
 pub use compat::*;
+mod error;
+pub use error::*;
 pub mod ir;
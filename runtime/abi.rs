@@ -1,3 +1,6 @@
+use core::Error;
+use std::ops::ControlFlow;
+
 use crate::{codegen::Context, SoftMmu};
 
 mod aarch64_unknown_linux;
@@ -8,13 +11,36 @@ pub trait Abi {
     fn new() -> Self;
 
     /// Initialize the ABI with the given binary.
-    fn on_initialize<C: Context>(&mut self, binary: &[u8], ctx: &mut C, mmu: &mut SoftMmu);
-    /// Called when an exception occurs.
-    fn on_exception<C: Context>(&self, exception: u64, ctx: &C, mmu: &SoftMmu);
-    /// Called when an interrupt occurs.
-    fn on_interrupt<C: Context>(&self, interrupt: u64, ctx: &C, mmu: &SoftMmu);
-    /// Called when a system call occurs.
-    fn on_system_call<C: Context>(&self, system_call: u64, ctx: &C, mmu: &SoftMmu);
+    fn on_initialize<C: Context>(
+        &mut self,
+        binary: &[u8],
+        ctx: &mut C,
+        mmu: &mut SoftMmu,
+    ) -> Result<(), Error>;
+    /// Called when an exception occurs. An error stops the execution of the guest, `Break`
+    /// ends it with the given exit status.
+    fn on_exception<C: Context>(
+        &mut self,
+        exception: u64,
+        ctx: &C,
+        mmu: &SoftMmu,
+    ) -> Result<ControlFlow<i32>, Error>;
+    /// Called when an interrupt occurs. An error stops the execution of the guest, `Break`
+    /// ends it with the given exit status.
+    fn on_interrupt<C: Context>(
+        &mut self,
+        interrupt: u64,
+        ctx: &C,
+        mmu: &SoftMmu,
+    ) -> Result<ControlFlow<i32>, Error>;
+    /// Called when a system call occurs. An error stops the execution of the guest, `Break`
+    /// ends it with the given exit status.
+    fn on_system_call<C: Context>(
+        &mut self,
+        system_call: u64,
+        ctx: &C,
+        mmu: &SoftMmu,
+    ) -> Result<ControlFlow<i32>, Error>;
     /// Called when an IRQ occurs.
-    fn on_irq<C: Context>(&self, id: usize, level: usize, ctx: &C, mmu: &SoftMmu);
+    fn on_irq<C: Context>(&mut self, id: usize, level: usize, ctx: &C, mmu: &SoftMmu);
 }
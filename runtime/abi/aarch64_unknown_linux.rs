@@ -1,64 +1,246 @@
 use core::{
     ir::{IrType, IrValue},
-    Architecture, ArchitectureCompat, Register,
+    Access, Architecture, ArchitectureCompat, Error, Register,
+};
+use std::{
+    ffi::OsString,
+    fmt,
+    ops::{ControlFlow, Range},
+    path::PathBuf,
+    time::Instant,
 };
 
-use arch_desc::aarch64::AArch64Architecture;
-use device::{devices::Memory, IoDevice};
-use elf::{endian::AnyEndian, ElfBytes};
+use arch_desc::aarch64::{AArch64Architecture, AArch64Register};
 
 use crate::{codegen::Context, SoftMmu};
 
 use super::Abi;
 
-pub struct AArch64UnknownLinux {}
+mod cpu;
+mod file;
+mod fs;
+mod loader;
+mod stack;
+mod syscall;
+
+use file::FileTable;
+pub use fs::Mount;
+use fs::{GuestFs, Node};
+use stack::ProgramInfo;
+
+const PAGE_SIZE: u64 = 4096;
+/// Position independent executables are loaded here.
+const ELF_ET_DYN_BASE: u64 = 0x5555_5555_0000;
+/// The stack grows downward from here.
+const STACK_TOP: u64 = 0x7fff_ffff_f000;
+/// Anonymous mappings are allocated downward from here.
+const MMAP_TOP: u64 = 0x7f00_0000_0000;
+
+/// How the guest process is started.
+#[derive(Clone)]
+pub struct LinuxConfig {
+    /// Arguments of the process, starting with the program name.
+    pub args: Vec<OsString>,
+    /// Environment of the process, as `KEY=VALUE` strings.
+    pub env: Vec<OsString>,
+    pub stack_size: u64,
+    /// Host directory that holds the guest root filesystem, like the `-L` option of qemu-user.
+    /// Files missing from it are looked up on the host. It is never modified.
+    pub sysroot: Option<PathBuf>,
+    /// Host directories visible in the guest, they take precedence over the sysroot.
+    pub mounts: Vec<Mount>,
+    /// Guest path of the executable, `args[0]` if not set.
+    pub exe: Option<PathBuf>,
+    /// Stop the guest with `Error::UnsupportedSyscall` on an unknown system call, instead of
+    /// returning ENOSYS to it.
+    pub strict_syscalls: bool,
+}
+
+impl fmt::Debug for LinuxConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinuxConfig")
+            .field("args", &self.args)
+            .field("env", &self.env)
+            .field("stack_size", &self.stack_size)
+            .field("sysroot", &self.sysroot)
+            .field("mounts", &self.mounts)
+            .field("exe", &self.exe)
+            .field("strict_syscalls", &self.strict_syscalls)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for LinuxConfig {
+    fn default() -> Self {
+        Self {
+            args: Vec::new(),
+            env: Vec::new(),
+            stack_size: 8 << 20,
+            sysroot: None,
+            mounts: Vec::new(),
+            exe: None,
+            strict_syscalls: false,
+        }
+    }
+}
+
+pub struct AArch64UnknownLinux {
+    config: LinuxConfig,
+    fs: GuestFs,
+    files: FileTable,
+    // Loaded ELF images and their guest path, for /proc/self/maps
+    images: Vec<(Range<u64>, PathBuf)>,
+    brk_start: u64,
+    brk: u64,
+    mmap_top: u64,
+    boot_time: Instant,
+}
 impl ArchitectureCompat<AArch64Architecture> for AArch64UnknownLinux {}
 
+impl AArch64UnknownLinux {
+    pub fn with_config(config: LinuxConfig) -> Self {
+        let exe = config
+            .exe
+            .clone()
+            .or_else(|| config.args.first().map(PathBuf::from))
+            .unwrap_or_default();
+        let exe = std::env::current_dir().unwrap_or_default().join(exe);
+
+        Self {
+            fs: GuestFs::new(&config.mounts, config.sysroot.as_deref(), exe),
+            config,
+            files: FileTable::with_stdio().expect("Failed to open standard streams"),
+            images: Vec::new(),
+            brk_start: 0,
+            brk: 0,
+            mmap_top: MMAP_TOP,
+            boot_time: Instant::now(),
+        }
+    }
+}
+
 impl Abi for AArch64UnknownLinux {
     fn new() -> Self {
-        Self {}
+        Self::with_config(LinuxConfig::default())
     }
 
-    fn on_initialize<C: Context>(&mut self, binary: &[u8], ctx: &mut C, mmu: &mut SoftMmu) {
-        let elf =
-            ElfBytes::<AnyEndian>::minimal_parse(&binary).expect("Failed to parse ELF binary");
-        for seg in elf.segments().unwrap() {
-            let addr = seg.p_paddr;
-            let size = seg.p_memsz;
-            let data = elf.segment_data(&seg).expect("Bad segment data");
-
-            mmu.map(addr, size, Memory::allocate(size as usize));
-            unsafe {
-                mmu.write_all_at(addr, &data);
+    fn on_initialize<C: Context>(
+        &mut self,
+        binary: &[u8],
+        ctx: &mut C,
+        mmu: &mut SoftMmu,
+    ) -> Result<(), Error> {
+        let program = loader::load_elf(mmu, binary, |_| Some(ELF_ET_DYN_BASE))?;
+
+        // The interpreter is mapped like a shared library, in the mmap area
+        let interp = match &program.interp {
+            Some(path) => {
+                let binary = match self.fs.resolve(path, true) {
+                    Ok(Node::Host { path: host, .. }) => std::fs::read(host).ok(),
+                    _ => None,
+                };
+                let binary = binary.ok_or_else(|| {
+                    Error::LoadFailure(format!(
+                        "cannot read the program interpreter {}",
+                        path.display()
+                    ))
+                })?;
+
+                let interp =
+                    loader::load_elf(mmu, &binary, |size| self.find_free_area(mmu, size).ok())?;
+                self.images.push((interp.start..interp.end, path.clone()));
+                Some(interp)
             }
-        }
+            None => None,
+        };
+        self.images
+            .push((program.start..program.end, self.fs.exe().to_path_buf()));
 
-        for sec in elf.section_headers().unwrap() {
-            let addr = sec.sh_addr;
-            let size = sec.sh_size;
+        // The program break starts right after the highest segment of the program
+        self.brk_start = program.end;
+        self.brk = self.brk_start;
 
-            mmu.map(addr, size, Memory::allocate(size as usize));
-        }
+        let info = ProgramInfo {
+            phdr: program.phdr,
+            phent: program.phent,
+            phnum: program.phnum,
+            entry: program.entry,
+            base: interp.as_ref().map_or(0, |interp| interp.bias),
+        };
+        let sp = stack::setup_stack(
+            mmu,
+            STACK_TOP,
+            self.config.stack_size,
+            &self.config.args,
+            &self.config.env,
+            &info,
+        )?;
 
+        ctx.set(
+            IrValue::Register(IrType::B64, AArch64Register::Sp.raw()),
+            sp,
+        );
         ctx.set(
             IrValue::Register(IrType::B64, AArch64Architecture::get_pc_register().raw()),
-            elf.ehdr.e_entry,
+            interp.map_or(program.entry, |interp| interp.entry),
         );
+        Ok(())
     }
 
-    fn on_exception<C: Context>(&self, exception: u64, ctx: &C, mmu: &SoftMmu) {
-        todo!()
+    fn on_exception<C: Context>(
+        &mut self,
+        _exception: u64,
+        ctx: &C,
+        mmu: &SoftMmu,
+    ) -> Result<ControlFlow<i32>, Error> {
+        // The only exception raised so far is an undefined instruction, which kills the
+        // process with SIGILL.
+        let pc = ctx.get::<u64>(IrValue::Register(
+            IrType::B64,
+            AArch64Architecture::get_pc_register().raw(),
+        ));
+        let mut encoding = [0; 4];
+        unsafe { mmu.read(pc, &mut encoding, Access::Execute)? };
+        Err(Error::UndefinedInstruction(u32::from_le_bytes(encoding)))
     }
 
-    fn on_interrupt<C: Context>(&self, interrupt: u64, ctx: &C, mmu: &SoftMmu) {
-        todo!()
+    fn on_interrupt<C: Context>(
+        &mut self,
+        interrupt: u64,
+        ctx: &C,
+        mmu: &SoftMmu,
+    ) -> Result<ControlFlow<i32>, Error> {
+        // Processes are never interrupted, there is nothing to deliver
+        Ok(ControlFlow::Continue(()))
     }
 
-    fn on_system_call<C: Context>(&self, system_call: u64, ctx: &C, mmu: &SoftMmu) {
-        todo!()
+    fn on_system_call<C: Context>(
+        &mut self,
+        system_call: u64,
+        ctx: &C,
+        mmu: &SoftMmu,
+    ) -> Result<ControlFlow<i32>, Error> {
+        let reg = |n| IrValue::Register(IrType::B64, AArch64Register::X(n).raw());
+
+        // Linux takes the system call number from X8 and ignores the SVC immediate.
+        let _ = system_call;
+        let nr = ctx.get::<u64>(reg(8));
+        let args = [0, 1, 2, 3, 4, 5].map(|n| ctx.get::<u64>(reg(n)));
+
+        let ret = match self.system_call(nr, args, mmu)? {
+            ControlFlow::Continue(Ok(value)) => value,
+            ControlFlow::Continue(Err(errno)) => (-errno) as u64,
+            ControlFlow::Break(status) => return Ok(ControlFlow::Break(status)),
+        };
+        ctx.set(reg(0), ret);
+        Ok(ControlFlow::Continue(()))
     }
 
-    fn on_irq<C: Context>(&self, id: usize, level: usize, ctx: &C, mmu: &SoftMmu) {
+    fn on_irq<C: Context>(&mut self, id: usize, level: usize, ctx: &C, mmu: &SoftMmu) {
         // Do nothing, we are in the userland.
     }
 }
+
+fn page_align_up(addr: u64) -> u64 {
+    (addr + PAGE_SIZE - 1) & !(PAGE_SIZE - 1)
+}
@@ -0,0 +1,44 @@
+//! The emulated CPU, as Linux describes it to userland.
+
+use std::fmt::Write;
+
+use arch_desc::aarch64::{AArch64Architecture, AArch64Feature};
+
+/// AT_HWCAP bit and /proc/cpuinfo name of a feature.
+fn hwcap_of(feature: AArch64Feature) -> (u64, &'static str) {
+    match feature {
+        AArch64Feature::Fp => (1 << 0, "fp"),
+        AArch64Feature::AdvSimd => (1 << 1, "asimd"),
+        AArch64Feature::Atomics => (1 << 8, "atomics"),
+        AArch64Feature::FpHp => (1 << 9, "fphp"),
+        AArch64Feature::AdvSimdHp => (1 << 10, "asimdhp"),
+    }
+}
+
+/// Value of AT_HWCAP.
+pub(super) fn hwcap() -> u64 {
+    AArch64Architecture::FEATURES
+        .iter()
+        .map(|&feature| hwcap_of(feature).0)
+        .fold(0, |hwcap, bit| hwcap | bit)
+}
+
+/// Contents of /proc/cpuinfo.
+pub(super) fn cpuinfo() -> String {
+    let features: Vec<_> = AArch64Architecture::FEATURES
+        .iter()
+        .map(|&feature| hwcap_of(feature).1)
+        .collect();
+
+    let mut cpuinfo = String::new();
+    writeln!(cpuinfo, "processor\t: 0").unwrap();
+    writeln!(cpuinfo, "BogoMIPS\t: 100.00").unwrap();
+    writeln!(cpuinfo, "Features\t: {}", features.join(" ")).unwrap();
+    writeln!(cpuinfo, "CPU implementer\t: 0x00").unwrap();
+    writeln!(cpuinfo, "CPU architecture: 8").unwrap();
+    writeln!(cpuinfo, "CPU variant\t: 0x0").unwrap();
+    writeln!(cpuinfo, "CPU part\t: 0x000").unwrap();
+    writeln!(cpuinfo, "CPU revision\t: 0").unwrap();
+    writeln!(cpuinfo).unwrap();
+    cpuinfo
+}
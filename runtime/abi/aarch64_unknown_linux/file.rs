@@ -0,0 +1,84 @@
+use std::{
+    fs::File,
+    io,
+    os::fd::AsFd,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// An open file description of the guest.
+#[derive(Clone)]
+pub(super) struct FileDesc {
+    pub(super) file: Rc<File>,
+    /// Guest path the file has been opened with, used to resolve `*at` system calls.
+    pub(super) path: Option<PathBuf>,
+    pub(super) flags: u64,
+}
+
+/// Guest file descriptor table.
+pub(super) struct FileTable {
+    files: Vec<Option<FileDesc>>,
+}
+
+impl FileTable {
+    /// Create a table where 0, 1 and 2 refer to the host standard streams.
+    pub(super) fn with_stdio() -> io::Result<Self> {
+        fn dup(fd: impl AsFd) -> io::Result<Option<FileDesc>> {
+            Ok(Some(FileDesc {
+                file: Rc::new(File::from(fd.as_fd().try_clone_to_owned()?)),
+                path: None,
+                flags: 0,
+            }))
+        }
+
+        Ok(Self {
+            files: vec![dup(io::stdin())?, dup(io::stdout())?, dup(io::stderr())?],
+        })
+    }
+
+    pub(super) fn get(&self, fd: u64) -> Option<&FileDesc> {
+        self.files.get(fd as usize)?.as_ref()
+    }
+
+    /// Insert a file at the lowest free descriptor.
+    pub(super) fn insert(&mut self, desc: FileDesc) -> u64 {
+        self.insert_from(0, desc)
+    }
+
+    /// Insert a file at the lowest free descriptor not less than `min`.
+    pub(super) fn insert_from(&mut self, min: u64, desc: FileDesc) -> u64 {
+        let min = min as usize;
+        if self.files.len() < min {
+            self.files.resize(min, None);
+        }
+
+        match self.files[min..].iter().position(Option::is_none) {
+            Some(idx) => {
+                self.files[min + idx] = Some(desc);
+                (min + idx) as u64
+            }
+            None => {
+                self.files.push(Some(desc));
+                (self.files.len() - 1) as u64
+            }
+        }
+    }
+
+    /// Insert a file at `fd`, closing the file that was there.
+    pub(super) fn replace(&mut self, fd: u64, desc: FileDesc) {
+        let fd = fd as usize;
+        if self.files.len() <= fd {
+            self.files.resize(fd + 1, None);
+        }
+
+        self.files[fd] = Some(desc);
+    }
+
+    pub(super) fn remove(&mut self, fd: u64) -> Option<FileDesc> {
+        self.files.get_mut(fd as usize)?.take()
+    }
+
+    pub(super) fn path(&self, fd: u64) -> Option<&Path> {
+        self.get(fd)?.path.as_deref()
+    }
+}
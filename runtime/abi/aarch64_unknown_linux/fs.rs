@@ -0,0 +1,237 @@
+//! Guest view of the filesystem.
+//!
+//! Absolute guest paths are looked up in a stack of mounts, the sysroot and finally the host
+//! root. Symbolic links are followed in the guest view, so an absolute link inside the sysroot
+//! stays inside it.
+
+use std::{
+    collections::VecDeque,
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Seek, Write},
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::syscall::errno;
+
+/// A host directory made visible in the guest.
+#[derive(Debug, Clone)]
+pub struct Mount {
+    /// Guest directory the mount appears at.
+    pub guest: PathBuf,
+    /// Host directory backing the mount.
+    pub host: PathBuf,
+    /// Reject anything that would modify the host directory.
+    pub read_only: bool,
+}
+
+/// A file of /proc generated by the emulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ProcFile {
+    Maps,
+    Cpuinfo,
+}
+
+impl ProcFile {
+    /// Host file that stands in for the generated one when it is only stat'ed.
+    pub(super) fn host_path(self) -> &'static Path {
+        match self {
+            Self::Maps => Path::new("/proc/self/maps"),
+            Self::Cpuinfo => Path::new("/proc/cpuinfo"),
+        }
+    }
+}
+
+/// What a guest path resolves to.
+pub(super) enum Node {
+    Host { path: PathBuf, read_only: bool },
+    Proc(ProcFile),
+}
+
+const ELOOP: i64 = 40;
+
+/// Maximum number of symbolic links followed while resolving a path, as Linux.
+const MAX_LINKS: usize = 40;
+
+pub(super) struct GuestFs {
+    // Searched in order, the most specific mount first
+    mounts: Vec<Mount>,
+    /// Guest path of the executable, the target of /proc/self/exe.
+    exe: PathBuf,
+    proc_self: PathBuf,
+}
+
+impl GuestFs {
+    pub(super) fn new(mounts: &[Mount], sysroot: Option<&Path>, exe: PathBuf) -> Self {
+        let mut mounts = mounts.to_vec();
+
+        // The sysroot is a fixture shared by every run, the guest must not modify it
+        mounts.extend(sysroot.map(|sysroot| Mount {
+            guest: "/".into(),
+            host: sysroot.into(),
+            read_only: true,
+        }));
+        mounts.push(Mount {
+            guest: "/".into(),
+            host: "/".into(),
+            read_only: false,
+        });
+
+        // A stable sort keeps the configured order between mounts of the same directory
+        mounts.sort_by_key(|mount| std::cmp::Reverse(mount.guest.components().count()));
+
+        Self {
+            mounts,
+            exe,
+            proc_self: Path::new("/proc").join(std::process::id().to_string()),
+        }
+    }
+
+    pub(super) fn exe(&self) -> &Path {
+        &self.exe
+    }
+
+    /// Resolve the absolute guest `path`. The last component is followed if it is a symbolic
+    /// link and `follow` is set.
+    pub(super) fn resolve(&self, path: &Path, follow: bool) -> Result<Node, i64> {
+        let path = self.walk(path, follow)?;
+
+        if path == Path::new("/proc/cpuinfo") {
+            return Ok(Node::Proc(ProcFile::Cpuinfo));
+        }
+        if path == self.proc_self.join("maps") {
+            return Ok(Node::Proc(ProcFile::Maps));
+        }
+
+        let (path, read_only) = self.lookup(&path);
+        Ok(Node::Host { path, read_only })
+    }
+
+    /// Target of the symbolic link at the absolute guest `path`.
+    pub(super) fn read_link(&self, path: &Path) -> Result<PathBuf, i64> {
+        let path = self.walk(path, false)?;
+
+        match self.link_target(&path) {
+            Some(target) => Ok(target),
+            None => {
+                let (host, _) = self.lookup(&path);
+                fs::read_link(host).map_err(errno)
+            }
+        }
+    }
+
+    /// Canonical guest path of `path`, following symbolic links in the guest view.
+    fn walk(&self, path: &Path, follow: bool) -> Result<PathBuf, i64> {
+        let mut pending: VecDeque<OsString> = components(path).collect();
+        let mut resolved = PathBuf::from("/");
+        let mut links = 0;
+
+        while let Some(name) = pending.pop_front() {
+            if name == ".." {
+                resolved.pop();
+                continue;
+            }
+
+            let next = resolved.join(&name);
+            if pending.is_empty() && !follow {
+                resolved = next;
+                break;
+            }
+
+            let Some(target) = self.link_target(&next) else {
+                resolved = next;
+                continue;
+            };
+
+            links += 1;
+            if links > MAX_LINKS {
+                return Err(ELOOP);
+            }
+
+            if target.is_absolute() {
+                resolved = PathBuf::from("/");
+            }
+            for name in components(&target).collect::<Vec<_>>().into_iter().rev() {
+                pending.push_front(name);
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    /// Target of the guest path if it is a symbolic link.
+    fn link_target(&self, path: &Path) -> Option<PathBuf> {
+        if path == self.proc_self.join("exe") {
+            return Some(self.exe.clone());
+        }
+
+        let (host, _) = self.lookup(path);
+        if !fs::symlink_metadata(&host).ok()?.is_symlink() {
+            return None;
+        }
+
+        fs::read_link(host).ok()
+    }
+
+    /// Host path backing the canonical guest `path`, and whether it is read only.
+    ///
+    /// The first mount that has the file wins. A file that exists nowhere belongs to the first
+    /// writable mount that has its parent directory, so new files can be created next to a
+    /// read only sysroot.
+    fn lookup(&self, path: &Path) -> (PathBuf, bool) {
+        let candidates: Vec<_> = self
+            .mounts
+            .iter()
+            .filter_map(|mount| {
+                let relative = path.strip_prefix(&mount.guest).ok()?;
+                Some((mount.host.join(relative), mount.read_only))
+            })
+            .collect();
+
+        if let Some(found) = candidates
+            .iter()
+            .find(|(host, _)| fs::symlink_metadata(host).is_ok())
+        {
+            return found.clone();
+        }
+
+        let creatable = candidates.iter().find(|(host, read_only)| {
+            !read_only && host.parent().map_or(false, |parent| parent.is_dir())
+        });
+        creatable
+            .or(candidates.first())
+            .cloned()
+            .expect("The host root is always mounted")
+    }
+}
+
+/// Normal components of `path`, including `..`.
+fn components(path: &Path) -> impl Iterator<Item = OsString> + '_ {
+    path.components().filter_map(|component| match component {
+        Component::Normal(name) => Some(name.to_owned()),
+        Component::ParentDir => Some("..".into()),
+        Component::RootDir | Component::CurDir | Component::Prefix(_) => None,
+    })
+}
+
+/// An unnamed host file that holds `contents`.
+pub(super) fn memfile(contents: &[u8]) -> io::Result<File> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let path = std::env::temp_dir().join(format!(
+        "gasang-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    fs::remove_file(&path)?;
+
+    file.write_all(contents)?;
+    file.rewind()?;
+    Ok(file)
+}
@@ -0,0 +1,162 @@
+//! Loading ELF images into the guest address space.
+
+use core::Error;
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf};
+
+use device::{devices::Memory, IoDevice};
+use elf::{
+    abi::{ET_DYN, PF_R, PF_W, PF_X, PT_INTERP, PT_LOAD, PT_PHDR},
+    endian::AnyEndian,
+    segment::ProgramHeader,
+    ElfBytes,
+};
+
+use crate::{Protection, SoftMmu};
+
+use super::{page_align_up, PAGE_SIZE};
+
+/// An ELF image that has been mapped into the guest.
+pub(super) struct LoadedElf {
+    /// Difference between the load address and the addresses in the image.
+    pub(super) bias: u64,
+    /// Start of the lowest segment, page aligned.
+    pub(super) start: u64,
+    pub(super) entry: u64,
+    /// Address of the program headers in the guest, zero if they are not loaded.
+    pub(super) phdr: u64,
+    pub(super) phent: u64,
+    pub(super) phnum: u64,
+    /// End of the highest segment, page aligned.
+    pub(super) end: u64,
+    /// Program interpreter requested by PT_INTERP.
+    pub(super) interp: Option<PathBuf>,
+}
+
+/// Map the loadable segments of `binary` with the access rights of their flags.
+///
+/// Executables are loaded at their linked address. Position independent images (ET_DYN) are
+/// relocated to the address `choose_base` returns for the page aligned size of the image, or
+/// fail to load if it finds no room.
+pub(super) fn load_elf(
+    mmu: &SoftMmu,
+    binary: &[u8],
+    choose_base: impl FnOnce(u64) -> Option<u64>,
+) -> Result<LoadedElf, Error> {
+    let malformed = |error: elf::ParseError| Error::LoadFailure(format!("malformed ELF: {error}"));
+    let elf = ElfBytes::<AnyEndian>::minimal_parse(binary).map_err(malformed)?;
+    let segments = elf
+        .segments()
+        .ok_or_else(|| Error::LoadFailure("ELF without program headers".into()))?;
+
+    let loads: Vec<_> = segments
+        .iter()
+        .filter(|seg| seg.p_type == PT_LOAD)
+        .collect();
+    // Segments must hold their file contents and fit in the address space
+    let bad_segment = |seg: &ProgramHeader| {
+        seg.p_filesz > seg.p_memsz
+            || seg
+                .p_memsz
+                .checked_add(PAGE_SIZE)
+                .and_then(|size| seg.p_vaddr.checked_add(size))
+                .is_none()
+    };
+    if loads.iter().any(bad_segment) {
+        return Err(Error::LoadFailure(
+            "malformed ELF: bad PT_LOAD segment".into(),
+        ));
+    }
+    let low = loads.iter().map(|seg| seg.p_vaddr).min().unwrap_or(0) & !(PAGE_SIZE - 1);
+    let high = loads
+        .iter()
+        .map(|seg| page_align_up(seg.p_vaddr + seg.p_memsz))
+        .max()
+        .unwrap_or(0);
+
+    let bias = match elf.ehdr.e_type {
+        ET_DYN => choose_base(high - low)
+            .ok_or_else(|| Error::LoadFailure("no room for the image".into()))?
+            .wrapping_sub(low),
+        _ => 0,
+    };
+
+    let mut end = 0;
+    let mut protections = Vec::new();
+    let mut phdr = None;
+    let mut interp = None;
+    for seg in segments.iter() {
+        match seg.p_type {
+            PT_PHDR => phdr = Some(seg.p_vaddr + bias),
+            PT_INTERP => {
+                let data = elf.segment_data(&seg).map_err(malformed)?;
+                let path = data.split(|&byte| byte == 0).next().unwrap_or_default();
+                interp = Some(PathBuf::from(OsStr::from_bytes(path)));
+            }
+            _ => {}
+        }
+        if seg.p_type != PT_LOAD {
+            continue;
+        }
+
+        // Without PT_PHDR, the program headers are found in the segment that loads them
+        let phoff = elf.ehdr.e_phoff;
+        if phdr.is_none() && (seg.p_offset..seg.p_offset + seg.p_filesz).contains(&phoff) {
+            phdr = Some(seg.p_vaddr + bias + phoff - seg.p_offset);
+        }
+
+        let addr = seg.p_vaddr + bias;
+        let size = seg.p_memsz;
+        let data = elf.segment_data(&seg).map_err(malformed)?;
+
+        // Segments are mapped in whole pages, the first page may be shared with the previous one
+        let start = (addr & !(PAGE_SIZE - 1)).max(end);
+        let stop = page_align_up(addr + size);
+        if stop > start {
+            mmu.map(
+                start,
+                stop - start,
+                Memory::allocate((stop - start) as usize),
+            );
+        }
+        unsafe {
+            mmu.write_all_at(addr, data);
+        }
+
+        let prot = [
+            (PF_R, Protection::READ),
+            (PF_W, Protection::WRITE),
+            (PF_X, Protection::EXEC),
+        ]
+        .into_iter()
+        .filter(|&(flag, _)| seg.p_flags & flag != 0)
+        .fold(Protection::NONE, |prot, (_, bit)| prot | bit);
+        protections.push((addr & !(PAGE_SIZE - 1)..stop, prot));
+
+        end = end.max(stop);
+    }
+
+    // Segments are written before they are protected. A page shared by two segments gets the
+    // rights of both.
+    for (range, prot) in &protections {
+        mmu.protect(range.clone(), *prot)?;
+    }
+    for pair in protections.windows(2) {
+        let [(prev, prev_prot), (next, next_prot)] = pair else {
+            unreachable!()
+        };
+        if prev.end > next.start {
+            mmu.protect(next.start..prev.end, *prev_prot | *next_prot)?;
+        }
+    }
+
+    Ok(LoadedElf {
+        bias,
+        start: low.wrapping_add(bias),
+        entry: elf.ehdr.e_entry + bias,
+        phdr: phdr.unwrap_or(0),
+        phent: elf.ehdr.e_phentsize as u64,
+        phnum: elf.ehdr.e_phnum as u64,
+        end,
+        interp,
+    })
+}
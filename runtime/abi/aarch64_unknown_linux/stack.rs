@@ -0,0 +1,130 @@
+//! Initial stack of a freshly exec'd process.
+
+use core::Error;
+use std::{ffi::OsString, fs::File, io::Read, os::unix::ffi::OsStrExt};
+
+use device::{devices::Memory, IoDevice};
+
+use crate::{Protection, SoftMmu};
+
+use super::{cpu, syscall::process_owner, PAGE_SIZE};
+
+const AT_NULL: u64 = 0;
+const AT_PHDR: u64 = 3;
+const AT_PHENT: u64 = 4;
+const AT_PHNUM: u64 = 5;
+const AT_PAGESZ: u64 = 6;
+const AT_BASE: u64 = 7;
+const AT_FLAGS: u64 = 8;
+const AT_ENTRY: u64 = 9;
+const AT_UID: u64 = 11;
+const AT_EUID: u64 = 12;
+const AT_GID: u64 = 13;
+const AT_EGID: u64 = 14;
+const AT_PLATFORM: u64 = 15;
+const AT_HWCAP: u64 = 16;
+const AT_CLKTCK: u64 = 17;
+const AT_SECURE: u64 = 23;
+const AT_RANDOM: u64 = 25;
+const AT_HWCAP2: u64 = 26;
+const AT_EXECFN: u64 = 31;
+
+/// The loaded program, as described to the guest by the auxiliary vector.
+pub(super) struct ProgramInfo {
+    /// Address of the program headers in the guest.
+    pub(super) phdr: u64,
+    pub(super) phent: u64,
+    pub(super) phnum: u64,
+    pub(super) entry: u64,
+    /// Load address of the program interpreter, zero if there is none.
+    pub(super) base: u64,
+}
+
+/// Map a stack of `size` bytes below `top` and lay out argc, argv, envp and the auxiliary
+/// vector on it like the kernel does. Returns the initial stack pointer.
+pub(super) fn setup_stack(
+    mmu: &SoftMmu,
+    top: u64,
+    size: u64,
+    args: &[OsString],
+    env: &[OsString],
+    program: &ProgramInfo,
+) -> Result<u64, Error> {
+    let size = (size + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+    mmu.map(top - size, size, Memory::allocate(size as usize));
+    mmu.protect(top - size..top, Protection::READ | Protection::WRITE)?;
+
+    let mut stack = Stack { mmu, sp: top };
+
+    // Strings are at the top of the stack, the end marker first
+    stack.push(&[0; 8]);
+    let execfn = args.first().map_or(0, |arg| stack.push_str(arg));
+    let envp: Vec<u64> = env.iter().rev().map(|var| stack.push_str(var)).collect();
+    let argv: Vec<u64> = args.iter().rev().map(|arg| stack.push_str(arg)).collect();
+    let platform = stack.push_str(&"aarch64".into());
+
+    let mut random = [0u8; 16];
+    File::open("/dev/urandom")
+        .and_then(|mut urandom| urandom.read_exact(&mut random))
+        .map_err(|error| Error::LoadFailure(format!("cannot read /dev/urandom: {error}")))?;
+    let random = stack.push(&random);
+
+    let (uid, gid) = process_owner();
+    let auxv = [
+        (AT_PHDR, program.phdr),
+        (AT_PHENT, program.phent),
+        (AT_PHNUM, program.phnum),
+        (AT_PAGESZ, PAGE_SIZE),
+        (AT_BASE, program.base),
+        (AT_FLAGS, 0),
+        (AT_ENTRY, program.entry),
+        (AT_UID, uid as u64),
+        (AT_EUID, uid as u64),
+        (AT_GID, gid as u64),
+        (AT_EGID, gid as u64),
+        (AT_PLATFORM, platform),
+        (AT_HWCAP, cpu::hwcap()),
+        (AT_HWCAP2, 0),
+        (AT_CLKTCK, 100),
+        (AT_SECURE, 0),
+        (AT_RANDOM, random),
+        (AT_EXECFN, execfn),
+        (AT_NULL, 0),
+    ];
+
+    // argc, argv and envp terminated by null, then the auxiliary vector
+    let mut table = vec![args.len() as u64];
+    table.extend(argv.iter().rev());
+    table.push(0);
+    table.extend(envp.iter().rev());
+    table.push(0);
+    table.extend(auxv.iter().flat_map(|&(key, value)| [key, value]));
+
+    let table: Vec<u8> = table.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+    // The stack pointer must be 16 bytes aligned at argc
+    stack.sp -= (stack.sp - table.len() as u64) % 16;
+    Ok(stack.push(&table))
+}
+
+struct Stack<'a> {
+    mmu: &'a SoftMmu,
+    sp: u64,
+}
+
+impl Stack<'_> {
+    /// Push `bytes` and return their address.
+    fn push(&mut self, bytes: &[u8]) -> u64 {
+        self.sp -= bytes.len() as u64;
+        unsafe {
+            self.mmu.write_all_at(self.sp, bytes);
+        }
+        self.sp
+    }
+
+    fn push_str(&mut self, str: &OsString) -> u64 {
+        let mut bytes = str.as_bytes().to_vec();
+        bytes.push(0);
+        self.push(&bytes)
+    }
+}
@@ -0,0 +1,1102 @@
+//! Linux system calls of the AArch64 generic syscall table.
+
+use core::{Access, Error};
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    fs::{self, File, Metadata, OpenOptions},
+    io::{self, IsTerminal, Read, Seek, SeekFrom, Write},
+    ops::ControlFlow,
+    os::unix::{
+        ffi::{OsStrExt, OsStringExt},
+        fs::{FileExt, MetadataExt, OpenOptionsExt},
+    },
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use device::devices::Memory;
+
+use crate::{Protection, SoftMmu};
+
+use super::{
+    cpu,
+    file::FileDesc,
+    fs::{memfile, Node, ProcFile},
+    page_align_up, AArch64UnknownLinux, PAGE_SIZE, STACK_TOP,
+};
+
+/// Result of a system call, either the return value or a positive errno.
+type SyscallResult = Result<u64, i64>;
+
+const SYS_GETCWD: u64 = 17;
+const SYS_DUP: u64 = 23;
+const SYS_DUP3: u64 = 24;
+const SYS_FCNTL: u64 = 25;
+const SYS_IOCTL: u64 = 29;
+const SYS_FACCESSAT: u64 = 48;
+const SYS_OPENAT: u64 = 56;
+const SYS_CLOSE: u64 = 57;
+const SYS_LSEEK: u64 = 62;
+const SYS_READ: u64 = 63;
+const SYS_WRITE: u64 = 64;
+const SYS_READV: u64 = 65;
+const SYS_WRITEV: u64 = 66;
+const SYS_PREAD64: u64 = 67;
+const SYS_PWRITE64: u64 = 68;
+const SYS_READLINKAT: u64 = 78;
+const SYS_NEWFSTATAT: u64 = 79;
+const SYS_FSTAT: u64 = 80;
+const SYS_EXIT: u64 = 93;
+const SYS_EXIT_GROUP: u64 = 94;
+const SYS_SET_TID_ADDRESS: u64 = 96;
+const SYS_FUTEX: u64 = 98;
+const SYS_SET_ROBUST_LIST: u64 = 99;
+const SYS_NANOSLEEP: u64 = 101;
+const SYS_CLOCK_GETTIME: u64 = 113;
+const SYS_CLOCK_GETRES: u64 = 114;
+const SYS_CLOCK_NANOSLEEP: u64 = 115;
+const SYS_SCHED_YIELD: u64 = 124;
+const SYS_SIGALTSTACK: u64 = 132;
+const SYS_RT_SIGACTION: u64 = 134;
+const SYS_RT_SIGPROCMASK: u64 = 135;
+const SYS_UNAME: u64 = 160;
+const SYS_GETRLIMIT: u64 = 163;
+const SYS_GETTIMEOFDAY: u64 = 169;
+const SYS_GETPID: u64 = 172;
+const SYS_GETPPID: u64 = 173;
+const SYS_GETUID: u64 = 174;
+const SYS_GETEUID: u64 = 175;
+const SYS_GETGID: u64 = 176;
+const SYS_GETEGID: u64 = 177;
+const SYS_GETTID: u64 = 178;
+const SYS_BRK: u64 = 214;
+const SYS_MUNMAP: u64 = 215;
+const SYS_MMAP: u64 = 222;
+const SYS_MPROTECT: u64 = 226;
+const SYS_MADVISE: u64 = 233;
+const SYS_PRLIMIT64: u64 = 261;
+const SYS_GETRANDOM: u64 = 278;
+
+const EPERM: i64 = 1;
+const ENOENT: i64 = 2;
+const EIO: i64 = 5;
+const EBADF: i64 = 9;
+const ENOMEM: i64 = 12;
+const EACCES: i64 = 13;
+const EFAULT: i64 = 14;
+const EEXIST: i64 = 17;
+const ENOTDIR: i64 = 20;
+const EINVAL: i64 = 22;
+const ENOTTY: i64 = 25;
+const EROFS: i64 = 30;
+const ERANGE: i64 = 34;
+const ENAMETOOLONG: i64 = 36;
+const ENOSYS: i64 = 38;
+const ELOOP: i64 = 40;
+
+const AT_FDCWD: i32 = -100;
+const AT_SYMLINK_NOFOLLOW: u64 = 0x100;
+const AT_EMPTY_PATH: u64 = 0x1000;
+
+const O_ACCMODE: u64 = 0o3;
+const O_WRONLY: u64 = 0o1;
+const O_RDWR: u64 = 0o2;
+const O_CREAT: u64 = 0o100;
+const O_EXCL: u64 = 0o200;
+const O_TRUNC: u64 = 0o1000;
+const O_APPEND: u64 = 0o2000;
+const O_DIRECTORY: u64 = 0o40000;
+const O_NOFOLLOW: u64 = 0o100000;
+const O_CLOEXEC: u64 = 0o2000000;
+
+const W_OK: u64 = 2;
+
+const F_DUPFD: u64 = 0;
+const F_GETFD: u64 = 1;
+const F_SETFD: u64 = 2;
+const F_GETFL: u64 = 3;
+const F_SETFL: u64 = 4;
+const F_DUPFD_CLOEXEC: u64 = 1030;
+
+const TCGETS: u64 = 0x5401;
+const TCSETS: u64 = 0x5402;
+const TCSETSW: u64 = 0x5403;
+const TCSETSF: u64 = 0x5404;
+const TIOCGWINSZ: u64 = 0x5413;
+
+const MAP_FIXED: u64 = 0x10;
+const MAP_ANONYMOUS: u64 = 0x20;
+const MAP_FIXED_NOREPLACE: u64 = 0x100000;
+
+const CLOCK_REALTIME: u64 = 0;
+const CLOCK_REALTIME_COARSE: u64 = 5;
+const TIMER_ABSTIME: u64 = 1;
+
+const RLIMIT_STACK: u64 = 3;
+const RLIMIT_NOFILE: u64 = 7;
+const RLIM_INFINITY: u64 = u64::MAX;
+/// Soft and hard `RLIMIT_NOFILE`, descriptors from the soft limit on are never handed out.
+const NOFILE_LIMIT: (u64, u64) = (1024, 4096);
+
+const FUTEX_WAKE: u64 = 1;
+const FUTEX_CMD_MASK: u64 = 0x7f;
+
+const PATH_MAX: usize = 4096;
+/// Upper bound of the bytes transferred by a single read or write, the guest sees a short count.
+const IO_CHUNK: usize = 1 << 20;
+
+impl AArch64UnknownLinux {
+    /// Run system call `nr`, `Break` carries the exit status of the process. Unknown system
+    /// calls fail with ENOSYS, or stop the guest if `strict_syscalls` is set.
+    pub(super) fn system_call(
+        &mut self,
+        nr: u64,
+        args: [u64; 6],
+        mmu: &SoftMmu,
+    ) -> Result<ControlFlow<i32, SyscallResult>, Error> {
+        let [a0, a1, a2, a3, a4, a5] = args;
+        Ok(ControlFlow::Continue(match nr {
+            SYS_GETCWD => self.sys_getcwd(mmu, a0, a1),
+            SYS_DUP => self.sys_dup(a0),
+            SYS_DUP3 => self.sys_dup3(a0, a1, a2),
+            SYS_FCNTL => self.sys_fcntl(a0, a1, a2),
+            SYS_IOCTL => self.sys_ioctl(mmu, a0, a1, a2),
+            SYS_FACCESSAT => self.sys_faccessat(mmu, a0, a1, a2),
+            SYS_OPENAT => self.sys_openat(mmu, a0, a1, a2, a3),
+            SYS_CLOSE => self.sys_close(a0),
+            SYS_LSEEK => self.sys_lseek(a0, a1, a2),
+            SYS_READ => self.sys_read(mmu, a0, a1, a2),
+            SYS_WRITE => self.sys_write(mmu, a0, a1, a2),
+            SYS_READV => self.sys_readv(mmu, a0, a1, a2),
+            SYS_WRITEV => self.sys_writev(mmu, a0, a1, a2),
+            SYS_PREAD64 => self.sys_pread64(mmu, a0, a1, a2, a3),
+            SYS_PWRITE64 => self.sys_pwrite64(mmu, a0, a1, a2, a3),
+            SYS_READLINKAT => self.sys_readlinkat(mmu, a0, a1, a2, a3),
+            SYS_NEWFSTATAT => self.sys_newfstatat(mmu, a0, a1, a2, a3),
+            SYS_FSTAT => self.sys_fstat(mmu, a0, a1),
+            // The parent only sees the low byte of the status
+            SYS_EXIT | SYS_EXIT_GROUP => return Ok(ControlFlow::Break((a0 & 0xff) as i32)),
+            SYS_SET_TID_ADDRESS => Ok(std::process::id() as u64),
+            SYS_FUTEX => sys_futex(a1),
+            SYS_SET_ROBUST_LIST | SYS_SIGALTSTACK => Ok(0),
+            SYS_NANOSLEEP => self.sys_clock_nanosleep(mmu, CLOCK_REALTIME, 0, a0),
+            SYS_CLOCK_GETTIME => self.sys_clock_gettime(mmu, a0, a1),
+            SYS_CLOCK_GETRES => sys_clock_getres(mmu, a1),
+            SYS_CLOCK_NANOSLEEP => self.sys_clock_nanosleep(mmu, a0, a1, a2),
+            SYS_SCHED_YIELD => {
+                std::thread::yield_now();
+                Ok(0)
+            }
+            SYS_RT_SIGACTION => sys_rt_sigaction(mmu, a2),
+            SYS_RT_SIGPROCMASK => sys_rt_sigprocmask(mmu, a2, a3),
+            SYS_UNAME => sys_uname(mmu, a0),
+            SYS_GETRLIMIT => self.sys_prlimit64(mmu, a0, 0, a1),
+            SYS_GETTIMEOFDAY => sys_gettimeofday(mmu, a0, a1),
+            SYS_GETPID | SYS_GETTID => Ok(std::process::id() as u64),
+            SYS_GETPPID => Ok(std::os::unix::process::parent_id() as u64),
+            SYS_GETUID | SYS_GETEUID => Ok(process_owner().0 as u64),
+            SYS_GETGID | SYS_GETEGID => Ok(process_owner().1 as u64),
+            SYS_BRK => self.sys_brk(mmu, a0),
+            SYS_MUNMAP => sys_munmap(mmu, a0, a1),
+            SYS_MMAP => self.sys_mmap(mmu, a0, a1, a3, a4, a5).and_then(|addr| {
+                // The file is copied into the mapping before it is protected
+                let prot = Protection::from_bits(a2);
+                mmu.protect(addr..addr + page_align_up(a1), prot)
+                    .map_err(|_| ENOMEM)?;
+                Ok(addr)
+            }),
+            SYS_MPROTECT => sys_mprotect(mmu, a0, a1, a2),
+            SYS_MADVISE => Ok(0),
+            SYS_PRLIMIT64 => self.sys_prlimit64(mmu, a1, a2, a3),
+            SYS_GETRANDOM => sys_getrandom(mmu, a0, a1),
+            _ if self.config.strict_syscalls => return Err(Error::UnsupportedSyscall(nr)),
+            _ => Err(ENOSYS),
+        }))
+    }
+
+    fn file(&self, fd: u64) -> Result<&FileDesc, i64> {
+        self.files.get(fd).ok_or(EBADF)
+    }
+
+    /// Absolute guest path of `path`, relative to the directory `dirfd` refers to.
+    fn absolute_path(&self, dirfd: u64, path: PathBuf) -> Result<PathBuf, i64> {
+        if path.is_absolute() {
+            return Ok(path);
+        }
+        if dirfd as i32 == AT_FDCWD {
+            // The guest shares the working directory of the emulator
+            return Ok(std::env::current_dir().map_err(errno)?.join(path));
+        }
+
+        self.file(dirfd)?;
+        let dir = self.files.path(dirfd).ok_or(ENOTDIR)?;
+        Ok(dir.join(path))
+    }
+
+    /// Resolve the guest `path` relative to the directory `dirfd` refers to.
+    fn resolve(&self, dirfd: u64, path: PathBuf, follow: bool) -> Result<(PathBuf, Node), i64> {
+        let path = self.absolute_path(dirfd, path)?;
+        let node = self.fs.resolve(&path, follow)?;
+        Ok((path, node))
+    }
+
+    fn proc_contents(&self, file: ProcFile, mmu: &SoftMmu) -> String {
+        match file {
+            ProcFile::Maps => self.proc_maps(mmu),
+            ProcFile::Cpuinfo => cpu::cpuinfo(),
+        }
+    }
+
+    fn proc_maps(&self, mmu: &SoftMmu) -> String {
+        let stack = STACK_TOP - page_align_up(self.config.stack_size)..STACK_TOP;
+        let heap = self.brk_start..page_align_up(self.brk);
+
+        let mut maps = String::new();
+        for (range, prot) in mmu.mappings() {
+            let name = if stack.contains(&range.start) {
+                "[stack]".into()
+            } else if heap.contains(&range.start) {
+                "[heap]".into()
+            } else {
+                self.images
+                    .iter()
+                    .find(|(image, _)| image.contains(&range.start))
+                    .map_or(String::new(), |(_, path)| path.display().to_string())
+            };
+
+            let perms: String = [
+                (Protection::READ, 'r'),
+                (Protection::WRITE, 'w'),
+                (Protection::EXEC, 'x'),
+            ]
+            .into_iter()
+            .map(|(bit, c)| {
+                if prot.bits() & bit.bits() != 0 {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+            let line = format!(
+                "{:08x}-{:08x} {perms}p 00000000 00:00 0",
+                range.start, range.end
+            );
+            if name.is_empty() {
+                writeln!(maps, "{line}").unwrap();
+            } else {
+                writeln!(maps, "{line:<72} {name}").unwrap();
+            }
+        }
+
+        maps
+    }
+
+    fn sys_getcwd(&self, mmu: &SoftMmu, buf: u64, size: u64) -> SyscallResult {
+        let cwd = std::env::current_dir().map_err(errno)?;
+        let mut cwd = cwd.into_os_string().into_vec();
+        cwd.push(0);
+
+        if cwd.len() as u64 > size {
+            return Err(ERANGE);
+        }
+
+        write_guest(mmu, buf, &cwd)?;
+        Ok(cwd.len() as u64)
+    }
+
+    fn sys_dup(&mut self, fd: u64) -> SyscallResult {
+        let desc = self.file(fd)?.clone();
+        Ok(self.files.insert(desc))
+    }
+
+    fn sys_dup3(&mut self, old: u64, new: u64, flags: u64) -> SyscallResult {
+        if old == new || flags & !O_CLOEXEC != 0 {
+            return Err(EINVAL);
+        }
+        if new >= NOFILE_LIMIT.0 {
+            return Err(EBADF);
+        }
+
+        let desc = self.file(old)?.clone();
+        self.files.replace(new, desc);
+        Ok(new)
+    }
+
+    fn sys_fcntl(&mut self, fd: u64, cmd: u64, arg: u64) -> SyscallResult {
+        let desc = self.file(fd)?.clone();
+        match cmd {
+            F_DUPFD | F_DUPFD_CLOEXEC if arg >= NOFILE_LIMIT.0 => Err(EINVAL),
+            F_DUPFD | F_DUPFD_CLOEXEC => Ok(self.files.insert_from(arg, desc)),
+            // Descriptors are never inherited by an exec, so FD_CLOEXEC has no effect
+            F_GETFD | F_SETFD => Ok(0),
+            F_GETFL => Ok(desc.flags),
+            F_SETFL => Ok(0),
+            _ => Err(EINVAL),
+        }
+    }
+
+    fn sys_ioctl(&self, mmu: &SoftMmu, fd: u64, request: u64, arg: u64) -> SyscallResult {
+        let desc = self.file(fd)?;
+        if !desc.file.is_terminal() {
+            return Err(ENOTTY);
+        }
+
+        match request {
+            TCGETS => {
+                // struct termios of a terminal in cooked mode
+                let mut termios = [0u8; 36];
+                termios[0..4].copy_from_slice(&0x0500u32.to_le_bytes()); // ICRNL | IXON
+                termios[4..8].copy_from_slice(&0x0005u32.to_le_bytes()); // OPOST | ONLCR
+                termios[8..12].copy_from_slice(&0x00bfu32.to_le_bytes()); // B38400 | CS8 | CREAD
+                termios[12..16].copy_from_slice(&0x8a3bu32.to_le_bytes()); // ISIG | ICANON | ECHO...
+                termios[17..28].copy_from_slice(&[3, 28, 127, 21, 4, 0, 1, 0, 17, 19, 26]);
+                write_guest(mmu, arg, &termios)?;
+                Ok(0)
+            }
+            TCSETS | TCSETSW | TCSETSF => Ok(0),
+            TIOCGWINSZ => {
+                // struct winsize of a 80x24 terminal
+                let mut winsize = [0u8; 8];
+                winsize[0..2].copy_from_slice(&24u16.to_le_bytes());
+                winsize[2..4].copy_from_slice(&80u16.to_le_bytes());
+                write_guest(mmu, arg, &winsize)?;
+                Ok(0)
+            }
+            _ => Err(EINVAL),
+        }
+    }
+
+    fn sys_faccessat(&self, mmu: &SoftMmu, dirfd: u64, path: u64, mode: u64) -> SyscallResult {
+        let (_, node) = self.resolve(dirfd, read_path(mmu, path)?, true)?;
+        match node {
+            Node::Host { path, read_only } => {
+                fs::metadata(path).map_err(errno)?;
+                if read_only && mode & W_OK != 0 {
+                    return Err(EROFS);
+                }
+            }
+            Node::Proc(_) if mode & W_OK != 0 => return Err(EACCES),
+            Node::Proc(_) => {}
+        }
+
+        Ok(0)
+    }
+
+    fn sys_openat(
+        &mut self,
+        mmu: &SoftMmu,
+        dirfd: u64,
+        path: u64,
+        flags: u64,
+        mode: u64,
+    ) -> SyscallResult {
+        let follow = flags & O_NOFOLLOW == 0;
+        let (path, node) = self.resolve(dirfd, read_path(mmu, path)?, follow)?;
+        let writes = flags & O_ACCMODE != 0 || flags & O_TRUNC != 0;
+
+        let host = match node {
+            Node::Host { path, read_only } => {
+                let exists = fs::symlink_metadata(&path);
+                if exists
+                    .as_ref()
+                    .map_or(false, |metadata| metadata.is_symlink())
+                    && !follow
+                {
+                    return Err(ELOOP);
+                }
+                if read_only && (writes || (flags & O_CREAT != 0 && exists.is_err())) {
+                    return Err(EROFS);
+                }
+                path
+            }
+            Node::Proc(_) if writes => return Err(EACCES),
+            Node::Proc(file) => {
+                let contents = self.proc_contents(file, mmu);
+                let file = memfile(contents.as_bytes()).map_err(errno)?;
+                return Ok(self.files.insert(FileDesc {
+                    file: Rc::new(file),
+                    path: Some(path),
+                    flags,
+                }));
+            }
+        };
+
+        let mut options = OpenOptions::new();
+        match flags & O_ACCMODE {
+            O_WRONLY => options.write(true),
+            O_RDWR => options.read(true).write(true),
+            _ => options.read(true),
+        };
+        options
+            .append(flags & O_APPEND != 0)
+            .truncate(flags & O_TRUNC != 0)
+            .mode(mode as u32);
+        if flags & O_CREAT != 0 {
+            if flags & O_EXCL != 0 {
+                options.create_new(true);
+            } else {
+                options.create(true);
+            }
+        }
+
+        let file = options.open(host).map_err(errno)?;
+        if flags & O_DIRECTORY != 0 && !file.metadata().map_err(errno)?.is_dir() {
+            return Err(ENOTDIR);
+        }
+
+        Ok(self.files.insert(FileDesc {
+            file: Rc::new(file),
+            path: Some(path),
+            flags,
+        }))
+    }
+
+    fn sys_close(&mut self, fd: u64) -> SyscallResult {
+        self.files.remove(fd).ok_or(EBADF)?;
+        Ok(0)
+    }
+
+    fn sys_lseek(&self, fd: u64, offset: u64, whence: u64) -> SyscallResult {
+        let pos = match whence {
+            0 => SeekFrom::Start(offset),
+            1 => SeekFrom::Current(offset as i64),
+            2 => SeekFrom::End(offset as i64),
+            _ => return Err(EINVAL),
+        };
+
+        (&*self.file(fd)?.file).seek(pos).map_err(errno)
+    }
+
+    fn sys_read(&self, mmu: &SoftMmu, fd: u64, buf: u64, count: u64) -> SyscallResult {
+        let file = &*self.file(fd)?.file;
+        let mut data = vec![0; (count as usize).min(IO_CHUNK)];
+        let len = (&*file).read(&mut data).map_err(errno)?;
+
+        write_guest(mmu, buf, &data[..len])?;
+        Ok(len as u64)
+    }
+
+    fn sys_write(&self, mmu: &SoftMmu, fd: u64, buf: u64, count: u64) -> SyscallResult {
+        let file = &*self.file(fd)?.file;
+        let data = read_guest(mmu, buf, (count as usize).min(IO_CHUNK))?;
+
+        let len = (&*file).write(&data).map_err(errno)?;
+        Ok(len as u64)
+    }
+
+    fn sys_readv(&self, mmu: &SoftMmu, fd: u64, iov: u64, iovcnt: u64) -> SyscallResult {
+        let mut total = 0;
+        for (base, len) in read_iovec(mmu, iov, iovcnt)? {
+            let read = self.sys_read(mmu, fd, base, len)?;
+            total += read;
+            if read < len {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn sys_writev(&self, mmu: &SoftMmu, fd: u64, iov: u64, iovcnt: u64) -> SyscallResult {
+        let mut total = 0;
+        for (base, len) in read_iovec(mmu, iov, iovcnt)? {
+            let written = self.sys_write(mmu, fd, base, len)?;
+            total += written;
+            if written < len {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn sys_pread64(
+        &self,
+        mmu: &SoftMmu,
+        fd: u64,
+        buf: u64,
+        count: u64,
+        offset: u64,
+    ) -> SyscallResult {
+        let file = &*self.file(fd)?.file;
+        let mut data = vec![0; (count as usize).min(IO_CHUNK)];
+        let len = file.read_at(&mut data, offset).map_err(errno)?;
+
+        write_guest(mmu, buf, &data[..len])?;
+        Ok(len as u64)
+    }
+
+    fn sys_pwrite64(
+        &self,
+        mmu: &SoftMmu,
+        fd: u64,
+        buf: u64,
+        count: u64,
+        offset: u64,
+    ) -> SyscallResult {
+        let file = &*self.file(fd)?.file;
+        let data = read_guest(mmu, buf, (count as usize).min(IO_CHUNK))?;
+
+        let len = file.write_at(&data, offset).map_err(errno)?;
+        Ok(len as u64)
+    }
+
+    fn sys_readlinkat(
+        &self,
+        mmu: &SoftMmu,
+        dirfd: u64,
+        path: u64,
+        buf: u64,
+        size: u64,
+    ) -> SyscallResult {
+        let path = self.absolute_path(dirfd, read_path(mmu, path)?)?;
+        let target = self.fs.read_link(&path)?;
+        let target = target.as_os_str().as_bytes();
+
+        // The result is truncated and not terminated
+        let len = target.len().min(size as usize);
+        write_guest(mmu, buf, &target[..len])?;
+        Ok(len as u64)
+    }
+
+    fn sys_newfstatat(
+        &self,
+        mmu: &SoftMmu,
+        dirfd: u64,
+        path: u64,
+        statbuf: u64,
+        flags: u64,
+    ) -> SyscallResult {
+        let path = read_path(mmu, path);
+        let metadata = match path {
+            Err(ENOENT) if flags & AT_EMPTY_PATH != 0 => {
+                self.file(dirfd)?.file.metadata().map_err(errno)?
+            }
+            path => {
+                let follow = flags & AT_SYMLINK_NOFOLLOW == 0;
+                let path = match self.resolve(dirfd, path?, follow)?.1 {
+                    Node::Host { path, .. } => path,
+                    Node::Proc(file) => file.host_path().to_path_buf(),
+                };
+
+                if follow {
+                    fs::metadata(path).map_err(errno)?
+                } else {
+                    fs::symlink_metadata(path).map_err(errno)?
+                }
+            }
+        };
+
+        write_guest(mmu, statbuf, &stat(&metadata))?;
+        Ok(0)
+    }
+
+    fn sys_fstat(&self, mmu: &SoftMmu, fd: u64, statbuf: u64) -> SyscallResult {
+        let metadata = self.file(fd)?.file.metadata().map_err(errno)?;
+
+        write_guest(mmu, statbuf, &stat(&metadata))?;
+        Ok(0)
+    }
+
+    fn now(&self, clock: u64) -> Duration {
+        match clock {
+            CLOCK_REALTIME | CLOCK_REALTIME_COARSE => {
+                SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+            }
+            // Every other clock counts from the start of the guest
+            _ => self.boot_time.elapsed(),
+        }
+    }
+
+    fn sys_clock_gettime(&self, mmu: &SoftMmu, clock: u64, tp: u64) -> SyscallResult {
+        let now = self.now(clock);
+
+        write_guest(mmu, tp, &timespec(now))?;
+        Ok(0)
+    }
+
+    fn sys_clock_nanosleep(
+        &self,
+        mmu: &SoftMmu,
+        clock: u64,
+        flags: u64,
+        request: u64,
+    ) -> SyscallResult {
+        let request = read_guest(mmu, request, 16)?;
+        let sec = u64::from_le_bytes(request[0..8].try_into().unwrap());
+        let nsec = u64::from_le_bytes(request[8..16].try_into().unwrap());
+        if nsec >= 1_000_000_000 {
+            return Err(EINVAL);
+        }
+
+        let mut duration = Duration::new(sec, nsec as u32);
+        if flags & TIMER_ABSTIME != 0 {
+            duration = duration.saturating_sub(self.now(clock));
+        }
+
+        std::thread::sleep(duration);
+        Ok(0)
+    }
+
+    fn sys_brk(&mut self, mmu: &SoftMmu, addr: u64) -> SyscallResult {
+        // An invalid request returns the current break
+        if addr < self.brk_start {
+            return Ok(self.brk);
+        }
+
+        let mapped = page_align_up(self.brk);
+        let end = page_align_up(addr);
+        if end > mapped {
+            if mmu.is_mapped(mapped..end) {
+                return Ok(self.brk);
+            }
+
+            mmu.map(
+                mapped,
+                end - mapped,
+                Memory::allocate((end - mapped) as usize),
+            );
+            mmu.protect(mapped..end, Protection::READ | Protection::WRITE)
+                .map_err(|_| ENOMEM)?;
+        }
+
+        // Shrinking keeps the pages mapped, they are reused when the break grows again
+        self.brk = addr;
+        Ok(self.brk)
+    }
+
+    fn sys_mmap(
+        &mut self,
+        mmu: &SoftMmu,
+        addr: u64,
+        len: u64,
+        flags: u64,
+        fd: u64,
+        offset: u64,
+    ) -> SyscallResult {
+        if len == 0 || addr % PAGE_SIZE != 0 || offset % PAGE_SIZE != 0 {
+            return Err(EINVAL);
+        }
+
+        let len = page_align_up(len);
+        let file = match flags & MAP_ANONYMOUS {
+            0 => Some(self.file(fd)?.file.clone()),
+            _ => None,
+        };
+
+        let addr = if flags & MAP_FIXED != 0 {
+            addr
+        } else if flags & MAP_FIXED_NOREPLACE != 0 {
+            if mmu.is_mapped(addr..addr + len) {
+                return Err(EEXIST);
+            }
+            addr
+        } else if addr != 0 && !mmu.is_mapped(addr..addr + len) {
+            addr
+        } else {
+            self.find_free_area(mmu, len)?
+        };
+
+        mmu.map(addr, len, Memory::allocate(len as usize));
+
+        // Private file mappings are copies, changes are never written back
+        if let Some(file) = file {
+            let mut data = vec![0; len as usize];
+            let mut read = 0;
+            while read < data.len() {
+                match file.read_at(&mut data[read..], offset + read as u64) {
+                    Ok(0) => break,
+                    Ok(len) => read += len,
+                    Err(err) => {
+                        mmu.unmap(addr..addr + len);
+                        return Err(errno(err));
+                    }
+                }
+            }
+
+            write_guest(mmu, addr, &data[..read])?;
+        }
+
+        Ok(addr)
+    }
+
+    fn sys_prlimit64(
+        &self,
+        mmu: &SoftMmu,
+        resource: u64,
+        new_limit: u64,
+        old_limit: u64,
+    ) -> SyscallResult {
+        if new_limit != 0 {
+            return Err(EPERM);
+        }
+
+        if old_limit != 0 {
+            let (cur, max) = match resource {
+                RLIMIT_STACK => (self.config.stack_size, RLIM_INFINITY),
+                RLIMIT_NOFILE => NOFILE_LIMIT,
+                _ => (RLIM_INFINITY, RLIM_INFINITY),
+            };
+
+            let mut rlimit = [0u8; 16];
+            rlimit[0..8].copy_from_slice(&cur.to_le_bytes());
+            rlimit[8..16].copy_from_slice(&max.to_le_bytes());
+            write_guest(mmu, old_limit, &rlimit)?;
+        }
+
+        Ok(0)
+    }
+
+    pub(super) fn find_free_area(&mut self, mmu: &SoftMmu, len: u64) -> Result<u64, i64> {
+        let mut addr = self.mmap_top;
+        loop {
+            addr = addr.checked_sub(len).ok_or(ENOMEM)?;
+            if !mmu.is_mapped(addr..addr + len) {
+                break;
+            }
+        }
+
+        self.mmap_top = addr;
+        Ok(addr)
+    }
+}
+
+fn sys_munmap(mmu: &SoftMmu, addr: u64, len: u64) -> SyscallResult {
+    if len == 0 || addr % PAGE_SIZE != 0 {
+        return Err(EINVAL);
+    }
+
+    mmu.unmap(addr..addr + page_align_up(len));
+    Ok(0)
+}
+
+fn sys_mprotect(mmu: &SoftMmu, addr: u64, len: u64, prot: u64) -> SyscallResult {
+    if addr % PAGE_SIZE != 0 {
+        return Err(EINVAL);
+    }
+
+    // Like Linux, a range that is not entirely mapped is an error and nothing changes
+    let end = len
+        .checked_add(PAGE_SIZE - 1)
+        .and_then(|len| addr.checked_add(len & !(PAGE_SIZE - 1)))
+        .ok_or(ENOMEM)?;
+    mmu.protect(addr..end, Protection::from_bits(prot))
+        .map_err(|_| ENOMEM)?;
+    Ok(0)
+}
+
+fn sys_futex(op: u64) -> SyscallResult {
+    // There is a single guest thread, so nobody is ever waiting
+    match op & FUTEX_CMD_MASK {
+        FUTEX_WAKE => Ok(0),
+        _ => Err(ENOSYS),
+    }
+}
+
+fn sys_clock_getres(mmu: &SoftMmu, res: u64) -> SyscallResult {
+    if res != 0 {
+        write_guest(mmu, res, &timespec(Duration::from_nanos(1)))?;
+    }
+
+    Ok(0)
+}
+
+fn sys_gettimeofday(mmu: &SoftMmu, tv: u64, tz: u64) -> SyscallResult {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    if tv != 0 {
+        let mut timeval = [0u8; 16];
+        timeval[0..8].copy_from_slice(&now.as_secs().to_le_bytes());
+        timeval[8..16].copy_from_slice(&(now.subsec_micros() as u64).to_le_bytes());
+        write_guest(mmu, tv, &timeval)?;
+    }
+
+    if tz != 0 {
+        write_guest(mmu, tz, &[0; 8])?;
+    }
+
+    Ok(0)
+}
+
+fn sys_rt_sigaction(mmu: &SoftMmu, oldact: u64) -> SyscallResult {
+    // Signals are never delivered, every handler stays SIG_DFL
+    if oldact != 0 {
+        write_guest(mmu, oldact, &[0; 32])?;
+    }
+
+    Ok(0)
+}
+
+fn sys_rt_sigprocmask(mmu: &SoftMmu, oldset: u64, size: u64) -> SyscallResult {
+    if size != 8 {
+        return Err(EINVAL);
+    }
+
+    if oldset != 0 {
+        write_guest(mmu, oldset, &[0; 8])?;
+    }
+
+    Ok(0)
+}
+
+fn sys_uname(mmu: &SoftMmu, buf: u64) -> SyscallResult {
+    let fields = ["Linux", "gasang", "6.1.0", "#1 SMP", "aarch64", "(none)"];
+
+    let mut utsname = [0u8; 65 * 6];
+    for (field, value) in utsname.chunks_mut(65).zip(fields) {
+        field[..value.len()].copy_from_slice(value.as_bytes());
+    }
+
+    write_guest(mmu, buf, &utsname)?;
+    Ok(0)
+}
+
+fn sys_getrandom(mmu: &SoftMmu, buf: u64, len: u64) -> SyscallResult {
+    let mut data = vec![0; (len as usize).min(IO_CHUNK)];
+    File::open("/dev/urandom")
+        .and_then(|mut urandom| urandom.read_exact(&mut data))
+        .map_err(errno)?;
+
+    write_guest(mmu, buf, &data)?;
+    Ok(data.len() as u64)
+}
+
+/// User and group ids the emulator runs as.
+pub(super) fn process_owner() -> (u32, u32) {
+    fs::metadata("/proc/self").map_or((0, 0), |metadata| (metadata.uid(), metadata.gid()))
+}
+
+pub(super) fn errno(err: io::Error) -> i64 {
+    err.raw_os_error().map_or(EIO, i64::from)
+}
+
+fn read_guest(mmu: &SoftMmu, addr: u64, len: usize) -> Result<Vec<u8>, i64> {
+    let mut buf = vec![0; len];
+    unsafe { mmu.read(addr, &mut buf, Access::Read) }.map_err(|_| EFAULT)?;
+    Ok(buf)
+}
+
+fn write_guest(mmu: &SoftMmu, addr: u64, buf: &[u8]) -> Result<(), i64> {
+    unsafe { mmu.write(addr, buf) }.map_err(|_| EFAULT)
+}
+
+/// Read a NUL terminated path from the guest.
+fn read_path(mmu: &SoftMmu, addr: u64) -> Result<PathBuf, i64> {
+    let mut path = Vec::new();
+    let mut byte = [0u8];
+    loop {
+        let addr = addr + path.len() as u64;
+        unsafe { mmu.read(addr, &mut byte, Access::Read) }.map_err(|_| EFAULT)?;
+
+        match byte[0] {
+            0 => break,
+            _ if path.len() >= PATH_MAX => return Err(ENAMETOOLONG),
+            byte => path.push(byte),
+        }
+    }
+
+    if path.is_empty() {
+        return Err(ENOENT);
+    }
+
+    Ok(PathBuf::from(OsStr::from_bytes(&path)))
+}
+
+/// Read `iovcnt` entries of a `struct iovec` array.
+fn read_iovec(mmu: &SoftMmu, iov: u64, iovcnt: u64) -> Result<Vec<(u64, u64)>, i64> {
+    if iovcnt > 1024 {
+        return Err(EINVAL);
+    }
+
+    let data = read_guest(mmu, iov, iovcnt as usize * 16)?;
+    Ok(data
+        .chunks(16)
+        .map(|iovec| {
+            let base = u64::from_le_bytes(iovec[0..8].try_into().unwrap());
+            let len = u64::from_le_bytes(iovec[8..16].try_into().unwrap());
+            (base, len)
+        })
+        .collect())
+}
+
+fn timespec(time: Duration) -> [u8; 16] {
+    let mut timespec = [0u8; 16];
+    timespec[0..8].copy_from_slice(&time.as_secs().to_le_bytes());
+    timespec[8..16].copy_from_slice(&(time.subsec_nanos() as u64).to_le_bytes());
+    timespec
+}
+
+/// Layout `metadata` as the AArch64 `struct stat`.
+fn stat(metadata: &Metadata) -> [u8; 128] {
+    let mut stat = [0u8; 128];
+    stat[0..8].copy_from_slice(&metadata.dev().to_le_bytes());
+    stat[8..16].copy_from_slice(&metadata.ino().to_le_bytes());
+    stat[16..20].copy_from_slice(&metadata.mode().to_le_bytes());
+    stat[20..24].copy_from_slice(&(metadata.nlink() as u32).to_le_bytes());
+    stat[24..28].copy_from_slice(&metadata.uid().to_le_bytes());
+    stat[28..32].copy_from_slice(&metadata.gid().to_le_bytes());
+    stat[32..40].copy_from_slice(&metadata.rdev().to_le_bytes());
+    stat[48..56].copy_from_slice(&metadata.size().to_le_bytes());
+    stat[56..60].copy_from_slice(&(metadata.blksize() as u32).to_le_bytes());
+    stat[64..72].copy_from_slice(&metadata.blocks().to_le_bytes());
+    stat[72..80].copy_from_slice(&metadata.atime().to_le_bytes());
+    stat[80..88].copy_from_slice(&metadata.atime_nsec().to_le_bytes());
+    stat[88..96].copy_from_slice(&metadata.mtime().to_le_bytes());
+    stat[96..104].copy_from_slice(&metadata.mtime_nsec().to_le_bytes());
+    stat[104..112].copy_from_slice(&metadata.ctime().to_le_bytes());
+    stat[112..120].copy_from_slice(&metadata.ctime_nsec().to_le_bytes());
+    stat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::aarch64_unknown_linux::LinuxConfig;
+
+    const BASE: u64 = 0x10000;
+
+    /// A process with 4 pages of memory at `BASE`, and `path` as a string at its start.
+    fn process(config: LinuxConfig, path: &str) -> (AArch64UnknownLinux, SoftMmu) {
+        let mmu = SoftMmu::new();
+        mmu.map(
+            BASE,
+            4 * PAGE_SIZE,
+            Memory::allocate(4 * PAGE_SIZE as usize),
+        );
+        write_guest(&mmu, BASE, format!("{path}\0").as_bytes()).unwrap();
+        (AArch64UnknownLinux::with_config(config), mmu)
+    }
+
+    fn call(
+        linux: &mut AArch64UnknownLinux,
+        mmu: &SoftMmu,
+        nr: u64,
+        args: &[u64],
+    ) -> SyscallResult {
+        let mut all = [0; 6];
+        all[..args.len()].copy_from_slice(args);
+        match linux.system_call(nr, all, mmu).unwrap() {
+            ControlFlow::Continue(result) => result,
+            ControlFlow::Break(status) => panic!("the process exited with {status}"),
+        }
+    }
+
+    #[test]
+    fn unknown_system_call() {
+        let (mut linux, mmu) = process(LinuxConfig::default(), "");
+        assert_eq!(call(&mut linux, &mmu, 0x1234, &[]), Err(ENOSYS));
+
+        let config = LinuxConfig {
+            strict_syscalls: true,
+            ..LinuxConfig::default()
+        };
+        let (mut linux, mmu) = process(config, "");
+        assert!(matches!(
+            linux.system_call(0x1234, [0; 6], &mmu),
+            Err(Error::UnsupportedSyscall(0x1234))
+        ));
+    }
+
+    #[test]
+    fn exit_status_is_truncated() {
+        let (mut linux, mmu) = process(LinuxConfig::default(), "");
+        assert!(matches!(
+            linux.system_call(SYS_EXIT_GROUP, [0x1ff, 0, 0, 0, 0, 0], &mmu),
+            Ok(ControlFlow::Break(0xff))
+        ));
+    }
+
+    #[test]
+    fn mprotect() {
+        let (mut linux, mmu) = process(LinuxConfig::default(), "");
+        let before = mmu.mappings();
+
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_MPROTECT, &[BASE + 1, 1, 1]),
+            Err(EINVAL)
+        );
+        // The last page is past the mapping
+        let len = 5 * PAGE_SIZE;
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_MPROTECT, &[BASE, len, 1]),
+            Err(ENOMEM)
+        );
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_MPROTECT, &[BASE, u64::MAX, 1]),
+            Err(ENOMEM)
+        );
+        assert_eq!(mmu.mappings(), before);
+
+        assert_eq!(call(&mut linux, &mmu, SYS_MPROTECT, &[BASE, 1, 1]), Ok(0));
+        assert_eq!(
+            mmu.mappings(),
+            [
+                (BASE..BASE + PAGE_SIZE, Protection::READ),
+                (BASE + PAGE_SIZE..BASE + 4 * PAGE_SIZE, Protection::ALL),
+            ]
+        );
+    }
+
+    #[test]
+    fn munmap_of_unaligned_address() {
+        let (mut linux, mmu) = process(LinuxConfig::default(), "");
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_MUNMAP, &[BASE + 8, PAGE_SIZE]),
+            Err(EINVAL)
+        );
+        assert_eq!(call(&mut linux, &mmu, SYS_MUNMAP, &[BASE, 0]), Err(EINVAL));
+    }
+
+    #[test]
+    fn bad_file_descriptors_and_buffers() {
+        let (mut linux, mmu) = process(LinuxConfig::default(), "");
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_WRITE, &[99, BASE, 1]),
+            Err(EBADF)
+        );
+        assert_eq!(call(&mut linux, &mmu, SYS_CLOSE, &[99]), Err(EBADF));
+        // Nothing is mapped at the buffer, so nothing is written
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_WRITE, &[2, 0x1000, 1]),
+            Err(EFAULT)
+        );
+    }
+
+    #[test]
+    fn duplicates_are_bounded_by_the_descriptor_limit() {
+        let (mut linux, mmu) = process(LinuxConfig::default(), "");
+        let limit = NOFILE_LIMIT.0;
+
+        assert_eq!(call(&mut linux, &mmu, SYS_DUP3, &[2, limit, 0]), Err(EBADF));
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_DUP3, &[2, u64::MAX, 0]),
+            Err(EBADF)
+        );
+        assert_eq!(call(&mut linux, &mmu, SYS_DUP3, &[2, 3, 1]), Err(EINVAL));
+        assert_eq!(
+            call(&mut linux, &mmu, SYS_DUP3, &[2, limit - 1, O_CLOEXEC]),
+            Ok(limit - 1)
+        );
+
+        for cmd in [F_DUPFD, F_DUPFD_CLOEXEC] {
+            assert_eq!(
+                call(&mut linux, &mmu, SYS_FCNTL, &[2, cmd, limit]),
+                Err(EINVAL)
+            );
+            assert_eq!(
+                call(&mut linux, &mmu, SYS_FCNTL, &[2, cmd, u64::MAX]),
+                Err(EINVAL)
+            );
+        }
+        assert_eq!(call(&mut linux, &mmu, SYS_FCNTL, &[2, F_DUPFD, 10]), Ok(10));
+    }
+
+    #[test]
+    fn open_of_missing_file() {
+        let (mut linux, mmu) = process(LinuxConfig::default(), "/nonexistent/file");
+        let args = [AT_FDCWD as u64, BASE, 0];
+        assert_eq!(call(&mut linux, &mmu, SYS_OPENAT, &args), Err(ENOENT));
+    }
+}
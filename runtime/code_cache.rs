@@ -0,0 +1,447 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+    rc::{Rc, Weak},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use smallvec::SmallVec;
+
+use crate::CODE_PAGE_SIZE;
+
+/// Policy used to make room when the code cache is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Drop every translated block at once.
+    Flush,
+    /// Drop the block that has been translated first.
+    Fifo,
+    /// Drop the block that has not been executed for the longest time.
+    Lru,
+}
+
+/// Statistics of a code cache.
+///
+/// Counters are atomic so the embedder can keep a handle and read them while the runtime runs.
+#[derive(Debug, Default)]
+pub struct CodeCacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    invalidations: AtomicU64,
+    chained: AtomicU64,
+    compiled_bytes: AtomicU64,
+}
+
+impl CodeCacheStats {
+    /// Number of times a translated block was reused, through the dispatcher or a link.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Number of blocks dropped because their guest code has been modified.
+    pub fn invalidations(&self) -> u64 {
+        self.invalidations.load(Ordering::Relaxed)
+    }
+
+    /// Number of times a block continued directly into its linked successor, part of the hits.
+    pub fn chained(&self) -> u64 {
+        self.chained.load(Ordering::Relaxed)
+    }
+
+    /// Total size of the guest code that has been translated.
+    pub fn compiled_bytes(&self) -> u64 {
+        self.compiled_bytes.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CodeCacheConfig {
+    /// Maximum number of translated blocks. Zero disables caching.
+    pub capacity: usize,
+    pub policy: EvictionPolicy,
+    pub stats: Arc<CodeCacheStats>,
+}
+
+impl Default for CodeCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            policy: EvictionPolicy::Lru,
+            stats: Arc::new(CodeCacheStats::default()),
+        }
+    }
+}
+
+/// A translated basic block and the direct links to its successors.
+pub struct CachedBlock<E> {
+    exec: E,
+    successors: SmallVec<[u64; 2]>,
+    links: RefCell<SmallVec<[(u64, Weak<CachedBlock<E>>); 2]>>,
+    valid: Cell<bool>,
+}
+
+impl<E> CachedBlock<E> {
+    /// `successors` are the guest addresses the block is allowed to be linked to.
+    pub fn new(exec: E, successors: &[u64]) -> Self {
+        Self {
+            exec,
+            successors: successors.into(),
+            links: RefCell::new(SmallVec::new()),
+            valid: Cell::new(true),
+        }
+    }
+
+    pub fn exec(&self) -> &E {
+        &self.exec
+    }
+
+    /// Link this block to `next`, which has been translated at `addr`.
+    ///
+    /// Returns false if `addr` is not a static successor of this block.
+    pub fn link(&self, addr: u64, next: &Rc<Self>) -> bool {
+        if !self.valid.get() || !next.valid.get() || !self.successors.contains(&addr) {
+            return false;
+        }
+
+        let mut links = self.links.borrow_mut();
+        links.retain(|(link, _)| *link != addr);
+        links.push((addr, Rc::downgrade(next)));
+        true
+    }
+
+    fn linked(&self, addr: u64) -> Option<Rc<Self>> {
+        let links = self.links.borrow();
+        let (_, next) = links.iter().find(|(link, _)| *link == addr)?;
+        next.upgrade().filter(|next| next.valid.get())
+    }
+
+    fn unlink(&self) {
+        self.valid.set(false);
+        self.links.borrow_mut().clear();
+    }
+}
+
+struct CodeCacheEntry<E> {
+    block: Rc<CachedBlock<E>>,
+    range: Range<u64>,
+    stamp: u64,
+}
+
+/// Translated basic blocks keyed by guest address.
+pub struct CodeCache<E> {
+    config: CodeCacheConfig,
+    entries: HashMap<u64, CodeCacheEntry<E>>,
+    // Eviction order, oldest stamp first.
+    order: BTreeMap<u64, u64>,
+    // Blocks that have code in each guest page.
+    pages: HashMap<u64, Vec<u64>>,
+    clock: u64,
+}
+
+impl<E> CodeCache<E> {
+    pub fn new(config: CodeCacheConfig) -> Self {
+        Self {
+            config,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            pages: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    pub fn stats(&self) -> &CodeCacheStats {
+        &self.config.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the block translated at `addr`, translating it with `compile` on a miss.
+    ///
+    /// `compile` returns the block and the size of the guest code it covers. Nothing is cached
+    /// if it fails.
+    pub fn get_or_compile<Err>(
+        &mut self,
+        addr: u64,
+        compile: impl FnOnce() -> Result<(CachedBlock<E>, u64), Err>,
+    ) -> Result<Rc<CachedBlock<E>>, Err> {
+        if let Some(block) = self.touch(addr) {
+            return Ok(block);
+        }
+
+        let stats = &self.config.stats;
+        stats.misses.fetch_add(1, Ordering::Relaxed);
+        let (block, size) = compile()?;
+        stats.compiled_bytes.fetch_add(size, Ordering::Relaxed);
+
+        let block = Rc::new(block);
+        if self.config.capacity == 0 {
+            return Ok(block);
+        }
+
+        if self.entries.len() >= self.config.capacity {
+            self.evict();
+        }
+
+        let range = addr..addr + size;
+        for page in pages_of(&range) {
+            self.pages.entry(page).or_default().push(addr);
+        }
+
+        self.clock += 1;
+        self.order.insert(self.clock, addr);
+        self.entries.insert(
+            addr,
+            CodeCacheEntry {
+                block: block.clone(),
+                range,
+                stamp: self.clock,
+            },
+        );
+
+        Ok(block)
+    }
+
+    /// Returns the successor `block` is directly linked to at `addr`, if any.
+    ///
+    /// Following a link is a hit like a lookup, so hot chained blocks are not evicted first.
+    pub fn follow_link(&mut self, block: &CachedBlock<E>, addr: u64) -> Option<Rc<CachedBlock<E>>> {
+        let next = block.linked(addr)?;
+        self.config.stats.chained.fetch_add(1, Ordering::Relaxed);
+        self.touch(addr);
+        Some(next)
+    }
+
+    /// Count a hit on the block translated at `addr` and mark it as the most recently used.
+    fn touch(&mut self, addr: u64) -> Option<Rc<CachedBlock<E>>> {
+        let entry = self.entries.get_mut(&addr)?;
+        self.config.stats.hits.fetch_add(1, Ordering::Relaxed);
+
+        if self.config.policy == EvictionPolicy::Lru {
+            self.clock += 1;
+            self.order.remove(&entry.stamp);
+            self.order.insert(self.clock, addr);
+            entry.stamp = self.clock;
+        }
+
+        Some(entry.block.clone())
+    }
+
+    /// Remove the block translated at `addr`.
+    pub fn remove(&mut self, addr: u64) -> bool {
+        let Some(entry) = self.entries.remove(&addr) else {
+            return false;
+        };
+
+        // Links to the block are dropped lazily as they see it is not valid anymore.
+        entry.block.unlink();
+        self.order.remove(&entry.stamp);
+        for page in pages_of(&entry.range) {
+            if let Some(blocks) = self.pages.get_mut(&page) {
+                blocks.retain(|&block| block != addr);
+                if blocks.is_empty() {
+                    self.pages.remove(&page);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Remove every block that has code in the guest page starting at `page`.
+    pub fn invalidate_page(&mut self, page: u64) {
+        let Some(blocks) = self.pages.get(&page).cloned() else {
+            return;
+        };
+
+        for addr in blocks {
+            if self.remove(addr) {
+                self.config
+                    .stats
+                    .invalidations
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for entry in self.entries.values() {
+            entry.block.unlink();
+        }
+
+        self.entries.clear();
+        self.order.clear();
+        self.pages.clear();
+    }
+
+    fn evict(&mut self) {
+        let stats = &self.config.stats;
+        match self.config.policy {
+            EvictionPolicy::Flush => {
+                stats
+                    .evictions
+                    .fetch_add(self.entries.len() as u64, Ordering::Relaxed);
+                self.clear();
+            }
+            EvictionPolicy::Fifo | EvictionPolicy::Lru => {
+                let Some((_, addr)) = self.order.pop_first() else {
+                    return;
+                };
+
+                stats.evictions.fetch_add(1, Ordering::Relaxed);
+                self.remove(addr);
+            }
+        }
+    }
+}
+
+/// Base addresses of the guest pages `range` touches.
+fn pages_of(range: &Range<u64>) -> impl Iterator<Item = u64> {
+    let start = range.start / CODE_PAGE_SIZE;
+    let end = (range.end + CODE_PAGE_SIZE - 1) / CODE_PAGE_SIZE;
+    (start..end).map(|page| page * CODE_PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(capacity: usize, policy: EvictionPolicy) -> CodeCache<()> {
+        CodeCache::new(CodeCacheConfig {
+            capacity,
+            policy,
+            ..CodeCacheConfig::default()
+        })
+    }
+
+    /// Look up the block at `addr`, translating 16 bytes of code linked to `successors` on a
+    /// miss. Returns the block and whether it was a hit.
+    fn get(
+        cache: &mut CodeCache<()>,
+        addr: u64,
+        successors: &[u64],
+    ) -> (Rc<CachedBlock<()>>, bool) {
+        let mut hit = true;
+        let block = cache
+            .get_or_compile(addr, || {
+                hit = false;
+                Ok::<_, ()>((CachedBlock::new((), successors), 16))
+            })
+            .unwrap();
+        (block, hit)
+    }
+
+    #[test]
+    fn lru_evicts_the_least_recently_used() {
+        let mut cache = cache(2, EvictionPolicy::Lru);
+        get(&mut cache, 0x100, &[]);
+        get(&mut cache, 0x200, &[]);
+        assert!(get(&mut cache, 0x100, &[]).1);
+
+        get(&mut cache, 0x300, &[]);
+        assert_eq!(cache.stats().evictions(), 1);
+        assert!(get(&mut cache, 0x100, &[]).1);
+        assert!(!get(&mut cache, 0x200, &[]).1);
+    }
+
+    #[test]
+    fn fifo_ignores_hits() {
+        let mut cache = cache(2, EvictionPolicy::Fifo);
+        get(&mut cache, 0x100, &[]);
+        get(&mut cache, 0x200, &[]);
+        assert!(get(&mut cache, 0x100, &[]).1);
+
+        get(&mut cache, 0x300, &[]);
+        assert!(get(&mut cache, 0x200, &[]).1);
+        assert!(!get(&mut cache, 0x100, &[]).1);
+    }
+
+    #[test]
+    fn flush_drops_everything() {
+        let mut cache = cache(2, EvictionPolicy::Flush);
+        get(&mut cache, 0x100, &[]);
+        get(&mut cache, 0x200, &[]);
+        get(&mut cache, 0x300, &[]);
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.stats().evictions(), 2);
+    }
+
+    #[test]
+    fn following_a_link_is_a_hit() {
+        let mut cache = cache(2, EvictionPolicy::Lru);
+        let (next, _) = get(&mut cache, 0x200, &[]);
+        let (block, _) = get(&mut cache, 0x100, &[0x200]);
+        assert!(block.link(0x200, &next));
+
+        let hits = cache.stats().hits();
+        assert!(Rc::ptr_eq(
+            &cache.follow_link(&block, 0x200).unwrap(),
+            &next
+        ));
+        assert_eq!(cache.stats().hits(), hits + 1);
+        assert_eq!(cache.stats().chained(), 1);
+
+        // The linked block has been used last, its predecessor goes first
+        get(&mut cache, 0x300, &[]);
+        assert!(get(&mut cache, 0x200, &[]).1);
+        assert!(!get(&mut cache, 0x100, &[]).1);
+    }
+
+    #[test]
+    fn links_are_only_made_to_successors() {
+        let mut cache = cache(4, EvictionPolicy::Lru);
+        let (next, _) = get(&mut cache, 0x200, &[]);
+        let (block, _) = get(&mut cache, 0x100, &[0x300]);
+
+        assert!(!block.link(0x200, &next));
+        assert!(cache.follow_link(&block, 0x200).is_none());
+    }
+
+    #[test]
+    fn invalidation_drops_blocks_and_their_links() {
+        let mut cache = cache(4, EvictionPolicy::Lru);
+        let (next, _) = get(&mut cache, CODE_PAGE_SIZE - 8, &[]);
+        let (block, _) = get(&mut cache, 0x100, &[CODE_PAGE_SIZE - 8]);
+        get(&mut cache, CODE_PAGE_SIZE + 0x100, &[]);
+        assert!(block.link(CODE_PAGE_SIZE - 8, &next));
+
+        // The block crossing into the second page is dropped with it
+        cache.invalidate_page(CODE_PAGE_SIZE);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.stats().invalidations(), 2);
+        assert!(cache.follow_link(&block, CODE_PAGE_SIZE - 8).is_none());
+        assert!(!block.link(CODE_PAGE_SIZE - 8, &next));
+        assert!(get(&mut cache, 0x100, &[]).1);
+    }
+
+    #[test]
+    fn nothing_is_cached_without_capacity_or_on_failure() {
+        let mut uncached = cache(0, EvictionPolicy::Lru);
+        get(&mut uncached, 0x100, &[]);
+        assert!(uncached.is_empty());
+
+        let mut cache = cache(4, EvictionPolicy::Lru);
+        assert!(cache.get_or_compile(0x100, || Err(())).is_err());
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().misses(), 1);
+    }
+}
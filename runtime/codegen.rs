@@ -6,8 +6,9 @@ pub use context::*;
 
 pub mod analysis;
 pub mod rustjit;
+pub mod softfp;
 
-use core::{ir::BasicBlock, Architecture};
+use core::{ir::BasicBlock, Architecture, Error};
 
 pub trait Codegen {
     type Context: Context;
@@ -17,5 +18,6 @@ pub trait Codegen {
 
     /// Allocate a new context for the given architecture.
     fn allocate_execution_context<A: Architecture>() -> Self::Context;
-    fn compile<A: Architecture>(&self, bb: &BasicBlock) -> Self::Executable;
+    /// Compile `bb`, failing if it uses an operation the code generator does not support.
+    fn compile<A: Architecture>(&self, bb: &BasicBlock) -> Result<Self::Executable, Error>;
 }
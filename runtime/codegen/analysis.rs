@@ -2,6 +2,8 @@ mod variable_liveness;
 pub use variable_liveness::*;
 mod ir_cost;
 pub use ir_cost::*;
+mod static_successors;
+pub use static_successors::*;
 
 pub trait Analysis {
     type Output;
@@ -0,0 +1,193 @@
+use core::{
+    ir::{BasicBlock, BasicBlockTerminator, IrConstant, IrInst, IrType, IrValue, TypeOf},
+    Architecture, RawRegisterId, Register,
+};
+use std::collections::HashMap;
+
+use smallvec::SmallVec;
+
+use super::Analysis;
+
+/// Finds the guest addresses a basic block can continue at, if they are known at compile time.
+///
+/// The program counter is the block address on entry, so pc-relative branch targets are
+/// propagated as constants.
+pub struct StaticSuccessorAnalysis<'bb> {
+    basic_block: &'bb BasicBlock,
+    pc: RawRegisterId,
+}
+
+impl<'bb> StaticSuccessorAnalysis<'bb> {
+    pub fn new<A: Architecture>(basic_block: &'bb BasicBlock) -> Self {
+        Self {
+            basic_block,
+            pc: A::get_pc_register().raw(),
+        }
+    }
+}
+
+pub struct StaticSuccessors {
+    successors: SmallVec<[u64; 2]>,
+}
+
+impl StaticSuccessors {
+    /// Addresses of the successors. Empty if any of them is only known at runtime.
+    pub fn successors(&self) -> &[u64] {
+        &self.successors
+    }
+}
+
+impl Analysis for StaticSuccessorAnalysis<'_> {
+    type Output = StaticSuccessors;
+
+    fn analyze(&self) -> Self::Output {
+        // Only the program counter and variables are tracked, other registers are unknown on entry.
+        let pc = IrValue::Register(IrType::B64, self.pc);
+        let mut known = HashMap::from([(pc, self.basic_block.addr())]);
+
+        let get = |known: &HashMap<IrValue, u64>, value: IrValue| match value {
+            IrValue::Constant(IrConstant::B8(v)) => Some(v as u64),
+            IrValue::Constant(IrConstant::B16(v)) => Some(v as u64),
+            IrValue::Constant(IrConstant::B32(v)) => Some(v as u64),
+            IrValue::Constant(IrConstant::B64(v)) => Some(v),
+            _ => known.get(&value).copied(),
+        };
+
+        for inst in self.basic_block.inst() {
+            let (dst, value) = match *inst {
+                IrInst::Add { dst, lhs, rhs }
+                | IrInst::Sub { dst, lhs, rhs }
+                | IrInst::And { dst, lhs, rhs }
+                | IrInst::Or { dst, lhs, rhs }
+                | IrInst::Xor { dst, lhs, rhs } => {
+                    let value =
+                        get(&known, lhs)
+                            .zip(get(&known, rhs))
+                            .map(|(lhs, rhs)| match inst {
+                                IrInst::Add { .. } => lhs.wrapping_add(rhs),
+                                IrInst::Sub { .. } => lhs.wrapping_sub(rhs),
+                                IrInst::And { .. } => lhs & rhs,
+                                IrInst::Or { .. } => lhs | rhs,
+                                _ => lhs ^ rhs,
+                            });
+
+                    (dst, value)
+                }
+                IrInst::Assign { dst, src } | IrInst::ZextCast { dst, src } => {
+                    (dst, get(&known, src))
+                }
+                IrInst::Mul { dst, .. }
+                | IrInst::Div { dst, .. }
+                | IrInst::Rem { dst, .. }
+                | IrInst::Sdiv { dst, .. }
+                | IrInst::Srem { dst, .. }
+                | IrInst::Umulh { dst, .. }
+                | IrInst::Smulh { dst, .. }
+                | IrInst::Not { dst, .. }
+                | IrInst::Clz { dst, .. }
+                | IrInst::Cls { dst, .. }
+                | IrInst::Bitrev { dst, .. }
+                | IrInst::Bswap { dst, .. }
+                | IrInst::Popcnt { dst, .. }
+                | IrInst::Max { dst, .. }
+                | IrInst::Min { dst, .. }
+                | IrInst::AddSat { dst, .. }
+                | IrInst::SubSat { dst, .. }
+                | IrInst::TruncSat { dst, .. }
+                | IrInst::Shl { dst, .. }
+                | IrInst::Lshr { dst, .. }
+                | IrInst::Ashr { dst, .. }
+                | IrInst::Rotr { dst, .. }
+                | IrInst::Load { dst, .. }
+                | IrInst::LoadLinked { dst, .. }
+                | IrInst::StoreConditional { stored: dst, .. }
+                | IrInst::AtomicRmw { dst, .. }
+                | IrInst::CompareExchange { dst, .. }
+                | IrInst::SextCast { dst, .. }
+                | IrInst::Splat { dst, .. }
+                | IrInst::Shuffle { dst, .. }
+                | IrInst::Swizzle { dst, .. }
+                | IrInst::ExtractLane { dst, .. }
+                | IrInst::InsertLane { dst, .. }
+                | IrInst::MoveFlag { dst, .. }
+                | IrInst::Cmp { dst, .. }
+                | IrInst::Select { dst, .. }
+                | IrInst::Fadd { dst, .. }
+                | IrInst::Fsub { dst, .. }
+                | IrInst::Fmul { dst, .. }
+                | IrInst::Fdiv { dst, .. }
+                | IrInst::Fmulx { dst, .. }
+                | IrInst::Fmax { dst, .. }
+                | IrInst::Fmin { dst, .. }
+                | IrInst::Fmaxnm { dst, .. }
+                | IrInst::Fminnm { dst, .. }
+                | IrInst::Fma { dst, .. }
+                | IrInst::Fneg { dst, .. }
+                | IrInst::Fabs { dst, .. }
+                | IrInst::Fsqrt { dst, .. }
+                | IrInst::FrecipEstimate { dst, .. }
+                | IrInst::FrsqrtEstimate { dst, .. }
+                | IrInst::UrecipEstimate { dst, .. }
+                | IrInst::UrsqrtEstimate { dst, .. }
+                | IrInst::FrecipExponent { dst, .. }
+                | IrInst::FrecipStep { dst, .. }
+                | IrInst::FrsqrtStep { dst, .. }
+                | IrInst::Fround { dst, .. }
+                | IrInst::FpCast { dst, .. }
+                | IrInst::FpToInt { dst, .. }
+                | IrInst::IntToFp { dst, .. }
+                | IrInst::Fcmp { dst, .. } => (dst, None),
+                IrInst::Interrupt(_) => {
+                    // Interrupt handlers may redirect the program counter.
+                    known.remove(&pc);
+                    continue;
+                }
+                IrInst::Store { .. }
+                | IrInst::Fence(_)
+                | IrInst::ClearLink
+                | IrInst::Intrinsic(_) => continue,
+            };
+
+            match dst {
+                IrValue::Variable(..) => {}
+                IrValue::Register(_, id) if id == self.pc && dst == pc => {}
+                IrValue::Register(_, id) if id == self.pc => {
+                    // Partial writes to the program counter are not tracked.
+                    known.remove(&pc);
+                    continue;
+                }
+                _ => continue,
+            }
+
+            // Values wider than 64 bits are not tracked.
+            let bits = dst.ty().size_of() * 8;
+            match value {
+                Some(value) if bits <= 64 => {
+                    let mask = u64::MAX >> (64 - bits);
+                    known.insert(dst, value & mask);
+                }
+                _ => {
+                    known.remove(&dst);
+                }
+            }
+        }
+
+        let targets: SmallVec<[IrValue; 2]> = match self.basic_block.terminator() {
+            BasicBlockTerminator::Branch(target) => [target].into_iter().collect(),
+            BasicBlockTerminator::BranchCond {
+                target_true,
+                target_false,
+                ..
+            } => [target_true, target_false].into_iter().collect(),
+            BasicBlockTerminator::None | BasicBlockTerminator::Next => SmallVec::new(),
+        };
+
+        let successors = targets
+            .into_iter()
+            .map(|target| get(&known, target))
+            .collect::<Option<SmallVec<_>>>()
+            .unwrap_or_default();
+
+        StaticSuccessors { successors }
+    }
+}
@@ -1,4 +1,4 @@
-use core::ir::{BasicBlock, IrInst, IrValue};
+use core::ir::{BasicBlock, IrInst, IrIntrinsic, IrValue};
 use std::collections::HashSet;
 
 use super::Analysis;
@@ -58,28 +58,130 @@ impl Analysis for VariableLivenessAnalysis<'_> {
                 | &IrInst::Mul { dst, lhs, rhs }
                 | &IrInst::Div { dst, lhs, rhs }
                 | &IrInst::Rem { dst, lhs, rhs }
+                | &IrInst::Sdiv { dst, lhs, rhs }
+                | &IrInst::Srem { dst, lhs, rhs }
+                | &IrInst::Umulh { dst, lhs, rhs }
+                | &IrInst::Smulh { dst, lhs, rhs }
                 | &IrInst::And { dst, lhs, rhs }
                 | &IrInst::Or { dst, lhs, rhs }
                 | &IrInst::Xor { dst, lhs, rhs }
                 | &IrInst::Shl { dst, lhs, rhs }
                 | &IrInst::Lshr { dst, lhs, rhs }
                 | &IrInst::Ashr { dst, lhs, rhs }
-                | &IrInst::Rotr { dst, lhs, rhs } => {
+                | &IrInst::Rotr { dst, lhs, rhs }
+                | &IrInst::Max { dst, lhs, rhs, .. }
+                | &IrInst::Min { dst, lhs, rhs, .. }
+                | &IrInst::AddSat { dst, lhs, rhs, .. }
+                | &IrInst::SubSat { dst, lhs, rhs, .. }
+                | &IrInst::Cmp { dst, lhs, rhs, .. }
+                | &IrInst::Fadd { dst, lhs, rhs }
+                | &IrInst::Fsub { dst, lhs, rhs }
+                | &IrInst::Fmul { dst, lhs, rhs }
+                | &IrInst::Fdiv { dst, lhs, rhs }
+                | &IrInst::Fmulx { dst, lhs, rhs }
+                | &IrInst::Fmax { dst, lhs, rhs }
+                | &IrInst::Fmin { dst, lhs, rhs }
+                | &IrInst::Fmaxnm { dst, lhs, rhs }
+                | &IrInst::Fminnm { dst, lhs, rhs }
+                | &IrInst::FrecipStep { dst, lhs, rhs }
+                | &IrInst::FrsqrtStep { dst, lhs, rhs }
+                | &IrInst::Shuffle { dst, lhs, rhs, .. }
+                | &IrInst::Swizzle {
+                    dst,
+                    src: lhs,
+                    indices: rhs,
+                }
+                | &IrInst::InsertLane {
+                    dst,
+                    src: lhs,
+                    value: rhs,
+                    ..
+                }
+                | &IrInst::Fcmp { dst, lhs, rhs, .. } => {
                     try_mark_as_dead(idx, dst);
                     try_mark_as_dead(idx, lhs);
                     try_mark_as_dead(idx, rhs);
                 }
                 &IrInst::Not { dst, src }
+                | &IrInst::Clz { dst, src }
+                | &IrInst::Cls { dst, src }
+                | &IrInst::Bitrev { dst, src }
+                | &IrInst::Bswap { dst, src }
+                | &IrInst::Popcnt { dst, src }
+                | &IrInst::TruncSat { dst, src, .. }
                 | &IrInst::Assign { dst, src }
                 | &IrInst::Load { dst, src, .. }
                 | &IrInst::Store { dst, src, .. }
+                | &IrInst::LoadLinked { dst, src }
                 | &IrInst::ZextCast { dst, src }
-                | &IrInst::SextCast { dst, src } => {
+                | &IrInst::SextCast { dst, src }
+                | &IrInst::Splat { dst, src }
+                | &IrInst::ExtractLane { dst, src, .. }
+                | &IrInst::Fneg { dst, src }
+                | &IrInst::Fabs { dst, src }
+                | &IrInst::Fsqrt { dst, src }
+                | &IrInst::FrecipEstimate { dst, src }
+                | &IrInst::FrsqrtEstimate { dst, src }
+                | &IrInst::UrecipEstimate { dst, src }
+                | &IrInst::UrsqrtEstimate { dst, src }
+                | &IrInst::FrecipExponent { dst, src }
+                | &IrInst::Fround { dst, src, .. }
+                | &IrInst::FpCast { dst, src, .. }
+                | &IrInst::FpToInt { dst, src, .. }
+                | &IrInst::IntToFp { dst, src, .. } => {
+                    try_mark_as_dead(idx, dst);
+                    try_mark_as_dead(idx, src);
+                }
+                &IrInst::StoreConditional { dst, src, stored } => {
                     try_mark_as_dead(idx, dst);
                     try_mark_as_dead(idx, src);
+                    try_mark_as_dead(idx, stored);
+                }
+                &IrInst::AtomicRmw { dst, addr, src, .. } => {
+                    try_mark_as_dead(idx, dst);
+                    try_mark_as_dead(idx, addr);
+                    try_mark_as_dead(idx, src);
+                }
+                &IrInst::CompareExchange {
+                    dst,
+                    addr,
+                    expected,
+                    new,
+                    ..
+                } => {
+                    try_mark_as_dead(idx, dst);
+                    try_mark_as_dead(idx, addr);
+                    try_mark_as_dead(idx, expected);
+                    try_mark_as_dead(idx, new);
                 }
-                IrInst::Fence { .. } | IrInst::MoveFlag { .. } | IrInst::Interrupt(_) => {}
-                IrInst::Intrinsic(_) => todo!(),
+                &IrInst::Fma {
+                    dst,
+                    lhs,
+                    rhs,
+                    addend,
+                } => {
+                    try_mark_as_dead(idx, dst);
+                    try_mark_as_dead(idx, lhs);
+                    try_mark_as_dead(idx, rhs);
+                    try_mark_as_dead(idx, addend);
+                }
+                &IrInst::MoveFlag { dst, .. } => try_mark_as_dead(idx, dst),
+                &IrInst::Select {
+                    dst,
+                    cond,
+                    if_true,
+                    if_false,
+                } => {
+                    try_mark_as_dead(idx, dst);
+                    try_mark_as_dead(idx, cond);
+                    try_mark_as_dead(idx, if_true);
+                    try_mark_as_dead(idx, if_false);
+                }
+                IrInst::Fence { .. } | IrInst::ClearLink | IrInst::Interrupt(_) => {}
+                IrInst::Intrinsic(intrinsic) => match *intrinsic {
+                    IrIntrinsic::InvalidateCode { addr } => try_mark_as_dead(idx, addr),
+                    IrIntrinsic::InvalidateAllCode => {}
+                },
             }
         }
 
@@ -101,13 +203,46 @@ impl Analysis for VariableLivenessAnalysis<'_> {
                 | &IrInst::Mul { dst, lhs, rhs }
                 | &IrInst::Div { dst, lhs, rhs }
                 | &IrInst::Rem { dst, lhs, rhs }
+                | &IrInst::Sdiv { dst, lhs, rhs }
+                | &IrInst::Srem { dst, lhs, rhs }
+                | &IrInst::Umulh { dst, lhs, rhs }
+                | &IrInst::Smulh { dst, lhs, rhs }
                 | &IrInst::And { dst, lhs, rhs }
                 | &IrInst::Or { dst, lhs, rhs }
                 | &IrInst::Xor { dst, lhs, rhs }
                 | &IrInst::Shl { dst, lhs, rhs }
                 | &IrInst::Lshr { dst, lhs, rhs }
                 | &IrInst::Ashr { dst, lhs, rhs }
-                | &IrInst::Rotr { dst, lhs, rhs } => {
+                | &IrInst::Rotr { dst, lhs, rhs }
+                | &IrInst::Max { dst, lhs, rhs, .. }
+                | &IrInst::Min { dst, lhs, rhs, .. }
+                | &IrInst::AddSat { dst, lhs, rhs, .. }
+                | &IrInst::SubSat { dst, lhs, rhs, .. }
+                | &IrInst::Cmp { dst, lhs, rhs, .. }
+                | &IrInst::Fadd { dst, lhs, rhs }
+                | &IrInst::Fsub { dst, lhs, rhs }
+                | &IrInst::Fmul { dst, lhs, rhs }
+                | &IrInst::Fdiv { dst, lhs, rhs }
+                | &IrInst::Fmulx { dst, lhs, rhs }
+                | &IrInst::Fmax { dst, lhs, rhs }
+                | &IrInst::Fmin { dst, lhs, rhs }
+                | &IrInst::Fmaxnm { dst, lhs, rhs }
+                | &IrInst::Fminnm { dst, lhs, rhs }
+                | &IrInst::FrecipStep { dst, lhs, rhs }
+                | &IrInst::FrsqrtStep { dst, lhs, rhs }
+                | &IrInst::Shuffle { dst, lhs, rhs, .. }
+                | &IrInst::Swizzle {
+                    dst,
+                    src: lhs,
+                    indices: rhs,
+                }
+                | &IrInst::InsertLane {
+                    dst,
+                    src: lhs,
+                    value: rhs,
+                    ..
+                }
+                | &IrInst::Fcmp { dst, lhs, rhs, .. } => {
                     try_mark_as_live(dst, &mut variable_live);
                     try_mark_as_live(lhs, &mut variable_live);
                     try_mark_as_live(rhs, &mut variable_live);
@@ -120,12 +255,57 @@ impl Analysis for VariableLivenessAnalysis<'_> {
                     maximum_variable_live = maximum_variable_live.max(variable_live.len());
                 }
                 &IrInst::Not { dst, src }
+                | &IrInst::Clz { dst, src }
+                | &IrInst::Cls { dst, src }
+                | &IrInst::Bitrev { dst, src }
+                | &IrInst::Bswap { dst, src }
+                | &IrInst::Popcnt { dst, src }
+                | &IrInst::TruncSat { dst, src, .. }
                 | &IrInst::Assign { dst, src }
                 | &IrInst::Load { dst, src, .. }
                 | &IrInst::Store { dst, src, .. }
+                | &IrInst::LoadLinked { dst, src }
                 | &IrInst::ZextCast { dst, src }
-                | &IrInst::SextCast { dst, src } => {
+                | &IrInst::SextCast { dst, src }
+                | &IrInst::Splat { dst, src }
+                | &IrInst::ExtractLane { dst, src, .. }
+                | &IrInst::Fneg { dst, src }
+                | &IrInst::Fabs { dst, src }
+                | &IrInst::Fsqrt { dst, src }
+                | &IrInst::FrecipEstimate { dst, src }
+                | &IrInst::FrsqrtEstimate { dst, src }
+                | &IrInst::UrecipEstimate { dst, src }
+                | &IrInst::UrsqrtEstimate { dst, src }
+                | &IrInst::FrecipExponent { dst, src }
+                | &IrInst::Fround { dst, src, .. }
+                | &IrInst::FpCast { dst, src, .. }
+                | &IrInst::FpToInt { dst, src, .. }
+                | &IrInst::IntToFp { dst, src, .. } => {
+                    try_mark_as_live(dst, &mut variable_live);
+                    try_mark_as_live(src, &mut variable_live);
+
+                    // Remove dead variables
+                    for value in &killed[idx] {
+                        variable_live.remove(value);
+                    }
+
+                    maximum_variable_live = maximum_variable_live.max(variable_live.len());
+                }
+                &IrInst::StoreConditional { dst, src, stored } => {
+                    try_mark_as_live(dst, &mut variable_live);
+                    try_mark_as_live(src, &mut variable_live);
+                    try_mark_as_live(stored, &mut variable_live);
+
+                    // Remove dead variables
+                    for value in &killed[idx] {
+                        variable_live.remove(value);
+                    }
+
+                    maximum_variable_live = maximum_variable_live.max(variable_live.len());
+                }
+                &IrInst::AtomicRmw { dst, addr, src, .. } => {
                     try_mark_as_live(dst, &mut variable_live);
+                    try_mark_as_live(addr, &mut variable_live);
                     try_mark_as_live(src, &mut variable_live);
 
                     // Remove dead variables
@@ -135,8 +315,85 @@ impl Analysis for VariableLivenessAnalysis<'_> {
 
                     maximum_variable_live = maximum_variable_live.max(variable_live.len());
                 }
-                IrInst::Fence { .. } | IrInst::MoveFlag { .. } | IrInst::Interrupt(_) => {}
-                IrInst::Intrinsic(_) => todo!(),
+                &IrInst::CompareExchange {
+                    dst,
+                    addr,
+                    expected,
+                    new,
+                    ..
+                } => {
+                    try_mark_as_live(dst, &mut variable_live);
+                    try_mark_as_live(addr, &mut variable_live);
+                    try_mark_as_live(expected, &mut variable_live);
+                    try_mark_as_live(new, &mut variable_live);
+
+                    // Remove dead variables
+                    for value in &killed[idx] {
+                        variable_live.remove(value);
+                    }
+
+                    maximum_variable_live = maximum_variable_live.max(variable_live.len());
+                }
+                &IrInst::Select {
+                    dst,
+                    cond,
+                    if_true,
+                    if_false,
+                } => {
+                    try_mark_as_live(dst, &mut variable_live);
+                    try_mark_as_live(cond, &mut variable_live);
+                    try_mark_as_live(if_true, &mut variable_live);
+                    try_mark_as_live(if_false, &mut variable_live);
+
+                    // Remove dead variables
+                    for value in &killed[idx] {
+                        variable_live.remove(value);
+                    }
+
+                    maximum_variable_live = maximum_variable_live.max(variable_live.len());
+                }
+                &IrInst::Fma {
+                    dst,
+                    lhs,
+                    rhs,
+                    addend,
+                } => {
+                    try_mark_as_live(dst, &mut variable_live);
+                    try_mark_as_live(lhs, &mut variable_live);
+                    try_mark_as_live(rhs, &mut variable_live);
+                    try_mark_as_live(addend, &mut variable_live);
+
+                    // Remove dead variables
+                    for value in &killed[idx] {
+                        variable_live.remove(value);
+                    }
+
+                    maximum_variable_live = maximum_variable_live.max(variable_live.len());
+                }
+                &IrInst::MoveFlag { dst, .. } => {
+                    try_mark_as_live(dst, &mut variable_live);
+
+                    // Remove dead variables
+                    for value in &killed[idx] {
+                        variable_live.remove(value);
+                    }
+
+                    maximum_variable_live = maximum_variable_live.max(variable_live.len());
+                }
+                IrInst::Fence { .. } | IrInst::ClearLink | IrInst::Interrupt(_) => {}
+                IrInst::Intrinsic(intrinsic) => match *intrinsic {
+                    IrIntrinsic::InvalidateCode { addr } => {
+                        try_mark_as_live(addr, &mut variable_live);
+
+                        // Remove dead variables
+                        for value in &killed[idx] {
+                            variable_live.remove(value);
+                        }
+
+                        maximum_variable_live = maximum_variable_live.max(variable_live.len());
+                    }
+                    IrIntrinsic::InvalidateAllCode => {}
+                },
             }
         }
 
@@ -1 +1,383 @@
+mod translator;
 
+use arch_desc::aarch64::AArch64Architecture;
+use cranelift::{
+    codegen::{self, ir::types, isa::OwnedTargetIsa},
+    prelude::{settings, AbiParam, Configurable, FunctionBuilderContext},
+};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, FuncId, Linkage, Module, ModuleError};
+
+use core::{ir::BasicBlock, Access, Architecture, ArchitectureCompat, Error, Interrupt};
+use std::{
+    cell::{Cell, RefCell},
+    mem::{self, ManuallyDrop},
+    ops::Generator,
+    rc::Rc,
+    slice,
+};
+
+use crate::SoftMmu;
+
+use self::translator::{FunctionTranslator, Translated};
+use super::{
+    rustjit::{context::RustjitContext, RustjitCodegen},
+    Codegen, Executable,
+};
+
+/// Number of basic blocks compiled into a JIT module before a new one is started.
+const BLOCKS_PER_MODULE: usize = 256;
+
+/// An IR instruction that is not lowered natively and is executed by rustjit instead.
+type Fallback = Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>>;
+
+/// Native entry point of a compiled basic block.
+///
+/// The block starts executing at segment `entry` and returns the index of the exit it left through.
+type CompiledBlock = unsafe extern "C" fn(
+    frame: *const Frame,
+    regs: *mut u8,
+    flags: *mut u8,
+    vars: *mut u8,
+    entry: u32,
+) -> u32;
+
+/// The way a compiled basic block returned to the runtime.
+enum Exit {
+    /// The basic block has been executed till the end.
+    Done,
+    /// A statically known interrupt. Execution continues at the given segment.
+    Interrupt(Interrupt, u32),
+    /// An interrupt raised by a fallback. Execution continues at the given segment.
+    Pending(u32),
+    /// A memory access faulted, the error is in the frame.
+    Fault,
+}
+
+/// State that runtime helpers called from native code need.
+struct Frame<'a> {
+    ctx: &'a RustjitContext,
+    mmu: &'a SoftMmu,
+    fallbacks: &'a [Fallback],
+    pending: &'a Cell<Option<Interrupt>>,
+    fault: &'a Cell<Option<Error>>,
+}
+
+impl Frame<'_> {
+    /// Store the error of a faulting helper, returns the value native code checks for.
+    fn fault(&self, result: Result<(), Error>) -> u8 {
+        match result {
+            Ok(()) => 0,
+            Err(error) => {
+                self.fault.set(Some(error));
+                1
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn cranelift_mmu_read(
+    frame: *const Frame,
+    addr: u64,
+    buf: *mut u8,
+    len: u64,
+) -> u8 {
+    let frame = &*frame;
+    let buf = slice::from_raw_parts_mut(buf, len as usize);
+    frame.fault(frame.mmu.read(addr, buf, Access::Read))
+}
+
+unsafe extern "C" fn cranelift_mmu_write(
+    frame: *const Frame,
+    addr: u64,
+    buf: *const u8,
+    len: u64,
+) -> u8 {
+    let frame = &*frame;
+    let buf = slice::from_raw_parts(buf, len as usize);
+    frame.fault(frame.mmu.write(addr, buf))
+}
+
+unsafe extern "C" fn cranelift_fallback(frame: *const Frame, idx: u64) -> u8 {
+    let frame = &*frame;
+    match frame.fallbacks[idx as usize](frame.ctx, frame.mmu) {
+        Ok(Some(interrupt)) => {
+            frame.pending.set(Some(interrupt));
+            1
+        }
+        Ok(None) => 0,
+        Err(error) => frame.fault(Err(error)),
+    }
+}
+
+/// A JIT module shared by the basic blocks compiled into it.
+///
+/// Cranelift only releases the memory of a module as a whole, so it is freed once no more blocks
+/// are compiled into it and every block that was has been dropped.
+struct SharedModule {
+    module: ManuallyDrop<RefCell<JITModule>>,
+    blocks: Cell<usize>,
+}
+
+impl SharedModule {
+    fn new(isa: OwnedTargetIsa) -> Self {
+        let mut builder = JITBuilder::with_isa(isa, default_libcall_names());
+        builder.symbol("cranelift_mmu_read", cranelift_mmu_read as *const u8);
+        builder.symbol("cranelift_mmu_write", cranelift_mmu_write as *const u8);
+        builder.symbol("cranelift_fallback", cranelift_fallback as *const u8);
+
+        Self {
+            module: ManuallyDrop::new(RefCell::new(JITModule::new(builder))),
+            blocks: Cell::new(0),
+        }
+    }
+}
+
+impl Drop for SharedModule {
+    fn drop(&mut self) {
+        // Every executable pointing into the module holds a reference to it, so none is left.
+        unsafe {
+            ManuallyDrop::take(&mut self.module)
+                .into_inner()
+                .free_memory()
+        }
+    }
+}
+
+pub struct CraneliftExecutable {
+    func: CompiledBlock,
+    /// Keeps the code of `func` alive.
+    _module: Rc<SharedModule>,
+    exits: Vec<Exit>,
+    fallbacks: Vec<Fallback>,
+    variable_count: usize,
+    pending: Cell<Option<Interrupt>>,
+    fault: Cell<Option<Error>>,
+}
+
+impl Executable for CraneliftExecutable {
+    type Context = RustjitContext;
+    type Generator<'a> = impl Generator<Yield = Interrupt, Return = Result<(), Error>> + 'a;
+
+    unsafe fn execute<'a>(
+        &'a self,
+        context: &'a Self::Context,
+        mmu: &'a SoftMmu,
+    ) -> Self::Generator<'a> {
+        move || {
+            // Variables live in the same slots rustjit uses, so fallbacks can see them.
+            let vars = {
+                let mut variables = context.variables.borrow_mut();
+                if variables
+                    .as_ref()
+                    .map_or(true, |v| v.len() < self.variable_count)
+                {
+                    *variables = Some(vec![Cell::new(0); self.variable_count].into_boxed_slice());
+                }
+
+                variables.as_ref().unwrap().as_ptr() as *mut u8
+            };
+
+            let regs = context.registers.as_mut_ptr();
+            let flags = context.flag.as_ptr() as *mut u8;
+            let frame = Frame {
+                ctx: context,
+                mmu,
+                fallbacks: &self.fallbacks,
+                pending: &self.pending,
+                fault: &self.fault,
+            };
+
+            let mut entry = 0;
+            loop {
+                let exit = (self.func)(&frame, regs, flags, vars, entry);
+                match &self.exits[exit as usize] {
+                    Exit::Done => return Ok(()),
+                    Exit::Interrupt(interrupt, resume) => {
+                        yield interrupt.clone();
+                        entry = *resume;
+                    }
+                    Exit::Pending(resume) => {
+                        if let Some(error) = self.fault.take() {
+                            return Err(error);
+                        }
+
+                        yield self.pending.take().unwrap();
+                        entry = *resume;
+                    }
+                    Exit::Fault => return Err(self.fault.take().unwrap()),
+                }
+            }
+        }
+    }
+}
+
+pub struct CraneliftCodegen {
+    isa: OwnedTargetIsa,
+    /// The module new basic blocks are compiled into.
+    module: RefCell<Rc<SharedModule>>,
+    ctx: RefCell<codegen::Context>,
+    builder_ctx: RefCell<FunctionBuilderContext>,
+}
+
+impl ArchitectureCompat<AArch64Architecture> for CraneliftCodegen {}
+
+impl Codegen for CraneliftCodegen {
+    type Context = RustjitContext;
+    type Executable = CraneliftExecutable;
+
+    fn new() -> Self {
+        let mut flag_builder = settings::builder();
+        flag_builder.set("use_colocated_libcalls", "false").unwrap();
+        flag_builder.set("is_pic", "false").unwrap();
+        flag_builder.set("opt_level", "speed").unwrap();
+
+        let isa = cranelift_native::builder()
+            .expect("host machine is not supported by cranelift")
+            .finish(settings::Flags::new(flag_builder))
+            .unwrap();
+
+        let module = SharedModule::new(isa.clone());
+        let ctx = module.module.borrow().make_context();
+        Self {
+            isa,
+            module: RefCell::new(Rc::new(module)),
+            ctx: RefCell::new(ctx),
+            builder_ctx: RefCell::new(FunctionBuilderContext::new()),
+        }
+    }
+
+    fn allocate_execution_context<A: Architecture>() -> Self::Context {
+        // Native code works directly on the rustjit context layout.
+        RustjitCodegen::allocate_execution_context::<A>()
+    }
+
+    fn compile<A: Architecture>(&self, bb: &BasicBlock) -> Result<Self::Executable, Error> {
+        let shared = {
+            let mut current = self.module.borrow_mut();
+            if current.blocks.get() == BLOCKS_PER_MODULE {
+                *current = Rc::new(SharedModule::new(self.isa.clone()));
+            }
+
+            Rc::clone(&current)
+        };
+
+        let mut module = shared.module.borrow_mut();
+        let mut ctx = self.ctx.borrow_mut();
+        let mut builder_ctx = self.builder_ctx.borrow_mut();
+
+        let defined = Self::define::<A>(&mut module, &mut ctx, &mut builder_ctx, bb);
+        module.clear_context(&mut ctx);
+        let (id, translated) = defined?;
+        module.finalize_definitions().map_err(codegen_failure)?;
+
+        let func = module.get_finalized_function(id);
+        shared.blocks.set(shared.blocks.get() + 1);
+        Ok(CraneliftExecutable {
+            func: unsafe { mem::transmute::<*const u8, CompiledBlock>(func) },
+            _module: Rc::clone(&shared),
+            exits: translated.exits,
+            fallbacks: translated.fallbacks,
+            variable_count: bb.variable_count(),
+            pending: Cell::new(None),
+            fault: Cell::new(None),
+        })
+    }
+}
+
+impl CraneliftCodegen {
+    /// Translate `bb` into `ctx` and define it in `module`. The context is left for the caller to
+    /// clear, whether this succeeds or not.
+    fn define<A: Architecture>(
+        module: &mut JITModule,
+        ctx: &mut codegen::Context,
+        builder_ctx: &mut FunctionBuilderContext,
+        bb: &BasicBlock,
+    ) -> Result<(FuncId, Translated), Error> {
+        let ptr = module.target_config().pointer_type();
+        let mut sig = module.make_signature();
+        sig.params.extend([ptr, ptr, ptr, ptr].map(AbiParam::new));
+        sig.params.push(AbiParam::new(types::I32));
+        sig.returns.push(AbiParam::new(types::I32));
+        ctx.func.signature = sig.clone();
+
+        let helpers = [
+            (
+                "cranelift_mmu_read",
+                &[ptr, types::I64, ptr, types::I64][..],
+                Some(types::I8),
+            ),
+            (
+                "cranelift_mmu_write",
+                &[ptr, types::I64, ptr, types::I64][..],
+                Some(types::I8),
+            ),
+            (
+                "cranelift_fallback",
+                &[ptr, types::I64][..],
+                Some(types::I8),
+            ),
+        ]
+        .map(|(name, params, ret)| -> Result<_, Error> {
+            let mut sig = module.make_signature();
+            sig.params.extend(params.iter().copied().map(AbiParam::new));
+            sig.returns.extend(ret.map(AbiParam::new));
+
+            let id = module
+                .declare_function(name, Linkage::Import, &sig)
+                .map_err(codegen_failure)?;
+            Ok(module.declare_func_in_func(id, &mut ctx.func))
+        });
+        let [mmu_read, mmu_write, fallback] = helpers;
+        let helpers = [mmu_read?, mmu_write?, fallback?];
+
+        let translated =
+            FunctionTranslator::new::<A>(&mut ctx.func, builder_ctx, ptr, helpers).translate(bb)?;
+
+        let id = module
+            .declare_anonymous_function(&sig)
+            .map_err(codegen_failure)?;
+        module.define_function(id, ctx).map_err(codegen_failure)?;
+        Ok((id, translated))
+    }
+}
+
+/// Report an error of the JIT module as a guest error instead of panicking the host.
+fn codegen_failure(error: ModuleError) -> Error {
+    Error::CodegenFailure(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ir::{BasicBlockTerminator, IrConstant, IrValue};
+    use std::rc::Weak;
+
+    fn compile_block(codegen: &CraneliftCodegen) -> CraneliftExecutable {
+        let mut bb = BasicBlock::new(0);
+        bb.set_terminator(BasicBlockTerminator::Branch(IrValue::Constant(
+            IrConstant::B64(4),
+        )));
+        codegen.compile::<AArch64Architecture>(&bb).unwrap()
+    }
+
+    #[test]
+    fn module_is_freed_with_its_blocks() {
+        let codegen = CraneliftCodegen::new();
+        let first: Vec<_> = (0..BLOCKS_PER_MODULE)
+            .map(|_| compile_block(&codegen))
+            .collect();
+        let module: Weak<SharedModule> = Rc::downgrade(&first[0]._module);
+        assert!(first
+            .iter()
+            .all(|exec| Rc::ptr_eq(&exec._module, &first[0]._module)));
+
+        // The full module stays current until the next block needs a new one.
+        drop(first);
+        assert!(module.upgrade().is_some());
+
+        let next = compile_block(&codegen);
+        assert!(module.upgrade().is_none());
+        assert_eq!(next._module.blocks.get(), 1);
+    }
+}
@@ -0,0 +1,943 @@
+use core::{
+    ir::{
+        BasicBlock, BasicBlockTerminator, Comparison, Flag, FpComparison, FpControl, FpExceptions,
+        FpRounding, IrConstant, IrInst, IrType, IrValue, TypeOf,
+    },
+    Architecture, Error, Interrupt, Register, RegisterFileDesc,
+};
+
+use cranelift::{
+    codegen::ir::{FuncRef, Function, StackSlot},
+    prelude::*,
+};
+
+use crate::codegen::{rustjit::gen_inst, softfp::FpEnv};
+
+use super::{Exit, Fallback};
+
+/// Output of the translation besides the native code itself.
+pub(super) struct Translated {
+    pub(super) exits: Vec<Exit>,
+    pub(super) fallbacks: Vec<Fallback>,
+}
+
+/// Translates a basic block into a cranelift function.
+///
+/// Registers, flags and variables are kept in memory owned by the execution context,
+/// so native code and rustjit fallbacks always observe the same state.
+pub(super) struct FunctionTranslator<'a> {
+    builder: FunctionBuilder<'a>,
+    desc: RegisterFileDesc,
+    pc: IrValue,
+    fp_control: IrValue,
+    fp_status: IrValue,
+    fp_env: FpEnv,
+    ptr: Type,
+
+    frame: Value,
+    regs: Value,
+    flags: Value,
+    vars: Value,
+    entry: Value,
+
+    mmu_read: FuncRef,
+    mmu_write: FuncRef,
+    fallback: FuncRef,
+    scratch: StackSlot,
+
+    dispatch: Block,
+    segments: Vec<Block>,
+    exits: Vec<Exit>,
+    fallbacks: Vec<Fallback>,
+    // first instruction that could be neither lowered nor executed by a fallback
+    error: Option<Error>,
+}
+
+impl<'a> FunctionTranslator<'a> {
+    pub(super) fn new<A: Architecture>(
+        func: &'a mut Function,
+        builder_ctx: &'a mut FunctionBuilderContext,
+        ptr: Type,
+        [mmu_read, mmu_write, fallback]: [FuncRef; 3],
+    ) -> Self {
+        let mut builder = FunctionBuilder::new(func, builder_ctx);
+
+        let entry_block = builder.create_block();
+        let dispatch = builder.create_block();
+        builder.append_block_params_for_function_params(entry_block);
+        builder.switch_to_block(entry_block);
+        builder.ins().jump(dispatch, &[]);
+
+        let params = builder.block_params(entry_block).to_vec();
+        let scratch =
+            builder.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, 16));
+
+        let first = builder.create_block();
+        builder.switch_to_block(first);
+
+        Self {
+            builder,
+            desc: A::get_register_file_desc(),
+            pc: IrValue::Register(IrType::B64, A::get_pc_register().raw()),
+            fp_control: IrValue::Register(IrType::B64, A::get_fp_control_register().raw()),
+            fp_status: IrValue::Register(IrType::B64, A::get_fp_status_register().raw()),
+            fp_env: FpEnv::new::<A>(),
+            ptr,
+            frame: params[0],
+            regs: params[1],
+            flags: params[2],
+            vars: params[3],
+            entry: params[4],
+            mmu_read,
+            mmu_write,
+            fallback,
+            scratch,
+            dispatch,
+            segments: vec![first],
+            exits: vec![Exit::Done],
+            fallbacks: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Translate `bb`, failing if it contains an instruction no code generator supports.
+    pub(super) fn translate(mut self, bb: &BasicBlock) -> Result<Translated, Error> {
+        for inst in bb.inst() {
+            if !self.lower(inst) {
+                self.lower_fallback(inst);
+            }
+        }
+
+        self.lower_terminator(bb.terminator());
+        let done = self.builder.ins().iconst(types::I32, 0);
+        self.builder.ins().return_(&[done]);
+
+        // Jump to the segment the block is entered or resumed at.
+        self.builder.switch_to_block(self.dispatch);
+        let segments: Vec<_> = self
+            .segments
+            .iter()
+            .map(|&segment| self.builder.func.dfg.block_call(segment, &[]))
+            .collect();
+        let jt = self
+            .builder
+            .create_jump_table(JumpTableData::new(segments[0], &segments));
+        self.builder.ins().br_table(self.entry, jt);
+
+        self.builder.seal_all_blocks();
+        self.builder.finalize();
+
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        Ok(Translated {
+            exits: self.exits,
+            fallbacks: self.fallbacks,
+        })
+    }
+
+    /// Lower an instruction to native code.
+    ///
+    /// Returns false without emitting anything if the instruction has to be executed by a fallback.
+    fn lower(&mut self, inst: &IrInst) -> bool {
+        match inst {
+            &IrInst::Add { dst, lhs, rhs } => {
+                let Some(ty) = int_type(dst.ty()) else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, ty);
+                let v = self.builder.ins().iadd(lhs, rhs);
+                self.set(dst, v);
+
+                let cf = self.builder.ins().icmp(IntCC::UnsignedGreaterThan, lhs, v);
+                let lhs_sign = self.builder.ins().bxor(lhs, v);
+                let rhs_sign = self.builder.ins().bxor(rhs, v);
+                let sign = self.builder.ins().band(lhs_sign, rhs_sign);
+                self.set_overflow_flags(v, cf, sign);
+            }
+            &IrInst::Sub { dst, lhs, rhs } => {
+                let Some(ty) = int_type(dst.ty()) else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, ty);
+                let v = self.builder.ins().isub(lhs, rhs);
+                self.set(dst, v);
+
+                let cf = self.builder.ins().icmp(IntCC::UnsignedLessThan, lhs, v);
+                let operand_sign = self.builder.ins().bxor(lhs, rhs);
+                let lhs_sign = self.builder.ins().bxor(lhs, v);
+                let sign = self.builder.ins().band(operand_sign, lhs_sign);
+                self.set_overflow_flags(v, cf, sign);
+            }
+            &IrInst::Mul { dst, lhs, rhs } => {
+                // The overflow flags need the high half, which x86-64 only computes for 16 to
+                // 64-bit products.
+                let Some(ty) =
+                    int_type(dst.ty()).filter(|&ty| ty != types::I8 && ty != types::I128)
+                else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, ty);
+                let v = self.builder.ins().imul(lhs, rhs);
+                self.set(dst, v);
+
+                // The unsigned product overflowed if its high half is not zero, the signed one
+                // if its high half is not the sign extension of the result.
+                let high = self.builder.ins().umulhi(lhs, rhs);
+                let cf = self.builder.ins().icmp_imm(IntCC::NotEqual, high, 0);
+                let high = self.builder.ins().smulhi(lhs, rhs);
+                let sign = self.builder.ins().sshr_imm(v, (ty.bits() - 1) as i64);
+                let of = self.builder.ins().icmp(IntCC::NotEqual, high, sign);
+
+                self.set_flag(Flag::CF, cf);
+                self.set_flag(Flag::OF, of);
+                self.set_result_flags(v);
+            }
+            &IrInst::Div { dst, lhs, rhs }
+            | &IrInst::Rem { dst, lhs, rhs }
+            | &IrInst::Sdiv { dst, lhs, rhs }
+            | &IrInst::Srem { dst, lhs, rhs } => {
+                // cranelift can't divide 128-bit integers on every target.
+                let Some(ty) = int_type(dst.ty()).filter(|&ty| ty != types::I128) else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, ty);
+
+                let is_zero = self.builder.ins().icmp_imm(IntCC::Equal, rhs, 0);
+                let divide = self.builder.create_block();
+                let fault = self.builder.create_block();
+                self.builder.ins().brif(is_zero, fault, &[], divide, &[]);
+
+                self.builder.switch_to_block(fault);
+                let resume = self.new_segment();
+                self.exit(Exit::Interrupt(Interrupt::Exception(0), resume));
+
+                self.builder.switch_to_block(divide);
+                let v = match inst {
+                    IrInst::Div { .. } => self.builder.ins().udiv(lhs, rhs),
+                    IrInst::Rem { .. } => self.builder.ins().urem(lhs, rhs),
+                    _ => {
+                        // Signed division traps on overflow, dividing by -1 is negation instead.
+                        let is_minus_one = self.builder.ins().icmp_imm(IntCC::Equal, rhs, -1);
+                        let one = self.builder.ins().iconst(ty, 1);
+                        let divisor = self.builder.ins().select(is_minus_one, one, rhs);
+                        if matches!(inst, IrInst::Sdiv { .. }) {
+                            let v = self.builder.ins().sdiv(lhs, divisor);
+                            let negated = self.builder.ins().ineg(lhs);
+                            self.builder.ins().select(is_minus_one, negated, v)
+                        } else {
+                            let v = self.builder.ins().srem(lhs, divisor);
+                            let zero = self.builder.ins().iconst(ty, 0);
+                            self.builder.ins().select(is_minus_one, zero, v)
+                        }
+                    }
+                };
+                self.set(dst, v);
+                self.set_result_flags(v);
+
+                let resume = self.segments[resume as usize];
+                self.builder.ins().jump(resume, &[]);
+                self.builder.switch_to_block(resume);
+            }
+            &IrInst::Umulh { dst, lhs, rhs } | &IrInst::Smulh { dst, lhs, rhs } => {
+                // x86-64 only computes the high half of 16 to 64-bit products.
+                let Some(ty) =
+                    int_type(dst.ty()).filter(|&ty| ty != types::I8 && ty != types::I128)
+                else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, ty);
+                let v = if matches!(inst, IrInst::Umulh { .. }) {
+                    self.builder.ins().umulhi(lhs, rhs)
+                } else {
+                    self.builder.ins().smulhi(lhs, rhs)
+                };
+                self.set(dst, v);
+                self.set_result_flags(v);
+            }
+            &IrInst::And { dst, lhs, rhs }
+            | &IrInst::Or { dst, lhs, rhs }
+            | &IrInst::Xor { dst, lhs, rhs } => {
+                let Some(ty) = int_type(dst.ty()) else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, ty);
+                let v = match inst {
+                    IrInst::And { .. } => self.builder.ins().band(lhs, rhs),
+                    IrInst::Or { .. } => self.builder.ins().bor(lhs, rhs),
+                    _ => self.builder.ins().bxor(lhs, rhs),
+                };
+                self.set(dst, v);
+                self.set_result_flags(v);
+            }
+            &IrInst::Not { dst, src } => {
+                let Some(ty) = int_type(dst.ty()) else {
+                    return false;
+                };
+
+                let src = self.value(src, ty);
+                let v = self.builder.ins().bnot(src);
+                self.set(dst, v);
+                self.set_result_flags(v);
+            }
+            &IrInst::Clz { dst, src }
+            | &IrInst::Bitrev { dst, src }
+            | &IrInst::Bswap { dst, src } => {
+                // A single byte has nothing to swap, which cranelift rejects.
+                let Some(ty) = int_type(dst.ty())
+                    .filter(|&ty| ty != types::I8 || !matches!(inst, IrInst::Bswap { .. }))
+                else {
+                    return false;
+                };
+
+                let src = self.value(src, ty);
+                let v = match inst {
+                    IrInst::Clz { .. } => self.builder.ins().clz(src),
+                    IrInst::Bitrev { .. } => self.builder.ins().bitrev(src),
+                    _ => self.builder.ins().bswap(src),
+                };
+                self.set(dst, v);
+            }
+            // cls is not implemented by the x86-64 backend.
+            IrInst::Cls { .. } => return false,
+            // Only used by the lowering of SIMD instructions, whose vectors are left to rustjit
+            // anyway.
+            IrInst::Popcnt { .. }
+            | IrInst::Max { .. }
+            | IrInst::Min { .. }
+            | IrInst::AddSat { .. }
+            | IrInst::SubSat { .. }
+            | IrInst::TruncSat { .. }
+            | IrInst::Splat { .. }
+            | IrInst::Shuffle { .. }
+            | IrInst::Swizzle { .. }
+            | IrInst::ExtractLane { .. }
+            | IrInst::InsertLane { .. } => return false,
+            &IrInst::Shl { dst, lhs, rhs }
+            | &IrInst::Lshr { dst, lhs, rhs }
+            | &IrInst::Ashr { dst, lhs, rhs } => {
+                let (Some(ty), Some(amount_ty)) = (int_type(dst.ty()), int_type(rhs.ty())) else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, amount_ty);
+                let v = match inst {
+                    IrInst::Shl { .. } => self.builder.ins().ishl(lhs, rhs),
+                    IrInst::Lshr { .. } => self.builder.ins().ushr(lhs, rhs),
+                    _ => self.builder.ins().sshr(lhs, rhs),
+                };
+                self.set(dst, v);
+                self.set_result_flags(v);
+            }
+            &IrInst::Rotr { dst, lhs, rhs } => {
+                let (Some(ty), Some(amount_ty)) = (int_type(dst.ty()), int_type(rhs.ty())) else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, amount_ty);
+                let v = self.builder.ins().rotr(lhs, rhs);
+                self.set(dst, v);
+            }
+            &IrInst::Assign { dst, src } => {
+                let Some(ty) = bits_type(dst.ty()) else {
+                    return false;
+                };
+
+                let v = self.value(src, ty);
+                self.set(dst, v);
+            }
+            &IrInst::Load { dst, src } => {
+                let Some(ty) = bits_type(dst.ty()) else {
+                    return false;
+                };
+
+                let addr = self.value(src, types::I64);
+                let buf = self.builder.ins().stack_addr(self.ptr, self.scratch, 0);
+                let len = self.builder.ins().iconst(types::I64, ty.bytes() as i64);
+                let call = self
+                    .builder
+                    .ins()
+                    .call(self.mmu_read, &[self.frame, addr, buf, len]);
+                let failed = self.builder.inst_results(call)[0];
+                self.check_fault(failed);
+
+                let v = self.builder.ins().stack_load(ty, self.scratch, 0);
+                self.set(dst, v);
+            }
+            &IrInst::Store { dst, src } => {
+                let Some(ty) = bits_type(src.ty()) else {
+                    return false;
+                };
+
+                let addr = self.value(dst, types::I64);
+                let v = self.value(src, ty);
+                self.builder.ins().stack_store(v, self.scratch, 0);
+
+                let buf = self.builder.ins().stack_addr(self.ptr, self.scratch, 0);
+                let len = self.builder.ins().iconst(types::I64, ty.bytes() as i64);
+                let call = self
+                    .builder
+                    .ins()
+                    .call(self.mmu_write, &[self.frame, addr, buf, len]);
+                let failed = self.builder.inst_results(call)[0];
+                self.check_fault(failed);
+            }
+            &IrInst::ZextCast { dst, src } | &IrInst::SextCast { dst, src } => {
+                // Floating-point values are only ever zero extended, as raw bits.
+                let (Some(dst_ty), Some(src_ty)) = (bits_type(dst.ty()), bits_type(src.ty()))
+                else {
+                    return false;
+                };
+
+                let src = self.value(src, src_ty);
+                let v = if dst_ty.bits() > src_ty.bits() {
+                    if matches!(inst, IrInst::ZextCast { .. }) {
+                        self.builder.ins().uextend(dst_ty, src)
+                    } else {
+                        self.builder.ins().sextend(dst_ty, src)
+                    }
+                } else if dst_ty.bits() < src_ty.bits() {
+                    self.builder.ins().ireduce(dst_ty, src)
+                } else {
+                    src
+                };
+                self.set(dst, v);
+            }
+            &IrInst::MoveFlag { dst, dst_pos, flag } => {
+                let Some(ty) = int_type(dst.ty()) else {
+                    return false;
+                };
+
+                let v = self.flag(flag);
+                let v = if ty != types::I8 {
+                    self.builder.ins().uextend(ty, v)
+                } else {
+                    v
+                };
+                let v = self.builder.ins().ishl_imm(v, dst_pos as i64);
+                self.set(dst, v);
+            }
+            &IrInst::Cmp {
+                dst,
+                lhs,
+                rhs,
+                cond,
+            } => {
+                let Some(ty) = int_type(lhs.ty()) else {
+                    return false;
+                };
+
+                let lhs = self.value(lhs, ty);
+                let rhs = self.value(rhs, ty);
+                let cc = match cond {
+                    Comparison::Eq => IntCC::Equal,
+                    Comparison::Ne => IntCC::NotEqual,
+                    Comparison::Ult => IntCC::UnsignedLessThan,
+                    Comparison::Ule => IntCC::UnsignedLessThanOrEqual,
+                    Comparison::Ugt => IntCC::UnsignedGreaterThan,
+                    Comparison::Uge => IntCC::UnsignedGreaterThanOrEqual,
+                    Comparison::Slt => IntCC::SignedLessThan,
+                    Comparison::Sle => IntCC::SignedLessThanOrEqual,
+                    Comparison::Sgt => IntCC::SignedGreaterThan,
+                    Comparison::Sge => IntCC::SignedGreaterThanOrEqual,
+                };
+                let v = self.builder.ins().icmp(cc, lhs, rhs);
+                self.set(dst, v);
+            }
+            &IrInst::Select {
+                dst,
+                cond,
+                if_true,
+                if_false,
+            } => {
+                let Some(ty) = bits_type(dst.ty()) else {
+                    return false;
+                };
+
+                let cond = self.value(cond, types::I8);
+                let if_true = self.value(if_true, ty);
+                let if_false = self.value(if_false, ty);
+                let v = self.builder.ins().select(cond, if_true, if_false);
+                self.set(dst, v);
+            }
+            IrInst::Fence(_) => {
+                self.builder.ins().fence();
+            }
+            IrInst::Interrupt(interrupt) => {
+                let resume = self.new_segment();
+                self.exit(Exit::Interrupt(interrupt.clone(), resume));
+                self.builder.switch_to_block(self.segments[resume as usize]);
+            }
+            // The exclusive monitor lives in the soft MMU, which has no native helper for it.
+            IrInst::LoadLinked { .. } | IrInst::StoreConditional { .. } | IrInst::ClearLink => {
+                return false
+            }
+            // Atomic accesses have to hold the same lock in the soft MMU as writes do
+            IrInst::AtomicRmw { .. } | IrInst::CompareExchange { .. } => return false,
+            &IrInst::Fneg { dst, src } | &IrInst::Fabs { dst, src } => {
+                // Only the sign bit changes, whatever the floating-point control is.
+                let (IrType::F16 | IrType::F32 | IrType::F64) = dst.ty() else {
+                    return false;
+                };
+
+                let ty = bits_type(dst.ty()).unwrap();
+                let sign = (1u64 << (ty.bits() - 1)) as i64;
+                let src = self.value(src, ty);
+                let v = if matches!(inst, IrInst::Fneg { .. }) {
+                    self.builder.ins().bxor_imm(src, sign)
+                } else {
+                    self.builder.ins().band_imm(src, !sign)
+                };
+                self.set(dst, v);
+            }
+            &IrInst::Fadd { dst, lhs, rhs } | &IrInst::Fsub { dst, lhs, rhs } => {
+                let Some(ty) = float_type(dst.ty()) else {
+                    return false;
+                };
+
+                self.lower_fp(inst, dst, |this| {
+                    let lhs = this.float_value(lhs, ty);
+                    let rhs = this.float_value(rhs, ty);
+                    let rhs = if matches!(inst, IrInst::Fsub { .. }) {
+                        this.builder.ins().fneg(rhs)
+                    } else {
+                        rhs
+                    };
+                    let v = this.builder.ins().fadd(lhs, rhs);
+
+                    // The rounding error of a finite sum is exactly the sum of the parts of
+                    // each operand that did not make it into it.
+                    let rhs_part = this.builder.ins().fsub(v, lhs);
+                    let lhs_part = this.builder.ins().fsub(v, rhs_part);
+                    let lhs_error = this.builder.ins().fsub(lhs, lhs_part);
+                    let rhs_error = this.builder.ins().fsub(rhs, rhs_part);
+                    let error = this.builder.ins().fadd(lhs_error, rhs_error);
+
+                    let exact = this.in_range(v, 0.0);
+                    (v, exact, Some(error))
+                });
+            }
+            &IrInst::Fmul { dst, lhs, rhs } | &IrInst::Fdiv { dst, lhs, rhs } => {
+                let Some(ty) = float_type(dst.ty()) else {
+                    return false;
+                };
+
+                self.lower_fp(inst, dst, |this| {
+                    let lhs = this.float_value(lhs, ty);
+                    let rhs = this.float_value(rhs, ty);
+                    let min = min_exact(ty);
+                    if matches!(inst, IrInst::Fmul { .. }) {
+                        let v = this.builder.ins().fmul(lhs, rhs);
+                        let neg = this.builder.ins().fneg(v);
+                        let error = this.builder.ins().fma(lhs, rhs, neg);
+
+                        let exact = this.in_range(v, min);
+                        (v, exact, Some(error))
+                    } else {
+                        let v = this.builder.ins().fdiv(lhs, rhs);
+                        let neg = this.builder.ins().fneg(v);
+                        let remainder = this.builder.ins().fma(neg, rhs, lhs);
+
+                        let v_exact = this.in_range(v, min);
+                        let lhs_exact = this.in_range(lhs, min);
+                        let exact = this.builder.ins().band(v_exact, lhs_exact);
+                        (v, exact, Some(remainder))
+                    }
+                });
+            }
+            &IrInst::Fsqrt { dst, src } => {
+                let Some(ty) = float_type(dst.ty()) else {
+                    return false;
+                };
+
+                self.lower_fp(inst, dst, |this| {
+                    let src = this.float_value(src, ty);
+                    let v = this.builder.ins().sqrt(src);
+                    let neg = this.builder.ins().fneg(v);
+                    let remainder = this.builder.ins().fma(neg, v, src);
+
+                    // Negative operands give a NaN.
+                    let src_exact = this.in_range(src, min_exact(ty));
+                    let v_exact = this.in_range(v, 0.0);
+                    let exact = this.builder.ins().band(src_exact, v_exact);
+                    (v, exact, Some(remainder))
+                });
+            }
+            &IrInst::FpCast {
+                dst,
+                src,
+                rounding: FpRounding::Current,
+            } => {
+                let (Some(dst_ty), Some(src_ty)) = (float_type(dst.ty()), float_type(src.ty()))
+                else {
+                    return false;
+                };
+
+                self.lower_fp(inst, dst, |this| {
+                    let src = this.float_value(src, src_ty);
+                    if dst_ty.bits() > src_ty.bits() {
+                        let v = this.builder.ins().fpromote(dst_ty, src);
+                        let exact = this.builder.ins().fcmp(FloatCC::Ordered, src, src);
+                        (v, exact, None)
+                    } else if dst_ty.bits() < src_ty.bits() {
+                        // Denormal results might underflow.
+                        let v = this.builder.ins().fdemote(dst_ty, src);
+                        let promoted = this.builder.ins().fpromote(src_ty, v);
+                        let error = this.builder.ins().fsub(promoted, src);
+
+                        let exact = this.in_range(v, f32::MIN_POSITIVE as f64);
+                        (v, exact, Some(error))
+                    } else {
+                        let exact = this.builder.ins().fcmp(FloatCC::Ordered, src, src);
+                        (src, exact, None)
+                    }
+                });
+            }
+            &IrInst::Fcmp {
+                dst,
+                lhs,
+                rhs,
+                cond,
+                signaling: _,
+            } => {
+                let (IrType::Bool, Some(ty)) = (dst.ty(), float_type(lhs.ty())) else {
+                    return false;
+                };
+
+                // NaN operands are left to the soft-float, they might raise invalid operation.
+                self.lower_fp(inst, dst, |this| {
+                    let lhs = this.float_value(lhs, ty);
+                    let rhs = this.float_value(rhs, ty);
+                    let cc = match cond {
+                        FpComparison::Eq => FloatCC::Equal,
+                        FpComparison::Lt => FloatCC::LessThan,
+                        FpComparison::Le => FloatCC::LessThanOrEqual,
+                        FpComparison::Unordered => FloatCC::Unordered,
+                    };
+                    let v = this.builder.ins().fcmp(cc, lhs, rhs);
+                    let exact = this.builder.ins().fcmp(FloatCC::Ordered, lhs, rhs);
+                    (v, exact, None)
+                });
+            }
+            // Everything else shares the soft-float of rustjit, so rounding, NaNs and exceptions
+            // are the same on every backend.
+            IrInst::Fmulx { .. }
+            | IrInst::Fmax { .. }
+            | IrInst::Fmin { .. }
+            | IrInst::Fmaxnm { .. }
+            | IrInst::Fminnm { .. }
+            | IrInst::Fma { .. }
+            | IrInst::FrecipEstimate { .. }
+            | IrInst::FrsqrtEstimate { .. }
+            | IrInst::UrecipEstimate { .. }
+            | IrInst::UrsqrtEstimate { .. }
+            | IrInst::FrecipExponent { .. }
+            | IrInst::FrecipStep { .. }
+            | IrInst::FrsqrtStep { .. }
+            | IrInst::Fround { .. }
+            | IrInst::FpCast { .. }
+            | IrInst::FpToInt { .. }
+            | IrInst::IntToFp { .. } => return false,
+            // Intrinsics talk to the runtime, which only rustjit closures can reach.
+            IrInst::Intrinsic(_) => return false,
+        }
+
+        true
+    }
+
+    /// Execute the instruction with rustjit, leaving native code if it raised an interrupt.
+    fn lower_fallback(&mut self, inst: &IrInst) {
+        let fallback = match gen_inst(inst, self.fp_env, |value| value) {
+            Ok(fallback) => fallback,
+            Err(error) => {
+                self.error.get_or_insert(error);
+                return;
+            }
+        };
+
+        let idx = self.fallbacks.len();
+        self.fallbacks.push(fallback);
+
+        let idx = self.builder.ins().iconst(types::I64, idx as i64);
+        let call = self.builder.ins().call(self.fallback, &[self.frame, idx]);
+        let raised = self.builder.inst_results(call)[0];
+
+        let resume = self.new_segment();
+        let fault = self.builder.create_block();
+        let resume_block = self.segments[resume as usize];
+        self.builder
+            .ins()
+            .brif(raised, fault, &[], resume_block, &[]);
+
+        self.builder.switch_to_block(fault);
+        self.exit(Exit::Pending(resume));
+        self.builder.switch_to_block(resume_block);
+    }
+
+    /// Lower a floating-point instruction natively while the floating-point control rounds to
+    /// nearest and keeps denormals, like the host does, and with the soft-float otherwise.
+    ///
+    /// `native` returns the result, whether it is the one the soft-float computes, and the
+    /// rounding error, which raises the inexact exception if it isn't zero. Results it can't
+    /// vouch for, like NaNs or ones that might raise other exceptions, go to the soft-float too.
+    fn lower_fp(
+        &mut self,
+        inst: &IrInst,
+        dst: IrValue,
+        native: impl FnOnce(&mut Self) -> (Value, Value, Option<Value>),
+    ) {
+        let native_block = self.builder.create_block();
+        let commit = self.builder.create_block();
+        let soft = self.builder.create_block();
+        let done = self.builder.create_block();
+
+        // The rounding mode in bits 1:0 and flush to zero
+        let control = self.value(self.fp_control, types::I64);
+        let control = self
+            .builder
+            .ins()
+            .band_imm(control, (0b11 | FpControl::FLUSH_TO_ZERO) as i64);
+        self.builder
+            .ins()
+            .brif(control, soft, &[], native_block, &[]);
+
+        self.builder.switch_to_block(native_block);
+        let (v, exact, error) = native(self);
+        self.builder.ins().brif(exact, commit, &[], soft, &[]);
+
+        self.builder.switch_to_block(commit);
+        self.set(dst, v);
+        if let Some(error) = error {
+            let zero = self.float_const(self.builder.func.dfg.value_type(error), 0.0);
+            let inexact = self.builder.ins().fcmp(FloatCC::NotEqual, error, zero);
+            let status = self.value(self.fp_status, types::I64);
+            let raised = self
+                .builder
+                .ins()
+                .bor_imm(status, FpExceptions::INEXACT.bits() as i64);
+            let status = self.builder.ins().select(inexact, raised, status);
+            self.set(self.fp_status, status);
+        }
+        self.builder.ins().jump(done, &[]);
+
+        self.builder.switch_to_block(soft);
+        self.lower_fallback(inst);
+        self.builder.ins().jump(done, &[]);
+        self.builder.switch_to_block(done);
+    }
+
+    /// Leave native code through the fault exit if the helper call returned `failed`.
+    fn check_fault(&mut self, failed: Value) {
+        let fault = self.builder.create_block();
+        let next = self.builder.create_block();
+        self.builder.ins().brif(failed, fault, &[], next, &[]);
+
+        self.builder.switch_to_block(fault);
+        self.exit(Exit::Fault);
+        self.builder.switch_to_block(next);
+    }
+
+    fn lower_terminator(&mut self, terminator: BasicBlockTerminator) {
+        match terminator {
+            BasicBlockTerminator::None => unreachable!("unreachable basic block"),
+            BasicBlockTerminator::Next => {}
+            BasicBlockTerminator::BranchCond {
+                cond,
+                target_true,
+                target_false,
+            } => {
+                let cond_ty = int_type(cond.ty()).unwrap();
+                let cond = self.value(cond, cond_ty);
+                let target_true = self.value(target_true, types::I64);
+                let target_false = self.value(target_false, types::I64);
+
+                let target = self.builder.ins().select(cond, target_true, target_false);
+                self.set(self.pc, target);
+            }
+            BasicBlockTerminator::Branch(target) => {
+                let target = self.value(target, types::I64);
+                self.set(self.pc, target);
+            }
+        }
+    }
+
+    /// Create a new segment the block can be resumed at and return its index.
+    fn new_segment(&mut self) -> u32 {
+        let block = self.builder.create_block();
+        self.segments.push(block);
+        (self.segments.len() - 1) as u32
+    }
+
+    /// Return from the current block through a new exit.
+    fn exit(&mut self, exit: Exit) {
+        let idx = self.exits.len();
+        self.exits.push(exit);
+
+        let idx = self.builder.ins().iconst(types::I32, idx as i64);
+        self.builder.ins().return_(&[idx]);
+    }
+
+    /// Address of the storage backing a register or variable.
+    fn addr(&self, value: IrValue) -> (Value, i32) {
+        match value {
+            IrValue::Register(_, id) => (self.regs, self.desc.register(id).offset as i32),
+            IrValue::Variable(_, id) => (self.vars, (id * 16) as i32),
+            IrValue::Constant(_) => unreachable!("constants have no storage"),
+        }
+    }
+
+    fn value(&mut self, value: IrValue, ty: Type) -> Value {
+        match value {
+            IrValue::Constant(constant) => {
+                let imm = match constant {
+                    IrConstant::B8(v) => v as i64,
+                    IrConstant::B16(v) => v as i64,
+                    IrConstant::B32(v) => v as i64,
+                    IrConstant::B64(v) => v as i64,
+                };
+
+                if ty == types::I128 {
+                    let v = self.builder.ins().iconst(types::I64, imm);
+                    self.builder.ins().uextend(types::I128, v)
+                } else {
+                    self.builder.ins().iconst(ty, imm)
+                }
+            }
+            _ => {
+                let (base, offset) = self.addr(value);
+                self.builder
+                    .ins()
+                    .load(ty, MemFlags::trusted(), base, offset)
+            }
+        }
+    }
+
+    /// Get a floating-point value, constants only hold its bits.
+    fn float_value(&mut self, value: IrValue, ty: Type) -> Value {
+        match value {
+            IrValue::Constant(_) => {
+                let bits = self.value(value, ty.as_int());
+                self.builder.ins().bitcast(ty, MemFlags::new(), bits)
+            }
+            _ => self.value(value, ty),
+        }
+    }
+
+    fn float_const(&mut self, ty: Type, v: f64) -> Value {
+        if ty == types::F32 {
+            self.builder.ins().f32const(v as f32)
+        } else {
+            self.builder.ins().f64const(v)
+        }
+    }
+
+    /// Whether `min <= |v| < infinity`, false for NaNs.
+    fn in_range(&mut self, v: Value, min: f64) -> Value {
+        let ty = self.builder.func.dfg.value_type(v);
+        let abs = self.builder.ins().fabs(v);
+        let min = self.float_const(ty, min);
+        let infinity = self.float_const(ty, f64::INFINITY);
+        let above = self
+            .builder
+            .ins()
+            .fcmp(FloatCC::GreaterThanOrEqual, abs, min);
+        let below = self.builder.ins().fcmp(FloatCC::LessThan, abs, infinity);
+        self.builder.ins().band(above, below)
+    }
+
+    fn set(&mut self, dst: IrValue, v: Value) {
+        let (base, offset) = self.addr(dst);
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), v, base, offset);
+    }
+
+    fn flag(&mut self, flag: Flag) -> Value {
+        self.builder.ins().load(
+            types::I8,
+            MemFlags::trusted(),
+            self.flags,
+            flag.into_index() as i32,
+        )
+    }
+
+    fn set_flag(&mut self, flag: Flag, v: Value) {
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), v, self.flags, flag.into_index() as i32);
+    }
+
+    /// Set the zero and negative flags from the result `v`.
+    fn set_result_flags(&mut self, v: Value) {
+        let zf = self.builder.ins().icmp_imm(IntCC::Equal, v, 0);
+        let nf = self.builder.ins().icmp_imm(IntCC::SignedLessThan, v, 0);
+        self.set_flag(Flag::ZF, zf);
+        self.set_flag(Flag::NF, nf);
+    }
+
+    /// Set flags the same way rustjit does for add and sub, it overflowed if `sign` is negative.
+    fn set_overflow_flags(&mut self, v: Value, cf: Value, sign: Value) {
+        let of = self.builder.ins().icmp_imm(IntCC::SignedLessThan, sign, 0);
+
+        self.set_flag(Flag::CF, cf);
+        self.set_flag(Flag::OF, of);
+        self.set_result_flags(v);
+    }
+}
+
+/// Get the native integer type of an IR type, if it has one.
+fn int_type(ty: IrType) -> Option<Type> {
+    match ty {
+        IrType::B8 | IrType::Bool => Some(types::I8),
+        IrType::B16 => Some(types::I16),
+        IrType::B32 => Some(types::I32),
+        IrType::B64 => Some(types::I64),
+        IrType::B128 => Some(types::I128),
+        _ => None,
+    }
+}
+
+/// Get the native integer type holding the bits of an IR type, if it has one.
+fn bits_type(ty: IrType) -> Option<Type> {
+    match ty {
+        IrType::F16 => Some(types::I16),
+        IrType::F32 => Some(types::I32),
+        IrType::F64 => Some(types::I64),
+        _ => int_type(ty),
+    }
+}
+
+/// Get the native floating-point type of an IR type, half precision has none on every host.
+fn float_type(ty: IrType) -> Option<Type> {
+    match ty {
+        IrType::F32 => Some(types::F32),
+        IrType::F64 => Some(types::F64),
+        _ => None,
+    }
+}
+
+/// Smallest magnitude of results and operands whose products have an exact rounding error,
+/// smaller ones also might underflow.
+fn min_exact(ty: Type) -> f64 {
+    // Twice the precision above the smallest normal
+    if ty == types::F32 {
+        2f64.powi(-126 + 2 * 24)
+    } else {
+        2f64.powi(-1022 + 2 * 53)
+    }
+}
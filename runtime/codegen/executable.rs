@@ -1,4 +1,4 @@
-use core::Interrupt;
+use core::{Error, Interrupt};
 use std::ops::Generator;
 
 use crate::SoftMmu;
@@ -6,9 +6,11 @@ use crate::SoftMmu;
 use super::Context;
 
 /// An executable object that can be executed on a context.
+///
+/// The generator completes with an error if the guest faults.
 pub trait Executable {
     type Context: Context;
-    type Generator<'a>: Generator<Yield = Interrupt> + 'a
+    type Generator<'a>: Generator<Yield = Interrupt, Return = Result<(), Error>> + 'a
     where
         Self: 'a;
 
@@ -1,38 +1,43 @@
 pub mod context;
 mod register_file;
 use arch_desc::aarch64::AArch64Architecture;
-use num_traits::{PrimInt, WrappingAdd, WrappingMul, WrappingSub};
+use num_traits::{PrimInt, WrappingAdd, WrappingSub};
 pub use register_file::*;
 use smallvec::SmallVec;
 
 use core::{
-    ir::{BasicBlock, BasicBlockTerminator, Flag, IrInst, IrType, IrValue, TypeOf},
-    Architecture, ArchitectureCompat, Interrupt, Register,
+    ir::{
+        AtomicOp, BasicBlock, BasicBlockTerminator, Comparison, Flag, FpComparison, FpExceptions,
+        FpRounding, IrInst, IrIntrinsic, IrType, IrValue, Reordering, TypeOf,
+    },
+    Access, Architecture, ArchitectureCompat, Error, Interrupt, Register,
 };
 use std::{
     cell::{Cell, RefCell},
+    cmp,
     collections::{HashMap, VecDeque},
-    mem,
+    iter, mem,
     ops::Generator,
+    sync::atomic::{self, Ordering},
 };
 
-use crate::IoDevice;
 use crate::SoftMmu;
 
 use self::context::RustjitContext;
 use super::{
     analysis::{Analysis, VariableLivenessAnalysis},
+    softfp::{self, FpEnv, FpFormat, SoftFp},
     Codegen, Context, Executable,
 };
 
 pub struct RustjitExectuable {
-    exec: Vec<Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>>>,
+    exec: Vec<Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>>>,
     terminator: Box<dyn Fn(&RustjitContext, &SoftMmu)>,
 }
 
 impl Executable for RustjitExectuable {
     type Context = RustjitContext;
-    type Generator<'a> = impl Generator<Yield = Interrupt, Return = ()> + 'a;
+    type Generator<'a> = impl Generator<Yield = Interrupt, Return = Result<(), Error>> + 'a;
 
     unsafe fn execute<'a>(
         &'a self,
@@ -41,7 +46,7 @@ impl Executable for RustjitExectuable {
     ) -> Self::Generator<'a> {
         || {
             for inst in &self.exec {
-                let Some(interrput) = inst(context, mmu) else {
+                let Some(interrput) = inst(context, mmu)? else {
                     continue;
                 };
 
@@ -49,6 +54,7 @@ impl Executable for RustjitExectuable {
             }
 
             (self.terminator)(context, mmu);
+            Ok(())
         }
     }
 }
@@ -75,8 +81,10 @@ impl Codegen for RustjitCodegen {
         }
     }
 
-    fn compile<A: Architecture>(&self, bb: &BasicBlock) -> Self::Executable {
-        let mut exec: Vec<Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>>> = Vec::new();
+    fn compile<A: Architecture>(&self, bb: &BasicBlock) -> Result<Self::Executable, Error> {
+        let mut exec: Vec<
+            Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>>,
+        > = Vec::new();
 
         let variable_liveness = VariableLivenessAnalysis::new(bb).analyze();
         let max_variables = variable_liveness.maximum_variable_live();
@@ -85,8 +93,7 @@ impl Codegen for RustjitCodegen {
         let mut var_allocation_ids: VecDeque<_> = (0usize..max_variables).collect();
 
         let mut map_variable = |value: IrValue, idx: usize| -> IrValue {
-            let IrValue::Variable(ty, id) = value
-            else {
+            let IrValue::Variable(ty, id) = value else {
                 return value;
             };
 
@@ -107,114 +114,12 @@ impl Codegen for RustjitCodegen {
         exec.push(Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
             *ctx.variables.borrow_mut() =
                 Some(vec![Cell::new(0); max_variables].into_boxed_slice());
-            None
+            Ok(None)
         }) as Box<_>);
 
+        let fp_env = FpEnv::new::<A>();
         for (idx, inst) in bb.inst().iter().enumerate() {
-            let inst = match inst {
-                &IrInst::Add { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_add(dst, lhs, rhs)
-                }
-                &IrInst::Sub { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_sub(dst, lhs, rhs)
-                }
-                &IrInst::Mul { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_mul(dst, lhs, rhs)
-                }
-                &IrInst::Div { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_div(dst, lhs, rhs)
-                }
-                &IrInst::Rem { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_rem(dst, lhs, rhs)
-                }
-                &IrInst::And { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_bit_and(dst, lhs, rhs)
-                }
-                &IrInst::Or { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_bit_or(dst, lhs, rhs)
-                }
-                &IrInst::Xor { dst, lhs, rhs } => {
-                    let dst = map_variable(dst, idx);
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-
-                    gen_bit_xor(dst, lhs, rhs)
-                }
-                &IrInst::Not { dst, src } => {
-                    let dst = map_variable(dst, idx);
-                    let src = map_variable(src, idx);
-
-                    gen_bit_not(dst, src)
-                }
-                &IrInst::MoveFlag { dst, dst_pos, flag } => {
-                    let dst = map_variable(dst, idx);
-
-                    gen_move_flag(dst, dst_pos, flag)
-                }
-                &IrInst::Assign { dst, src } => {
-                    let src = map_variable(src, idx);
-                    let dst = map_variable(dst, idx);
-
-                    gen_assign(dst, src)
-                }
-                &IrInst::Shl { dst, lhs, rhs } => {
-                    let lhs = map_variable(lhs, idx);
-                    let rhs = map_variable(rhs, idx);
-                    let dst = map_variable(dst, idx);
-
-                    gen_shl(dst, lhs, rhs)
-                }
-                &IrInst::Load { dst, src } => {
-                    let src = map_variable(src, idx);
-                    let dst = map_variable(dst, idx);
-
-                    gen_load(dst, src)
-                }
-                &IrInst::Store { dst, src } => {
-                    let src = map_variable(src, idx);
-                    let dst = map_variable(dst, idx);
-
-                    gen_store(dst, src)
-                }
-                &IrInst::ZextCast { dst, src } => {
-                    let src = map_variable(src, idx);
-                    let dst = map_variable(dst, idx);
-
-                    gen_zext_cast(dst, src)
-                }
-                &IrInst::Fence(_) => gen_fence(),
-                x => todo!("todo {:?}", x),
-            };
-
-            exec.push(inst);
+            exec.push(gen_inst(inst, fp_env, |value| map_variable(value, idx))?);
         }
 
         let terminator = match bb.terminator() {
@@ -227,7 +132,12 @@ impl Codegen for RustjitCodegen {
             } => {
                 let cond = map_variable(cond, bb.inst().len());
                 Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
-                    let cond = ctx.get::<u64>(cond);
+                    let cond = match cond.ty() {
+                        IrType::B8 | IrType::Bool => ctx.get::<u8>(cond) as u64,
+                        IrType::B16 => ctx.get::<u16>(cond) as u64,
+                        IrType::B32 => ctx.get::<u32>(cond) as u64,
+                        _ => ctx.get::<u64>(cond),
+                    };
 
                     let pc = IrValue::Register(IrType::B64, A::get_pc_register().raw());
                     if cond != 0 {
@@ -246,24 +156,598 @@ impl Codegen for RustjitCodegen {
             }
         };
 
-        RustjitExectuable { exec, terminator }
+        Ok(RustjitExectuable { exec, terminator })
     }
 }
 
+/// Generate an executable closure for a single IR instruction.
+///
+/// `map` is applied to every operand before it is captured by the closure and `fp_env` holds
+/// the floating-point environment of the architecture. Fails if the instruction is not supported.
+pub(crate) fn gen_inst(
+    inst: &IrInst,
+    fp_env: FpEnv,
+    mut map: impl FnMut(IrValue) -> IrValue,
+) -> Result<Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>>, Error> {
+    Ok(match inst {
+        &IrInst::Add { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_add(dst, lhs, rhs)
+        }
+        &IrInst::Sub { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_sub(dst, lhs, rhs)
+        }
+        &IrInst::Mul { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_mul(dst, lhs, rhs)
+        }
+        &IrInst::Div { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_div(dst, lhs, rhs)
+        }
+        &IrInst::Rem { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_rem(dst, lhs, rhs)
+        }
+        &IrInst::Sdiv { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_sdiv(dst, lhs, rhs)
+        }
+        &IrInst::Srem { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_srem(dst, lhs, rhs)
+        }
+        &IrInst::Umulh { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_umulh(dst, lhs, rhs)
+        }
+        &IrInst::Smulh { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_smulh(dst, lhs, rhs)
+        }
+        &IrInst::And { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_bit_and(dst, lhs, rhs)
+        }
+        &IrInst::Or { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_bit_or(dst, lhs, rhs)
+        }
+        &IrInst::Xor { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_bit_xor(dst, lhs, rhs)
+        }
+        &IrInst::Not { dst, src } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_bit_not(dst, src)
+        }
+        &IrInst::Clz { dst, src } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_clz(dst, src)
+        }
+        &IrInst::Cls { dst, src } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_cls(dst, src)
+        }
+        &IrInst::Bitrev { dst, src } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_bitrev(dst, src)
+        }
+        &IrInst::Bswap { dst, src } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_bswap(dst, src)
+        }
+        &IrInst::Popcnt { dst, src } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_popcnt(dst, src)
+        }
+        &IrInst::Max {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_max_min(dst, lhs, rhs, signed, cmp::Ordering::Greater)
+        }
+        &IrInst::Min {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_max_min(dst, lhs, rhs, signed, cmp::Ordering::Less)
+        }
+        &IrInst::AddSat {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_saturating(dst, lhs, rhs, signed, fp_env, |lhs, rhs| lhs + rhs)
+        }
+        &IrInst::SubSat {
+            dst,
+            lhs,
+            rhs,
+            signed,
+        } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_saturating(dst, lhs, rhs, signed, fp_env, |lhs, rhs| lhs - rhs)
+        }
+        &IrInst::TruncSat {
+            dst,
+            src,
+            src_signed,
+            dst_signed,
+        } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_trunc_sat(dst, src, src_signed, dst_signed, fp_env)
+        }
+        &IrInst::MoveFlag { dst, dst_pos, flag } => {
+            let dst = map(dst);
+
+            gen_move_flag(dst, dst_pos, flag)
+        }
+        &IrInst::Cmp {
+            dst,
+            lhs,
+            rhs,
+            cond,
+        } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_cmp(dst, lhs, rhs, cond)
+        }
+        &IrInst::Select {
+            dst,
+            cond,
+            if_true,
+            if_false,
+        } => {
+            let dst = map(dst);
+            let cond = map(cond);
+            let if_true = map(if_true);
+            let if_false = map(if_false);
+
+            gen_select(dst, cond, if_true, if_false)
+        }
+        &IrInst::Assign { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_assign(dst, src)
+        }
+        &IrInst::Shl { dst, lhs, rhs } => {
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+            let dst = map(dst);
+
+            gen_shl(dst, lhs, rhs)
+        }
+        &IrInst::Lshr { dst, lhs, rhs } => {
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+            let dst = map(dst);
+
+            gen_lshr(dst, lhs, rhs)
+        }
+        &IrInst::Ashr { dst, lhs, rhs } => {
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+            let dst = map(dst);
+
+            gen_ashr(dst, lhs, rhs)
+        }
+        &IrInst::Rotr { dst, lhs, rhs } => {
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+            let dst = map(dst);
+
+            gen_rotr(dst, lhs, rhs)
+        }
+        &IrInst::Load { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_load(dst, src)
+        }
+        &IrInst::Store { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_store(dst, src)
+        }
+        &IrInst::LoadLinked { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_load_linked(dst, src)
+        }
+        &IrInst::StoreConditional { dst, src, stored } => {
+            let src = map(src);
+            let dst = map(dst);
+            let stored = map(stored);
+
+            gen_store_conditional(dst, src, stored)
+        }
+        IrInst::ClearLink => gen_clear_link(),
+        &IrInst::AtomicRmw {
+            dst,
+            addr,
+            src,
+            op,
+            ref ordering,
+        } => {
+            let addr = map(addr);
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_atomic_rmw(dst, addr, src, op, ordering.clone())
+        }
+        &IrInst::CompareExchange {
+            dst,
+            addr,
+            expected,
+            new,
+            ref ordering,
+        } => {
+            let addr = map(addr);
+            let expected = map(expected);
+            let new = map(new);
+            let dst = map(dst);
+
+            gen_compare_exchange(dst, addr, expected, new, ordering.clone())
+        }
+        &IrInst::ZextCast { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_zext_cast(dst, src)
+        }
+        &IrInst::SextCast { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_sext_cast(dst, src)
+        }
+        &IrInst::Splat { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_splat(dst, src)
+        }
+        IrInst::Shuffle {
+            dst,
+            lhs,
+            rhs,
+            lanes,
+        } => {
+            let dst = map(*dst);
+            let lhs = map(*lhs);
+            let rhs = map(*rhs);
+
+            gen_shuffle(dst, lhs, rhs, lanes.clone())
+        }
+        &IrInst::Swizzle { dst, src, indices } => {
+            let dst = map(dst);
+            let src = map(src);
+            let indices = map(indices);
+
+            gen_swizzle(dst, src, indices)
+        }
+        &IrInst::ExtractLane { dst, src, lane } => {
+            let dst = map(dst);
+            let src = map(src);
+
+            gen_extract_lane(dst, src, lane)
+        }
+        &IrInst::InsertLane {
+            dst,
+            src,
+            value,
+            lane,
+        } => {
+            let dst = map(dst);
+            let src = map(src);
+            let value = map(value);
+
+            gen_insert_lane(dst, src, value, lane)
+        }
+        &IrInst::Fadd { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::add)
+        }
+        &IrInst::Fsub { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::sub)
+        }
+        &IrInst::Fmul { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::mul)
+        }
+        &IrInst::Fdiv { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::div)
+        }
+        &IrInst::Fmulx { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::mulx)
+        }
+        &IrInst::Fmax { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::max)
+        }
+        &IrInst::Fmin { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::min)
+        }
+        &IrInst::Fmaxnm { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::maxnm)
+        }
+        &IrInst::Fminnm { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::minnm)
+        }
+        &IrInst::Fma {
+            dst,
+            lhs,
+            rhs,
+            addend,
+        } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+            let addend = map(addend);
+
+            gen_fma(dst, lhs, rhs, addend, fp_env)
+        }
+        &IrInst::Fneg { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_sign(dst, src, FpFormat::neg)
+        }
+        &IrInst::Fabs { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_sign(dst, src, FpFormat::abs)
+        }
+        &IrInst::Fsqrt { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_unary(dst, src, fp_env, SoftFp::sqrt)
+        }
+        &IrInst::FrecipEstimate { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_unary(dst, src, fp_env, SoftFp::recip_estimate)
+        }
+        &IrInst::FrsqrtEstimate { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_unary(dst, src, fp_env, SoftFp::rsqrt_estimate)
+        }
+        &IrInst::UrecipEstimate { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_lanewise_unary(dst, src, |src, _| {
+                softfp::unsigned_recip_estimate(src as u32) as u128
+            })
+        }
+        &IrInst::UrsqrtEstimate { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_lanewise_unary(dst, src, |src, _| {
+                softfp::unsigned_rsqrt_estimate(src as u32) as u128
+            })
+        }
+        &IrInst::FrecipExponent { dst, src } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_unary(dst, src, fp_env, SoftFp::recip_exponent)
+        }
+        &IrInst::FrecipStep { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::recip_step)
+        }
+        &IrInst::FrsqrtStep { dst, lhs, rhs } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fp_binary(dst, lhs, rhs, fp_env, SoftFp::rsqrt_step)
+        }
+        &IrInst::Fround {
+            dst,
+            src,
+            rounding,
+            signal_inexact,
+        } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_unary(dst, src, fp_env, move |fp, fmt, src| {
+                fp.round_int(fmt, src, rounding, signal_inexact)
+            })
+        }
+        &IrInst::FpCast { dst, src, rounding } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_cast(dst, src, rounding, fp_env)
+        }
+        &IrInst::FpToInt {
+            dst,
+            src,
+            signed,
+            frac_bits,
+            rounding,
+        } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_fp_to_int(dst, src, signed, frac_bits, rounding, fp_env)
+        }
+        &IrInst::IntToFp {
+            dst,
+            src,
+            signed,
+            frac_bits,
+        } => {
+            let src = map(src);
+            let dst = map(dst);
+
+            gen_int_to_fp(dst, src, signed, frac_bits, fp_env)
+        }
+        &IrInst::Fcmp {
+            dst,
+            lhs,
+            rhs,
+            cond,
+            signaling,
+        } => {
+            let dst = map(dst);
+            let lhs = map(lhs);
+            let rhs = map(rhs);
+
+            gen_fcmp(dst, lhs, rhs, cond, signaling, fp_env)
+        }
+        IrInst::Fence(ordering) => gen_fence(ordering.clone()),
+        IrInst::Interrupt(interrupt) => gen_interrupt(interrupt.clone()),
+        IrInst::Intrinsic(IrIntrinsic::InvalidateCode { addr }) => {
+            let addr = map(*addr);
+
+            gen_invalidate_code(addr)
+        }
+        IrInst::Intrinsic(IrIntrinsic::InvalidateAllCode) => gen_invalidate_all_code(),
+    })
+}
+
 fn gen_add(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
     #[inline(always)]
-    fn carrying_add<T: WrappingAdd + PrimInt>(a: T, b: T, carry_in: bool) -> (T, bool, bool, bool) {
+    fn carrying_add<T: WrappingAdd + PrimInt>(
+        a: T,
+        b: T,
+        carry_in: bool,
+    ) -> (T, bool, bool, bool, bool) {
         let carry = if carry_in { T::one() } else { T::zero() };
         let sum = a.wrapping_add(&b).wrapping_add(&carry);
         let cf = (a > sum) || (carry == T::one() && b == T::max_value());
-        let of = (a ^ sum).leading_zeros() == 0;
+        let of = ((a ^ sum) & (b ^ sum)).leading_zeros() == 0;
         let zf = sum.is_zero();
-        (sum, cf, of, zf)
+        let nf = sum.leading_zeros() == 0;
+        (sum, cf, of, zf, nf)
     }
 
     macro_rules! gen_add_impl {
@@ -272,13 +756,14 @@ fn gen_add(
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let (v, cf, of, zf) = carrying_add(lhs, rhs, false);
+                let (v, cf, of, zf, nf) = carrying_add(lhs, rhs, false);
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::CF, cf);
                 ctx.set_flag(Flag::OF, of);
                 ctx.set_flag(Flag::ZF, zf);
+                ctx.set_flag(Flag::NF, nf);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -290,6 +775,8 @@ fn gen_add(
         IrType::B64 => gen_add_impl!(u64),
         IrType::B128 => gen_add_impl!(u128),
 
+        IrType::Vector(..) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, _| lhs.wrapping_add(rhs)),
+
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
@@ -298,16 +785,21 @@ fn gen_sub(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
     #[inline(always)]
-    fn carrying_sub<T: WrappingSub + PrimInt>(a: T, b: T, carry_in: bool) -> (T, bool, bool, bool) {
+    fn carrying_sub<T: WrappingSub + PrimInt>(
+        a: T,
+        b: T,
+        carry_in: bool,
+    ) -> (T, bool, bool, bool, bool) {
         let carry = if carry_in { T::one() } else { T::zero() };
         let sum = a.wrapping_sub(&b).wrapping_sub(&carry);
         let cf = (a < sum) || (carry == T::one() && b == T::max_value());
-        let of = (a ^ sum).leading_zeros() == 0;
+        let of = ((a ^ b) & (a ^ sum)).leading_zeros() == 0;
         let zf = sum.is_zero();
-        (sum, cf, of, zf)
+        let nf = sum.leading_zeros() == 0;
+        (sum, cf, of, zf, nf)
     }
 
     macro_rules! gen_sub_impl {
@@ -316,13 +808,14 @@ fn gen_sub(
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let (v, cf, of, zf) = carrying_sub(lhs, rhs, false);
+                let (v, cf, of, zf, nf) = carrying_sub(lhs, rhs, false);
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::CF, cf);
                 ctx.set_flag(Flag::OF, of);
                 ctx.set_flag(Flag::ZF, zf);
+                ctx.set_flag(Flag::NF, nf);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -334,6 +827,8 @@ fn gen_sub(
         IrType::B64 => gen_sub_impl!(u64),
         IrType::B128 => gen_sub_impl!(u128),
 
+        IrType::Vector(..) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, _| lhs.wrapping_sub(rhs)),
+
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
@@ -342,41 +837,35 @@ fn gen_mul(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
-    #[inline(always)]
-    fn carrying_mul<T: WrappingMul + PrimInt>(a: T, b: T, carry_in: bool) -> (T, bool, bool, bool) {
-        let carry = if carry_in { T::one() } else { T::zero() };
-        let sum = a.wrapping_mul(&b).wrapping_mul(&carry);
-        let cf = (a > sum) || (carry == T::one() && b == T::max_value());
-        let of = (a ^ sum).leading_zeros() == 0;
-        let zf = sum.is_zero();
-        (sum, cf, of, zf)
-    }
-
     macro_rules! gen_mul_impl {
-        ($ty:ty) => {
+        ($ty:ty, $signed_ty:ty) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let (v, cf, of, zf) = carrying_mul(lhs, rhs, false);
+                let (v, cf) = lhs.overflowing_mul(rhs);
+                let (_, of) = (lhs as $signed_ty).overflowing_mul(rhs as $signed_ty);
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::CF, cf);
                 ctx.set_flag(Flag::OF, of);
-                ctx.set_flag(Flag::ZF, zf);
+                ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
 
     match dst.ty() {
-        IrType::B8 => gen_mul_impl!(u8),
-        IrType::B16 => gen_mul_impl!(u16),
-        IrType::B32 => gen_mul_impl!(u32),
-        IrType::B64 => gen_mul_impl!(u64),
-        IrType::B128 => gen_mul_impl!(u128),
+        IrType::B8 => gen_mul_impl!(u8, i8),
+        IrType::B16 => gen_mul_impl!(u16, i16),
+        IrType::B32 => gen_mul_impl!(u32, i32),
+        IrType::B64 => gen_mul_impl!(u64, i64),
+        IrType::B128 => gen_mul_impl!(u128, i128),
+
+        IrType::Vector(..) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, _| lhs.wrapping_mul(rhs)),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
@@ -386,7 +875,7 @@ fn gen_div(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
     macro_rules! gen_div_impl {
         ($ty:ty) => {
@@ -395,14 +884,15 @@ fn gen_div(
                 let rhs: $ty = ctx.get(rhs);
 
                 if rhs == 0 {
-                    return Some(Interrupt::Exception(0));
+                    return Ok(Some(Interrupt::Exception(0)));
                 }
 
                 let v = lhs.wrapping_div(rhs);
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -422,7 +912,7 @@ fn gen_rem(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
     macro_rules! gen_rem_impl {
         ($ty:ty) => {
@@ -431,14 +921,15 @@ fn gen_rem(
                 let rhs: $ty = ctx.get(rhs);
 
                 if rhs == 0 {
-                    return Some(Interrupt::Exception(0));
+                    return Ok(Some(Interrupt::Exception(0)));
                 }
 
                 let v = lhs.wrapping_rem(rhs);
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -454,244 +945,802 @@ fn gen_rem(
     }
 }
 
-fn gen_bit_and(
+fn gen_sdiv(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
-    macro_rules! gen_bit_and_impl {
-        ($ty:ty) => {
+    macro_rules! gen_sdiv_impl {
+        ($ty:ty, $signed_ty:ty) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let v = lhs & rhs;
+                if rhs == 0 {
+                    return Ok(Some(Interrupt::Exception(0)));
+                }
+
+                let v = (lhs as $signed_ty).wrapping_div(rhs as $signed_ty) as $ty;
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
 
     match dst.ty() {
-        IrType::B8 => gen_bit_and_impl!(u8),
-        IrType::B16 => gen_bit_and_impl!(u16),
-        IrType::B32 => gen_bit_and_impl!(u32),
-        IrType::B64 => gen_bit_and_impl!(u64),
-        IrType::B128 => gen_bit_and_impl!(u128),
+        IrType::B8 => gen_sdiv_impl!(u8, i8),
+        IrType::B16 => gen_sdiv_impl!(u16, i16),
+        IrType::B32 => gen_sdiv_impl!(u32, i32),
+        IrType::B64 => gen_sdiv_impl!(u64, i64),
+        IrType::B128 => gen_sdiv_impl!(u128, i128),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
-fn gen_bit_or(
+fn gen_srem(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
-    macro_rules! gen_bit_or_impl {
-        ($ty:ty) => {
+    macro_rules! gen_srem_impl {
+        ($ty:ty, $signed_ty:ty) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let v = lhs | rhs;
+                if rhs == 0 {
+                    return Ok(Some(Interrupt::Exception(0)));
+                }
+
+                let v = (lhs as $signed_ty).wrapping_rem(rhs as $signed_ty) as $ty;
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
 
     match dst.ty() {
-        IrType::B8 => gen_bit_or_impl!(u8),
-        IrType::B16 => gen_bit_or_impl!(u16),
-        IrType::B32 => gen_bit_or_impl!(u32),
-        IrType::B64 => gen_bit_or_impl!(u64),
-        IrType::B128 => gen_bit_or_impl!(u128),
+        IrType::B8 => gen_srem_impl!(u8, i8),
+        IrType::B16 => gen_srem_impl!(u16, i16),
+        IrType::B32 => gen_srem_impl!(u32, i32),
+        IrType::B64 => gen_srem_impl!(u64, i64),
+        IrType::B128 => gen_srem_impl!(u128, i128),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
-fn gen_bit_xor(
+/// High half of the 256 bit product of `a` and `b`.
+#[inline(always)]
+fn umulh_u128(a: u128, b: u128) -> u128 {
+    let (a_lo, a_hi) = (a as u64 as u128, a >> 64);
+    let (b_lo, b_hi) = (b as u64 as u128, b >> 64);
+
+    let lo = a_lo * b_lo;
+    let mid1 = a_hi * b_lo;
+    let mid2 = a_lo * b_hi;
+    let carry = ((lo >> 64) + (mid1 as u64 as u128) + (mid2 as u64 as u128)) >> 64;
+
+    a_hi * b_hi + (mid1 >> 64) + (mid2 >> 64) + carry
+}
+
+fn gen_umulh(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
-    macro_rules! gen_bit_xor_impl {
-        ($ty:ty) => {
+    macro_rules! gen_umulh_impl {
+        ($ty:ty, $mulh:expr) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let v = lhs ^ rhs;
+                let v: $ty = $mulh(lhs, rhs);
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
+        ($ty:ty, $wide_ty:ty, $bits:expr) => {
+            gen_umulh_impl!($ty, |lhs: $ty, rhs: $ty| {
+                ((lhs as $wide_ty * rhs as $wide_ty) >> $bits) as $ty
+            })
+        };
     }
 
     match dst.ty() {
-        IrType::B8 => gen_bit_xor_impl!(u8),
-        IrType::B16 => gen_bit_xor_impl!(u16),
-        IrType::B32 => gen_bit_xor_impl!(u32),
-        IrType::B64 => gen_bit_xor_impl!(u64),
-        IrType::B128 => gen_bit_xor_impl!(u128),
+        IrType::B8 => gen_umulh_impl!(u8, u16, 8),
+        IrType::B16 => gen_umulh_impl!(u16, u32, 16),
+        IrType::B32 => gen_umulh_impl!(u32, u64, 32),
+        IrType::B64 => gen_umulh_impl!(u64, u128, 64),
+        IrType::B128 => gen_umulh_impl!(u128, umulh_u128),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
-fn gen_bit_not(
+fn gen_smulh(
     dst: IrValue,
-    src: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
-    assert!(dst.ty() == src.ty());
-    macro_rules! gen_bit_not_impl {
-        ($ty:ty) => {
+    lhs: IrValue,
+    rhs: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
+    macro_rules! gen_smulh_impl {
+        ($ty:ty, $mulh:expr) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
-                let src: $ty = ctx.get(src);
+                let lhs: $ty = ctx.get(lhs);
+                let rhs: $ty = ctx.get(rhs);
 
-                let v = !src;
+                let v: $ty = $mulh(lhs, rhs);
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
+        ($ty:ty, $signed_ty:ty, $wide_ty:ty, $bits:expr) => {
+            gen_smulh_impl!($ty, |lhs: $ty, rhs: $ty| {
+                ((lhs as $signed_ty as $wide_ty * rhs as $signed_ty as $wide_ty) >> $bits) as $ty
+            })
+        };
     }
 
     match dst.ty() {
-        IrType::B8 => gen_bit_not_impl!(u8),
-        IrType::B16 => gen_bit_not_impl!(u16),
-        IrType::B32 => gen_bit_not_impl!(u32),
-        IrType::B64 => gen_bit_not_impl!(u64),
-        IrType::B128 => gen_bit_not_impl!(u128),
+        IrType::B8 => gen_smulh_impl!(u8, i8, i16, 8),
+        IrType::B16 => gen_smulh_impl!(u16, i16, i32, 16),
+        IrType::B32 => gen_smulh_impl!(u32, i32, i64, 32),
+        IrType::B64 => gen_smulh_impl!(u64, i64, i128, 64),
+        // The unsigned high half is off by the other operand for every negative one
+        IrType::B128 => gen_smulh_impl!(u128, |lhs: u128, rhs: u128| {
+            let mut v = umulh_u128(lhs, rhs);
+            if (lhs as i128) < 0 {
+                v = v.wrapping_sub(rhs);
+            }
+            if (rhs as i128) < 0 {
+                v = v.wrapping_sub(lhs);
+            }
+            v
+        }),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
-fn gen_shl(
+fn gen_bit_and(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
-    assert!(dst.ty() == lhs.ty());
-    macro_rules! gen_shl_impl {
-        ($lhs_ty:ty, $rhs_ty:ty) => {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
+    macro_rules! gen_bit_and_impl {
+        ($ty:ty) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
-                let lhs: $lhs_ty = ctx.get(lhs);
-                let rhs: $rhs_ty = ctx.get(rhs);
+                let lhs: $ty = ctx.get(lhs);
+                let rhs: $ty = ctx.get(rhs);
 
-                let v = lhs << rhs;
-                ctx.set::<$lhs_ty>(dst, v);
+                let v = lhs & rhs;
+                ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
 
-    match (lhs.ty(), rhs.ty()) {
-        (IrType::B8, IrType::B8) => gen_shl_impl!(u8, u8),
-        (IrType::B16, IrType::B8) => gen_shl_impl!(u16, u8),
-        (IrType::B32, IrType::B8) => gen_shl_impl!(u32, u8),
-        (IrType::B64, IrType::B8) => gen_shl_impl!(u64, u8),
-        (IrType::B128, IrType::B8) => gen_shl_impl!(u128, u8),
+    match dst.ty() {
+        IrType::B8 => gen_bit_and_impl!(u8),
+        IrType::B16 => gen_bit_and_impl!(u16),
+        IrType::B32 => gen_bit_and_impl!(u32),
+        IrType::B64 => gen_bit_and_impl!(u64),
+        IrType::B128 => gen_bit_and_impl!(u128),
 
-        _ => unimplemented!("Unsupported type: {:?} << {:?}", lhs.ty(), rhs.ty()),
+        IrType::Vector(..) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, _| lhs & rhs),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
-fn gen_lshr(
+fn gen_bit_or(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
-    macro_rules! gen_lshr_impl {
+    macro_rules! gen_bit_or_impl {
         ($ty:ty) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let v = lhs >> rhs;
+                let v = lhs | rhs;
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
 
     match dst.ty() {
-        IrType::B8 => gen_lshr_impl!(u8),
-        IrType::B16 => gen_lshr_impl!(u16),
-        IrType::B32 => gen_lshr_impl!(u32),
-        IrType::B64 => gen_lshr_impl!(u64),
-        IrType::B128 => gen_lshr_impl!(u128),
+        IrType::B8 => gen_bit_or_impl!(u8),
+        IrType::B16 => gen_bit_or_impl!(u16),
+        IrType::B32 => gen_bit_or_impl!(u32),
+        IrType::B64 => gen_bit_or_impl!(u64),
+        IrType::B128 => gen_bit_or_impl!(u128),
+
+        IrType::Vector(..) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, _| lhs | rhs),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
-fn gen_ashr(
+fn gen_bit_xor(
     dst: IrValue,
     lhs: IrValue,
     rhs: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
-    macro_rules! gen_ashr_impl {
+    macro_rules! gen_bit_xor_impl {
         ($ty:ty) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
                 let lhs: $ty = ctx.get(lhs);
                 let rhs: $ty = ctx.get(rhs);
 
-                let v = lhs >> rhs;
+                let v = lhs ^ rhs;
                 ctx.set::<$ty>(dst, v);
                 ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
 
     match dst.ty() {
-        IrType::B8 => gen_ashr_impl!(u8),
-        IrType::B16 => gen_ashr_impl!(u16),
-        IrType::B32 => gen_ashr_impl!(u32),
-        IrType::B64 => gen_ashr_impl!(u64),
-        IrType::B128 => gen_ashr_impl!(u128),
+        IrType::B8 => gen_bit_xor_impl!(u8),
+        IrType::B16 => gen_bit_xor_impl!(u16),
+        IrType::B32 => gen_bit_xor_impl!(u32),
+        IrType::B64 => gen_bit_xor_impl!(u64),
+        IrType::B128 => gen_bit_xor_impl!(u128),
+
+        IrType::Vector(..) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, _| lhs ^ rhs),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_bit_not(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    macro_rules! gen_bit_not_impl {
+        ($ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let src: $ty = ctx.get(src);
+
+                let v = !src;
+                ctx.set::<$ty>(dst, v);
+                ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
+
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match dst.ty() {
+        IrType::B8 => gen_bit_not_impl!(u8),
+        IrType::B16 => gen_bit_not_impl!(u16),
+        IrType::B32 => gen_bit_not_impl!(u32),
+        IrType::B64 => gen_bit_not_impl!(u64),
+        IrType::B128 => gen_bit_not_impl!(u128),
+
+        IrType::Vector(..) => gen_lanewise_unary(dst, src, |src, _| !src),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_clz(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    macro_rules! gen_clz_impl {
+        ($ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let src: $ty = ctx.get(src);
+
+                ctx.set::<$ty>(dst, src.leading_zeros() as $ty);
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match dst.ty() {
+        IrType::B8 => gen_clz_impl!(u8),
+        IrType::B16 => gen_clz_impl!(u16),
+        IrType::B32 => gen_clz_impl!(u32),
+        IrType::B64 => gen_clz_impl!(u64),
+        IrType::B128 => gen_clz_impl!(u128),
+
+        IrType::Vector(..) => gen_lanewise_unary(dst, src, |src, bits| {
+            (src.leading_zeros() - (u128::BITS - bits)) as u128
+        }),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
+fn gen_cls(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    macro_rules! gen_cls_impl {
+        ($ty:ty, $signed_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let src: $ty = ctx.get(src);
+
+                // Bits equal to the sign bit become leading zeros
+                let sign = ((src as $signed_ty) >> (<$ty>::BITS - 1)) as $ty;
+                ctx.set::<$ty>(dst, ((src ^ sign).leading_zeros() - 1) as $ty);
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match dst.ty() {
+        IrType::B8 => gen_cls_impl!(u8, i8),
+        IrType::B16 => gen_cls_impl!(u16, i16),
+        IrType::B32 => gen_cls_impl!(u32, i32),
+        IrType::B64 => gen_cls_impl!(u64, i64),
+        IrType::B128 => gen_cls_impl!(u128, i128),
+
+        IrType::Vector(..) => gen_lanewise_unary(dst, src, |src, bits| {
+            let src = sext(src, bits);
+            ((src ^ (src >> (i128::BITS - 1))).leading_zeros() - (i128::BITS - bits) - 1) as u128
+        }),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_bitrev(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    macro_rules! gen_bitrev_impl {
+        ($ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let src: $ty = ctx.get(src);
+
+                ctx.set::<$ty>(dst, src.reverse_bits());
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match dst.ty() {
+        IrType::B8 => gen_bitrev_impl!(u8),
+        IrType::B16 => gen_bitrev_impl!(u16),
+        IrType::B32 => gen_bitrev_impl!(u32),
+        IrType::B64 => gen_bitrev_impl!(u64),
+        IrType::B128 => gen_bitrev_impl!(u128),
+
+        IrType::Vector(..) => gen_lanewise_unary(dst, src, |src, bits| {
+            src.reverse_bits() >> (u128::BITS - bits)
+        }),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_bswap(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    macro_rules! gen_bswap_impl {
+        ($ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let src: $ty = ctx.get(src);
+
+                ctx.set::<$ty>(dst, src.swap_bytes());
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match dst.ty() {
+        IrType::B8 => gen_bswap_impl!(u8),
+        IrType::B16 => gen_bswap_impl!(u16),
+        IrType::B32 => gen_bswap_impl!(u32),
+        IrType::B64 => gen_bswap_impl!(u64),
+        IrType::B128 => gen_bswap_impl!(u128),
+
+        IrType::Vector(..) => gen_lanewise_unary(dst, src, |src, bits| {
+            src.swap_bytes() >> (u128::BITS - bits)
+        }),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_popcnt(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    gen_lanewise_unary(dst, src, |src, _| src.count_ones() as u128)
+}
+
+/// `Max` if `keep` is `Ordering::Greater`, `Min` if it is `Ordering::Less`.
+fn gen_max_min(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    signed: bool,
+    keep: cmp::Ordering,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
+    gen_lanewise(dst, lhs, rhs, move |lhs, rhs, bits| {
+        let ordering = if signed {
+            sext(lhs, bits).cmp(&sext(rhs, bits))
+        } else {
+            lhs.cmp(&rhs)
+        };
+
+        if ordering == keep {
+            lhs
+        } else {
+            rhs
+        }
+    })
+}
+
+/// Apply `op` to the exact values of the lanes and clamp the results to the range of a lane.
+fn gen_saturating(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    signed: bool,
+    fp_env: FpEnv,
+    op: fn(i128, i128) -> i128,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
+    let (_, bits) = lane_layout(dst.ty());
+    // The exact result of 64-bit lanes still fits into an i128
+    assert!(bits <= 64);
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let lhs = get_lanes(ctx, lhs);
+        let rhs = get_lanes(ctx, rhs);
+
+        let mut saturated = false;
+        let lanes: Lanes = lhs
+            .iter()
+            .zip(&rhs)
+            .map(|(&lhs, &rhs)| {
+                let v = op(lane_value(lhs, bits, signed), lane_value(rhs, bits, signed));
+                let (v, clamped) = saturate(v, bits, signed);
+                saturated |= clamped;
+                v
+            })
+            .collect();
+        set_lanes(ctx, dst, lanes);
+
+        if saturated {
+            fp_env.raise(ctx, FpExceptions::SATURATION);
+        }
+        Ok(None)
+    })
+}
+
+fn gen_shl(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty());
+    macro_rules! gen_shl_impl {
+        ($lhs_ty:ty, $rhs_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let lhs: $lhs_ty = ctx.get(lhs);
+                let rhs: $rhs_ty = ctx.get(rhs);
+
+                let v = lhs.wrapping_shl(rhs as u32);
+                ctx.set::<$lhs_ty>(dst, v);
+                ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
+
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match (lhs.ty(), rhs.ty()) {
+        (IrType::B8, IrType::B8) => gen_shl_impl!(u8, u8),
+        (IrType::B16, IrType::B8) => gen_shl_impl!(u16, u8),
+        (IrType::B32, IrType::B8) => gen_shl_impl!(u32, u8),
+        (IrType::B64, IrType::B8) => gen_shl_impl!(u64, u8),
+        (IrType::B128, IrType::B8) => gen_shl_impl!(u128, u8),
+
+        (IrType::Vector(..), _) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, bits| {
+            if rhs < bits as u128 {
+                lhs << rhs
+            } else {
+                0
+            }
+        }),
+
+        _ => unimplemented!("Unsupported type: {:?} << {:?}", lhs.ty(), rhs.ty()),
+    }
+}
+
+fn gen_lshr(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty());
+    macro_rules! gen_lshr_impl {
+        ($lhs_ty:ty, $rhs_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let lhs: $lhs_ty = ctx.get(lhs);
+                let rhs: $rhs_ty = ctx.get(rhs);
+
+                let v = lhs.wrapping_shr(rhs as u32);
+                ctx.set::<$lhs_ty>(dst, v);
+                ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
+
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match (lhs.ty(), rhs.ty()) {
+        (IrType::B8, IrType::B8) => gen_lshr_impl!(u8, u8),
+        (IrType::B16, IrType::B8) => gen_lshr_impl!(u16, u8),
+        (IrType::B32, IrType::B8) => gen_lshr_impl!(u32, u8),
+        (IrType::B64, IrType::B8) => gen_lshr_impl!(u64, u8),
+        (IrType::B128, IrType::B8) => gen_lshr_impl!(u128, u8),
+
+        (IrType::Vector(..), _) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, bits| {
+            if rhs < bits as u128 {
+                lhs >> rhs
+            } else {
+                0
+            }
+        }),
+
+        _ => unimplemented!("Unsupported type: {:?} >> {:?}", lhs.ty(), rhs.ty()),
+    }
+}
+
+fn gen_ashr(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty());
+    macro_rules! gen_ashr_impl {
+        ($lhs_ty:ty, $signed_ty:ty, $rhs_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let lhs: $lhs_ty = ctx.get(lhs);
+                let rhs: $rhs_ty = ctx.get(rhs);
+
+                let v = (lhs as $signed_ty).wrapping_shr(rhs as u32) as $lhs_ty;
+                ctx.set::<$lhs_ty>(dst, v);
+                ctx.set_flag(Flag::ZF, v == 0);
+                ctx.set_flag(Flag::NF, v.leading_zeros() == 0);
+
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match (lhs.ty(), rhs.ty()) {
+        (IrType::B8, IrType::B8) => gen_ashr_impl!(u8, i8, u8),
+        (IrType::B16, IrType::B8) => gen_ashr_impl!(u16, i16, u8),
+        (IrType::B32, IrType::B8) => gen_ashr_impl!(u32, i32, u8),
+        (IrType::B64, IrType::B8) => gen_ashr_impl!(u64, i64, u8),
+        (IrType::B128, IrType::B8) => gen_ashr_impl!(u128, i128, u8),
+
+        (IrType::Vector(..), _) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, bits| {
+            (sext(lhs, bits) >> rhs.min(i128::BITS as u128 - 1)) as u128
+        }),
+
+        _ => unimplemented!("Unsupported type: {:?} >> {:?}", lhs.ty(), rhs.ty()),
+    }
+}
+
+fn gen_rotr(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty());
+    macro_rules! gen_rotr_impl {
+        ($lhs_ty:ty, $rhs_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let lhs: $lhs_ty = ctx.get(lhs);
+                let rhs: $rhs_ty = ctx.get(rhs);
+
+                ctx.set::<$lhs_ty>(dst, lhs.rotate_right(rhs as u32));
+
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match (lhs.ty(), rhs.ty()) {
+        (IrType::B8, IrType::B8) => gen_rotr_impl!(u8, u8),
+        (IrType::B16, IrType::B8) => gen_rotr_impl!(u16, u8),
+        (IrType::B32, IrType::B8) => gen_rotr_impl!(u32, u8),
+        (IrType::B64, IrType::B8) => gen_rotr_impl!(u64, u8),
+        (IrType::B128, IrType::B8) => gen_rotr_impl!(u128, u8),
+
+        (IrType::Vector(..), _) => gen_lanewise(dst, lhs, rhs, |lhs, rhs, bits| {
+            match (rhs % bits as u128) as u32 {
+                0 => lhs,
+                rhs => (lhs >> rhs) | (lhs << (bits - rhs)),
+            }
+        }),
+
+        _ => unimplemented!("Unsupported type: {:?} >> {:?}", lhs.ty(), rhs.ty()),
+    }
+}
+
 fn gen_move_flag(
     dst: IrValue,
     dst_pos: usize,
     flag: Flag,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
         let v = ctx.get_flag(flag) as u64;
         ctx.set::<u64>(dst, v << dst_pos as u64);
-        None
+        Ok(None)
     })
 }
 
+fn gen_cmp(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    cond: Comparison,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(lhs.ty() == rhs.ty());
+    if let IrType::Vector(..) = lhs.ty() {
+        assert!(dst.ty() == lhs.ty());
+        return gen_lanewise(dst, lhs, rhs, move |lhs, rhs, bits| {
+            let holds = match cond {
+                Comparison::Eq => lhs == rhs,
+                Comparison::Ne => lhs != rhs,
+                Comparison::Ult => lhs < rhs,
+                Comparison::Ule => lhs <= rhs,
+                Comparison::Ugt => lhs > rhs,
+                Comparison::Uge => lhs >= rhs,
+                Comparison::Slt => sext(lhs, bits) < sext(rhs, bits),
+                Comparison::Sle => sext(lhs, bits) <= sext(rhs, bits),
+                Comparison::Sgt => sext(lhs, bits) > sext(rhs, bits),
+                Comparison::Sge => sext(lhs, bits) >= sext(rhs, bits),
+            };
+
+            if holds {
+                u128::MAX
+            } else {
+                0
+            }
+        });
+    }
+
+    assert!(dst.ty() == IrType::Bool);
+    macro_rules! gen_cmp_impl {
+        ($ty:ty, $signed_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let lhs: $ty = ctx.get(lhs);
+                let rhs: $ty = ctx.get(rhs);
+
+                let v = match cond {
+                    Comparison::Eq => lhs == rhs,
+                    Comparison::Ne => lhs != rhs,
+                    Comparison::Ult => lhs < rhs,
+                    Comparison::Ule => lhs <= rhs,
+                    Comparison::Ugt => lhs > rhs,
+                    Comparison::Uge => lhs >= rhs,
+                    Comparison::Slt => (lhs as $signed_ty) < (rhs as $signed_ty),
+                    Comparison::Sle => (lhs as $signed_ty) <= (rhs as $signed_ty),
+                    Comparison::Sgt => (lhs as $signed_ty) > (rhs as $signed_ty),
+                    Comparison::Sge => (lhs as $signed_ty) >= (rhs as $signed_ty),
+                };
+                ctx.set::<u8>(dst, v as u8);
+
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match lhs.ty() {
+        IrType::B8 | IrType::Bool => gen_cmp_impl!(u8, i8),
+        IrType::B16 => gen_cmp_impl!(u16, i16),
+        IrType::B32 => gen_cmp_impl!(u32, i32),
+        IrType::B64 => gen_cmp_impl!(u64, i64),
+        IrType::B128 => gen_cmp_impl!(u128, i128),
+
+        _ => unimplemented!("Unsupported type: {:?}", lhs.ty()),
+    }
+}
+
+fn gen_select(
+    dst: IrValue,
+    cond: IrValue,
+    if_true: IrValue,
+    if_false: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(cond.ty() == IrType::Bool);
+    assert!(dst.ty() == if_true.ty() && if_true.ty() == if_false.ty());
+    macro_rules! gen_select_impl {
+        ($ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let v: $ty = if ctx.get::<u8>(cond) != 0 {
+                    ctx.get(if_true)
+                } else {
+                    ctx.get(if_false)
+                };
+                ctx.set::<$ty>(dst, v);
+
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match dst.ty() {
+        IrType::B8 | IrType::Bool => gen_select_impl!(u8),
+        IrType::B16 => gen_select_impl!(u16),
+        IrType::B32 => gen_select_impl!(u32),
+        IrType::B64 => gen_select_impl!(u64),
+        IrType::B128 => gen_select_impl!(u128),
+        IrType::F16 => gen_select_impl!(u16),
+        IrType::F32 => gen_select_impl!(u32),
+        IrType::F64 => gen_select_impl!(u64),
+        IrType::Vector(..) => Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+            let v = if ctx.get::<u8>(cond) != 0 {
+                get_raw(ctx, if_true)
+            } else {
+                get_raw(ctx, if_false)
+            };
+            set_raw(ctx, dst, v);
+
+            Ok(None)
+        }) as Box<_>,
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
 fn gen_assign(
     dst: IrValue,
     src: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     assert!(dst.ty() == src.ty());
     macro_rules! gen_assign_impl {
         ($ty:ty) => {
@@ -699,7 +1748,7 @@ fn gen_assign(
                 let src: $ty = ctx.get(src);
 
                 ctx.set::<$ty>(dst, src);
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -710,6 +1759,13 @@ fn gen_assign(
         IrType::B32 => gen_assign_impl!(u32),
         IrType::B64 => gen_assign_impl!(u64),
         IrType::B128 => gen_assign_impl!(u128),
+        IrType::F16 => gen_assign_impl!(u16),
+        IrType::F32 => gen_assign_impl!(u32),
+        IrType::F64 => gen_assign_impl!(u64),
+        IrType::Vector(..) => Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+            set_raw(ctx, dst, get_raw(ctx, src));
+            Ok(None)
+        }) as Box<_>,
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
@@ -718,7 +1774,7 @@ fn gen_assign(
 fn gen_load(
     dst: IrValue,
     src: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     // TODO: check that size of src is same as pointer size
     macro_rules! gen_load_impl {
         ($src_ty:ty, $dst_ty:ty) => {
@@ -728,12 +1784,12 @@ fn gen_load(
 
                 let mut buf = SmallVec::<[u8; 32]>::new();
                 buf.resize(mem::size_of::<$dst_ty>(), 0);
-                mmu.read_at(src, &mut buf);
+                mmu.read(src, &mut buf, Access::Read)?;
                 ctx.set::<$dst_ty>(
                     dst,
                     <$dst_ty>::from_ne_bytes(buf[..mem::size_of::<$dst_ty>()].try_into().unwrap()),
                 );
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -752,7 +1808,7 @@ fn gen_load(
 fn gen_store(
     dst: IrValue,
     src: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
     macro_rules! gen_store_impl {
         ($src_ty:ty, $dst_ty:ty) => {
             Box::new(move |ctx: &RustjitContext, mmu: &SoftMmu| unsafe {
@@ -760,10 +1816,9 @@ fn gen_store(
                 let dst: $dst_ty = ctx.get(dst);
                 let dst = dst as u64;
 
-                let mut buf = src.to_ne_bytes();
-                mmu.write_at(dst, &mut buf);
+                mmu.write(dst, &src.to_ne_bytes())?;
 
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -779,11 +1834,194 @@ fn gen_store(
     }
 }
 
+fn gen_load_linked(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    macro_rules! gen_load_linked_impl {
+        ($dst_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, mmu: &SoftMmu| unsafe {
+                let src: u64 = ctx.get(src);
+
+                let mut buf = [0u8; mem::size_of::<$dst_ty>()];
+                mmu.ll(src, &mut buf)?;
+                ctx.set::<$dst_ty>(dst, <$dst_ty>::from_ne_bytes(buf));
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match (src.ty(), dst.ty()) {
+        (IrType::B64, IrType::B8) => gen_load_linked_impl!(u8),
+        (IrType::B64, IrType::B16) => gen_load_linked_impl!(u16),
+        (IrType::B64, IrType::B32) => gen_load_linked_impl!(u32),
+        (IrType::B64, IrType::B64) => gen_load_linked_impl!(u64),
+        (IrType::B64, IrType::B128) => gen_load_linked_impl!(u128),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_store_conditional(
+    dst: IrValue,
+    src: IrValue,
+    stored: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(stored.ty() == IrType::Bool);
+    macro_rules! gen_store_conditional_impl {
+        ($src_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, mmu: &SoftMmu| unsafe {
+                let src: $src_ty = ctx.get(src);
+                let dst: u64 = ctx.get(dst);
+
+                let success = mmu.sc(dst, &src.to_ne_bytes())?;
+                ctx.set::<u8>(stored, success as u8);
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match (src.ty(), dst.ty()) {
+        (IrType::B8, IrType::B64) => gen_store_conditional_impl!(u8),
+        (IrType::B16, IrType::B64) => gen_store_conditional_impl!(u16),
+        (IrType::B32, IrType::B64) => gen_store_conditional_impl!(u32),
+        (IrType::B64, IrType::B64) => gen_store_conditional_impl!(u64),
+        (IrType::B128, IrType::B64) => gen_store_conditional_impl!(u128),
+
+        _ => unimplemented!("Unsupported type: {:?}", src.ty()),
+    }
+}
+
+fn gen_clear_link() -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |_: &RustjitContext, mmu: &SoftMmu| {
+        mmu.clear_link();
+        Ok(None)
+    })
+}
+
+/// Fences to put before and after an atomic access with `ordering`.
+fn atomic_fences(ordering: Reordering) -> (Option<Ordering>, Option<Ordering>) {
+    match ordering {
+        Reordering::Relaxed => (None, None),
+        Reordering::Acquire => (None, Some(Ordering::Acquire)),
+        Reordering::Release => (Some(Ordering::Release), None),
+        Reordering::SeqCst => (Some(Ordering::Release), Some(Ordering::SeqCst)),
+    }
+}
+
+fn gen_atomic_rmw(
+    dst: IrValue,
+    addr: IrValue,
+    src: IrValue,
+    op: AtomicOp,
+    ordering: Reordering,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    let (before, after) = atomic_fences(ordering);
+    macro_rules! gen_atomic_rmw_impl {
+        ($ty:ty, $signed_ty:ty) => {{
+            let apply: fn($ty, $ty) -> $ty = match op {
+                AtomicOp::Add => |old, src| old.wrapping_add(src),
+                AtomicOp::AndNot => |old, src| old & !src,
+                AtomicOp::Or => |old, src| old | src,
+                AtomicOp::Xor => |old, src| old ^ src,
+                AtomicOp::Smax => |old, src| (old as $signed_ty).max(src as $signed_ty) as $ty,
+                AtomicOp::Smin => |old, src| (old as $signed_ty).min(src as $signed_ty) as $ty,
+                AtomicOp::Umax => |old, src| old.max(src),
+                AtomicOp::Umin => |old, src| old.min(src),
+                AtomicOp::Swap => |_, src| src,
+            };
+
+            Box::new(move |ctx: &RustjitContext, mmu: &SoftMmu| unsafe {
+                let addr: u64 = ctx.get(addr);
+                let src: $ty = ctx.get(src);
+
+                if let Some(ordering) = before {
+                    atomic::fence(ordering);
+                }
+                let old = mmu.atomic_update(addr, |old| {
+                    Some(apply(<$ty>::from_ne_bytes(old), src).to_ne_bytes())
+                })?;
+                if let Some(ordering) = after {
+                    atomic::fence(ordering);
+                }
+
+                ctx.set::<$ty>(dst, <$ty>::from_ne_bytes(old));
+                Ok(None)
+            }) as Box<_>
+        }};
+    }
+
+    match (addr.ty(), dst.ty()) {
+        (IrType::B64, IrType::B8) => gen_atomic_rmw_impl!(u8, i8),
+        (IrType::B64, IrType::B16) => gen_atomic_rmw_impl!(u16, i16),
+        (IrType::B64, IrType::B32) => gen_atomic_rmw_impl!(u32, i32),
+        (IrType::B64, IrType::B64) => gen_atomic_rmw_impl!(u64, i64),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_compare_exchange(
+    dst: IrValue,
+    addr: IrValue,
+    expected: IrValue,
+    new: IrValue,
+    ordering: Reordering,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == expected.ty() && dst.ty() == new.ty());
+    let (before, after) = atomic_fences(ordering);
+    macro_rules! gen_compare_exchange_impl {
+        ($ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, mmu: &SoftMmu| unsafe {
+                let addr: u64 = ctx.get(addr);
+                let expected: $ty = ctx.get(expected);
+                let new: $ty = ctx.get(new);
+
+                if let Some(ordering) = before {
+                    atomic::fence(ordering);
+                }
+                let old = mmu.atomic_update(addr, |old| {
+                    (<$ty>::from_ne_bytes(old) == expected).then(|| new.to_ne_bytes())
+                })?;
+                if let Some(ordering) = after {
+                    atomic::fence(ordering);
+                }
+
+                ctx.set::<$ty>(dst, <$ty>::from_ne_bytes(old));
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match (addr.ty(), dst.ty()) {
+        (IrType::B64, IrType::B8) => gen_compare_exchange_impl!(u8),
+        (IrType::B64, IrType::B16) => gen_compare_exchange_impl!(u16),
+        (IrType::B64, IrType::B32) => gen_compare_exchange_impl!(u32),
+        (IrType::B64, IrType::B64) => gen_compare_exchange_impl!(u64),
+        (IrType::B64, IrType::B128) => gen_compare_exchange_impl!(u128),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
 fn gen_zext_cast(
     dst: IrValue,
     src: IrValue,
-) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
-    assert!(dst.ty().size_of() >= src.ty().size_of());
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    match (dst.ty(), src.ty()) {
+        (IrType::Vector(_, dst_lanes), IrType::Vector(_, src_lanes)) if dst_lanes == src_lanes => {
+            return gen_lanewise_unary(dst, src, |src, _| src);
+        }
+        (IrType::Vector(..), _) | (_, IrType::Vector(..)) => {
+            return Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                set_raw(ctx, dst, get_raw(ctx, src));
+                Ok(None)
+            });
+        }
+        _ => {}
+    }
+
     macro_rules! gen_zext_cast_impl {
         ($ty:ty) => {
             Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
@@ -793,12 +2031,15 @@ fn gen_zext_cast(
                     IrType::B32 => ctx.get::<u32>(src) as $ty,
                     IrType::B64 => ctx.get::<u64>(src) as $ty,
                     IrType::B128 => ctx.get::<u128>(src) as $ty,
+                    IrType::F16 => ctx.get::<u16>(src) as $ty,
+                    IrType::F32 => ctx.get::<u32>(src) as $ty,
+                    IrType::F64 => ctx.get::<u64>(src) as $ty,
 
                     _ => unimplemented!("Unsupported type: {:?}", src.ty()),
                 };
 
                 ctx.set::<$ty>(dst, src_ext);
-                None
+                Ok(None)
             }) as Box<_>
         };
     }
@@ -809,11 +2050,514 @@ fn gen_zext_cast(
         IrType::B32 => gen_zext_cast_impl!(u32),
         IrType::B64 => gen_zext_cast_impl!(u64),
         IrType::B128 => gen_zext_cast_impl!(u128),
+        IrType::F16 => gen_zext_cast_impl!(u16),
+        IrType::F32 => gen_zext_cast_impl!(u32),
+        IrType::F64 => gen_zext_cast_impl!(u64),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_sext_cast(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty().size_of() >= src.ty().size_of());
+    if let (IrType::Vector(_, dst_lanes), IrType::Vector(_, src_lanes)) = (dst.ty(), src.ty()) {
+        assert!(dst_lanes == src_lanes);
+        return gen_lanewise_unary(dst, src, |src, bits| sext(src, bits) as u128);
+    }
+
+    macro_rules! gen_sext_cast_impl {
+        ($ty:ty, $signed_ty:ty) => {
+            Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+                let src_ext = match src.ty() {
+                    IrType::B8 => ctx.get::<u8>(src) as i8 as $signed_ty,
+                    IrType::B16 => ctx.get::<u16>(src) as i16 as $signed_ty,
+                    IrType::B32 => ctx.get::<u32>(src) as i32 as $signed_ty,
+                    IrType::B64 => ctx.get::<u64>(src) as i64 as $signed_ty,
+                    IrType::B128 => ctx.get::<u128>(src) as i128 as $signed_ty,
+
+                    _ => unimplemented!("Unsupported type: {:?}", src.ty()),
+                };
+
+                ctx.set::<$ty>(dst, src_ext as $ty);
+                Ok(None)
+            }) as Box<_>
+        };
+    }
+
+    match dst.ty() {
+        IrType::B8 => gen_sext_cast_impl!(u8, i8),
+        IrType::B16 => gen_sext_cast_impl!(u16, i16),
+        IrType::B32 => gen_sext_cast_impl!(u32, i32),
+        IrType::B64 => gen_sext_cast_impl!(u64, i64),
+        IrType::B128 => gen_sext_cast_impl!(u128, i128),
+
+        _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
+    }
+}
+
+fn gen_trunc_sat(
+    dst: IrValue,
+    src: IrValue,
+    src_signed: bool,
+    dst_signed: bool,
+    fp_env: FpEnv,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let (lanes, src_bits) = lane_layout(src.ty());
+    let (dst_lanes, dst_bits) = lane_layout(dst.ty());
+    assert!(lanes == dst_lanes && dst_bits <= 64);
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let mut saturated = false;
+        let lanes: Lanes = get_lanes(ctx, src)
+            .into_iter()
+            .map(|src| {
+                let (v, clamped) =
+                    saturate(lane_value(src, src_bits, src_signed), dst_bits, dst_signed);
+                saturated |= clamped;
+                v
+            })
+            .collect();
+        set_lanes(ctx, dst, lanes);
+
+        if saturated {
+            fp_env.raise(ctx, FpExceptions::SATURATION);
+        }
+        Ok(None)
+    })
+}
+
+fn gen_splat(
+    dst: IrValue,
+    src: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let (lanes, _) = lane_layout(dst.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        set_lanes(ctx, dst, iter::repeat_n(get_raw(ctx, src), lanes));
+        Ok(None)
+    })
+}
+
+fn gen_shuffle(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    lanes: Vec<u8>,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let mut src = get_lanes(ctx, lhs);
+        src.extend(get_lanes(ctx, rhs));
+
+        set_lanes(ctx, dst, lanes.iter().map(|&lane| src[lane as usize]));
+        Ok(None)
+    })
+}
+
+fn gen_swizzle(
+    dst: IrValue,
+    src: IrValue,
+    indices: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let src = get_lanes(ctx, src);
+        let lanes = get_lanes(ctx, indices).into_iter().map(|index| {
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| src.get(index).copied())
+                .unwrap_or(0)
+        });
+
+        set_lanes(ctx, dst, lanes);
+        Ok(None)
+    })
+}
+
+fn gen_extract_lane(
+    dst: IrValue,
+    src: IrValue,
+    lane: u8,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        set_raw(ctx, dst, get_lanes(ctx, src)[lane as usize]);
+        Ok(None)
+    })
+}
+
+fn gen_insert_lane(
+    dst: IrValue,
+    src: IrValue,
+    value: IrValue,
+    lane: u8,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let mut lanes = get_lanes(ctx, src);
+        lanes[lane as usize] = get_raw(ctx, value);
+
+        set_lanes(ctx, dst, lanes);
+        Ok(None)
+    })
+}
+
+/// Lanes of a vector, or the single lane of a scalar, zero extended to 128 bits.
+type Lanes = SmallVec<[u128; 16]>;
+
+/// Number of lanes of `ty` and their width in bits, a scalar is a single lane.
+fn lane_layout(ty: IrType) -> (usize, u32) {
+    match ty {
+        IrType::Vector(lane, lanes) => (lanes as usize, lane.size_of() as u32 * 8),
+        ty => (1, ty.size_of() as u32 * 8),
+    }
+}
+
+/// Read all bits of a value of any type.
+fn get_raw(ctx: &RustjitContext, value: IrValue) -> u128 {
+    match value.ty().size_of() {
+        1 => ctx.get::<u8>(value) as u128,
+        2 => ctx.get::<u16>(value) as u128,
+        4 => ctx.get::<u32>(value) as u128,
+        8 => ctx.get::<u64>(value) as u128,
+        16 => ctx.get::<u128>(value),
+
+        _ => unimplemented!("Unsupported type: {:?}", value.ty()),
+    }
+}
+
+/// Write all bits of a value of any type, `bits` is truncated to its size.
+fn set_raw(ctx: &RustjitContext, dst: IrValue, bits: u128) {
+    match dst.ty().size_of() {
+        1 => ctx.set(dst, bits as u8),
+        2 => ctx.set(dst, bits as u16),
+        4 => ctx.set(dst, bits as u32),
+        8 => ctx.set(dst, bits as u64),
+        16 => ctx.set(dst, bits),
 
         _ => unimplemented!("Unsupported type: {:?}", dst.ty()),
     }
 }
 
-fn gen_fence() -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Option<Interrupt>> {
-    Box::new(move |_: &RustjitContext, _: &SoftMmu| None)
+fn lane_mask(bits: u32) -> u128 {
+    u128::MAX >> (u128::BITS - bits)
+}
+
+fn get_lanes(ctx: &RustjitContext, value: IrValue) -> Lanes {
+    let (lanes, bits) = lane_layout(value.ty());
+    let raw = get_raw(ctx, value);
+
+    (0..lanes as u32)
+        .map(|i| raw.checked_shr(i * bits).unwrap_or(0) & lane_mask(bits))
+        .collect()
+}
+
+/// Write `lanes` to `dst`, every lane is truncated to the width of the lanes of `dst`.
+fn set_lanes(ctx: &RustjitContext, dst: IrValue, lanes: impl IntoIterator<Item = u128>) {
+    let (_, bits) = lane_layout(dst.ty());
+    let raw = lanes
+        .into_iter()
+        .zip((0..).step_by(bits as usize))
+        .fold(0, |raw, (lane, shift)| {
+            raw | (lane & lane_mask(bits)) << shift
+        });
+
+    set_raw(ctx, dst, raw);
+}
+
+/// Sign extend the `bits` wide `value`.
+fn sext(value: u128, bits: u32) -> i128 {
+    ((value << (u128::BITS - bits)) as i128) >> (u128::BITS - bits)
+}
+
+fn lane_value(value: u128, bits: u32, signed: bool) -> i128 {
+    if signed {
+        sext(value, bits)
+    } else {
+        value as i128
+    }
+}
+
+/// Clamp `value` to the range of a `bits` wide integer, returns whether it was out of range.
+fn saturate(value: i128, bits: u32, signed: bool) -> (u128, bool) {
+    let (min, max) = if signed {
+        (-1 << (bits - 1), (1 << (bits - 1)) - 1)
+    } else {
+        (0, (1 << bits) - 1)
+    };
+
+    let clamped = value.clamp(min, max);
+    (clamped as u128, clamped != value)
+}
+
+/// Apply `op` to every pair of lanes of `lhs` and `rhs`, a scalar `rhs` is paired with every
+/// lane. `op` gets the width of the lanes of `lhs` as well.
+fn gen_lanewise(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    op: impl Fn(u128, u128, u32) -> u128 + 'static,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let (_, bits) = lane_layout(lhs.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let lhs = get_lanes(ctx, lhs);
+        let rhs = get_lanes(ctx, rhs);
+
+        let lanes = lhs
+            .iter()
+            .enumerate()
+            .map(|(i, &lhs)| op(lhs, rhs[i % rhs.len()], bits));
+        set_lanes(ctx, dst, lanes);
+        Ok(None)
+    })
+}
+
+/// Apply `op` to every lane of `src`, `op` gets the width of the lanes of `src` as well.
+fn gen_lanewise_unary(
+    dst: IrValue,
+    src: IrValue,
+    op: impl Fn(u128, u32) -> u128 + 'static,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let (_, bits) = lane_layout(src.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let lanes = get_lanes(ctx, src).into_iter().map(|src| op(src, bits));
+        set_lanes(ctx, dst, lanes);
+        Ok(None)
+    })
+}
+
+fn gen_fp_binary(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    fp_env: FpEnv,
+    op: fn(&mut SoftFp, FpFormat, u64, u64) -> u64,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty());
+    let fmt = FpFormat::of(dst.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let lhs = get_lanes(ctx, lhs);
+        let rhs = get_lanes(ctx, rhs);
+
+        let result: Lanes = fp_env.run(ctx, |fp| {
+            iter::zip(lhs, rhs)
+                .map(|(lhs, rhs)| op(fp, fmt, lhs as u64, rhs as u64) as u128)
+                .collect()
+        });
+        set_lanes(ctx, dst, result);
+        Ok(None)
+    })
+}
+
+fn gen_fma(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    addend: IrValue,
+    fp_env: FpEnv,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == lhs.ty() && lhs.ty() == rhs.ty() && rhs.ty() == addend.ty());
+    let fmt = FpFormat::of(dst.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let lhs = get_lanes(ctx, lhs);
+        let rhs = get_lanes(ctx, rhs);
+        let addend = get_lanes(ctx, addend);
+
+        let result: Lanes = fp_env.run(ctx, |fp| {
+            (0..lhs.len())
+                .map(|i| fp.fma(fmt, lhs[i] as u64, rhs[i] as u64, addend[i] as u64) as u128)
+                .collect()
+        });
+        set_lanes(ctx, dst, result);
+        Ok(None)
+    })
+}
+
+fn gen_fp_unary(
+    dst: IrValue,
+    src: IrValue,
+    fp_env: FpEnv,
+    op: impl Fn(&mut SoftFp, FpFormat, u64) -> u64 + 'static,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    let fmt = FpFormat::of(dst.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let src = get_lanes(ctx, src);
+
+        let result: Lanes = fp_env.run(ctx, |fp| {
+            src.into_iter()
+                .map(|src| op(fp, fmt, src as u64) as u128)
+                .collect()
+        });
+        set_lanes(ctx, dst, result);
+        Ok(None)
+    })
+}
+
+/// Generate an operation on the sign bit, which neither processes NaNs nor raises exceptions.
+fn gen_fp_sign(
+    dst: IrValue,
+    src: IrValue,
+    op: fn(FpFormat, u64) -> u64,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(dst.ty() == src.ty());
+    let fmt = FpFormat::of(dst.ty());
+
+    gen_lanewise_unary(dst, src, move |src, _| op(fmt, src as u64) as u128)
+}
+
+fn gen_fp_cast(
+    dst: IrValue,
+    src: IrValue,
+    rounding: FpRounding,
+    fp_env: FpEnv,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let from = FpFormat::of(src.ty());
+    let to = FpFormat::of(dst.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let src = get_lanes(ctx, src);
+
+        let result: Lanes = fp_env.run(ctx, |fp| {
+            src.into_iter()
+                .map(|src| fp.convert(from, to, src as u64, rounding) as u128)
+                .collect()
+        });
+        set_lanes(ctx, dst, result);
+        Ok(None)
+    })
+}
+
+fn gen_fp_to_int(
+    dst: IrValue,
+    src: IrValue,
+    signed: bool,
+    frac_bits: u8,
+    rounding: FpRounding,
+    fp_env: FpEnv,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let fmt = FpFormat::of(src.ty());
+    let (_, width) = lane_layout(dst.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let src = get_lanes(ctx, src);
+
+        let result: Lanes = fp_env.run(ctx, |fp| {
+            src.into_iter()
+                .map(|src| fp.to_int(fmt, src as u64, width, signed, frac_bits, rounding) as u128)
+                .collect()
+        });
+        set_lanes(ctx, dst, result);
+        Ok(None)
+    })
+}
+
+fn gen_int_to_fp(
+    dst: IrValue,
+    src: IrValue,
+    signed: bool,
+    frac_bits: u8,
+    fp_env: FpEnv,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let fmt = FpFormat::of(dst.ty());
+    let (_, width) = lane_layout(src.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let src = get_lanes(ctx, src);
+
+        let result: Lanes = fp_env.run(ctx, |fp| {
+            src.into_iter()
+                .map(|src| fp.from_int(fmt, src as u64, width, signed, frac_bits) as u128)
+                .collect()
+        });
+        set_lanes(ctx, dst, result);
+        Ok(None)
+    })
+}
+
+/// Compare into a `IrType::Bool`, or into lanes of all ones or zero for vectors.
+fn gen_fcmp(
+    dst: IrValue,
+    lhs: IrValue,
+    rhs: IrValue,
+    cond: FpComparison,
+    signaling: bool,
+    fp_env: FpEnv,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    assert!(lhs.ty() == rhs.ty());
+    assert!(dst.ty() == IrType::Bool || lane_layout(dst.ty()) == lane_layout(lhs.ty()));
+    let fmt = FpFormat::of(lhs.ty());
+    let (_, bits) = lane_layout(dst.ty());
+
+    Box::new(move |ctx: &RustjitContext, _: &SoftMmu| {
+        let lhs = get_lanes(ctx, lhs);
+        let rhs = get_lanes(ctx, rhs);
+
+        let holds: SmallVec<[bool; 16]> = fp_env.run(ctx, |fp| {
+            iter::zip(lhs, rhs)
+                .map(|(lhs, rhs)| {
+                    let ordering = fp.compare(fmt, lhs as u64, rhs as u64, signaling);
+                    match cond {
+                        FpComparison::Eq => ordering == Some(cmp::Ordering::Equal),
+                        FpComparison::Lt => ordering == Some(cmp::Ordering::Less),
+                        FpComparison::Le => ordering.is_some_and(|ordering| ordering.is_le()),
+                        FpComparison::Unordered => ordering.is_none(),
+                    }
+                })
+                .collect()
+        });
+
+        match dst.ty() {
+            IrType::Bool => ctx.set::<u8>(dst, holds[0] as u8),
+            _ => set_lanes(
+                ctx,
+                dst,
+                holds.into_iter().map(|v| v as u128 * lane_mask(bits)),
+            ),
+        }
+        Ok(None)
+    })
+}
+
+fn gen_fence(
+    ordering: Reordering,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    let ordering = match ordering {
+        Reordering::Relaxed => return Box::new(move |_: &RustjitContext, _: &SoftMmu| Ok(None)),
+        Reordering::Acquire => Ordering::Acquire,
+        Reordering::Release => Ordering::Release,
+        Reordering::SeqCst => Ordering::SeqCst,
+    };
+
+    Box::new(move |_: &RustjitContext, _: &SoftMmu| {
+        atomic::fence(ordering);
+        Ok(None)
+    })
+}
+
+fn gen_interrupt(
+    interrupt: Interrupt,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |_: &RustjitContext, _: &SoftMmu| Ok(Some(interrupt.clone())))
+}
+
+fn gen_invalidate_code(
+    addr: IrValue,
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |ctx: &RustjitContext, mmu: &SoftMmu| {
+        mmu.invalidate_code(ctx.get::<u64>(addr));
+        Ok(None)
+    })
+}
+
+fn gen_invalidate_all_code(
+) -> Box<dyn Fn(&RustjitContext, &SoftMmu) -> Result<Option<Interrupt>, Error>> {
+    Box::new(move |_: &RustjitContext, mmu: &SoftMmu| {
+        mmu.invalidate_all_code();
+        Ok(None)
+    })
 }
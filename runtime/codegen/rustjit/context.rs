@@ -9,19 +9,25 @@ use crate::codegen::{Context, ValueView};
 use super::RegisterFile;
 
 pub struct RustjitContext {
-    pub(super) registers: RegisterFile,
-    pub(super) variables: RefCell<Option<Box<[Cell<u128>]>>>,
-    pub(super) flag: Box<[Cell<bool>]>,
+    pub(crate) registers: RegisterFile,
+    pub(crate) variables: RefCell<Option<Box<[Cell<u128>]>>>,
+    pub(crate) flag: Box<[Cell<bool>]>,
 }
 
 impl Context for RustjitContext {
     #[inline(always)]
     fn get<T: ValueView>(&self, value: IrValue) -> T {
         match value {
-            IrValue::Constant(IrConstant::B8(value)) => T::from_bytes(&[value]),
-            IrValue::Constant(IrConstant::B16(value)) => T::from_bytes(&value.into_bytes()),
-            IrValue::Constant(IrConstant::B32(value)) => T::from_bytes(&value.into_bytes()),
-            IrValue::Constant(IrConstant::B64(value)) => T::from_bytes(&value.into_bytes()),
+            IrValue::Constant(constant) => {
+                // Constants are zero extended to the width they are read as
+                let value = match constant {
+                    IrConstant::B8(value) => value as u128,
+                    IrConstant::B16(value) => value as u128,
+                    IrConstant::B32(value) => value as u128,
+                    IrConstant::B64(value) => value as u128,
+                };
+                T::from_bytes(&value.into_bytes()[..mem::size_of::<T>()])
+            }
             IrValue::Register(_, id) => self.registers.get(id),
             IrValue::Variable(_, id) => {
                 let variable = self.variables.borrow().as_ref().unwrap()[id].get();
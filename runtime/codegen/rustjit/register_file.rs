@@ -19,6 +19,11 @@ impl RegisterFile {
         }
     }
 
+    /// Get raw pointer to the start of the register file.
+    pub fn as_mut_ptr(&self) -> *mut u8 {
+        unsafe { (*self.file.get()).as_mut_ptr() }
+    }
+
     /// Get reference of the the register as T
     ///
     /// This function will panic if the size of T and the register size does not match.
@@ -0,0 +1,1298 @@
+use core::{
+    ir::{FpControl, FpExceptions, FpRounding, IrType, IrValue, VecTy},
+    Architecture, Register,
+};
+use std::cmp::Ordering;
+
+use super::Context;
+
+/// Registers holding the floating-point environment of an architecture.
+#[derive(Debug, Clone, Copy)]
+pub struct FpEnv {
+    control: IrValue,
+    status: IrValue,
+}
+
+impl FpEnv {
+    pub fn new<A: Architecture>() -> Self {
+        Self {
+            control: IrValue::Register(IrType::B64, A::get_fp_control_register().raw()),
+            status: IrValue::Register(IrType::B64, A::get_fp_status_register().raw()),
+        }
+    }
+
+    /// Run `op` with the control of `ctx` and accumulate the exceptions it raised.
+    pub fn run<T>(self, ctx: &impl Context, op: impl FnOnce(&mut SoftFp) -> T) -> T {
+        let mut fp = SoftFp::new(FpControl::from_bits(ctx.get(self.control)));
+        let result = op(&mut fp);
+        self.raise(ctx, fp.exceptions());
+
+        result
+    }
+
+    /// Accumulate `exceptions` into the status of `ctx`.
+    pub fn raise(self, ctx: &impl Context, exceptions: FpExceptions) {
+        if exceptions != FpExceptions::NONE {
+            let status: u64 = ctx.get(self.status);
+            ctx.set(self.status, status | exceptions.bits());
+        }
+    }
+}
+
+/// Layout of a binary floating-point format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FpFormat {
+    exp_bits: u32,
+    frac_bits: u32,
+}
+
+impl FpFormat {
+    pub const F16: Self = Self {
+        exp_bits: 5,
+        frac_bits: 10,
+    };
+    pub const F32: Self = Self {
+        exp_bits: 8,
+        frac_bits: 23,
+    };
+    pub const F64: Self = Self {
+        exp_bits: 11,
+        frac_bits: 52,
+    };
+
+    /// Format of the floating-point type `ty`, or of its lanes if it is a vector.
+    pub fn of(ty: IrType) -> Self {
+        match ty {
+            IrType::F16 | IrType::Vector(VecTy::F16, _) => Self::F16,
+            IrType::F32 | IrType::Vector(VecTy::F32, _) => Self::F32,
+            IrType::F64 | IrType::Vector(VecTy::F64, _) => Self::F64,
+            _ => unimplemented!("Unsupported type: {:?}", ty),
+        }
+    }
+
+    /// `bits` with the sign flipped.
+    pub fn neg(self, bits: u64) -> u64 {
+        bits ^ self.sign_bit()
+    }
+
+    /// `bits` with the sign cleared.
+    pub fn abs(self, bits: u64) -> u64 {
+        bits & !self.sign_bit()
+    }
+
+    fn sign_bit(self) -> u64 {
+        1 << (self.exp_bits + self.frac_bits)
+    }
+
+    fn exp_mask(self) -> u64 {
+        ((1 << self.exp_bits) - 1) << self.frac_bits
+    }
+
+    fn frac_mask(self) -> u64 {
+        (1 << self.frac_bits) - 1
+    }
+
+    fn quiet_bit(self) -> u64 {
+        1 << (self.frac_bits - 1)
+    }
+
+    /// Biased exponent of infinities and NaNs.
+    fn max_exp(self) -> i32 {
+        (1 << self.exp_bits) - 1
+    }
+
+    fn bias(self) -> i32 {
+        (1 << (self.exp_bits - 1)) - 1
+    }
+
+    fn zero(self, sign: bool) -> u64 {
+        if sign {
+            self.sign_bit()
+        } else {
+            0
+        }
+    }
+
+    fn infinity(self, sign: bool) -> u64 {
+        self.zero(sign) | self.exp_mask()
+    }
+
+    fn max_normal(self, sign: bool) -> u64 {
+        self.infinity(sign) - 1
+    }
+
+    fn default_nan(self) -> u64 {
+        self.exp_mask() | self.quiet_bit()
+    }
+
+    fn is_quiet_nan(self, bits: u64) -> bool {
+        bits & self.exp_mask() == self.exp_mask() && bits & self.quiet_bit() != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Zero,
+    Finite,
+    Infinity,
+    QuietNan,
+    SignalingNan,
+}
+
+/// An operand split into its parts, a finite value is `mant * 2^exp`.
+#[derive(Debug, Clone, Copy)]
+struct Unpacked {
+    class: Class,
+    sign: bool,
+    mant: u128,
+    exp: i32,
+}
+
+impl Unpacked {
+    fn is_nan(&self) -> bool {
+        matches!(self.class, Class::QuietNan | Class::SignalingNan)
+    }
+
+    /// The magnitude as a key that orders like the value does.
+    fn magnitude(&self) -> (u8, i32, u128) {
+        match self.class {
+            Class::Zero => (0, 0, 0),
+            Class::Finite => {
+                let msb = 127 - self.mant.leading_zeros() as i32;
+                (1, self.exp + msb, self.mant << (127 - msb))
+            }
+            _ => (2, 0, 0),
+        }
+    }
+
+    /// Compare the values of two operands that are not NaNs, zeroes are equal.
+    fn cmp_value(&self, other: &Self) -> Ordering {
+        let negative = |v: &Self| v.sign && v.class != Class::Zero;
+
+        match (negative(self), negative(other)) {
+            (false, false) => self.magnitude().cmp(&other.magnitude()),
+            (true, true) => other.magnitude().cmp(&self.magnitude()),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+/// How far a value is from the integer below it, in units of that integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fraction {
+    Zero,
+    BelowHalf,
+    Half,
+    AboveHalf,
+}
+
+/// Split `mant`, plus less than one if `sticky` is set, into its bits above `shift` and the
+/// fraction below them.
+fn split(mant: u128, shift: i32, sticky: bool) -> (u128, Fraction) {
+    if shift <= 0 {
+        debug_assert!(!sticky, "bits below the last place are lost");
+        return (mant << -shift, Fraction::Zero);
+    }
+    if shift > 128 {
+        return (0, Fraction::BelowHalf);
+    }
+
+    let (int, rem) = if shift == 128 {
+        (0, mant)
+    } else {
+        (mant >> shift, mant & ((1 << shift) - 1))
+    };
+    let half = 1 << (shift - 1);
+
+    let fraction = match rem.cmp(&half) {
+        _ if rem == 0 && !sticky => Fraction::Zero,
+        Ordering::Less => Fraction::BelowHalf,
+        Ordering::Equal if !sticky => Fraction::Half,
+        _ => Fraction::AboveHalf,
+    };
+    (int, fraction)
+}
+
+/// Whether the magnitude `int` with `fraction` is rounded up to the next integer.
+fn round_up(rounding: FpRounding, sign: bool, int: u128, fraction: Fraction) -> bool {
+    match rounding {
+        FpRounding::TiesToEven => {
+            fraction == Fraction::AboveHalf || (fraction == Fraction::Half && int & 1 == 1)
+        }
+        FpRounding::TiesToAway => matches!(fraction, Fraction::Half | Fraction::AboveHalf),
+        FpRounding::TowardPositive => fraction != Fraction::Zero && !sign,
+        FpRounding::TowardNegative => fraction != Fraction::Zero && sign,
+        FpRounding::TowardZero | FpRounding::ToOdd => false,
+        FpRounding::Current => unreachable!("the current rounding mode is resolved first"),
+    }
+}
+
+/// The biased exponent of `bits` and its fraction widened to the 52 bits of double precision.
+fn fraction52(fmt: FpFormat, bits: u64) -> (i32, u64) {
+    let exp = ((bits & fmt.exp_mask()) >> fmt.frac_bits) as i32;
+    (exp, (bits & fmt.frac_mask()) << (52 - fmt.frac_bits))
+}
+
+/// RecipEstimate, the reciprocal of `a / 512` in units of 1/256 for `256 <= a < 512`.
+fn recip_estimate(a: u64) -> u64 {
+    let a = a * 2 + 1;
+    let b = (1 << 19) / a;
+    b.div_ceil(2)
+}
+
+/// RecipSqrtEstimate, the reciprocal square root of `a / 512` in units of 1/256 for
+/// `128 <= a < 512`.
+fn rsqrt_estimate(a: u64) -> u64 {
+    // In units of 1/512 below 0.5, and of 1/256 above it
+    let a = if a < 256 {
+        a * 2 + 1
+    } else {
+        ((a & !1) + 1) * 2
+    };
+
+    // The largest b below 2^14 / sqrt(a)
+    let mut b = 512;
+    while a * (b + 1) * (b + 1) < 1 << 28 {
+        b += 1;
+    }
+    b.div_ceil(2)
+}
+
+/// URECPE, the estimate of the reciprocal of the unsigned fraction `src / 2^32`. Fractions below
+/// one half give all ones.
+pub fn unsigned_recip_estimate(src: u32) -> u32 {
+    match src >> 31 {
+        0 => u32::MAX,
+        _ => (recip_estimate(src as u64 >> 23) as u32) << 23,
+    }
+}
+
+/// URSQRTE, the estimate of the reciprocal square root of the unsigned fraction `src / 2^32`.
+/// Fractions below one quarter give all ones.
+pub fn unsigned_rsqrt_estimate(src: u32) -> u32 {
+    match src >> 30 {
+        0 => u32::MAX,
+        _ => (rsqrt_estimate(src as u64 >> 23) as u32) << 23,
+    }
+}
+
+/// A signed term `mant * 2^exp`.
+type Term = (bool, u128, i32);
+
+/// Add two terms, as the sign, mantissa, exponent and whether bits were lost below the
+/// mantissa. The mantissa is zero if the terms cancel out exactly.
+fn add_terms(lhs: Term, rhs: Term) -> (bool, u128, i32, bool) {
+    // Align the leading bits below the top, so sums cannot overflow and a difference that
+    // loses bits keeps plenty of them.
+    let normalize = |(sign, mant, exp): Term| {
+        let shift = mant.leading_zeros() as i32 - 2;
+        (sign, mant << shift, exp - shift)
+    };
+
+    match (lhs.1, rhs.1) {
+        (0, _) => return (rhs.0, rhs.1, rhs.2, false),
+        (_, 0) => return (lhs.0, lhs.1, lhs.2, false),
+        _ => {}
+    }
+
+    let (lhs, rhs) = (normalize(lhs), normalize(rhs));
+    let ((sign, big, exp), (small_sign, small, small_exp)) = if lhs.2 >= rhs.2 {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+
+    let (small, sticky) = match exp - small_exp {
+        d if d >= 128 => (0, true),
+        d => (small >> d, small & ((1 << d) - 1) != 0),
+    };
+
+    if sign == small_sign {
+        (sign, big + small, exp, sticky)
+    } else if big >= small {
+        // Bits lost from the subtrahend make the difference a little smaller. They are only
+        // lost if it is far smaller than `big`, so this cannot reach zero.
+        (sign, big - small - sticky as u128, exp, sticky)
+    } else {
+        (small_sign, small - big, exp, false)
+    }
+}
+
+/// Floating-point operations of the IR on the bits of their operands.
+///
+/// Operations round and treat denormals and NaNs according to the control the `SoftFp` is
+/// created with, and accumulate the exceptions they raise.
+pub struct SoftFp {
+    control: FpControl,
+    exceptions: FpExceptions,
+    /// Conversions do not flush half-precision values and may use the alternative format
+    converting: bool,
+}
+
+impl SoftFp {
+    pub fn new(control: FpControl) -> Self {
+        Self {
+            control,
+            exceptions: FpExceptions::NONE,
+            converting: false,
+        }
+    }
+
+    pub fn exceptions(&self) -> FpExceptions {
+        self.exceptions
+    }
+
+    fn raise(&mut self, exceptions: FpExceptions) {
+        self.exceptions |= exceptions;
+    }
+
+    fn rounding(&self, rounding: FpRounding) -> FpRounding {
+        match rounding {
+            FpRounding::Current => self.control.rounding(),
+            rounding => rounding,
+        }
+    }
+
+    /// Whether denormals of `fmt` are flushed to zero.
+    fn flushes(&self, fmt: FpFormat) -> bool {
+        if fmt == FpFormat::F16 {
+            self.control.flush_half_to_zero() && !self.converting
+        } else {
+            self.control.flush_to_zero()
+        }
+    }
+
+    /// Whether `fmt` is the alternative half-precision format, which has no infinities or
+    /// NaNs and uses the largest exponent for normal values.
+    fn alternative(&self, fmt: FpFormat) -> bool {
+        fmt == FpFormat::F16 && self.converting && self.control.alternative_half()
+    }
+
+    fn unpack(&mut self, fmt: FpFormat, bits: u64) -> Unpacked {
+        let sign = bits & fmt.sign_bit() != 0;
+        let exp = ((bits & fmt.exp_mask()) >> fmt.frac_bits) as i32;
+        let frac = bits & fmt.frac_mask();
+
+        let (class, mant, exp) = if exp == 0 {
+            if frac == 0 {
+                (Class::Zero, 0, 0)
+            } else if self.flushes(fmt) {
+                // Flushing half-precision operands raises nothing
+                if fmt != FpFormat::F16 {
+                    self.raise(FpExceptions::INPUT_DENORMAL);
+                }
+                (Class::Zero, 0, 0)
+            } else {
+                (Class::Finite, frac, 1 - fmt.bias() - fmt.frac_bits as i32)
+            }
+        } else if exp == fmt.max_exp() && !self.alternative(fmt) {
+            if frac == 0 {
+                (Class::Infinity, 0, 0)
+            } else if bits & fmt.quiet_bit() != 0 {
+                (Class::QuietNan, 0, 0)
+            } else {
+                (Class::SignalingNan, 0, 0)
+            }
+        } else {
+            let mant = frac | 1 << fmt.frac_bits;
+            (Class::Finite, mant, exp - fmt.bias() - fmt.frac_bits as i32)
+        };
+
+        Unpacked {
+            class,
+            sign,
+            mant: mant as u128,
+            exp,
+        }
+    }
+
+    /// The NaN `bits` made quiet, or the default NaN if that is enabled.
+    fn process_nan(&mut self, fmt: FpFormat, bits: u64) -> u64 {
+        if !fmt.is_quiet_nan(bits) {
+            self.raise(FpExceptions::INVALID);
+        }
+
+        if self.control.default_nan() {
+            fmt.default_nan()
+        } else {
+            bits | fmt.quiet_bit()
+        }
+    }
+
+    /// The result of operands that include a NaN, the first signaling NaN wins over the first
+    /// quiet one.
+    fn process_nans(&mut self, fmt: FpFormat, operands: &[(Unpacked, u64)]) -> Option<u64> {
+        let nan = operands
+            .iter()
+            .find(|(op, _)| op.class == Class::SignalingNan)
+            .or_else(|| operands.iter().find(|(op, _)| op.class == Class::QuietNan))?;
+
+        Some(self.process_nan(fmt, nan.1))
+    }
+
+    /// The default NaN of an invalid operation.
+    fn invalid(&mut self, fmt: FpFormat) -> u64 {
+        self.raise(FpExceptions::INVALID);
+        fmt.default_nan()
+    }
+
+    /// Round the nonzero value `mant * 2^exp` to `fmt`. If `sticky` is set, bits were lost
+    /// below `mant` and it has to be wider than the significand.
+    fn round(
+        &mut self,
+        fmt: FpFormat,
+        sign: bool,
+        mant: u128,
+        exp: i32,
+        sticky: bool,
+        rounding: FpRounding,
+    ) -> u64 {
+        let rounding = self.rounding(rounding);
+        let frac_bits = fmt.frac_bits as i32;
+        let min_exp = 1 - fmt.bias();
+
+        // The exponent of the value as 1.x * 2^exponent
+        let exponent = exp + 127 - mant.leading_zeros() as i32;
+        if self.flushes(fmt) && exponent < min_exp {
+            self.raise(FpExceptions::UNDERFLOW);
+            return fmt.zero(sign);
+        }
+
+        // Denormals are rounded at the last place of the smallest normal
+        let mut biased_exp = (exponent - min_exp + 1).max(0);
+        let (mut int_mant, fraction) = split(mant, exponent.max(min_exp) - frac_bits - exp, sticky);
+
+        // Tininess is detected before rounding
+        if biased_exp == 0 && fraction != Fraction::Zero {
+            self.raise(FpExceptions::UNDERFLOW);
+        }
+
+        if rounding == FpRounding::ToOdd && fraction != Fraction::Zero {
+            int_mant |= 1;
+        } else if round_up(rounding, sign, int_mant, fraction) {
+            int_mant += 1;
+            if int_mant == 1 << frac_bits {
+                biased_exp = 1;
+            }
+            if int_mant == 1 << (frac_bits + 1) {
+                biased_exp += 1;
+                int_mant >>= 1;
+            }
+        }
+
+        if self.alternative(fmt) {
+            // The alternative format saturates to its largest value instead of overflowing
+            if biased_exp > fmt.max_exp() {
+                self.raise(FpExceptions::INVALID);
+                return fmt.zero(sign) | (fmt.sign_bit() - 1);
+            }
+        } else if biased_exp >= fmt.max_exp() {
+            self.raise(FpExceptions::OVERFLOW | FpExceptions::INEXACT);
+            let overflow_to_infinity = match rounding {
+                FpRounding::TowardPositive => !sign,
+                FpRounding::TowardNegative => sign,
+                FpRounding::TowardZero | FpRounding::ToOdd => false,
+                _ => true,
+            };
+            return if overflow_to_infinity {
+                fmt.infinity(sign)
+            } else {
+                fmt.max_normal(sign)
+            };
+        }
+
+        if fraction != Fraction::Zero {
+            self.raise(FpExceptions::INEXACT);
+        }
+        fmt.zero(sign)
+            | ((biased_exp as u64) << fmt.frac_bits)
+            | (int_mant as u64 & fmt.frac_mask())
+    }
+
+    /// Round a sum, an exact zero is negative only if rounding toward negative.
+    fn round_sum(&mut self, fmt: FpFormat, lhs: Term, rhs: Term) -> u64 {
+        let (sign, mant, exp, sticky) = add_terms(lhs, rhs);
+        if mant == 0 {
+            return fmt.zero(self.control.rounding() == FpRounding::TowardNegative);
+        }
+
+        self.round(fmt, sign, mant, exp, sticky, FpRounding::Current)
+    }
+
+    pub fn add(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.add_sub(fmt, lhs, rhs, false)
+    }
+
+    pub fn sub(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.add_sub(fmt, lhs, rhs, true)
+    }
+
+    fn add_sub(&mut self, fmt: FpFormat, lhs: u64, rhs: u64, negate_rhs: bool) -> u64 {
+        let a = self.unpack(fmt, lhs);
+        let mut b = self.unpack(fmt, rhs);
+        if let Some(nan) = self.process_nans(fmt, &[(a, lhs), (b, rhs)]) {
+            return nan;
+        }
+        b.sign ^= negate_rhs;
+
+        match (a.class, b.class) {
+            (Class::Infinity, Class::Infinity) if a.sign != b.sign => self.invalid(fmt),
+            (Class::Infinity, _) => fmt.infinity(a.sign),
+            (_, Class::Infinity) => fmt.infinity(b.sign),
+            (Class::Zero, Class::Zero) if a.sign == b.sign => fmt.zero(a.sign),
+            _ => self.round_sum(fmt, (a.sign, a.mant, a.exp), (b.sign, b.mant, b.exp)),
+        }
+    }
+
+    pub fn mul(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.multiply(fmt, lhs, rhs, false)
+    }
+
+    /// Like `mul`, but infinity times zero is 2 with the sign of the product and raises
+    /// nothing.
+    pub fn mulx(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.multiply(fmt, lhs, rhs, true)
+    }
+
+    fn multiply(&mut self, fmt: FpFormat, lhs: u64, rhs: u64, extended: bool) -> u64 {
+        let a = self.unpack(fmt, lhs);
+        let b = self.unpack(fmt, rhs);
+        if let Some(nan) = self.process_nans(fmt, &[(a, lhs), (b, rhs)]) {
+            return nan;
+        }
+
+        let sign = a.sign ^ b.sign;
+        match (a.class, b.class) {
+            (Class::Infinity, Class::Zero) | (Class::Zero, Class::Infinity) if extended => {
+                self.round(fmt, sign, 2, 0, false, FpRounding::Current)
+            }
+            (Class::Infinity, Class::Zero) | (Class::Zero, Class::Infinity) => self.invalid(fmt),
+            (Class::Infinity, _) | (_, Class::Infinity) => fmt.infinity(sign),
+            (Class::Zero, _) | (_, Class::Zero) => fmt.zero(sign),
+            _ => self.round(
+                fmt,
+                sign,
+                a.mant * b.mant,
+                a.exp + b.exp,
+                false,
+                FpRounding::Current,
+            ),
+        }
+    }
+
+    pub fn div(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        let a = self.unpack(fmt, lhs);
+        let b = self.unpack(fmt, rhs);
+        if let Some(nan) = self.process_nans(fmt, &[(a, lhs), (b, rhs)]) {
+            return nan;
+        }
+
+        let sign = a.sign ^ b.sign;
+        match (a.class, b.class) {
+            (Class::Infinity, Class::Infinity) | (Class::Zero, Class::Zero) => self.invalid(fmt),
+            (Class::Infinity, _) => fmt.infinity(sign),
+            (_, Class::Zero) => {
+                self.raise(FpExceptions::DIVIDE_BY_ZERO);
+                fmt.infinity(sign)
+            }
+            (Class::Zero, _) | (_, Class::Infinity) => fmt.zero(sign),
+            _ => {
+                // The dividend is widened so the quotient has enough bits to round
+                let shift = a.mant.leading_zeros() as i32 - 2;
+                let dividend = a.mant << shift;
+                let quotient = dividend / b.mant;
+                let sticky = !dividend.is_multiple_of(b.mant);
+
+                let exp = a.exp - shift - b.exp;
+                self.round(fmt, sign, quotient, exp, sticky, FpRounding::Current)
+            }
+        }
+    }
+
+    /// `lhs * rhs + addend` with a single rounding.
+    pub fn fma(&mut self, fmt: FpFormat, lhs: u64, rhs: u64, addend: u64) -> u64 {
+        let c = self.unpack(fmt, addend);
+        let a = self.unpack(fmt, lhs);
+        let b = self.unpack(fmt, rhs);
+
+        let inf_times_zero = matches!(
+            (a.class, b.class),
+            (Class::Infinity, Class::Zero) | (Class::Zero, Class::Infinity)
+        );
+        if let Some(nan) = self.process_nans(fmt, &[(c, addend), (a, lhs), (b, rhs)]) {
+            // A quiet NaN addend does not hide an invalid product
+            if c.class == Class::QuietNan && inf_times_zero {
+                return self.invalid(fmt);
+            }
+            return nan;
+        }
+
+        let sign = a.sign ^ b.sign;
+        let product_inf = a.class == Class::Infinity || b.class == Class::Infinity;
+        let product_zero = a.class == Class::Zero || b.class == Class::Zero;
+
+        if inf_times_zero || (c.class == Class::Infinity && product_inf && c.sign != sign) {
+            self.invalid(fmt)
+        } else if c.class == Class::Infinity {
+            fmt.infinity(c.sign)
+        } else if product_inf {
+            fmt.infinity(sign)
+        } else if c.class == Class::Zero && product_zero && c.sign == sign {
+            fmt.zero(sign)
+        } else {
+            self.round_sum(
+                fmt,
+                (c.sign, c.mant, c.exp),
+                (sign, a.mant * b.mant, a.exp + b.exp),
+            )
+        }
+    }
+
+    pub fn sqrt(&mut self, fmt: FpFormat, src: u64) -> u64 {
+        let op = self.unpack(fmt, src);
+
+        match op.class {
+            Class::QuietNan | Class::SignalingNan => self.process_nan(fmt, src),
+            Class::Zero => fmt.zero(op.sign),
+            _ if op.sign => self.invalid(fmt),
+            Class::Infinity => fmt.infinity(false),
+            _ => {
+                // Widen the operand to an even exponent, its root still has enough bits
+                let mut shift = op.mant.leading_zeros() as i32 - 3;
+                if (op.exp - shift) % 2 != 0 {
+                    shift += 1;
+                }
+                let mant = op.mant << shift;
+
+                let root = mant.isqrt();
+                let sticky = root * root != mant;
+                let exp = (op.exp - shift) / 2;
+                self.round(fmt, false, root, exp, sticky, FpRounding::Current)
+            }
+        }
+    }
+
+    /// An estimate of `1 / src` with 8 significant bits, exactly the one of FPRecipEstimate.
+    pub fn recip_estimate(&mut self, fmt: FpFormat, src: u64) -> u64 {
+        let op = self.unpack(fmt, src);
+
+        match op.class {
+            Class::QuietNan | Class::SignalingNan => return self.process_nan(fmt, src),
+            Class::Infinity => return fmt.zero(op.sign),
+            Class::Zero => {
+                self.raise(FpExceptions::DIVIDE_BY_ZERO);
+                return fmt.infinity(op.sign);
+            }
+            Class::Finite => {}
+        }
+
+        // The reciprocals of the smallest denormals overflow, those of the largest values are
+        // denormals, which may be flushed
+        let exponent = op.exp + 127 - op.mant.leading_zeros() as i32;
+        if exponent < -fmt.bias() - 1 {
+            self.raise(FpExceptions::OVERFLOW | FpExceptions::INEXACT);
+            let overflow_to_infinity = match self.control.rounding() {
+                FpRounding::TowardPositive => !op.sign,
+                FpRounding::TowardNegative => op.sign,
+                FpRounding::TowardZero => false,
+                _ => true,
+            };
+            return if overflow_to_infinity {
+                fmt.infinity(op.sign)
+            } else {
+                fmt.max_normal(op.sign)
+            };
+        }
+        if self.flushes(fmt) && exponent >= fmt.bias() - 1 {
+            self.raise(FpExceptions::UNDERFLOW);
+            return fmt.zero(op.sign);
+        }
+
+        // Scale the operand to a fixed-point value in [0.5, 1) with 8 fraction bits below
+        // the leading one
+        let (mut exp, mut fraction) = fraction52(fmt, src);
+        if exp == 0 {
+            if fraction >> 51 == 0 {
+                exp = -1;
+                fraction <<= 2;
+            } else {
+                fraction <<= 1;
+            }
+        }
+        let estimate = recip_estimate((1 << 8) | ((fraction >> 44) & 0xFF));
+
+        // The estimate in [1, 2] is the significand of the result, denormal if the exponent
+        // is 0 or -1
+        let mut result_exp = 2 * fmt.bias() - 1 - exp;
+        let mut fraction = (estimate & 0xFF) << 44;
+        if result_exp == 0 {
+            fraction = (1 << 51) | (fraction >> 1);
+        } else if result_exp == -1 {
+            fraction = (1 << 50) | (fraction >> 2);
+            result_exp = 0;
+        }
+
+        fmt.zero(op.sign)
+            | ((result_exp as u64) << fmt.frac_bits)
+            | (fraction >> (52 - fmt.frac_bits))
+    }
+
+    /// An estimate of `1 / sqrt(src)` with 8 significant bits, exactly the one of
+    /// FPRSqrtEstimate.
+    pub fn rsqrt_estimate(&mut self, fmt: FpFormat, src: u64) -> u64 {
+        let op = self.unpack(fmt, src);
+
+        match op.class {
+            Class::QuietNan | Class::SignalingNan => return self.process_nan(fmt, src),
+            Class::Zero => {
+                self.raise(FpExceptions::DIVIDE_BY_ZERO);
+                return fmt.infinity(op.sign);
+            }
+            _ if op.sign => return self.invalid(fmt),
+            Class::Infinity => return fmt.zero(false),
+            Class::Finite => {}
+        }
+
+        // Scale the operand to a fixed-point value in [0.25, 1) that keeps the parity of the
+        // exponent, with 8 fraction bits below the leading one
+        let (mut exp, mut fraction) = fraction52(fmt, src);
+        if exp == 0 {
+            while fraction >> 51 == 0 {
+                fraction <<= 1;
+                exp -= 1;
+            }
+            fraction = (fraction << 1) & ((1 << 52) - 1);
+        }
+        let scaled = match exp & 1 {
+            0 => (1 << 8) | (fraction >> 44),
+            _ => (1 << 7) | (fraction >> 45),
+        };
+        let estimate = rsqrt_estimate(scaled);
+
+        let result_exp = (3 * fmt.bias() - 1 - exp).div_euclid(2);
+        ((result_exp as u64) << fmt.frac_bits) | ((estimate & 0xFF) << (fmt.frac_bits - 8))
+    }
+
+    /// `src` with a zero significand and the bits of its exponent inverted, exactly the result
+    /// of FPRecpX. Zeroes and denormals get the largest finite exponent.
+    pub fn recip_exponent(&mut self, fmt: FpFormat, src: u64) -> u64 {
+        let op = self.unpack(fmt, src);
+        if op.is_nan() {
+            return self.process_nan(fmt, src);
+        }
+
+        let exp = match (src & fmt.exp_mask()) >> fmt.frac_bits {
+            0 => fmt.max_exp() as u64 - 1,
+            exp => exp ^ fmt.max_exp() as u64,
+        };
+        fmt.zero(op.sign) | exp << fmt.frac_bits
+    }
+
+    /// `2 - lhs * rhs` with a single rounding, infinity times zero is 2 and raises nothing.
+    pub fn recip_step(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.step_fused(fmt, lhs, rhs, 2, 0)
+    }
+
+    /// `(3 - lhs * rhs) / 2` with a single rounding, infinity times zero is 1.5 and raises
+    /// nothing.
+    pub fn rsqrt_step(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.step_fused(fmt, lhs, rhs, 3, -1)
+    }
+
+    /// `(constant - lhs * rhs) * 2^exp` with a single rounding.
+    fn step_fused(&mut self, fmt: FpFormat, lhs: u64, rhs: u64, constant: u128, exp: i32) -> u64 {
+        // The product is negated before anything else, a NaN `lhs` included
+        let lhs = fmt.neg(lhs);
+        let a = self.unpack(fmt, lhs);
+        let b = self.unpack(fmt, rhs);
+        if let Some(nan) = self.process_nans(fmt, &[(a, lhs), (b, rhs)]) {
+            return nan;
+        }
+
+        let sign = a.sign ^ b.sign;
+        match (a.class, b.class) {
+            (Class::Infinity, Class::Zero) | (Class::Zero, Class::Infinity) => {
+                self.round(fmt, false, constant, exp, false, FpRounding::Current)
+            }
+            (Class::Infinity, _) | (_, Class::Infinity) => fmt.infinity(sign),
+            _ => self.round_sum(
+                fmt,
+                (false, constant, exp),
+                (sign, a.mant * b.mant, a.exp + b.exp + exp),
+            ),
+        }
+    }
+
+    pub fn max(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.max_min(fmt, lhs, rhs, true)
+    }
+
+    pub fn min(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.max_min(fmt, lhs, rhs, false)
+    }
+
+    pub fn maxnm(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.max_min_number(fmt, lhs, rhs, true)
+    }
+
+    pub fn minnm(&mut self, fmt: FpFormat, lhs: u64, rhs: u64) -> u64 {
+        self.max_min_number(fmt, lhs, rhs, false)
+    }
+
+    fn max_min(&mut self, fmt: FpFormat, lhs: u64, rhs: u64, max: bool) -> u64 {
+        let a = self.unpack(fmt, lhs);
+        let b = self.unpack(fmt, rhs);
+        if let Some(nan) = self.process_nans(fmt, &[(a, lhs), (b, rhs)]) {
+            return nan;
+        }
+
+        let winner = if max {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+        let (op, bits) = if a.cmp_value(&b) == winner {
+            (a, lhs)
+        } else {
+            (b, rhs)
+        };
+
+        match op.class {
+            // +0 is larger than -0, and flushed denormals are zeroes
+            Class::Zero if max => fmt.zero(a.sign && b.sign),
+            Class::Zero => fmt.zero(a.sign || b.sign),
+            _ => bits,
+        }
+    }
+
+    /// A single quiet NaN operand loses against any number.
+    fn max_min_number(&mut self, fmt: FpFormat, lhs: u64, rhs: u64, max: bool) -> u64 {
+        let loser = fmt.infinity(max);
+        let (lhs, rhs) = match (fmt.is_quiet_nan(lhs), fmt.is_quiet_nan(rhs)) {
+            (true, false) => (loser, rhs),
+            (false, true) => (lhs, loser),
+            _ => (lhs, rhs),
+        };
+
+        self.max_min(fmt, lhs, rhs, max)
+    }
+
+    /// Round `src` to an integral value, raising the inexact exception only if
+    /// `signal_inexact` is set.
+    pub fn round_int(
+        &mut self,
+        fmt: FpFormat,
+        src: u64,
+        rounding: FpRounding,
+        signal_inexact: bool,
+    ) -> u64 {
+        let rounding = self.rounding(rounding);
+        let op = self.unpack(fmt, src);
+
+        match op.class {
+            Class::QuietNan | Class::SignalingNan => self.process_nan(fmt, src),
+            Class::Infinity => fmt.infinity(op.sign),
+            Class::Zero => fmt.zero(op.sign),
+            // Every value without bits below the binary point is integral already
+            _ if op.exp >= 0 => src,
+            _ => {
+                let (int, fraction) = split(op.mant, -op.exp, false);
+                let int = int + round_up(rounding, op.sign, int, fraction) as u128;
+
+                if fraction != Fraction::Zero && signal_inexact {
+                    self.raise(FpExceptions::INEXACT);
+                }
+                if int == 0 {
+                    fmt.zero(op.sign)
+                } else {
+                    self.round(fmt, op.sign, int, 0, false, FpRounding::TowardZero)
+                }
+            }
+        }
+    }
+
+    /// Convert `src` from the format `from` to `to` with `rounding`.
+    pub fn convert(&mut self, from: FpFormat, to: FpFormat, src: u64, rounding: FpRounding) -> u64 {
+        self.converting = true;
+        let result = self.convert_value(from, to, src, rounding);
+        self.converting = false;
+
+        result
+    }
+
+    fn convert_value(
+        &mut self,
+        from: FpFormat,
+        to: FpFormat,
+        src: u64,
+        rounding: FpRounding,
+    ) -> u64 {
+        let op = self.unpack(from, src);
+
+        match op.class {
+            Class::QuietNan | Class::SignalingNan => {
+                if op.class == Class::SignalingNan || self.alternative(to) {
+                    self.raise(FpExceptions::INVALID);
+                }
+                if self.alternative(to) {
+                    return to.zero(op.sign);
+                }
+                if self.control.default_nan() {
+                    return to.default_nan();
+                }
+
+                // The payload keeps its most significant bits
+                let payload = src & (from.quiet_bit() - 1);
+                let payload = if to.frac_bits > from.frac_bits {
+                    payload << (to.frac_bits - from.frac_bits)
+                } else {
+                    payload >> (from.frac_bits - to.frac_bits)
+                };
+                to.infinity(op.sign) | to.quiet_bit() | payload
+            }
+            Class::Infinity if self.alternative(to) => {
+                self.raise(FpExceptions::INVALID);
+                to.zero(op.sign) | (to.sign_bit() - 1)
+            }
+            Class::Infinity => to.infinity(op.sign),
+            Class::Zero => to.zero(op.sign),
+            Class::Finite => self.round(to, op.sign, op.mant, op.exp, false, rounding),
+        }
+    }
+
+    /// Convert `src` multiplied by `2^frac_bits` to an integer of `width` bits. Values out of
+    /// range saturate and NaNs convert to zero, both raise the invalid exception.
+    pub fn to_int(
+        &mut self,
+        fmt: FpFormat,
+        src: u64,
+        width: u32,
+        signed: bool,
+        frac_bits: u8,
+        rounding: FpRounding,
+    ) -> u64 {
+        let rounding = self.rounding(rounding);
+        let op = self.unpack(fmt, src);
+
+        let (int, fraction) = match op.class {
+            Class::QuietNan | Class::SignalingNan => {
+                self.raise(FpExceptions::INVALID);
+                return 0;
+            }
+            Class::Zero => (0, Fraction::Zero),
+            Class::Finite => {
+                let exp = op.exp + frac_bits as i32;
+                if exp > op.mant.leading_zeros() as i32 {
+                    (u128::MAX, Fraction::Zero)
+                } else {
+                    split(op.mant, -exp, false)
+                }
+            }
+            Class::Infinity => (u128::MAX, Fraction::Zero),
+        };
+        let int = int.saturating_add(round_up(rounding, op.sign, int, fraction) as u128);
+
+        let (max_negative, max_positive) = match signed {
+            true => (1u128 << (width - 1), (1u128 << (width - 1)) - 1),
+            false => (0, (1u128 << width) - 1),
+        };
+        let mask = u64::MAX >> (64 - width);
+
+        if op.sign && int > max_negative {
+            self.raise(FpExceptions::INVALID);
+            (max_negative as u64).wrapping_neg() & mask
+        } else if !op.sign && int > max_positive {
+            self.raise(FpExceptions::INVALID);
+            max_positive as u64
+        } else {
+            if fraction != Fraction::Zero {
+                self.raise(FpExceptions::INEXACT);
+            }
+            let int = int as u64;
+            if op.sign {
+                int.wrapping_neg() & mask
+            } else {
+                int
+            }
+        }
+    }
+
+    /// Convert the integer `src` of `width` bits divided by `2^frac_bits` to `fmt`.
+    pub fn from_int(
+        &mut self,
+        fmt: FpFormat,
+        src: u64,
+        width: u32,
+        signed: bool,
+        frac_bits: u8,
+    ) -> u64 {
+        let shift = 64 - width;
+        let (sign, int) = if signed {
+            let src = ((src << shift) as i64) >> shift;
+            (src < 0, src.unsigned_abs())
+        } else {
+            (false, src)
+        };
+
+        if int == 0 {
+            return fmt.zero(false);
+        }
+        let exp = -(frac_bits as i32);
+        self.round(fmt, sign, int as u128, exp, false, FpRounding::Current)
+    }
+
+    /// Compare `lhs` with `rhs`, `None` if they are unordered. Signaling NaNs raise the invalid
+    /// exception, and so do quiet ones if `signaling` is set.
+    pub fn compare(
+        &mut self,
+        fmt: FpFormat,
+        lhs: u64,
+        rhs: u64,
+        signaling: bool,
+    ) -> Option<Ordering> {
+        let a = self.unpack(fmt, lhs);
+        let b = self.unpack(fmt, rhs);
+
+        if a.is_nan() || b.is_nan() {
+            if signaling || a.class == Class::SignalingNan || b.class == Class::SignalingNan {
+                self.raise(FpExceptions::INVALID);
+            }
+            return None;
+        }
+
+        Some(a.cmp_value(&b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Evaluate `op` with the default control, returning its result and the raised exceptions.
+    fn eval(op: impl FnOnce(&mut SoftFp) -> u64) -> (u64, FpExceptions) {
+        eval_with(0, op)
+    }
+
+    /// Like `eval`, with the control given by `control`.
+    fn eval_with(control: u64, op: impl FnOnce(&mut SoftFp) -> u64) -> (u64, FpExceptions) {
+        let mut fp = SoftFp::new(FpControl::from_bits(control));
+        let result = op(&mut fp);
+        (result, fp.exceptions())
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let single = FpFormat::F32;
+        let inexact = FpExceptions::INEXACT;
+        let overflow = FpExceptions::OVERFLOW | FpExceptions::INEXACT;
+        let (nearest, positive, negative, zero) = (0b00, 0b01, 0b10, 0b11);
+        for (control, lhs, rhs, result, exceptions) in [
+            // 1 + 0.75 ulp
+            (nearest, 0x3f80_0000, 0x33c0_0000, 0x3f80_0001, inexact),
+            (positive, 0x3f80_0000, 0x33c0_0000, 0x3f80_0001, inexact),
+            (negative, 0x3f80_0000, 0x33c0_0000, 0x3f80_0000, inexact),
+            (zero, 0x3f80_0000, 0x33c0_0000, 0x3f80_0000, inexact),
+            (positive, 0xbf80_0000, 0xb3c0_0000, 0xbf80_0000, inexact),
+            (negative, 0xbf80_0000, 0xb3c0_0000, 0xbf80_0001, inexact),
+            // Ties go to the even neighbour
+            (nearest, 0x3f80_0000, 0x3380_0000, 0x3f80_0000, inexact),
+            (nearest, 0x3f80_0001, 0x3380_0000, 0x3f80_0002, inexact),
+            // Overflows give infinity or the largest normal, depending on the direction
+            (nearest, 0x7f7f_ffff, 0x7f7f_ffff, 0x7f80_0000, overflow),
+            (zero, 0x7f7f_ffff, 0x7f7f_ffff, 0x7f7f_ffff, overflow),
+            (negative, 0x7f7f_ffff, 0x7f7f_ffff, 0x7f7f_ffff, overflow),
+            // Exact zero sums are only negative when rounding toward minus infinity
+            (
+                nearest,
+                0x3f80_0000,
+                0xbf80_0000,
+                0x0000_0000,
+                FpExceptions::NONE,
+            ),
+            (
+                negative,
+                0x3f80_0000,
+                0xbf80_0000,
+                0x8000_0000,
+                FpExceptions::NONE,
+            ),
+        ] {
+            assert_eq!(
+                eval_with(control, |fp| fp.add(single, lhs, rhs)),
+                (result, exceptions),
+                "{lhs:#x} + {rhs:#x} with rounding {control:#b}"
+            );
+        }
+
+        // Explicit rounding modes ignore the control, 1 + 2^-24 is a tie in single precision
+        let tie = 0x3ff0_0000_1000_0000;
+        for (rounding, result) in [
+            (FpRounding::TiesToEven, 0x3f80_0000),
+            (FpRounding::TiesToAway, 0x3f80_0001),
+            (FpRounding::ToOdd, 0x3f80_0001),
+        ] {
+            assert_eq!(
+                eval_with(zero, |fp| fp.convert(FpFormat::F64, single, tie, rounding)),
+                (result, inexact),
+                "{rounding:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn nan_propagation() {
+        let single = FpFormat::F32;
+        let invalid = FpExceptions::INVALID;
+        let (quiet, other_quiet, signaling) = (0x7fc0_0001, 0xffc0_0003, 0x7f80_0002);
+        for (control, lhs, rhs, result, exceptions) in [
+            (0, quiet, 0x3f80_0000, quiet, FpExceptions::NONE),
+            (0, 0x3f80_0000, quiet, quiet, FpExceptions::NONE),
+            (0, quiet, other_quiet, quiet, FpExceptions::NONE),
+            // Signaling NaNs take precedence and are made quiet
+            (0, quiet, signaling, 0x7fc0_0002, invalid),
+            (0, 0x3f80_0000, signaling, 0x7fc0_0002, invalid),
+            // Invalid operations give the default NaN
+            (0, 0x7f80_0000, 0xff80_0000, 0x7fc0_0000, invalid),
+            // And so does every NaN with default NaN enabled
+            (
+                FpControl::DEFAULT_NAN,
+                quiet,
+                0x3f80_0000,
+                0x7fc0_0000,
+                FpExceptions::NONE,
+            ),
+            (
+                FpControl::DEFAULT_NAN,
+                quiet,
+                signaling,
+                0x7fc0_0000,
+                invalid,
+            ),
+        ] {
+            assert_eq!(
+                eval_with(control, |fp| fp.add(single, lhs, rhs)),
+                (result, exceptions),
+                "{lhs:#x} + {rhs:#x} with control {control:#b}"
+            );
+        }
+
+        // Conversions keep the most significant bits of the payload
+        assert_eq!(
+            eval(|fp| fp.convert(
+                FpFormat::F64,
+                single,
+                0x7ff4_0000_2000_0000,
+                FpRounding::Current
+            )),
+            (0x7fe0_0001, invalid)
+        );
+    }
+
+    #[test]
+    fn flush_to_zero() {
+        let (half, single) = (FpFormat::F16, FpFormat::F32);
+        let none = FpExceptions::NONE;
+        let flush = FpControl::FLUSH_TO_ZERO;
+        let flush_half = FpControl::FLUSH_HALF_TO_ZERO;
+        // 2^-100 * 2^-30 is a denormal
+        let (tiny, scale, product) = (0x0d80_0000, 0x3080_0000, 0x0008_0000);
+
+        assert_eq!(eval(|fp| fp.mul(single, tiny, scale)), (product, none));
+        assert_eq!(
+            eval_with(flush, |fp| fp.mul(single, tiny, scale)),
+            (0, FpExceptions::UNDERFLOW)
+        );
+        assert_eq!(
+            eval_with(flush, |fp| fp.add(single, 0x8000_0001, 0x8000_0000)),
+            (0x8000_0000, FpExceptions::INPUT_DENORMAL)
+        );
+
+        // Each flush control only applies to its formats, half precision raises nothing
+        assert_eq!(eval_with(flush_half, |fp| fp.add(single, 1, 0)), (1, none));
+        assert_eq!(eval_with(flush, |fp| fp.add(half, 1, 0)), (1, none));
+        assert_eq!(eval_with(flush_half, |fp| fp.add(half, 1, 0)), (0, none));
+    }
+
+    #[test]
+    fn recip_estimate_table() {
+        for (a, estimate) in [(256, 511), (300, 436), (384, 341), (511, 256)] {
+            assert_eq!(recip_estimate(a), estimate, "RecipEstimate({a})");
+        }
+    }
+
+    #[test]
+    fn rsqrt_estimate_table() {
+        for (a, estimate) in [(128, 511), (200, 409), (256, 361), (384, 295), (511, 256)] {
+            assert_eq!(rsqrt_estimate(a), estimate, "RecipSqrtEstimate({a})");
+        }
+    }
+
+    #[test]
+    fn frecpe() {
+        let (half, single, double) = (FpFormat::F16, FpFormat::F32, FpFormat::F64);
+        let none = FpExceptions::NONE;
+        let by_zero = FpExceptions::DIVIDE_BY_ZERO;
+        let overflow = FpExceptions::OVERFLOW | FpExceptions::INEXACT;
+        for (fmt, src, result, exceptions) in [
+            (single, 0x3f80_0000, 0x3f7f_8000, none),
+            (single, 0x4000_0000, 0x3eff_8000, none),
+            (single, 0x3fc0_0000, 0x3f2a_8000, none),
+            (single, 0x4040_0000, 0x3eaa_8000, none),
+            (single, 0xbf00_0000, 0xbfff_8000, none),
+            // The reciprocal of the largest normal is a denormal
+            (single, 0x7f7f_ffff, 0x0020_0000, none),
+            (single, 0x0000_0001, 0x7f80_0000, overflow),
+            (single, 0x8000_0000, 0xff80_0000, by_zero),
+            (single, 0x7f80_0000, 0x0000_0000, none),
+            (single, 0x7f80_0001, 0x7fc0_0001, FpExceptions::INVALID),
+            (double, 0x3ff0_0000_0000_0000, 0x3fef_f000_0000_0000, none),
+            (half, 0x3c00, 0x3bfc, none),
+        ] {
+            assert_eq!(
+                eval(|fp| fp.recip_estimate(fmt, src)),
+                (result, exceptions),
+                "FRECPE {src:#x}"
+            );
+        }
+    }
+
+    #[test]
+    fn frsqrte() {
+        let (half, single, double) = (FpFormat::F16, FpFormat::F32, FpFormat::F64);
+        let none = FpExceptions::NONE;
+        let by_zero = FpExceptions::DIVIDE_BY_ZERO;
+        for (fmt, src, result, exceptions) in [
+            (single, 0x3f80_0000, 0x3f7f_8000, none),
+            (single, 0x4000_0000, 0x3f34_8000, none),
+            (single, 0x4040_0000, 0x3f13_8000, none),
+            (single, 0x4080_0000, 0x3eff_8000, none),
+            (single, 0x3e80_0000, 0x3fff_8000, none),
+            (single, 0x0000_0000, 0x7f80_0000, by_zero),
+            (single, 0x7f80_0000, 0x0000_0000, none),
+            (single, 0xbf80_0000, 0x7fc0_0000, FpExceptions::INVALID),
+            (double, 0x4000_0000_0000_0000, 0x3fe6_9000_0000_0000, none),
+            (half, 0x3c00, 0x3bfc, none),
+        ] {
+            assert_eq!(
+                eval(|fp| fp.rsqrt_estimate(fmt, src)),
+                (result, exceptions),
+                "FRSQRTE {src:#x}"
+            );
+        }
+    }
+
+    #[test]
+    fn unsigned_estimates() {
+        for (src, recip, rsqrt) in [
+            (0x3fff_ffff, 0xffff_ffff, 0xffff_ffff),
+            (0x4000_0000, 0xffff_ffff, 0xff80_0000),
+            (0x8000_0000, 0xff80_0000, 0xb480_0000),
+            (0xc000_0000, 0xaa80_0000, 0x9380_0000),
+            (0xffff_ffff, 0x8000_0000, 0x8000_0000),
+        ] {
+            assert_eq!(unsigned_recip_estimate(src), recip, "URECPE {src:#x}");
+            assert_eq!(unsigned_rsqrt_estimate(src), rsqrt, "URSQRTE {src:#x}");
+        }
+    }
+}
@@ -1,20 +1,408 @@
-use std::convert::Infallible;
+use std::{
+    ffi::{OsStr, OsString},
+    os::unix::ffi::OsStrExt,
+    path::PathBuf,
+};
 
 use arch_desc::aarch64::AArch64Architecture;
-use execution::{abi::AArch64UnknownLinux, codegen::rustjit::RustjitCodegen, Runtime};
+use core::{ArchitectureCompat, Error};
+use execution::{
+    abi::{AArch64UnknownLinux, LinuxConfig, Mount},
+    codegen::{cranelift::CraneliftCodegen, rustjit::RustjitCodegen},
+    CodeCacheStats, Runtime, RuntimeConfig,
+};
+
+const USAGE: &str = "\
+Usage: execution [OPTIONS] PROGRAM [ARGS]...
+
+Run an AArch64 Linux program.
+
+Options:
+  -L, --sysroot DIR          Look up guest files in DIR before the host root
+  -m, --mount GUEST=HOST     Make the host directory HOST visible at GUEST
+      --mount-ro GUEST=HOST  Like --mount, but read only
+  -E, --env KEY=VALUE        Set a variable in the guest environment
+  -U, --unset KEY            Remove a variable from the guest environment
+      --clear-env            Start the guest with an empty environment
+  -0, --argv0 NAME           Pass NAME as argv[0] instead of PROGRAM
+  -b, --backend BACKEND      Code generator, `rustjit` (default) or `cranelift`
+  -s, --stack-size SIZE      Size of the guest stack, with an optional K, M or G suffix
+  -t, --trace                Print the address of every executed block
+      --strict-syscalls      Stop on system calls that are not implemented, instead of
+                             failing them with ENOSYS
+      --stats                Print code cache statistics when the guest exits
+  -h, --help                 Print this help
+
+The process exits with the exit status of the guest. If the guest faults, it exits with
+128 plus the number of the signal Linux would have sent: SIGILL, SIGBUS, SIGSEGV or SIGSYS.
+If the program cannot be loaded, it exits with 126.";
+
+/// Exit status for command line errors.
+const EXIT_USAGE: i32 = 2;
+/// Exit status when the program cannot be read, as shells do.
+const EXIT_NOT_FOUND: i32 = 127;
+/// Exit status when the program cannot be loaded, as shells do for files they cannot execute.
+const EXIT_CANNOT_EXECUTE: i32 = 126;
+
+/// Exit status of a guest killed by a fault, like a shell reports a signal, or of a program
+/// that could not be loaded.
+fn fault_status(error: &Error) -> i32 {
+    const SIGILL: i32 = 4;
+    const SIGBUS: i32 = 7;
+    const SIGSEGV: i32 = 11;
+    const SIGSYS: i32 = 31;
+
+    let signal = match error {
+        Error::LoadFailure(_) => return EXIT_CANNOT_EXECUTE,
+        Error::UndefinedInstruction(_)
+        | Error::UnimplementedInstruction(_)
+        | Error::CodegenFailure(_) => SIGILL,
+        Error::AlignmentFault { .. } => SIGBUS,
+        Error::UnmappedAccess { .. } | Error::PermissionFault { .. } => SIGSEGV,
+        Error::UnsupportedSyscall(_) => SIGSYS,
+    };
+    128 + signal
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Rustjit,
+    Cranelift,
+}
+
+struct Options {
+    program: PathBuf,
+    backend: Backend,
+    stats: bool,
+    linux: LinuxConfig,
+    runtime: RuntimeConfig,
+}
 
 fn main() {
-    let file = std::fs::read("./examples/main").unwrap();
+    let options = match parse_args(std::env::args_os().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("execution: {error}\n\n{USAGE}");
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+
+    let file = std::fs::read(&options.program).unwrap_or_else(|error| {
+        eprintln!("execution: {}: {error}", options.program.display());
+        std::process::exit(EXIT_NOT_FOUND);
+    });
+
+    let linux = options.linux;
+    let stats = options.runtime.code_cache.stats.clone();
+    let result = unsafe {
+        match options.backend {
+            Backend::Rustjit => execute_runtime::<RustjitCodegen>(&file, linux, options.runtime),
+            Backend::Cranelift => {
+                execute_runtime::<CraneliftCodegen>(&file, linux, options.runtime)
+            }
+        }
+    };
 
-    unsafe {
-        execute_runtime(&file);
+    let status = match result {
+        Ok(status) => status,
+        Err(error) => {
+            eprintln!("execution: {error}");
+            fault_status(&error)
+        }
+    };
+    if options.stats {
+        print_stats(&stats);
     }
+    std::process::exit(status);
 }
 
-unsafe fn execute_runtime(file: &[u8]) -> Infallible {
+unsafe fn execute_runtime<Codegen>(
+    file: &[u8],
+    config: LinuxConfig,
+    runtime: RuntimeConfig,
+) -> Result<i32, Error>
+where
+    Codegen: execution::codegen::Codegen + ArchitectureCompat<AArch64Architecture>,
+{
     type Arch = AArch64Architecture;
-    type Codegen = RustjitCodegen;
     type Abi = AArch64UnknownLinux;
 
-    Runtime::run::<Arch, Codegen, Abi>(&file, |_, _| {})
+    Runtime::run::<Arch, Codegen, Abi>(file, Abi::with_config(config), runtime, |_, _| {})
+}
+
+fn parse_args(mut args: impl Iterator<Item = OsString>) -> Result<Options, String> {
+    let mut sysroot = None;
+    let mut mounts = Vec::new();
+    let mut env: Vec<(OsString, OsString)> = std::env::vars_os().collect();
+    let mut argv0 = None;
+    let mut backend = Backend::Rustjit;
+    let mut stack_size = LinuxConfig::default().stack_size;
+    let mut trace = false;
+    let mut stats = false;
+    let mut strict_syscalls = false;
+
+    let program = loop {
+        let Some(arg) = args.next() else {
+            return Err("missing PROGRAM".into());
+        };
+        let Some(option) = arg.to_str().filter(|arg| arg.starts_with('-')) else {
+            break PathBuf::from(arg);
+        };
+
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("option `{name}` requires a value"))
+        };
+        match option {
+            "--" => break PathBuf::from(value("--")?),
+            "-L" | "--sysroot" => sysroot = Some(PathBuf::from(value(option)?)),
+            "-m" | "--mount" => mounts.push(parse_mount(&value(option)?, false)?),
+            "--mount-ro" => mounts.push(parse_mount(&value(option)?, true)?),
+            "-E" | "--env" => {
+                let (key, value) = split_once(&value(option)?, b'=')
+                    .ok_or_else(|| format!("option `{option}` expects KEY=VALUE"))?;
+                env.retain(|(var, _)| *var != key);
+                env.push((key, value));
+            }
+            "-U" | "--unset" => {
+                let key = value(option)?;
+                env.retain(|(var, _)| *var != key);
+            }
+            "--clear-env" => env.clear(),
+            "-0" | "--argv0" => argv0 = Some(value(option)?),
+            "-b" | "--backend" => {
+                backend = match value(option)?.to_str() {
+                    Some("rustjit") => Backend::Rustjit,
+                    Some("cranelift") => Backend::Cranelift,
+                    _ => return Err("unknown backend, expected `rustjit` or `cranelift`".into()),
+                }
+            }
+            "-s" | "--stack-size" => stack_size = parse_size(&value(option)?)?,
+            "-t" | "--trace" => trace = true,
+            "--strict-syscalls" => strict_syscalls = true,
+            "--stats" => stats = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown option `{option}`")),
+        }
+    };
+
+    let mut guest_args = vec![argv0.unwrap_or_else(|| program.clone().into())];
+    guest_args.extend(args);
+
+    let linux = LinuxConfig {
+        args: guest_args,
+        env: env
+            .into_iter()
+            .map(|(key, value)| {
+                let mut var = key;
+                var.push("=");
+                var.push(value);
+                var
+            })
+            .collect(),
+        stack_size,
+        sysroot,
+        mounts,
+        exe: Some(program.clone()),
+        strict_syscalls,
+    };
+    let runtime = RuntimeConfig {
+        trace,
+        ..Default::default()
+    };
+
+    Ok(Options {
+        program,
+        backend,
+        stats,
+        linux,
+        runtime,
+    })
+}
+
+fn split_once(str: &OsStr, separator: u8) -> Option<(OsString, OsString)> {
+    let bytes = str.as_bytes();
+    let idx = bytes.iter().position(|&byte| byte == separator)?;
+    Some((
+        OsStr::from_bytes(&bytes[..idx]).into(),
+        OsStr::from_bytes(&bytes[idx + 1..]).into(),
+    ))
+}
+
+fn parse_mount(value: &OsStr, read_only: bool) -> Result<Mount, String> {
+    let (guest, host) = split_once(value, b'=').ok_or("mounts are given as GUEST=HOST")?;
+    let guest = PathBuf::from(guest);
+    if !guest.is_absolute() {
+        return Err(format!("mount point {} is not absolute", guest.display()));
+    }
+
+    Ok(Mount {
+        guest,
+        host: host.into(),
+        read_only,
+    })
+}
+
+/// Parse a size in bytes, like `8M`.
+fn parse_size(value: &OsStr) -> Result<u64, String> {
+    let invalid = || format!("invalid size `{}`", value.to_string_lossy());
+    let value = value.to_str().ok_or_else(invalid)?;
+
+    let (digits, shift) = match value.as_bytes().last() {
+        Some(b'k' | b'K') => (&value[..value.len() - 1], 10),
+        Some(b'm' | b'M') => (&value[..value.len() - 1], 20),
+        Some(b'g' | b'G') => (&value[..value.len() - 1], 30),
+        _ => (value, 0),
+    };
+    let size: u64 = digits.parse().map_err(|_| invalid())?;
+
+    size.checked_mul(1 << shift)
+        .filter(|&size| size > 0)
+        .ok_or_else(invalid)
+}
+
+fn print_stats(stats: &CodeCacheStats) {
+    eprintln!("code cache hits:          {}", stats.hits());
+    eprintln!("code cache misses:        {}", stats.misses());
+    eprintln!("code cache evictions:     {}", stats.evictions());
+    eprintln!("code cache invalidations: {}", stats.invalidations());
+    eprintln!("chained block entries:    {}", stats.chained());
+    eprintln!("compiled bytes:           {}", stats.compiled_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(OsString::from))
+    }
+
+    /// The message `main` prints above the usage for invalid arguments.
+    fn usage_error(args: &[&str]) -> String {
+        match parse(args) {
+            Ok(_) => panic!("{args:?} are accepted"),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        let size = |value: &str| parse_size(OsStr::new(value));
+        assert_eq!(size("4096"), Ok(4096));
+        assert_eq!(size("8k"), Ok(8 << 10));
+        assert_eq!(size("8K"), Ok(8 << 10));
+        assert_eq!(size("16M"), Ok(16 << 20));
+        assert_eq!(size("2g"), Ok(2 << 30));
+
+        for value in ["", "0", "0M", "K", "-1", "1.5M", "1T", " 1", "17179869184G"] {
+            assert_eq!(size(value), Err(format!("invalid size `{value}`")));
+        }
+
+        let options = parse(&["-s", "1M", "prog"]).unwrap();
+        assert_eq!(options.linux.stack_size, 1 << 20);
+        assert_eq!(
+            usage_error(&["--stack-size", "1X", "prog"]),
+            "invalid size `1X`"
+        );
+    }
+
+    #[test]
+    fn sysroot_and_mounts() {
+        let options = parse(&[
+            "-L",
+            "/sysroot",
+            "--mount",
+            "/data=host",
+            "--mount-ro",
+            "/etc/conf=/a=b",
+            "prog",
+        ])
+        .unwrap();
+        assert_eq!(options.linux.sysroot, Some(PathBuf::from("/sysroot")));
+
+        let mounts: Vec<_> = options
+            .linux
+            .mounts
+            .iter()
+            .map(|mount| (mount.guest.to_str(), mount.host.to_str(), mount.read_only))
+            .collect();
+        assert_eq!(
+            mounts,
+            [
+                (Some("/data"), Some("host"), false),
+                (Some("/etc/conf"), Some("/a=b"), true),
+            ]
+        );
+
+        assert_eq!(
+            usage_error(&["-m", "/data", "prog"]),
+            "mounts are given as GUEST=HOST"
+        );
+        assert_eq!(
+            usage_error(&["-m", "data=/data", "prog"]),
+            "mount point data is not absolute"
+        );
+    }
+
+    #[test]
+    fn missing_values() {
+        assert_eq!(usage_error(&[]), "missing PROGRAM");
+        assert_eq!(usage_error(&["-t"]), "missing PROGRAM");
+        for option in [
+            "-L",
+            "--mount",
+            "--mount-ro",
+            "-E",
+            "--argv0",
+            "-b",
+            "-s",
+            "--",
+        ] {
+            assert_eq!(
+                usage_error(&[option]),
+                format!("option `{option}` requires a value")
+            );
+        }
+        assert_eq!(
+            usage_error(&["--env", "KEY", "prog"]),
+            "option `--env` expects KEY=VALUE"
+        );
+    }
+
+    #[test]
+    fn unknown_options() {
+        assert_eq!(
+            usage_error(&["--frobnicate", "prog"]),
+            "unknown option `--frobnicate`"
+        );
+        assert_eq!(usage_error(&["-x", "prog"]), "unknown option `-x`");
+        assert_eq!(
+            usage_error(&["-b", "llvm", "prog"]),
+            "unknown backend, expected `rustjit` or `cranelift`"
+        );
+    }
+
+    #[test]
+    fn program_and_guest_arguments() {
+        // Options after the program are passed to the guest
+        let options = parse(&["-b", "cranelift", "prog", "-t", "--", "x"]).unwrap();
+        assert_eq!(options.program, PathBuf::from("prog"));
+        assert_eq!(options.backend, Backend::Cranelift);
+        assert!(!options.runtime.trace);
+        assert_eq!(options.linux.args, ["prog", "-t", "--", "x"]);
+
+        // `--` ends the options, even if the program looks like one
+        let options = parse(&["-t", "--", "-prog", "--help"]).unwrap();
+        assert_eq!(options.program, PathBuf::from("-prog"));
+        assert!(options.runtime.trace);
+        assert_eq!(options.linux.args, ["-prog", "--help"]);
+
+        let options = parse(&["--argv0", "name", "prog", "a"]).unwrap();
+        assert_eq!(options.linux.args, ["name", "a"]);
+        assert_eq!(options.linux.exe, Some(PathBuf::from("prog")));
+    }
 }
@@ -1,29 +1,51 @@
 #![feature(generators, generator_trait)]
 #![feature(impl_trait_in_assoc_type)]
 pub mod abi;
+mod code_cache;
 pub mod codegen;
 mod soft_mmu;
 use core::{
-    ir::{BasicBlock, BasicBlockTerminator, IrValue, IrType},
-    Architecture, Instruction, Interrupt, ArchitectureCompat, Register,
+    ir::{BasicBlock, BasicBlockTerminator, IrConstant, IrValue, IrType},
+    Access, Architecture, Error, Instruction, Interrupt, ArchitectureCompat, Register,
 };
 use std::{
     collections::BinaryHeap,
-    convert::Infallible,
-    ops::{Generator, GeneratorState},
+    ops::{ControlFlow, Generator, GeneratorState},
     pin::pin,
+    rc::Rc,
 };
 
 use abi::Abi;
-use codegen::{Codegen, Executable};
-use device::{IoDevice, IrqQueue};
+use codegen::{
+    analysis::{Analysis, StaticSuccessorAnalysis},
+    Codegen, Executable,
+};
+use device::IrqQueue;
+pub use code_cache::*;
 pub use soft_mmu::*;
 
 use crate::codegen::Context;
 
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeConfig {
+    pub code_cache: CodeCacheConfig,
+    /// Print the address of every executed basic block to stderr.
+    pub trace: bool,
+}
+
+/// Maximum number of directly linked blocks executed before returning to the dispatcher.
+const MAX_CHAINED_BLOCKS: usize = 64;
+
 pub struct Runtime;
 impl Runtime {
-    pub unsafe fn run<A, C, I>(binary: &[u8], prepare: impl FnOnce(&mut SoftMmu, &mut IrqQueue)) -> Infallible
+    /// Run `binary` until the guest exits through the ABI and return its exit status, or
+    /// until it faults.
+    pub unsafe fn run<A, C, I>(
+        binary: &[u8],
+        mut abi: I,
+        config: RuntimeConfig,
+        prepare: impl FnOnce(&mut SoftMmu, &mut IrqQueue),
+    ) -> Result<i32, Error>
     where
         A: Architecture,
         C: ArchitectureCompat<A> + Codegen,
@@ -35,17 +57,17 @@ impl Runtime {
         let mut irq = IrqQueue::new();
         prepare(&mut mmu, &mut irq);
 
-        let mut abi = I::new();
         let mut ctx = C::allocate_execution_context::<A>();
         let cgn = C::new();
+        let mut cache = CodeCache::new(config.code_cache);
 
         // Initializes the ABI, execution context, and mmu with given binary
-        abi.on_initialize(binary, &mut ctx, &mut mmu);
+        abi.on_initialize(binary, &mut ctx, &mut mmu)?;
 
         let mut buffer = [0u8; 4096];
+        let mut prev_bb: Option<Rc<CachedBlock<C::Executable>>> = None;
         loop {
-            let mut pc = ctx.get(pc_reg);
-            mmu.read_all_at(pc, &mut buffer);
+            let pc = ctx.get(pc_reg);
 
             // Process device IRQs
             let mut irq_queue = BinaryHeap::new();
@@ -57,57 +79,140 @@ impl Runtime {
                 abi.on_irq(irq.id, irq.level, &ctx, &mmu);
             }
 
-            // Try to decode and compile instructions into BasicBlock
-            let mut bb = BasicBlock::new(pc);
-            let mut total_inst_size = 0u64;
-            loop {
-                let Some(raw_inst) = A::Inst::decode(&buffer[total_inst_size as usize..])
-                else {
-                    // If failed to parse an instruction, we need to read a new memory
-                    pc += total_inst_size;
-                    total_inst_size = 0;
-
-                    mmu.read_all_at(pc, &mut buffer);
-                    if total_inst_size == 0 {
-                        panic!("Failed to decode instruction at 0x{:x}", pc);
-                    }
-                    break;
-                };
+            // Drop translations of the code that has been modified
+            for page in mmu.take_modified_code() {
+                cache.invalidate_page(page);
+            }
 
-                raw_inst.compile_to_ir(&mut bb);
-                total_inst_size += raw_inst.size();
-                if bb.terminator() != BasicBlockTerminator::None {
-                    // If we have a terminator, we can stop parsing instructions
-                    break;
+            // Reuse the translated block if we have one, otherwise decode and compile it
+            let mut compiled_bb = cache.get_or_compile(pc, || {
+                if pc % A::Inst::ALIGNMENT != 0 {
+                    return Err(Error::AlignmentFault {
+                        addr: pc,
+                        size: A::Inst::ALIGNMENT,
+                    });
                 }
+
+                let start = pc;
+                let mut bb = BasicBlock::new(pc);
+                let mut total_inst_size = 0u64;
+
+                // Code is fetched a page at a time, the next page may not be mapped
+                let mut fetched = 0usize;
+                let mut offset = 0usize;
+                loop {
+                    let addr = start + total_inst_size;
+                    let inst = (|| {
+                        if offset == fetched {
+                            fetched = (CODE_PAGE_SIZE - addr % CODE_PAGE_SIZE) as usize;
+                            offset = 0;
+                            mmu.read(addr, &mut buffer[..fetched], Access::Execute)?;
+                        }
+
+                        let inst = A::Inst::decode(&buffer[offset..fetched])?;
+                        inst.compile_to_ir(&mut bb).map_err(|error| match error {
+                            // Reserved encodings found by the translator are reported with
+                            // the encoding that was fetched
+                            Error::UndefinedInstruction(_) => {
+                                let mut encoding = [0; 4];
+                                let size = (inst.size() as usize).min(4);
+                                encoding[..size].copy_from_slice(&buffer[offset..offset + size]);
+                                Error::UndefinedInstruction(u32::from_le_bytes(encoding))
+                            }
+                            error => error,
+                        })?;
+                        Ok(inst)
+                    })();
+
+                    let inst = match inst {
+                        Ok(inst) => inst,
+                        // Run what has been translated so far, the fault is raised when the
+                        // guest actually reaches the instruction.
+                        Err(_) if total_inst_size > 0 => {
+                            let addr = IrValue::Constant(IrConstant::B64(addr));
+                            bb.set_terminator(BasicBlockTerminator::Branch(addr));
+                            break;
+                        }
+                        Err(error) => return Err(error),
+                    };
+
+                    offset += inst.size() as usize;
+                    total_inst_size += inst.size();
+                    if bb.terminator() != BasicBlockTerminator::None {
+                        // If we have a terminator, we can stop parsing instructions
+                        break;
+                    }
+                }
+
+                mmu.track_code(start..start + total_inst_size);
+                let successors = StaticSuccessorAnalysis::new::<A>(&bb).analyze();
+                let compiled_bb = CachedBlock::new(cgn.compile::<A>(&bb)?, successors.successors());
+                Ok((compiled_bb, total_inst_size))
+            })?;
+
+            // Link the previous block to this one, so the next time it skips the dispatcher
+            if let Some(prev_bb) = prev_bb.take() {
+                prev_bb.link(pc, &compiled_bb);
             }
 
-            let compiled_bb = cgn.compile::<A>(&bb);
-            let gen = compiled_bb.execute(&ctx, &mmu);
-            let mut gen = pin!(gen);
-
-            while let GeneratorState::Yielded(interrupt) = gen.as_mut().resume(()) {
-                match interrupt {
-                    Interrupt::Exception(id) => abi.on_exception(id, &ctx, &mmu),
-                    Interrupt::Interrupt(id) => abi.on_interrupt(id, &ctx, &mmu),
-                    Interrupt::SystemCall(id) => abi.on_system_call(id, &ctx, &mmu),
-                    Interrupt::Aborts(value) => std::process::exit(value),
-                    Interrupt::Reset => std::process::exit(0),
-                    Interrupt::Yield => std::thread::yield_now(),
-                    Interrupt::WaitForInterrupt => {
-                        // TODO: wait for a interrupt using Parking
-                        loop {
-                            let Some(irq) = irq.recv() 
-                            else {
+            // Run directly linked blocks without going back to the dispatcher.
+            // The chain length is bounded so device IRQs are still processed in tight loops.
+            for _ in 0..MAX_CHAINED_BLOCKS {
+                if config.trace {
+                    eprintln!("[trace] 0x{:x}", ctx.get::<u64>(pc_reg));
+                }
+
+                {
+                    let gen = compiled_bb.exec().execute(&ctx, &mmu);
+                    let mut gen = pin!(gen);
+
+                    loop {
+                        let interrupt = match gen.as_mut().resume(()) {
+                            GeneratorState::Yielded(interrupt) => interrupt,
+                            GeneratorState::Complete(result) => break result?,
+                        };
+
+                        let flow = match interrupt {
+                            Interrupt::Exception(id) => abi.on_exception(id, &ctx, &mmu)?,
+                            Interrupt::Interrupt(id) => abi.on_interrupt(id, &ctx, &mmu)?,
+                            Interrupt::SystemCall(id) => abi.on_system_call(id, &ctx, &mmu)?,
+                            Interrupt::Aborts(value) => ControlFlow::Break(value),
+                            Interrupt::Reset => ControlFlow::Break(0),
+                            Interrupt::Yield => {
                                 std::thread::yield_now();
-                                continue;
-                            };
-                            
-                            abi.on_irq(irq.id, irq.level, &ctx, &mmu);
+                                ControlFlow::Continue(())
+                            }
+                            Interrupt::WaitForInterrupt => {
+                                // TODO: wait for a interrupt using Parking
+                                loop {
+                                    let Some(irq) = irq.recv() 
+                                    else {
+                                        std::thread::yield_now();
+                                        continue;
+                                    };
+                                
+                                    abi.on_irq(irq.id, irq.level, &ctx, &mmu);
+                                }
+                            },
+                        };
+                        if let ControlFlow::Break(status) = flow {
+                            return Ok(status);
                         }
-                    },
+                    }
+                }
+
+                // Modified code must not be reached through a link
+                for page in mmu.take_modified_code() {
+                    cache.invalidate_page(page);
                 }
+
+                let Some(next_bb) = cache.follow_link(&compiled_bb, ctx.get(pc_reg)) else {
+                    break;
+                };
+                compiled_bb = next_bb;
             }
+
+            prev_bb = Some(compiled_bb);
         }
     }
 }
@@ -1,13 +1,63 @@
+use core::{Access, Error};
 use device::IoDevice;
-use parking_lot::{RwLock, RwLockUpgradableReadGuard, RwLockWriteGuard};
-use std::{cell::RefCell, ops::Range, sync::Arc};
+use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard, RwLockWriteGuard};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    ops::{BitOr, Range},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use thread_local::ThreadLocal;
 
+/// Access rights of guest memory, with the bits of the PROT_* flags of mmap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Protection(u8);
+
+impl Protection {
+    pub const NONE: Self = Self(0);
+    pub const READ: Self = Self(1);
+    pub const WRITE: Self = Self(2);
+    pub const EXEC: Self = Self(4);
+    pub const ALL: Self = Self(7);
+
+    /// Protection from PROT_* bits, unknown bits are ignored.
+    pub fn from_bits(bits: u64) -> Self {
+        Self(bits as u8 & Self::ALL.0)
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0 as u64
+    }
+
+    pub fn allows(self, access: Access) -> bool {
+        let required = match access {
+            Access::Read => Self::READ,
+            Access::Write => Self::WRITE,
+            Access::Execute => Self::EXEC,
+        };
+        self.0 & required.0 != 0
+    }
+}
+
+impl BitOr for Protection {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
 #[derive(Clone)]
 struct DeviceBlock {
     base: u64,
     size: u64,
+    // offset of the block in the device, non-zero if the block has been split
+    offset: u64,
+    prot: Protection,
     link_state: Arc<LinkState>,
     device: Arc<dyn IoDevice + Sync + Send>,
 }
@@ -16,193 +66,718 @@ impl DeviceBlock {
     fn range(&self) -> Range<u64> {
         self.base..self.base + self.size
     }
+
+    /// Translate a guest address into the device offset and the length left in the block.
+    fn translate(&self, addr: u64) -> (u64, usize) {
+        let len = self.base + self.size - addr;
+        (
+            addr - self.base + self.offset,
+            len.min(usize::MAX as u64) as usize,
+        )
+    }
 }
 
+/// Granularity of code modification tracking.
+pub const CODE_PAGE_SIZE: u64 = 4096;
+
 pub struct SoftMmu {
     // map is sorted based on base address
-    map: Vec<DeviceBlock>,
-    last_access: ThreadLocal<RefCell<(usize, DeviceBlock)>>,
+    map: RwLock<Vec<DeviceBlock>>,
+    // bumped whenever the map changes, so cached blocks can be revalidated
+    generation: AtomicU64,
+    last_access: ThreadLocal<RefCell<Option<(u64, usize, DeviceBlock)>>>,
+    code_pages: CodePages,
+    // address and generation of the link of the last exclusive load of each thread
+    local_link: ThreadLocal<Cell<Option<(u64, u64)>>>,
 }
 
 impl SoftMmu {
     pub fn new() -> Self {
         Self {
-            map: Vec::new(),
+            map: RwLock::new(Vec::new()),
+            generation: AtomicU64::new(0),
             last_access: ThreadLocal::new(),
+            code_pages: CodePages::new(),
+            local_link: ThreadLocal::new(),
         }
     }
 
-    pub fn map<I>(&mut self, base: u64, size: u64, device: I)
+    /// Map `device` at `base`, replacing whatever was mapped in the range before.
+    ///
+    /// The guest has every access right to the new mapping until it is changed with `protect`.
+    pub fn map<I>(&self, base: u64, size: u64, device: I)
     where
         I: IoDevice + Sync + Send + 'static,
     {
-        self.map.push(DeviceBlock {
-            base,
-            size,
-            link_state: Arc::new(LinkState::new()),
-            device: Arc::new(device),
-        });
+        let mut map = self.map.write();
+        Self::unmap_locked(&mut map, base..base + size);
+
+        let idx = map.partition_point(|block| block.base < base);
+        map.insert(
+            idx,
+            DeviceBlock {
+                base,
+                size,
+                offset: 0,
+                prot: Protection::ALL,
+                link_state: Arc::new(LinkState::new()),
+                device: Arc::new(device),
+            },
+        );
+
+        self.generation.fetch_add(1, Ordering::Release);
+        self.code_pages.modify(base..base + size, false);
+    }
+
+    /// Unmap every device in `range`. Devices partially in the range are split.
+    pub fn unmap(&self, range: Range<u64>) {
+        let mut map = self.map.write();
+        Self::unmap_locked(&mut map, range.clone());
 
-        self.map.sort_by(|a, b| a.base.cmp(&b.base));
+        self.generation.fetch_add(1, Ordering::Release);
+        self.code_pages.modify(range, false);
     }
 
-    fn get_device_block(&self, offset: u64) -> DeviceBlock {
-        fn is_block_avail(block: &DeviceBlock, offset: u64) -> bool {
-            block.range().contains(&offset)
+    /// Change the access rights of the guest to `range`.
+    ///
+    /// If part of `range` is not mapped, nothing is changed and the error is an
+    /// `UnmappedAccess` of the first address that is not mapped.
+    pub fn protect(&self, range: Range<u64>, prot: Protection) -> Result<(), Error> {
+        let mut map = self.map.write();
+        if let Some(addr) = Self::first_unmapped_locked(&map, range.clone()) {
+            return Err(Error::UnmappedAccess {
+                addr,
+                access: Access::Read,
+            });
         }
 
-        let (idx, block) = &*self
-            .last_access
-            .get_or(|| RefCell::new(self.get_device_block_slow(offset)))
-            .borrow();
+        Self::split_locked(&mut map, range.start);
+        Self::split_locked(&mut map, range.end);
 
-        if is_block_avail(block, offset) {
-            return block.clone();
+        let start = map.partition_point(|block| block.base < range.start);
+        for block in map[start..]
+            .iter_mut()
+            .take_while(|block| block.base < range.end)
+        {
+            block.prot = prot;
         }
 
-        // Optimization: if the next block is within 4KB, then it is likely that the next access
-        if block.base + block.size + 4096 >= offset {
-            let next_block = &self.map[idx + 1];
-            if is_block_avail(&next_block, offset) {
-                return next_block.clone();
+        self.generation.fetch_add(1, Ordering::Release);
+        // Translations must be fetched again with the new rights
+        self.code_pages.modify(range, false);
+        Ok(())
+    }
+
+    /// Returns true if any byte in `range` is mapped.
+    pub fn is_mapped(&self, range: Range<u64>) -> bool {
+        let map = self.map.read();
+        let idx = map.partition_point(|block| block.base + block.size <= range.start);
+        map.get(idx).map_or(false, |block| block.base < range.end)
+    }
+
+    /// Address ranges of the mapped devices and their protection, in ascending order.
+    pub fn mappings(&self) -> Vec<(Range<u64>, Protection)> {
+        self.map
+            .read()
+            .iter()
+            .map(|block| (block.range(), block.prot))
+            .collect()
+    }
+
+    /// First address of `range` that no block maps.
+    fn first_unmapped_locked(map: &[DeviceBlock], range: Range<u64>) -> Option<u64> {
+        let mut addr = range.start;
+        let idx = map.partition_point(|block| block.base + block.size <= range.start);
+        for block in map[idx..].iter().take_while(|block| block.base < range.end) {
+            if block.base > addr {
+                return Some(addr);
             }
+            addr = block.base + block.size;
         }
 
-        let (_, block) = self.get_device_block_slow(offset);
-        block
+        (addr < range.end).then_some(addr)
     }
 
-    fn get_device_block_slow(&self, offset: u64) -> (usize, DeviceBlock) {
-        fn get_device_binary(
-            map: &[DeviceBlock],
-            range: Range<usize>,
-            finding: u64,
-        ) -> (usize, DeviceBlock) {
-            assert_ne!(range.start, range.end, "not exist");
-            let mid = (range.start + range.end) / 2;
+    /// Split the block that contains `addr`, so a block starts at `addr`.
+    fn split_locked(map: &mut Vec<DeviceBlock>, addr: u64) {
+        let idx = map.partition_point(|block| block.base + block.size <= addr);
+        let Some(block) = map.get_mut(idx) else {
+            return;
+        };
+        if block.base >= addr {
+            return;
+        }
+
+        let mut tail = block.clone();
+        tail.base = addr;
+        tail.size = block.base + block.size - addr;
+        tail.offset = block.offset + (addr - block.base);
+        block.size = addr - block.base;
+        map.insert(idx + 1, tail);
+    }
 
-            if map[mid].range().contains(&finding) {
-                (mid, map[mid].clone())
-            } else if map[mid].base > finding {
-                get_device_binary(map, range.start..mid, finding)
-            } else {
-                get_device_binary(map, mid..range.end, finding)
+    fn unmap_locked(map: &mut Vec<DeviceBlock>, range: Range<u64>) {
+        let mut idx = map.partition_point(|block| block.base + block.size <= range.start);
+        while idx < map.len() && map[idx].base < range.end {
+            let block = map.remove(idx);
+
+            if block.base < range.start {
+                let mut head = block.clone();
+                head.size = range.start - block.base;
+                map.insert(idx, head);
+                idx += 1;
+            }
+
+            let end = block.base + block.size;
+            if end > range.end {
+                let mut tail = block.clone();
+                tail.base = range.end;
+                tail.size = end - range.end;
+                tail.offset = block.offset + (range.end - block.base);
+                map.insert(idx, tail);
+                idx += 1;
             }
         }
+    }
 
-        get_device_binary(&self.map, 0..self.map.len(), offset)
+    fn get_device_block(&self, offset: u64) -> Option<DeviceBlock> {
+        let generation = self.generation.load(Ordering::Acquire);
+        let mut last_access = self.last_access.get_or(|| RefCell::new(None)).borrow_mut();
+
+        if let Some((last_generation, idx, block)) = &*last_access {
+            if *last_generation == generation {
+                if block.range().contains(&offset) {
+                    return Some(block.clone());
+                }
+
+                // Optimization: if the next block is within 4KB, then it is likely that the next access
+                if block.base + block.size + 4096 >= offset {
+                    let idx = idx + 1;
+                    let map = self.map.read();
+                    if let Some(next_block) = map.get(idx) {
+                        if next_block.range().contains(&offset) {
+                            let next_block = next_block.clone();
+                            *last_access = Some((generation, idx, next_block.clone()));
+                            return Some(next_block);
+                        }
+                    }
+                }
+            }
+        }
+
+        let found = self.get_device_block_slow(generation, offset)?;
+        let block = found.2.clone();
+        *last_access = Some(found);
+        Some(block)
     }
 
-    pub unsafe fn ll64(&self, offset: u64) -> u64 {
-        let device_block = self.get_device_block(offset);
-        let _ = device_block.link_state.link(offset);
+    fn get_device_block_slow(
+        &self,
+        generation: u64,
+        offset: u64,
+    ) -> Option<(u64, usize, DeviceBlock)> {
+        let map = self.map.read();
+        let idx = map.partition_point(|block| block.base + block.size <= offset);
+
+        match map.get(idx) {
+            Some(block) if block.range().contains(&offset) => {
+                Some((generation, idx, block.clone()))
+            }
+            _ => None,
+        }
+    }
 
-        let mut buf = [0u8; 8];
-        device_block
-            .device
-            .read_all_at(offset - device_block.base, &mut buf);
-        std::mem::transmute(buf)
+    /// Check that the guest is allowed to access `len` bytes at `addr`.
+    pub fn check(&self, addr: u64, len: u64, access: Access) -> Result<(), Error> {
+        let end = addr.saturating_add(len);
+        let mut addr = addr;
+        while addr < end {
+            let block = self
+                .get_device_block(addr)
+                .ok_or(Error::UnmappedAccess { addr, access })?;
+            if !block.prot.allows(access) {
+                return Err(Error::PermissionFault { addr, access });
+            }
+
+            addr = block.base + block.size;
+        }
+
+        Ok(())
     }
 
-    pub unsafe fn sc64(&self, offset: u64, value: u64) -> bool {
-        let device_block = self.get_device_block(offset);
-        let hold = device_block.link_state.hold(offset);
+    /// Read guest memory on behalf of the guest. `access` is either a data read or an
+    /// instruction fetch.
+    ///
+    /// Nothing is read unless the whole range may be accessed.
+    pub unsafe fn read(&self, addr: u64, buf: &mut [u8], access: Access) -> Result<(), Error> {
+        self.check(addr, buf.len() as u64, access)?;
+        self.read_all_at(addr, buf);
+        Ok(())
+    }
 
-        if hold.is_none() {
-            return false;
+    /// Write guest memory on behalf of the guest.
+    ///
+    /// Nothing is written unless the whole range may be written.
+    pub unsafe fn write(&self, addr: u64, buf: &[u8]) -> Result<(), Error> {
+        self.check(addr, buf.len() as u64, Access::Write)?;
+        self.write_all_at(addr, buf);
+        Ok(())
+    }
+
+    /// The block the guest accesses `size` bytes at `addr` through, for exclusive accesses
+    /// that must be naturally aligned.
+    fn exclusive_block(&self, addr: u64, size: u64, access: Access) -> Result<DeviceBlock, Error> {
+        if addr % size != 0 {
+            return Err(Error::AlignmentFault { addr, size });
         }
 
-        let mut buf: [u8; 8] = std::mem::transmute(value);
+        self.check(addr, size, access)?;
+        Ok(self.get_device_block(addr).unwrap())
+    }
+
+    /// Start tracking writes to the pages that back the translated code in `range`.
+    pub fn track_code(&self, range: Range<u64>) {
+        self.code_pages.track(range);
+    }
+
+    /// Mark the code of the page that contains `addr` as modified.
+    pub fn invalidate_code(&self, addr: u64) {
+        self.code_pages.modify(addr..addr + 1, true);
+    }
+
+    /// Mark the code of every page as modified.
+    pub fn invalidate_all_code(&self) {
+        self.code_pages.modify_all();
+    }
+
+    /// Take the base addresses of code pages modified since the last call.
+    ///
+    /// Pages are not tracked anymore once they are reported.
+    pub fn take_modified_code(&self) -> Vec<u64> {
+        self.code_pages.take_modified()
+    }
+
+    /// Read `buf.len()` bytes at `addr` and link the address for the next `sc` of this thread.
+    pub unsafe fn ll(&self, addr: u64, buf: &mut [u8]) -> Result<(), Error> {
+        let device_block = self.exclusive_block(addr, buf.len() as u64, Access::Read)?;
+        let generation = device_block.link_state.link(addr);
+        self.local_link().set(Some((addr, generation)));
+
         device_block
             .device
-            .write_all_at(offset - device_block.base, &mut buf);
-        true
+            .read_all_at(device_block.translate(addr).0, buf);
+        Ok(())
     }
 
-    pub unsafe fn ll32(&self, offset: u64) -> u32 {
-        let device_block = self.get_device_block(offset);
-        let _ = device_block.link_state.link(offset);
+    /// Write `buf` to `addr` if this thread linked it and nothing was written to it since.
+    /// Returns whether it was written, the link is cleared either way.
+    pub unsafe fn sc(&self, addr: u64, buf: &[u8]) -> Result<bool, Error> {
+        let device_block = self.exclusive_block(addr, buf.len() as u64, Access::Write)?;
+        let Some((linked, generation)) = self.local_link().take() else {
+            return Ok(false);
+        };
+        if linked != addr {
+            return Ok(false);
+        }
+        let Some(mut hold) = device_block.link_state.hold(addr, generation) else {
+            return Ok(false);
+        };
+        hold.clear();
 
-        let mut buf = [0u8; 4];
         device_block
             .device
-            .read_all_at(offset - device_block.base, &mut buf);
-        std::mem::transmute(buf)
+            .write_all_at(device_block.translate(addr).0, buf);
+        self.code_pages.modify(addr..addr + buf.len() as u64, false);
+        Ok(true)
     }
 
-    pub unsafe fn sc32(&self, offset: u64, value: u32) -> bool {
-        let device_block = self.get_device_block(offset);
-        let hold = device_block.link_state.hold(offset);
+    /// Clear the link of this thread, the next `sc` fails unless it is preceded by a `ll`.
+    pub fn clear_link(&self) {
+        self.local_link().set(None);
+    }
+
+    fn local_link(&self) -> &Cell<Option<(u64, u64)>> {
+        self.local_link.get_or(|| Cell::new(None))
+    }
 
-        if hold.is_none() {
-            return false;
+    /// Atomically replace the `N` bytes at `addr` with what `update` returns for them, or
+    /// leave them alone if it returns `None`. Returns the bytes found at `addr`.
+    ///
+    /// Like exclusive accesses the address must be naturally aligned, and it must be both
+    /// readable and writable whether it is written or not.
+    pub unsafe fn atomic_update<const N: usize>(
+        &self,
+        addr: u64,
+        update: impl FnOnce([u8; N]) -> Option<[u8; N]>,
+    ) -> Result<[u8; N], Error> {
+        let device_block = self.exclusive_block(addr, N as u64, Access::Write)?;
+        self.check(addr, N as u64, Access::Read)?;
+
+        // Normal writes to the block wait for the hold, so nothing is written in between
+        let _hold = device_block.link_state.hold_normal(addr..addr + N as u64);
+        let offset = device_block.translate(addr).0;
+
+        let mut old = [0u8; N];
+        device_block.device.read_all_at(offset, &mut old);
+        if let Some(new) = update(old) {
+            device_block.device.write_all_at(offset, &new);
+            self.code_pages.modify(addr..addr + N as u64, false);
         }
+        Ok(old)
+    }
 
-        let mut buf: [u8; 4] = std::mem::transmute(value);
-        device_block
-            .device
-            .write_all_at(offset - device_block.base, &mut buf);
-        true
+    pub unsafe fn ll64(&self, offset: u64) -> Result<u64, Error> {
+        let mut buf = [0u8; 8];
+        self.ll(offset, &mut buf)?;
+        Ok(u64::from_ne_bytes(buf))
+    }
+
+    pub unsafe fn sc64(&self, offset: u64, value: u64) -> Result<bool, Error> {
+        self.sc(offset, &value.to_ne_bytes())
+    }
+
+    pub unsafe fn ll32(&self, offset: u64) -> Result<u32, Error> {
+        let mut buf = [0u8; 4];
+        self.ll(offset, &mut buf)?;
+        Ok(u32::from_ne_bytes(buf))
+    }
+
+    pub unsafe fn sc32(&self, offset: u64, value: u32) -> Result<bool, Error> {
+        self.sc(offset, &value.to_ne_bytes())
     }
 }
 
 impl IoDevice for SoftMmu {
     unsafe fn read_at(&self, offset: u64, buf: &mut [u8]) -> usize {
-        let device_block = self.get_device_block(offset);
-        device_block.device.read_at(offset - device_block.base, buf)
+        let Some(device_block) = self.get_device_block(offset) else {
+            return 0;
+        };
+        let (offset, len) = device_block.translate(offset);
+        let len = buf.len().min(len);
+
+        device_block.device.read_at(offset, &mut buf[..len])
     }
 
     unsafe fn write_at(&self, offset: u64, buf: &[u8]) -> usize {
-        let device_block = self.get_device_block(offset);
-        let _ = device_block.link_state.hold_normal();
+        let Some(device_block) = self.get_device_block(offset) else {
+            return 0;
+        };
+        let _hold = device_block
+            .link_state
+            .hold_normal(offset..offset + buf.len() as u64);
+
+        let addr = offset;
+        let (offset, len) = device_block.translate(offset);
+        let len = buf.len().min(len);
+
+        let len = device_block.device.write_at(offset, &buf[..len]);
+        self.code_pages.modify(addr..addr + len as u64, false);
+        len
+    }
+}
 
-        device_block
-            .device
-            .write_at(offset - device_block.base, buf)
+/// Guest pages that back translated code.
+struct CodePages {
+    pages: RwLock<HashSet<u64>>,
+    modified: Mutex<Vec<u64>>,
+    has_modified: AtomicBool,
+}
+
+impl CodePages {
+    fn new() -> Self {
+        Self {
+            pages: RwLock::new(HashSet::new()),
+            modified: Mutex::new(Vec::new()),
+            has_modified: AtomicBool::new(false),
+        }
+    }
+
+    fn page_range(range: Range<u64>) -> Range<u64> {
+        let start = range.start / CODE_PAGE_SIZE;
+        let end = (range.end + CODE_PAGE_SIZE - 1) / CODE_PAGE_SIZE;
+        start..end
+    }
+
+    fn track(&self, range: Range<u64>) {
+        let mut pages = self.pages.write();
+        pages.extend(Self::page_range(range));
+    }
+
+    /// Report tracked pages in `range` as modified. `force` reports untracked pages too.
+    fn modify(&self, range: Range<u64>, force: bool) {
+        let page_range = Self::page_range(range);
+        if !force {
+            let pages = self.pages.read();
+            if !page_range.clone().any(|page| pages.contains(&page)) {
+                return;
+            }
+        }
+
+        let mut pages = self.pages.write();
+        let mut modified = self.modified.lock();
+        for page in page_range {
+            if pages.remove(&page) || force {
+                modified.push(page * CODE_PAGE_SIZE);
+            }
+        }
+
+        self.has_modified.store(true, Ordering::Release);
+    }
+
+    /// Report every tracked page as modified.
+    fn modify_all(&self) {
+        let mut pages = self.pages.write();
+        let mut modified = self.modified.lock();
+        modified.extend(pages.drain().map(|page| page * CODE_PAGE_SIZE));
+
+        self.has_modified.store(true, Ordering::Release);
+    }
+
+    fn take_modified(&self) -> Vec<u64> {
+        if !self.has_modified.load(Ordering::Acquire) {
+            return Vec::new();
+        }
+
+        self.has_modified.store(false, Ordering::Release);
+        std::mem::take(&mut *self.modified.lock())
     }
 }
 
+/// Size of the block around a linked address that normal writes unlink it for. Exclusive
+/// accesses are at most 16 bytes and naturally aligned, so they never cross it.
+const RESERVATION_GRANULE: u64 = 16;
+
 struct LinkState {
-    state: RwLock<u64>,
+    // linked address and the number of links so far, which tells links to the same address apart
+    state: RwLock<(u64, u64)>,
 }
 
 impl LinkState {
     fn new() -> Self {
         Self {
-            state: RwLock::new(0),
+            state: RwLock::new((0, 0)),
         }
     }
 
-    /// Creates a new link state.
-    fn link(&self, addr: u64) {
-        // Wait until the link is available.
-        // You can't link the state if it is already linked or writting.
-        *self.state.write() = addr;
+    /// Link `addr`, replacing the previous link. Returns the generation of the new link.
+    fn link(&self, addr: u64) -> u64 {
+        let mut state = self.state.write();
+        state.1 += 1;
+        state.0 = addr;
+        state.1
     }
 
-    /// Returns true if the link is up.
+    /// Hold the link state for an exclusive write, if `addr` is still linked by `generation`.
     #[must_use]
-    fn hold(&self, addr: u64) -> Option<LinkStateHold1<'_>> {
+    fn hold(&self, addr: u64, generation: u64) -> Option<LinkStateHold1<'_>> {
         let state = self.state.try_write()?;
-        if *state == addr {
+        if *state == (addr, generation) {
             Some(LinkStateHold1(state))
         } else {
             None
         }
     }
 
+    /// Hold the link state for a normal write to `range`, which unlinks the address if the
+    /// write touches its reservation granule.
     #[must_use]
-    fn hold_normal(&self) -> LinkStateHold2<'_> {
-        let mut state = self.state.upgradable_read();
-        if *state != 0 {
-            state.with_upgraded(|state| {
-                *state = 0;
-            });
+    fn hold_normal(&self, range: Range<u64>) -> LinkStateHold2<'_> {
+        let state = self.state.upgradable_read();
+        let granule = state.0 & !(RESERVATION_GRANULE - 1);
+        if state.0 == 0 || range.end <= granule || range.start >= granule + RESERVATION_GRANULE {
+            return LinkStateHold2(state);
         }
 
-        LinkStateHold2(state)
+        let mut state = RwLockUpgradableReadGuard::upgrade(state);
+        state.0 = 0;
+        LinkStateHold2(RwLockWriteGuard::downgrade_to_upgradable(state))
     }
 }
 
-struct LinkStateHold1<'a>(RwLockWriteGuard<'a, u64>);
-struct LinkStateHold2<'a>(RwLockUpgradableReadGuard<'a, u64>);
+struct LinkStateHold1<'a>(RwLockWriteGuard<'a, (u64, u64)>);
+struct LinkStateHold2<'a>(RwLockUpgradableReadGuard<'a, (u64, u64)>);
+
+impl LinkStateHold1<'_> {
+    /// Unlink the address, the store that follows consumes the link.
+    fn clear(&mut self) {
+        self.0 .0 = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use device::devices::Memory;
+    use std::thread;
+
+    const BASE: u64 = 0x10000;
+    const SIZE: u64 = 0x1000;
+
+    /// An MMU with a page of memory at `BASE`.
+    fn mmu() -> SoftMmu {
+        let mmu = SoftMmu::new();
+        mmu.map(BASE, SIZE, Memory::allocate(SIZE as usize));
+        mmu
+    }
+
+    fn read64(mmu: &SoftMmu, addr: u64) -> u64 {
+        let mut buf = [0; 8];
+        unsafe { mmu.read(addr, &mut buf, Access::Read).unwrap() };
+        u64::from_ne_bytes(buf)
+    }
+
+    #[test]
+    fn store_exclusive_consumes_the_link() {
+        let mmu = mmu();
+        unsafe {
+            assert_eq!(mmu.sc64(BASE, 1), Ok(false));
+            assert_eq!(mmu.ll64(BASE), Ok(0));
+            assert_eq!(mmu.sc64(BASE, 2), Ok(true));
+            assert_eq!(mmu.sc64(BASE, 3), Ok(false));
+        }
+        assert_eq!(read64(&mmu, BASE), 2);
+    }
+
+    #[test]
+    fn store_exclusive_to_another_address_fails() {
+        let mmu = mmu();
+        unsafe {
+            mmu.ll64(BASE).unwrap();
+            assert_eq!(mmu.sc64(BASE + 8, 1), Ok(false));
+            // The failed store cleared the link
+            assert_eq!(mmu.sc64(BASE, 1), Ok(false));
+
+            mmu.ll64(BASE).unwrap();
+            mmu.clear_link();
+            assert_eq!(mmu.sc64(BASE, 1), Ok(false));
+        }
+        assert_eq!(read64(&mmu, BASE), 0);
+    }
+
+    #[test]
+    fn writes_to_the_granule_break_the_link() {
+        let mmu = mmu();
+        unsafe {
+            mmu.ll64(BASE).unwrap();
+            mmu.write(BASE + 8, &[1]).unwrap();
+            assert_eq!(mmu.sc64(BASE, 1), Ok(false));
+
+            // The next granule is unrelated
+            mmu.ll64(BASE).unwrap();
+            mmu.write(BASE + RESERVATION_GRANULE, &[1]).unwrap();
+            assert_eq!(mmu.sc64(BASE, 1), Ok(true));
+        }
+    }
+
+    #[test]
+    fn links_of_other_threads_break_the_link() {
+        let mmu = mmu();
+        unsafe { mmu.ll64(BASE).unwrap() };
+        thread::scope(|scope| {
+            scope.spawn(|| unsafe {
+                assert_eq!(mmu.ll64(BASE), Ok(0));
+                assert_eq!(mmu.sc64(BASE, 1), Ok(true));
+            });
+        });
+
+        assert_eq!(unsafe { mmu.sc64(BASE, 2) }, Ok(false));
+        assert_eq!(read64(&mmu, BASE), 1);
+    }
+
+    #[test]
+    fn exclusive_accesses_are_aligned_and_checked() {
+        let mmu = mmu();
+        unsafe {
+            assert_eq!(
+                mmu.ll64(BASE + 4),
+                Err(Error::AlignmentFault {
+                    addr: BASE + 4,
+                    size: 8
+                })
+            );
+
+            mmu.ll64(BASE).unwrap();
+            mmu.protect(BASE..BASE + SIZE, Protection::READ).unwrap();
+            assert_eq!(
+                mmu.sc64(BASE, 1),
+                Err(Error::PermissionFault {
+                    addr: BASE,
+                    access: Access::Write
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn atomic_update_returns_the_old_value() {
+        let mmu = mmu();
+        unsafe {
+            mmu.write(BASE, &5u64.to_ne_bytes()).unwrap();
+            let old = mmu.atomic_update(BASE, |old| {
+                Some((u64::from_ne_bytes(old) + 1).to_ne_bytes())
+            });
+            assert_eq!(old, Ok(5u64.to_ne_bytes()));
+            assert_eq!(read64(&mmu, BASE), 6);
+
+            // A failed compare leaves memory alone
+            let old = mmu.atomic_update::<8>(BASE, |_| None);
+            assert_eq!(old, Ok(6u64.to_ne_bytes()));
+            assert_eq!(read64(&mmu, BASE), 6);
+        }
+    }
+
+    #[test]
+    fn atomic_update_breaks_links() {
+        let mmu = mmu();
+        unsafe {
+            mmu.ll64(BASE).unwrap();
+            mmu.atomic_update(BASE + 8, |_| Some([1; 4])).unwrap();
+            assert_eq!(mmu.sc64(BASE, 1), Ok(false));
+        }
+    }
+
+    #[test]
+    fn atomic_update_needs_write_access() {
+        let mmu = mmu();
+        unsafe {
+            assert_eq!(
+                mmu.atomic_update::<4>(BASE + 2, |_| None),
+                Err(Error::AlignmentFault {
+                    addr: BASE + 2,
+                    size: 4
+                })
+            );
+
+            // Even if nothing is written
+            mmu.protect(BASE..BASE + SIZE, Protection::READ).unwrap();
+            assert_eq!(
+                mmu.atomic_update::<4>(BASE, |_| None),
+                Err(Error::PermissionFault {
+                    addr: BASE,
+                    access: Access::Write
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn concurrent_atomic_updates_are_not_lost() {
+        let mmu = mmu();
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        unsafe {
+                            mmu.atomic_update(BASE, |old| {
+                                Some((u64::from_ne_bytes(old) + 1).to_ne_bytes())
+                            })
+                            .unwrap();
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(read64(&mmu, BASE), 4000);
+    }
+}
@@ -0,0 +1,132 @@
+//! Runs a handful of instruction words on every code generator and compares the results.
+
+#![allow(dead_code)]
+
+use std::{
+    ops::{Generator, GeneratorState},
+    pin::pin,
+};
+
+use arch_desc::aarch64::{AArch64Architecture, AArch64Inst, AArch64Register};
+use core::{
+    ir::{BasicBlock, BasicBlockTerminator, IrConstant, IrType, IrValue},
+    Error, Instruction, Register,
+};
+use execution::{
+    codegen::{cranelift::CraneliftCodegen, rustjit::RustjitCodegen, Codegen, Context, Executable},
+    SoftMmu,
+};
+
+/// Registers of the guest before or after running the instructions.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct State {
+    pub x: [u64; 31],
+    pub v: [u128; 32],
+    pub sp: u64,
+    pub nzcv: u64,
+    pub fpcr: u64,
+    pub fpsr: u64,
+}
+
+const X: IrType = IrType::B64;
+const V: IrType = IrType::B128;
+
+fn reg(ty: IrType, reg: AArch64Register) -> IrValue {
+    IrValue::Register(ty, reg.raw())
+}
+
+fn run_on<C: Codegen>(words: &[u32], state: &State, mmu: &SoftMmu) -> Result<State, Error> {
+    let mut bb = BasicBlock::new(0);
+    for word in words {
+        let inst = AArch64Inst::decode(&word.to_le_bytes())?;
+        inst.compile_to_ir(&mut bb)?;
+    }
+    if bb.terminator() == BasicBlockTerminator::None {
+        let next = IrConstant::B64(4 * words.len() as u64);
+        bb.set_terminator(BasicBlockTerminator::Branch(IrValue::Constant(next)));
+    }
+
+    let ctx = C::allocate_execution_context::<AArch64Architecture>();
+    for (i, &value) in state.x.iter().enumerate() {
+        ctx.set(reg(X, AArch64Register::X(i as u8)), value);
+    }
+    for (i, &value) in state.v.iter().enumerate() {
+        ctx.set(reg(V, AArch64Register::V(i as u8)), value);
+    }
+    ctx.set(reg(X, AArch64Register::Sp), state.sp);
+    ctx.set(reg(X, AArch64Register::Pstate), state.nzcv);
+    ctx.set(reg(X, AArch64Register::Fpcr), state.fpcr);
+    ctx.set(reg(X, AArch64Register::Fpsr), state.fpsr);
+
+    let exec = C::new().compile::<AArch64Architecture>(&bb)?;
+    let mut gen = pin!(unsafe { exec.execute(&ctx, mmu) });
+    match gen.as_mut().resume(()) {
+        GeneratorState::Yielded(interrupt) => panic!("unexpected {interrupt:?}"),
+        GeneratorState::Complete(result) => result?,
+    }
+
+    let mut state = State {
+        sp: ctx.get(reg(X, AArch64Register::Sp)),
+        nzcv: ctx.get(reg(X, AArch64Register::Pstate)),
+        fpcr: ctx.get(reg(X, AArch64Register::Fpcr)),
+        fpsr: ctx.get(reg(X, AArch64Register::Fpsr)),
+        ..State::default()
+    };
+    for i in 0..31 {
+        state.x[i] = ctx.get(reg(X, AArch64Register::X(i as u8)));
+    }
+    for i in 0..32 {
+        state.v[i] = ctx.get(reg(V, AArch64Register::V(i as u8)));
+    }
+    Ok(state)
+}
+
+/// Run `words` from `state` on every code generator, with `mmu` as the guest memory.
+///
+/// Panics if the code generators disagree.
+pub fn run_with_mmu(words: &[u32], state: &State, mmu: &SoftMmu) -> Result<State, Error> {
+    let rustjit = run_on::<RustjitCodegen>(words, state, mmu);
+    let cranelift = run_on::<CraneliftCodegen>(words, state, mmu);
+    assert_eq!(
+        format!("{rustjit:?}"),
+        format!("{cranelift:?}"),
+        "code generators disagree"
+    );
+    rustjit
+}
+
+/// Run `words` from `state` on every code generator, without guest memory.
+pub fn run(words: &[u32], state: &State) -> State {
+    run_with_mmu(words, state, &SoftMmu::new()).unwrap()
+}
+
+/// A static executable that loads `code` right after its headers at `base` and starts at its
+/// first instruction.
+pub fn static_elf(base: u64, code: &[u32]) -> Vec<u8> {
+    const EHDR_SIZE: u16 = 64;
+    const PHDR_SIZE: u16 = 56;
+    let size = (EHDR_SIZE + PHDR_SIZE) as u64 + 4 * code.len() as u64;
+
+    let mut elf = b"\x7fELF\x02\x01\x01".to_vec();
+    elf.resize(16, 0);
+    elf.extend(2u16.to_le_bytes()); // ET_EXEC
+    elf.extend(183u16.to_le_bytes()); // EM_AARCH64
+    elf.extend(1u32.to_le_bytes());
+    elf.extend((base + (EHDR_SIZE + PHDR_SIZE) as u64).to_le_bytes());
+    elf.extend((EHDR_SIZE as u64).to_le_bytes());
+    elf.extend(0u64.to_le_bytes());
+    elf.extend(0u32.to_le_bytes());
+    for half in [EHDR_SIZE, PHDR_SIZE, 1, 64, 0, 0] {
+        elf.extend(half.to_le_bytes());
+    }
+
+    // A single PT_LOAD segment, readable and executable, maps the whole file
+    elf.extend(1u32.to_le_bytes());
+    elf.extend(5u32.to_le_bytes());
+    for word in [0, base, base, size, size, 0x1000] {
+        elf.extend(word.to_le_bytes());
+    }
+
+    elf.extend(code.iter().flat_map(|word| word.to_le_bytes()));
+    elf
+}
@@ -0,0 +1,158 @@
+#![feature(generators, generator_trait)]
+
+mod common;
+
+use common::{run, State};
+
+/// FPSR.IOC, set by invalid operations.
+const INVALID: u64 = 1 << 0;
+/// FPSR.DZC, set by divisions by zero.
+const DIVIDE_BY_ZERO: u64 = 1 << 1;
+/// FPSR.OFC, set by overflowing results.
+const OVERFLOW: u64 = 1 << 2;
+/// FPSR.UFC, set by tiny inexact results.
+const UNDERFLOW: u64 = 1 << 3;
+/// FPSR.IXC, set by inexact results.
+const INEXACT: u64 = 1 << 4;
+/// FPSR.IDC, set by flushed denormal operands.
+const INPUT_DENORMAL: u64 = 1 << 7;
+
+/// Flushes denormals to zero, in the layout of the control register.
+const FLUSH_TO_ZERO: u64 = 1 << 2;
+/// Rounds toward zero, in the layout of the control register.
+const TOWARD_ZERO: u64 = 0b11;
+
+#[test]
+fn arithmetic_raises_inexact_only_when_rounding() {
+    let mut state = State::default();
+
+    // fadd s2, s0, s1
+    state.v[0] = 0x3f80_0000;
+    state.v[1] = 0x4000_0000;
+    let result = run(&[0x1e212802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x4040_0000, 0));
+    state.v[1] = 0x3080_0000;
+    let result = run(&[0x1e212802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x3f80_0000, INEXACT));
+
+    // fsub d2, d0, d1, the difference of equal values is +0
+    state.v[0] = 0xbff0_0000_0000_0000;
+    state.v[1] = 0xbff0_0000_0000_0000;
+    let result = run(&[0x1e613802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0, 0));
+
+    // fmul s2, s0, s1
+    state.v[0] = 0x3fc0_0000;
+    state.v[1] = 0x3fc0_0000;
+    let result = run(&[0x1e210802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x4010_0000, 0));
+    state.v[0] = 0x3f80_0001;
+    state.v[1] = 0x3f80_0001;
+    let result = run(&[0x1e210802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x3f80_0002, INEXACT));
+
+    // fdiv d2, d0, d1
+    state.v[0] = 0x4018_0000_0000_0000;
+    state.v[1] = 0x4008_0000_0000_0000;
+    let result = run(&[0x1e611802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x4000_0000_0000_0000, 0));
+    state.v[0] = 0x3ff0_0000_0000_0000;
+    let result = run(&[0x1e611802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x3fd5_5555_5555_5555, INEXACT));
+
+    // fsqrt d2, d0
+    state.v[0] = 0x4010_0000_0000_0000;
+    let result = run(&[0x1e61c002], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x4000_0000_0000_0000, 0));
+    state.v[0] = 0x4000_0000_0000_0000;
+    let result = run(&[0x1e61c002], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x3ff6_a09e_667f_3bcd, INEXACT));
+}
+
+#[test]
+fn special_results() {
+    let mut state = State::default();
+
+    // fmul s2, s0, s1, denormal results only underflow if they are inexact
+    state.v[0] = 0x0d80_0000;
+    state.v[1] = 0x2b80_0000;
+    let result = run(&[0x1e210802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x200, 0));
+    state.v[1] = 0x2740_0000;
+    let result = run(&[0x1e210802], &state);
+    assert_eq!((result.v[2], result.fpsr), (2, UNDERFLOW | INEXACT));
+
+    state.v[0] = 0x7180_0000;
+    state.v[1] = 0x7180_0000;
+    let result = run(&[0x1e210802], &state);
+    assert_eq!(
+        (result.v[2], result.fpsr),
+        (0x7f80_0000, OVERFLOW | INEXACT)
+    );
+
+    // fdiv d2, d0, d1
+    state.v[0] = 0x3ff0_0000_0000_0000;
+    state.v[1] = 0;
+    let result = run(&[0x1e611802], &state);
+    assert_eq!(
+        (result.v[2], result.fpsr),
+        (0x7ff0_0000_0000_0000, DIVIDE_BY_ZERO)
+    );
+
+    // fsqrt d2, d0 of a negative value is the default NaN
+    state.v[0] = 0xbff0_0000_0000_0000;
+    let result = run(&[0x1e61c002], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x7ff8_0000_0000_0000, INVALID));
+
+    // fadd s2, s0, s1 propagates quiet NaNs
+    state.v[0] = 0x7fc0_0001;
+    state.v[1] = 0x3f80_0000;
+    let result = run(&[0x1e212802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x7fc0_0001, 0));
+}
+
+#[test]
+fn control_selects_rounding_and_flushing() {
+    // fadd s2, s0, s1 flushes the denormal operand
+    let mut state = State {
+        fpcr: FLUSH_TO_ZERO,
+        ..State::default()
+    };
+    state.v[0] = 0x200;
+    let result = run(&[0x1e212802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0, INPUT_DENORMAL));
+
+    // fadd s2, s0, s1, rounding to nearest would give 0x3f80_0001
+    state.fpcr = TOWARD_ZERO;
+    state.v[0] = 0x3f80_0000;
+    state.v[1] = 0x33c0_0000;
+    let result = run(&[0x1e212802], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x3f80_0000, INEXACT));
+}
+
+#[test]
+fn convert_and_compare() {
+    let mut state = State::default();
+
+    // fcvt s2, d0
+    state.v[0] = 0x3ff0_0000_0040_0000;
+    let result = run(&[0x1e624002], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x3f80_0000, INEXACT));
+    state.v[0] = 0x37a1_6c26_2777_579c;
+    let result = run(&[0x1e624002], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x116c2, UNDERFLOW | INEXACT));
+
+    // fcvt d2, s0 quiets signaling NaNs
+    state.v[0] = 0x7f80_0001;
+    let result = run(&[0x1e22c002], &state);
+    assert_eq!((result.v[2], result.fpsr), (0x7ff8_0000_2000_0000, INVALID));
+
+    // fcmp d0, d1 only signals for signaling NaNs, fcmpe s0, s1 for every NaN
+    state.v[0] = 0x3ff0_0000_0000_0000;
+    state.v[1] = 0x4000_0000_0000_0000;
+    assert_eq!(run(&[0x1e612000], &state).fpsr, 0);
+    state.v[1] = 0x7ff8_0000_0000_0000;
+    assert_eq!(run(&[0x1e612000], &state).fpsr, 0);
+    state.v[1] = 0x7fc0_0000;
+    assert_eq!(run(&[0x1e212010], &state).fpsr, INVALID);
+}
@@ -0,0 +1,128 @@
+#![feature(generators, generator_trait)]
+
+mod common;
+
+use common::{run, State};
+
+/// Half-precision values of `lanes`, packed into a vector register.
+fn half_lanes(lanes: &[u16]) -> u128 {
+    lanes
+        .iter()
+        .rev()
+        .fold(0, |vec, &lane| vec << 16 | lane as u128)
+}
+
+#[test]
+fn vector_add() {
+    // fadd v2.8h, v0.8h, v1.8h
+    let mut state = State::default();
+    state.v[0] = half_lanes(&[
+        0x3c00, 0x3e00, 0x4000, 0xbc00, 0x7c00, 0x3800, 0x0001, 0x7bff,
+    ]);
+    state.v[1] = half_lanes(&[
+        0x3c00, 0x3c00, 0x3800, 0x3c00, 0x3c00, 0x3800, 0x0001, 0x7bff,
+    ]);
+    let state = run(&[0x4e411402], &state);
+    assert_eq!(
+        state.v[2],
+        half_lanes(&[0x4000, 0x4100, 0x4100, 0x0000, 0x7c00, 0x3c00, 0x0002, 0x7c00])
+    );
+}
+
+#[test]
+fn vector_multiply_clears_upper_half() {
+    // fmul v2.4h, v0.4h, v1.4h
+    let mut state = State::default();
+    state.v[0] = half_lanes(&[
+        0x3e00, 0x4000, 0xc000, 0x3800, 0x3c00, 0x3c00, 0x3c00, 0x3c00,
+    ]);
+    state.v[1] = half_lanes(&[
+        0x4000, 0x4000, 0x3800, 0x3800, 0x3c00, 0x3c00, 0x3c00, 0x3c00,
+    ]);
+    state.v[2] = u128::MAX;
+    let state = run(&[0x2e411c02], &state);
+    assert_eq!(state.v[2], half_lanes(&[0x4200, 0x4400, 0xbc00, 0x3400]));
+}
+
+#[test]
+fn round_to_integral() {
+    // frintn h0, h1
+    let mut state = State::default();
+    state.v[0] = u128::MAX;
+    for (src, dst) in [
+        (0x4100, 0x4000),
+        (0x3e00, 0x4000),
+        (0xb800, 0x8000),
+        (0x7e00, 0x7e00),
+    ] {
+        state.v[1] = src;
+        assert_eq!(run(&[0x1ee44020], &state).v[0], dst, "frintn {src:#x}");
+    }
+
+    // frintz h0, h1
+    for (src, dst) in [(0x4100, 0x4000), (0xc100, 0xc000), (0x3800, 0x0000)] {
+        state.v[1] = src;
+        assert_eq!(run(&[0x1ee5c020], &state).v[0], dst, "frintz {src:#x}");
+    }
+}
+
+#[test]
+fn convert() {
+    let mut state = State::default();
+
+    // fcvt s0, h1
+    state.v[1] = 0x3e00;
+    assert_eq!(run(&[0x1ee24020], &state).v[0], 0x3fc0_0000);
+
+    // fcvt h0, s1, 65520 rounds to infinity
+    state.v[1] = 0x3f80_0000;
+    assert_eq!(run(&[0x1e23c020], &state).v[0], 0x3c00);
+    state.v[1] = 65520f32.to_bits() as u128;
+    assert_eq!(run(&[0x1e23c020], &state).v[0], 0x7c00);
+
+    // fcvtzs w0, h1
+    state.v[1] = 0xc100;
+    assert_eq!(run(&[0x1ef80020], &state).x[0], 0xffff_fffe);
+}
+
+#[test]
+fn scalar_extended_multiply() {
+    // fmulx h0, h1, h2
+    let mut state = State::default();
+    for (lhs, rhs, dst) in [
+        (0x7c00, 0x0000, 0x4000),
+        (0xfc00, 0x0000, 0xc000),
+        (0x3e00, 0x4000, 0x4200),
+    ] {
+        state.v[1] = lhs;
+        state.v[2] = rhs;
+        assert_eq!(
+            run(&[0x5e421c20], &state).v[0],
+            dst,
+            "fmulx {lhs:#x}, {rhs:#x}"
+        );
+    }
+}
+
+#[test]
+fn reciprocal_exponent() {
+    // frecpx h0, h1
+    let mut state = State::default();
+    for (src, dst) in [
+        (0x4200, 0x3c00),
+        (0xc200, 0xbc00),
+        (0x0000, 0x7800),
+        (0x0001, 0x7800),
+    ] {
+        state.v[1] = src;
+        assert_eq!(run(&[0x5ef9f820], &state).v[0], dst, "frecpx {src:#x}");
+    }
+}
+
+#[test]
+fn pairwise_add() {
+    // faddp h0, v1.2h
+    let mut state = State::default();
+    state.v[1] = half_lanes(&[0x3c00, 0x3e00, 0x4000, 0x4000]);
+    assert_eq!(run(&[0x5e30d820], &state).v[0], 0x4100);
+}
@@ -0,0 +1,113 @@
+#![feature(generators, generator_trait)]
+
+mod common;
+
+use common::{run, State};
+
+/// `x + y + carry` of `bits` wide operands and its NZCV, the `AddWithCarry` of the reference
+/// manual.
+fn add_with_carry(bits: u32, x: u64, y: u64, carry: bool) -> (u64, u64) {
+    let mask = u64::MAX >> (64 - bits);
+    let signed = |v: u64| ((v << (64 - bits)) as i64 >> (64 - bits)) as i128;
+
+    let unsigned_sum = x as u128 + y as u128 + carry as u128;
+    let signed_sum = signed(x) + signed(y) + carry as i128;
+    let result = unsigned_sum as u64 & mask;
+
+    let n = result >> (bits - 1) & 1;
+    let z = (result == 0) as u64;
+    let c = (result as u128 != unsigned_sum) as u64;
+    let v = (signed(result) != signed_sum) as u64;
+    (result, n << 31 | z << 30 | c << 29 | v << 28)
+}
+
+/// Whether the condition code `cond` holds for `nzcv`, as the reference manual defines it.
+fn condition_holds(cond: u32, nzcv: u64) -> bool {
+    let [n, z, c, v] = [31, 30, 29, 28].map(|bit| nzcv >> bit & 1 == 1);
+    let holds = match cond >> 1 {
+        0b000 => z,
+        0b001 => c,
+        0b010 => n,
+        0b011 => v,
+        0b100 => c && !z,
+        0b101 => n == v,
+        0b110 => n == v && !z,
+        _ => true,
+    };
+
+    // NV is AL, not its inverse
+    if cond & 1 == 1 && cond != 0b1111 {
+        !holds
+    } else {
+        holds
+    }
+}
+
+#[test]
+fn every_condition_for_every_nzcv() {
+    let mut state = State::default();
+    for cond in 0..16 {
+        for flags in 0..16 {
+            // csinc x2, xzr, xzr, <cond>
+            state.nzcv = flags << 28;
+            let result = run(&[0x9a9f07e2 | cond << 12], &state);
+            assert_eq!(
+                result.x[2] == 0,
+                condition_holds(cond, state.nzcv),
+                "cond {cond:#06b} with nzcv {flags:#06b}"
+            );
+        }
+    }
+}
+
+#[test]
+fn add_and_subtract_with_carry() {
+    let mut state = State::default();
+    for (bits, sf) in [(32, 0), (64, 1 << 31)] {
+        let mask = u64::MAX >> (64 - bits);
+        let values = [
+            0,
+            1,
+            0x1234_5678_9abc_def0,
+            mask >> 1,
+            !(mask >> 1),
+            u64::MAX,
+        ]
+        .map(|v| v & mask);
+
+        for x in values {
+            for y in values {
+                for carry in [false, true] {
+                    state.x[0] = x;
+                    state.x[1] = y;
+                    state.nzcv = (carry as u64) << 29;
+
+                    // adcs <r>2, <r>0, <r>1
+                    let result = run(&[0x3a010002 | sf], &state);
+                    let expected = add_with_carry(bits, x, y, carry);
+                    assert_eq!(
+                        (result.x[2], result.nzcv),
+                        expected,
+                        "adcs {x:#x}, {y:#x}, {carry}"
+                    );
+
+                    // sbcs <r>2, <r>0, <r>1
+                    let result = run(&[0x7a010002 | sf], &state);
+                    let expected = add_with_carry(bits, x, !y & mask, carry);
+                    assert_eq!(
+                        (result.x[2], result.nzcv),
+                        expected,
+                        "sbcs {x:#x}, {y:#x}, {carry}"
+                    );
+
+                    // adc <r>2, <r>0, <r>1 leaves NZCV alone
+                    let result = run(&[0x1a010002 | sf], &state);
+                    assert_eq!(
+                        (result.x[2], result.nzcv),
+                        (add_with_carry(bits, x, y, carry).0, state.nzcv)
+                    );
+                }
+            }
+        }
+    }
+}
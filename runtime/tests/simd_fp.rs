@@ -0,0 +1,152 @@
+#![feature(generators, generator_trait)]
+
+mod common;
+
+use common::{run, run_with_mmu, State};
+use core::Error;
+use execution::SoftMmu;
+
+/// FPSR.OFC, set by overflowing results.
+const OVERFLOW: u64 = 1 << 2;
+/// FPSR.IXC, set by inexact results.
+const INEXACT: u64 = 1 << 4;
+
+/// `lanes`, each `bits` wide, packed into a vector register.
+fn pack(bits: u32, lanes: &[u64]) -> u128 {
+    lanes
+        .iter()
+        .rev()
+        .fold(0, |vec, &lane| vec << bits | lane as u128)
+}
+
+#[test]
+fn extended_multiply() {
+    // fmulx v2.4s, v0.4s, v1.4s, zero times infinity is two with the sign of the product
+    let mut state = State::default();
+    state.v[0] = pack(32, &[0x0000_0000, 0x8000_0000, 0x4000_0000, 0x3fc0_0000]);
+    state.v[1] = pack(32, &[0x7f80_0000, 0x7f80_0000, 0x4040_0000, 0xc000_0000]);
+    let result = run(&[0x4e21dc02], &state);
+    assert_eq!(
+        result.v[2],
+        pack(32, &[0x4000_0000, 0xc000_0000, 0x40c0_0000, 0xc040_0000])
+    );
+
+    // fmulx v2.2d, v0.2d, v1.2d
+    state.v[0] = pack(64, &[0x7ff0_0000_0000_0000, 0x3fe0_0000_0000_0000]);
+    state.v[1] = pack(64, &[0x8000_0000_0000_0000, 0x4010_0000_0000_0000]);
+    let result = run(&[0x4e61dc02], &state);
+    assert_eq!(
+        result.v[2],
+        pack(64, &[0xc000_0000_0000_0000, 0x4000_0000_0000_0000])
+    );
+}
+
+#[test]
+fn multiply_by_element() {
+    // fmulx v2.2d, v0.2d, v17.d[1]
+    let mut state = State::default();
+    state.v[0] = pack(64, &[0, 0x4008_0000_0000_0000]);
+    state.v[17] = pack(64, &[0x3ff0_0000_0000_0000, 0x7ff0_0000_0000_0000]);
+    let result = run(&[0x6fd19802], &state);
+    assert_eq!(
+        result.v[2],
+        pack(64, &[0x4000_0000_0000_0000, 0x7ff0_0000_0000_0000])
+    );
+
+    // fmul s2, s0, v17.s[2]
+    state.v[0] = pack(32, &[0x4040_0000, 0x3f80_0000]);
+    state.v[17] = pack(32, &[0, 0, 0xc000_0000, 0]);
+    state.v[2] = u128::MAX;
+    let result = run(&[0x5f919802], &state);
+    assert_eq!(result.v[2], 0xc0c0_0000);
+}
+
+#[test]
+fn scalar_by_element_clears_upper_lanes() {
+    // fmulx s2, s0, v1.s[3]
+    let mut state = State::default();
+    state.v[0] = pack(32, &[0x8000_0000, 0x40a0_0000]);
+    state.v[1] = pack(32, &[0, 0, 0, 0x7f80_0000]);
+    state.v[2] = u128::MAX;
+    let result = run(&[0x7fa19802], &state);
+    assert_eq!(result.v[2], 0xc000_0000);
+
+    // fmla s2, s0, v1.s[1]
+    state.v[0] = pack(32, &[0x4000_0000, 0x40e0_0000]);
+    state.v[1] = pack(32, &[0x4110_0000, 0x4040_0000]);
+    state.v[2] = pack(32, &[0x3f80_0000; 4]);
+    let result = run(&[0x5fa11002], &state);
+    assert_eq!(result.v[2], 0x40e0_0000);
+
+    // fmls d2, d0, v1.d[1]
+    state.v[0] = pack(64, &[0x4000_0000_0000_0000, 0x4000_0000_0000_0000]);
+    state.v[1] = pack(64, &[0, 0x3fe0_0000_0000_0000]);
+    state.v[2] = pack(64, &[0x4024_0000_0000_0000, 0x4014_0000_0000_0000]);
+    let result = run(&[0x5fc15802], &state);
+    assert_eq!(result.v[2], 0x4022_0000_0000_0000);
+}
+
+#[test]
+fn convert_rounding_to_odd() {
+    // fcvtxn v2.2s, v0.2d, a tie and a value just above one both round to odd
+    let mut state = State::default();
+    state.v[0] = pack(64, &[0x3ff0_0000_1000_0000, 0xbff0_0000_0000_0001]);
+    state.v[2] = u128::MAX;
+    let result = run(&[0x2e616802], &state);
+    assert_eq!(result.v[2], pack(32, &[0x3f80_0001, 0xbf80_0001]));
+    assert_eq!(result.fpsr, INEXACT);
+
+    // Exact values are unchanged and overflows give the largest normal
+    state.v[0] = pack(64, &[0x3ff0_0000_0000_0000, 0x7e37_e43c_8800_759c]);
+    let result = run(&[0x2e616802], &state);
+    assert_eq!(result.v[2], pack(32, &[0x3f80_0000, 0x7f7f_ffff]));
+    assert_eq!(result.fpsr, OVERFLOW | INEXACT);
+
+    // fcvtxn2 v2.4s, v0.2d
+    state.v[0] = pack(64, &[0x3ff0_0000_1800_0000, 0x4008_0000_0000_0000]);
+    state.v[2] = pack(32, &[1, 2, 3, 4]);
+    let result = run(&[0x6e616802], &state);
+    assert_eq!(result.v[2], pack(32, &[1, 2, 0x3f80_0001, 0x4040_0000]));
+}
+
+#[test]
+fn convert_of_single_to_odd_is_undefined() {
+    let result = run_with_mmu(&[0x2e216802], &State::default(), &SoftMmu::new());
+    assert!(matches!(result, Err(Error::UndefinedInstruction(_))));
+}
+
+#[test]
+fn reduce_across_lanes() {
+    let mut state = State::default();
+
+    // fmaxnmv s2, v0.4s and fminnmv s2, v0.4s ignore quiet NaNs
+    state.v[0] = pack(32, &[0x3f80_0000, 0x7fc0_0000, 0x4080_0000, 0xbf80_0000]);
+    assert_eq!(run(&[0x6e30c802], &state).v[2], 0x4080_0000);
+    assert_eq!(run(&[0x6eb0c802], &state).v[2], 0xbf80_0000);
+
+    // fmaxnmv h2, v0.8h
+    state.v[0] = pack(
+        16,
+        &[
+            0x3c00, 0x7e00, 0x4000, 0xfc00, 0x3800, 0x4200, 0x7e00, 0xbc00,
+        ],
+    );
+    assert_eq!(run(&[0x4e30c802], &state).v[2], 0x4200);
+
+    // fminnmv h2, v0.4h only reads the lower half
+    state.v[0] = pack(16, &[0x3c00, 0x7e00, 0xc000, 0x4000, 0xfc00, 0xfc00]);
+    assert_eq!(run(&[0x0eb0c802], &state).v[2], 0xc000);
+
+    // fmaxv h2, v0.4h propagates NaNs
+    state.v[0] = pack(16, &[0x3c00, 0x7e00, 0x4000, 0x3800]);
+    assert_eq!(run(&[0x0e30f802], &state).v[2], 0x7e00);
+
+    // fminv h2, v0.8h
+    state.v[0] = pack(
+        16,
+        &[
+            0x3c00, 0x4000, 0x3800, 0x4200, 0xbc00, 0x4400, 0x3c00, 0x3c00,
+        ],
+    );
+    assert_eq!(run(&[0x4eb0f802], &state).v[2], 0xbc00);
+}
@@ -0,0 +1,233 @@
+#![feature(generators, generator_trait)]
+
+mod common;
+
+use common::{run, run_with_mmu, State};
+use core::Error;
+use execution::SoftMmu;
+
+/// FPSR.QC, set by saturating instructions.
+const QC: u64 = 1 << 27;
+
+/// `lanes`, each `bits` wide, packed into a vector register.
+fn pack(bits: u32, lanes: &[u64]) -> u128 {
+    lanes
+        .iter()
+        .rev()
+        .fold(0, |vec, &lane| vec << bits | lane as u128)
+}
+
+#[test]
+fn multiply_by_element() {
+    // mul v2.8h, v0.8h, v1.h[5]
+    let mut state = State::default();
+    state.v[0] = pack(16, &[1, 2, 3, 0xffff, 0x100, 7, 0x8000, 5]);
+    state.v[1] = pack(16, &[9, 9, 9, 9, 9, 3, 9, 9]);
+    let result = run(&[0x4f518802], &state);
+    assert_eq!(
+        result.v[2],
+        pack(16, &[3, 6, 9, 0xfffd, 0x300, 0x15, 0x8000, 0xf])
+    );
+
+    // mla v2.4s, v0.4s, v17.s[3], the element register is V16-V31 for 32-bit lanes
+    state.v[0] = pack(32, &[1, 2, 3, 4]);
+    state.v[2] = pack(32, &[10, 20, 30, 40]);
+    state.v[17] = pack(32, &[0, 0, 0, 100]);
+    let result = run(&[0x6fb10802], &state);
+    assert_eq!(result.v[2], pack(32, &[110, 220, 330, 440]));
+
+    // mls v2.4h, v0.4h, v1.h[2]
+    state.v[0] = pack(16, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    state.v[1] = pack(16, &[0, 0, 10, 0, 0, 0, 0, 0]);
+    state.v[2] = pack(16, &[100, 100, 100, 100, 100, 100, 100, 100]);
+    let result = run(&[0x2f614002], &state);
+    assert_eq!(result.v[2], pack(16, &[90, 80, 70, 60]));
+}
+
+#[test]
+fn long_multiply_by_element() {
+    // smull2 v2.4s, v0.8h, v1.h[7]
+    let mut state = State::default();
+    state.v[0] = pack(16, &[1, 1, 1, 1, 0xfffe, 3, 0x7fff, 0x8000]);
+    state.v[1] = pack(16, &[0, 0, 0, 0, 0, 0, 0, 0xfffd]);
+    let result = run(&[0x4f71a802], &state);
+    assert_eq!(
+        result.v[2],
+        pack(32, &[6, 0xffff_fff7, 0xfffe_8003, 0x18000])
+    );
+
+    // umlsl v2.2d, v0.2s, v1.s[1]
+    state.v[0] = pack(32, &[3, 0xffff_ffff, 7, 7]);
+    state.v[1] = pack(32, &[0, 2, 0, 0]);
+    state.v[2] = pack(64, &[1000, 0]);
+    let result = run(&[0x2fa16002], &state);
+    assert_eq!(result.v[2], pack(64, &[0x3e2, 0xffff_fffe_0000_0002]));
+}
+
+#[test]
+fn saturating_doubling_multiply_by_element() {
+    // sqdmulh v2.4h, v0.4h, v1.h[1], only -0x8000 * -0x8000 saturates
+    let mut state = State::default();
+    state.v[0] = pack(16, &[0x4000, 0x8000, 0x1234, 1]);
+    state.v[1] = pack(16, &[0, 0x8000]);
+    let result = run(&[0x0f51c002], &state);
+    assert_eq!(result.v[2], pack(16, &[0xc000, 0x7fff, 0xedcc, 0xffff]));
+    assert_eq!(result.fpsr, QC);
+
+    // sqdmlsl2 v2.4s, v0.8h, v1.h[0]
+    state.v[0] = pack(16, &[0, 0, 0, 0, 10, 0xfff6, 0x7fff, 0x8000]);
+    state.v[1] = pack(16, &[2]);
+    state.v[2] = pack(32, &[100, 0xffff_ff9c, 0x7fff_ffff, 0x8000_0000]);
+    let result = run(&[0x4f417002], &state);
+    assert_eq!(
+        result.v[2],
+        pack(32, &[0x3c, 0xffff_ffc4, 0x7ffe_0003, 0x8002_0000])
+    );
+    assert_eq!(result.fpsr, 0);
+}
+
+#[test]
+fn scalar_by_element_clears_upper_lanes() {
+    // sqrdmulh s2, s0, v1.s[2]
+    let mut state = State::default();
+    state.v[0] = pack(32, &[0x4000_0000, 5, 6, 7]);
+    state.v[1] = pack(32, &[0, 0, 0x1234_5678, 0]);
+    state.v[2] = u128::MAX;
+    let result = run(&[0x5f81d802], &state);
+    assert_eq!(result.v[2], 0x091a_2b3c);
+
+    // sqdmull s2, h0, v1.h[3]
+    state.v[0] = pack(16, &[0x8000, 0x8000]);
+    state.v[1] = pack(16, &[0, 0, 0, 0x8000]);
+    let result = run(&[0x5f71b002], &state);
+    assert_eq!(result.v[2], 0x7fff_ffff);
+    assert_eq!(result.fpsr, QC);
+
+    // sqdmlal d2, s0, v1.s[1]
+    state.v[0] = pack(32, &[3, 3]);
+    state.v[1] = pack(32, &[0, 0xffff_fffc]);
+    state.v[2] = pack(64, &[5, 5]);
+    let result = run(&[0x5fa13002], &state);
+    assert_eq!(result.v[2], 0xffff_ffff_ffff_ffed);
+}
+
+#[test]
+fn by_element_of_byte_lanes_is_undefined() {
+    // mul v2.8h, v0.8h, v1.h[5] with a size of 0
+    let result = run_with_mmu(&[0x4f118802], &State::default(), &SoftMmu::new());
+    assert!(matches!(result, Err(Error::UndefinedInstruction(_))));
+}
+
+#[test]
+fn shift_by_register() {
+    let mut state = State::default();
+
+    // sshl v2.16b, v0.16b, v1.16b, the amount is the signed low byte of the lanes
+    state.v[0] = pack(8, &[0x81, 0x7f, 0x01, 0xff, 0x80, 0x10, 0x40, 0x03]);
+    state.v[1] = pack(8, &[0x80, 0x07, 0x08, 0x01, 0xf9, 0xfc, 0x01, 0xff]);
+    let result = run(&[0x4e214402], &state);
+    assert_eq!(
+        result.v[2],
+        pack(8, &[0xff, 0x80, 0x00, 0xfe, 0xff, 0x01, 0x80, 0x01])
+    );
+
+    state.v[0] = pack(16, &[5, 0xfffb, 0x8000, 0x7fff, 0xff, 0x1234, 0xffff, 3]);
+    state.v[1] = pack(
+        16,
+        &[0xffff, 0xffff, 0xfff0, 0xfff0, 0xffef, 4, 0xff80, 0x7f],
+    );
+    // srshl v2.8h, v0.8h, v1.8h
+    let result = run(&[0x4e615402], &state);
+    assert_eq!(result.v[2], pack(16, &[3, 0xfffe, 0, 0, 0, 0x2340, 0, 0]));
+    // urshl v2.8h, v0.8h, v1.8h
+    let result = run(&[0x6e615402], &state);
+    assert_eq!(result.v[2], pack(16, &[3, 0x7ffe, 1, 0, 0, 0x2340, 0, 0]));
+}
+
+#[test]
+fn saturating_shift_by_register() {
+    let mut state = State::default();
+    state.v[0] = pack(16, &[1, 0xffff, 0x4000, 0x4000, 0xc000, 5, 0x1234, 0xfff9]);
+    state.v[1] = pack(16, &[15, 15, 1, 2, 1, 0xffff, 16, 0xffec]);
+
+    // sqshl v2.8h, v0.8h, v1.8h
+    let result = run(&[0x4e614c02], &state);
+    assert_eq!(
+        result.v[2],
+        pack(
+            16,
+            &[0x7fff, 0x8000, 0x7fff, 0x7fff, 0x8000, 2, 0x7fff, 0xffff]
+        )
+    );
+    assert_eq!(result.fpsr, QC);
+
+    // uqshl v2.8h, v0.8h, v1.8h
+    let result = run(&[0x6e614c02], &state);
+    assert_eq!(
+        result.v[2],
+        pack(16, &[0x8000, 0xffff, 0x8000, 0xffff, 0xffff, 2, 0xffff, 0])
+    );
+    assert_eq!(result.fpsr, QC);
+
+    // sqrshl v2.2d, v0.2d, v1.2d
+    state.v[0] = pack(64, &[0x8000_0000_0000_0001, 0x7fff_ffff_ffff_ffff]);
+    state.v[1] = pack(64, &[0xc0, 1]);
+    let result = run(&[0x4ee15c02], &state);
+    assert_eq!(result.v[2], pack(64, &[0, 0x7fff_ffff_ffff_ffff]));
+    assert_eq!(result.fpsr, QC);
+
+    // uqrshl v2.4s, v0.4s, v1.4s, rounding right shifts never saturate
+    state.v[0] = pack(32, &[0xffff_ffff, 0x8000_0000, 3, 0x10]);
+    state.v[1] = pack(32, &[0xffff_ffe0, 0xffff_ffe1, 0x1e, 0xff]);
+    let result = run(&[0x6ea15c02], &state);
+    assert_eq!(result.v[2], pack(32, &[1, 1, 0xc000_0000, 8]));
+    assert_eq!(result.fpsr, 0);
+}
+
+#[test]
+fn saturating_accumulate_of_opposite_sign() {
+    let mut state = State::default();
+    state.v[0] = pack(8, &[0x01, 0xff, 0x20, 0xff, 0x80, 0x80, 0x7f, 0x01]);
+    state.v[2] = pack(8, &[0x7f, 0x80, 0xf0, 0x00, 0x10, 0xff, 0x01, 0x7e]);
+
+    // suqadd v2.16b, v0.16b
+    let result = run(&[0x4e203802], &state);
+    assert_eq!(
+        result.v[2],
+        pack(8, &[0x7f, 0x7f, 0x10, 0x7f, 0x7f, 0x7f, 0x7f, 0x7f])
+    );
+    assert_eq!(result.fpsr, QC);
+
+    // usqadd v2.16b, v0.16b
+    let result = run(&[0x6e203802], &state);
+    assert_eq!(
+        result.v[2],
+        pack(8, &[0x80, 0x7f, 0xff, 0x00, 0x00, 0x7f, 0x80, 0x7f])
+    );
+    assert_eq!(result.fpsr, QC);
+}
+
+#[test]
+fn unsigned_estimates() {
+    let mut state = State::default();
+
+    // urecpe v2.4s, v0.4s, fractions below one half give all ones
+    state.v[0] = pack(32, &[0x7fff_ffff, 0x8000_0000, 0xc000_0000, 0xffff_ffff]);
+    let result = run(&[0x4ea1c802], &state);
+    assert_eq!(
+        result.v[2],
+        pack(32, &[0xffff_ffff, 0xff80_0000, 0xaa80_0000, 0x8000_0000])
+    );
+
+    // ursqrte v2.2s, v0.2s, fractions below one quarter give all ones
+    state.v[0] = pack(32, &[0x3fff_ffff, 0x4000_0000, 0x8000_0000, 0xffff_ffff]);
+    let result = run(&[0x2ea1c802], &state);
+    assert_eq!(result.v[2], pack(32, &[0xffff_ffff, 0xff80_0000]));
+
+    // ursqrte v2.4s, v0.4s
+    let result = run(&[0x6ea1c802], &state);
+    assert_eq!(
+        result.v[2],
+        pack(32, &[0xffff_ffff, 0xff80_0000, 0xb480_0000, 0x8000_0000])
+    );
+}
@@ -0,0 +1,138 @@
+#![feature(generators, generator_trait)]
+
+mod common;
+
+use std::ffi::OsString;
+
+use arch_desc::aarch64::AArch64Architecture;
+use core::{ArchitectureCompat, Error};
+use execution::{
+    abi::{AArch64UnknownLinux, LinuxConfig},
+    codegen::{cranelift::CraneliftCodegen, rustjit::RustjitCodegen, Codegen},
+    Runtime, RuntimeConfig,
+};
+
+/// Checks the initial stack and the system registers a program can read, then exits with the
+/// number of the first check that failed, or zero.
+const STARTUP: &[u32] = &[
+    // _start:
+    0x910003f3, // mov x19, sp
+    0xd280003c, // mov x28, #1
+    0xf2400e7f, // tst x19, #0xf
+    0x54000941, // b.ne fail
+    0xd280005c, // mov x28, #2
+    0xf9400260, // ldr x0, [x19]
+    0xf100081f, // cmp x0, #2
+    0x540008c1, // b.ne fail
+    0xd280007c, // mov x28, #3
+    0xf9400661, // ldr x1, [x19, #8]
+    0x39400022, // ldrb w2, [x1]
+    0x7101c05f, // cmp w2, #112
+    0x54000821, // b.ne fail
+    0xf9400a61, // ldr x1, [x19, #16]
+    0x39400022, // ldrb w2, [x1]
+    0x7101845f, // cmp w2, #97
+    0x540007a1, // b.ne fail
+    0xf9400e61, // ldr x1, [x19, #24]
+    0xb5000761, // cbnz x1, fail
+    0xd280009c, // mov x28, #4
+    0xf9401261, // ldr x1, [x19, #32]
+    0x39400022, // ldrb w2, [x1]
+    0x71012c5f, // cmp w2, #75
+    0x540006c1, // b.ne fail
+    0xf9401661, // ldr x1, [x19, #40]
+    0xb5000681, // cbnz x1, fail
+    0xd28000bc, // mov x28, #5
+    0x9100c274, // add x20, x19, #48
+    0xd2800015, // mov x21, #0
+    0x10fffc76, // adr x22, _start
+    // next:
+    0xa8c10a81, // ldp x1, x2, [x20], #16
+    0xb40002c1, // cbz x1, done
+    0xf100183f, // cmp x1, #6
+    0x54000081, // b.ne 1f
+    0xf140045f, // cmp x2, #1, lsl #12
+    0x54000541, // b.ne fail
+    0xb24002b5, // orr x21, x21, #0x1
+    // 1:
+    0xf100243f, // cmp x1, #9
+    0x54000081, // b.ne 2f
+    0xeb16005f, // cmp x2, x22
+    0x540004a1, // b.ne fail
+    0xb27f02b5, // orr x21, x21, #0x2
+    // 2:
+    0xf100403f, // cmp x1, #16
+    0x540000a1, // b.ne 3f
+    0x92400442, // and x2, x2, #0x3
+    0xf1000c5f, // cmp x2, #3
+    0x540003e1, // b.ne fail
+    0xb27e02b5, // orr x21, x21, #0x4
+    // 3:
+    0xf100643f, // cmp x1, #25
+    0x54fffda1, // b.ne next
+    0xf9400442, // ldr x2, [x2, #8]
+    0xb27d02b5, // orr x21, x21, #0x8
+    0x17ffffea, // b next
+    // done:
+    0xd28000dc, // mov x28, #6
+    0xf1003ebf, // cmp x21, #15
+    0x540002c1, // b.ne fail
+    0xd28000fc, // mov x28, #7
+    0xd51bd053, // msr TPIDR_EL0, x19
+    0xd53bd041, // mrs x1, TPIDR_EL0
+    0xeb13003f, // cmp x1, x19
+    0x54000221, // b.ne fail
+    0xd280011c, // mov x28, #8
+    0xd53b0021, // mrs x1, CTR_EL0
+    0xd2980082, // mov x2, #49156
+    0xf2b08882, // movk x2, #33860, lsl #16
+    0xeb02003f, // cmp x1, x2
+    0x54000161, // b.ne fail
+    0xd280013c, // mov x28, #9
+    0xd53b00e1, // mrs x1, DCZID_EL0
+    0xf100103f, // cmp x1, #4
+    0x540000e1, // b.ne fail
+    0xd280015c, // mov x28, #10
+    0xd5380001, // mrs x1, MIDR_EL1
+    0xd2a001e2, // mov x2, #983040
+    0xeb02003f, // cmp x1, x2
+    0x54000041, // b.ne fail
+    0xd280001c, // mov x28, #0
+    // fail:
+    0xaa1c03e0, // mov x0, x28
+    0xd2800ba8, // mov x8, #93
+    0xd4000001, // svc #0
+];
+
+fn run<C: Codegen + ArchitectureCompat<AArch64Architecture>>(binary: &[u8]) -> i32 {
+    let config = LinuxConfig {
+        args: vec![OsString::from("prog"), OsString::from("arg")],
+        env: vec![OsString::from("K=V")],
+        ..LinuxConfig::default()
+    };
+    let abi = AArch64UnknownLinux::with_config(config);
+    let result = unsafe {
+        Runtime::run::<AArch64Architecture, C, _>(binary, abi, RuntimeConfig::default(), |_, _| {})
+    };
+    result.unwrap()
+}
+
+#[test]
+fn initial_stack_and_system_registers() {
+    let binary = common::static_elf(0x40_0000, STARTUP);
+    assert_eq!(run::<RustjitCodegen>(&binary), 0);
+    assert_eq!(run::<CraneliftCodegen>(&binary), 0);
+}
+
+#[test]
+fn malformed_binary_is_not_loaded() {
+    let error = unsafe {
+        Runtime::run::<AArch64Architecture, RustjitCodegen, _>(
+            b"\x7fELF",
+            AArch64UnknownLinux::with_config(LinuxConfig::default()),
+            RuntimeConfig::default(),
+            |_, _| {},
+        )
+    };
+    assert!(matches!(error, Err(Error::LoadFailure(_))));
+}
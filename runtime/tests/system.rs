@@ -0,0 +1,72 @@
+#![feature(generators, generator_trait)]
+
+mod common;
+
+use common::{run_with_mmu, State};
+use core::{Access, Error};
+use device::devices::Memory;
+use execution::SoftMmu;
+
+#[test]
+fn dc_zva_zeroes_the_aligned_block() {
+    let mmu = SoftMmu::new();
+    mmu.map(0x1000, 0x1000, Memory::allocate(0x1000));
+    unsafe { mmu.write(0x1000, &[0xff; 0x1000]).unwrap() };
+
+    // dc zva, x0
+    let mut state = State::default();
+    state.x[0] = 0x1234;
+    run_with_mmu(&[0xd50b7420], &state, &mmu).unwrap();
+
+    let mut memory = [0; 0x200];
+    unsafe { mmu.read(0x1100, &mut memory, Access::Read).unwrap() };
+    assert!(memory[..0x100].iter().all(|&byte| byte == 0xff));
+    assert!(memory[0x100..0x140].iter().all(|&byte| byte == 0));
+    assert!(memory[0x140..].iter().all(|&byte| byte == 0xff));
+}
+
+#[test]
+fn ic_iallu_invalidates_every_translation() {
+    let mmu = SoftMmu::new();
+    mmu.map(0x1000, 0x4000, Memory::allocate(0x4000));
+    mmu.track_code(0x1000..0x1010);
+    mmu.track_code(0x3ff0..0x4010);
+    assert!(mmu.take_modified_code().is_empty());
+
+    // ic iallu, then ic ialluis. Every page with translated code is reported.
+    for inst in [0xd508751f, 0xd508711f] {
+        run_with_mmu(&[inst], &State::default(), &mmu).unwrap();
+        let mut pages = mmu.take_modified_code();
+        pages.sort();
+        assert_eq!(pages, [0x1000, 0x3000, 0x4000]);
+
+        mmu.track_code(0x1000..0x1010);
+        mmu.track_code(0x3ff0..0x4010);
+    }
+}
+
+#[test]
+fn sysl_is_undefined() {
+    // sysl x0, #0, c7, c5, #0
+    let result = run_with_mmu(&[0xd5287500], &State::default(), &SoftMmu::new());
+    assert!(matches!(result, Err(Error::UndefinedInstruction(_))));
+}
+
+#[test]
+fn hints_execute_as_nop() {
+    let mut state = State::default();
+    state.x[30] = 0x1234_5678;
+    state.nzcv = 0x6000_0000;
+
+    // nop, yield, wfe, wfi, sev, sevl, paciasp, autiasp, xpaclri, pacia1716, bti c
+    let hints = [
+        0xd503201f, 0xd503203f, 0xd503205f, 0xd503207f, 0xd503209f, 0xd50320bf, 0xd503233f,
+        0xd50323bf, 0xd50320ff, 0xd503211f, 0xd503245f,
+    ];
+    // adr x3, #0 reads the PC after them
+    let code = [&hints[..], &[0x10000003]].concat();
+    let result = run_with_mmu(&code, &state, &SoftMmu::new()).unwrap();
+
+    state.x[3] = 4 * hints.len() as u64;
+    assert_eq!(result, state);
+}